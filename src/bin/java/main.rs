@@ -1,19 +1,24 @@
 use jvm::{
     descriptor,
     runtime::{
-        genesis, {self},
+        Exception, genesis, {self},
     },
 };
+use std::{env, process};
 
+// usage: java [class path] [main class]
 fn main() {
-    genesis(
-        "/opt/homebrew/Cellar/openjdk@17/17.0.15/libexec/openjdk.jdk/Contents/Home/",
-        "data/test/",
+    let mut args = env::args().skip(1);
+    let class_path = args.next().unwrap_or("data/test/".to_string());
+    let main_class = args.next().unwrap_or("D".to_string());
+    let java_home = env::var("JAVA_HOME").unwrap_or(
+        "/opt/homebrew/Cellar/openjdk@17/17.0.15/libexec/openjdk.jdk/Contents/Home/".to_string(),
     );
+    genesis(java_home, class_path);
 
-    let mut main_thread = runtime::Thread::new(1024);
+    let mut main_thread = runtime::Thread::new(0);
     main_thread.new_main_frame(
-        "D",
+        &main_class,
         "main",
         &[descriptor::FieldType::Array(Box::new(
             descriptor::FieldType::Object("java/lang/String".to_string()),
@@ -26,6 +31,11 @@ fn main() {
     // frame.add_local_int(20);
     // frame.add_local_reference(10);
     // frame.add_local_reference(20);
-    main_thread.execute().unwrap();
+    let result = main_thread.execute();
+    if let Err(Exception::Exit(status)) = result {
+        process::exit(status);
+    }
+    result.unwrap();
+    runtime::wait_for_non_daemon_threads();
     // println!("{}", unsafe { v.get_int() });
 }
@@ -56,7 +56,8 @@ impl JavaStr {
         }
     }
 
-    /// (bytes, has_multibyte)
+    /// (bytes, is_utf16), the `value` and `coder` of a `java.lang.String`, where latin1 strings
+    /// are compacted to one byte per char if `compact`
     pub fn to_java_string_bytes(&self, compact: bool) -> (Cow<'_, [u8]>, bool) {
         let (num_chars, mut is_latin1, mut has_multibyte) = self.calculate_unicode_info();
         if !compact {
@@ -80,11 +81,11 @@ impl JavaStr {
             bytes.set_len(bytes.capacity());
         }
 
-        (Cow::Owned(bytes), has_multibyte)
+        (Cow::Owned(bytes), !is_latin1)
     }
 
     pub fn to_java_string_bytes_arc(self: Arc<Self>, compact: bool) -> (Arc<[u8]>, bool) {
-        let (cow, has_multibyte) = self.to_java_string_bytes(compact);
+        let (cow, is_utf16) = self.to_java_string_bytes(compact);
 
         let arc = match cow {
             // SAFETY: JavaStr and [u8] has the same layout
@@ -92,7 +93,7 @@ impl JavaStr {
             Cow::Owned(string) => Arc::from(string),
         };
 
-        (arc, has_multibyte)
+        (arc, is_utf16)
     }
 
     ///  return: length in unicode chars, is_latin1, has_multibyte
@@ -245,8 +246,10 @@ impl From<JavaString> for Arc<JavaStr> {
 
 impl Debug for JavaStr {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        // TODO: resolve surrogate pairs
-        Debug::fmt(&String::from_utf8_lossy(&self.inner), f)
+        match cesu8_java::JavaStr::from_java_cesu8(&self.inner) {
+            Ok(java_str) => Debug::fmt(&cesu8_java::from_java_cesu8(java_str), f),
+            Err(_) => Debug::fmt(&String::from_utf8_lossy(&self.inner), f),
+        }
     }
 }
 
@@ -270,3 +273,37 @@ impl Deref for JavaString {
         self.borrow()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // U+1F600 encoded as a surrogate pair in modified utf-8
+    const GRINNING_FACE: &[u8] = &[0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80];
+
+    #[test]
+    fn test_supplementary_character() {
+        let bytes = [b"Foo$".as_slice(), GRINNING_FACE].concat();
+        let java_str = unsafe { JavaStr::new(&bytes) };
+        assert_eq!(java_str.to_str(), "Foo$\u{1F600}");
+        assert_eq!(format!("{java_str:?}"), "\"Foo$\u{1F600}\"");
+        assert_eq!(JavaStr::from_str("Foo$\u{1F600}").as_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_java_string_bytes() {
+        let ascii = JavaStr::from_str("abc");
+        let (bytes, is_utf16) = ascii.to_java_string_bytes(true);
+        assert_eq!((bytes.as_ref(), is_utf16), (b"abc".as_slice(), false));
+        // multibyte in modified utf-8, but still latin1
+        let latin1 = JavaStr::from_str("h\u{e9}");
+        let (bytes, is_utf16) = latin1.to_java_string_bytes(true);
+        assert_eq!((bytes.as_ref(), is_utf16), ([b'h', 0xE9].as_slice(), false));
+        let (bytes, is_utf16) = latin1.to_java_string_bytes(false);
+        assert_eq!((bytes.len(), is_utf16), (4, true));
+        let utf16 = JavaStr::from_str("\u{4e2d}");
+        let (bytes, is_utf16) = utf16.to_java_string_bytes(true);
+        let expected = 0x4E2D_u16.to_ne_bytes();
+        assert_eq!((bytes.as_ref(), is_utf16), (expected.as_slice(), true));
+    }
+}
@@ -1,9 +1,9 @@
 use nom::{
-    branch::alt, bytes::complete::take_until,
-    character::complete::{char, one_of},
-    combinator::{eof, map},
-    multi::many0,
-    sequence::delimited,
+    branch::alt, bytes::complete::is_not,
+    character::complete::{anychar, char},
+    combinator::{eof, map, map_opt, opt, verify},
+    multi::{many0, many1},
+    sequence::{delimited, preceded},
     IResult,
     Parser,
 };
@@ -25,11 +25,30 @@ impl Display for MethodDescriptor {
             write!(f, "{field_type}, ")?
         }
         write!(f, ")")?;
-        if let Some(ret) = &self.return_type {
-            write!(f, " -> {ret}")?
+        match &self.return_type {
+            Some(ret) => write!(f, " -> {ret}"),
+            None => write!(f, " -> void"),
         }
+    }
+}
 
-        Ok(())
+impl MethodDescriptor {
+    /// Slots the arguments take in the operand stack and the locals, without `this`
+    pub fn arg_slot_count(&self) -> usize {
+        self.parameters.iter().map(FieldType::slot_count).sum()
+    }
+
+    pub fn to_descriptor(&self) -> String {
+        let parameters: String = self
+            .parameters
+            .iter()
+            .map(FieldType::to_descriptor)
+            .collect();
+        let return_type = match &self.return_type {
+            Some(return_type) => return_type.to_descriptor(),
+            None => "V".to_string(),
+        };
+        format!("({parameters}){return_type}")
     }
 }
 
@@ -71,6 +90,11 @@ impl FieldType {
         matches!(self, FieldType::Long | FieldType::Double)
     }
 
+    /// Slots a value takes in the operand stack and the locals, 2 for long and double
+    pub fn slot_count(&self) -> usize {
+        if self.is_long() { 2 } else { 1 }
+    }
+
     pub fn is_primitive(&self) -> bool {
         matches!(
             self,
@@ -146,27 +170,28 @@ fn parse_field_type(input: &str) -> IResult<&str, FieldType> {
 }
 
 fn parse_base_type(input: &str) -> IResult<&str, FieldType> {
-    let (input, ch) = one_of("BCDFIJSZ")(input)?;
-    let field_type = match ch {
-        'B' => FieldType::Byte,
-        'C' => FieldType::Char,
-        'D' => FieldType::Double,
-        'F' => FieldType::Float,
-        'I' => FieldType::Int,
-        'J' => FieldType::Long,
-        'S' => FieldType::Short,
-        'Z' => FieldType::Boolean,
-        _ => {
-            todo!("error")
-        }
-    };
-    Ok((input, field_type))
+    map_opt(anychar, |ch| match ch {
+        'B' => Some(FieldType::Byte),
+        'C' => Some(FieldType::Char),
+        'D' => Some(FieldType::Double),
+        'F' => Some(FieldType::Float),
+        'I' => Some(FieldType::Int),
+        'J' => Some(FieldType::Long),
+        'S' => Some(FieldType::Short),
+        'Z' => Some(FieldType::Boolean),
+        _ => None,
+    })
+    .parse(input)
 }
 
 fn parse_object_type(input: &str) -> IResult<&str, FieldType> {
     let (input, _) = char('L')(input)?;
 
-    let (input, class_name) = take_until(";")(input)?;
+    // binary name in internal form, a non-empty name between each `/` (JVMS 4.2.1)
+    let (input, class_name) = verify(is_not(".;["), |name: &str| {
+        !name.split('/').any(str::is_empty)
+    })
+    .parse(input)?;
 
     let (input, _) = char(';')(input)?;
 
@@ -185,3 +210,508 @@ fn parse_void_type(input: &str) -> IResult<&str, Option<FieldType>> {
     let (input, _) = char('V')(input)?;
     Ok((input, None))
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassSignature {
+    pub(crate) type_parameters: Vec<TypeParameter>,
+    pub(crate) super_class: ClassTypeSignature,
+    pub(crate) interfaces: Vec<ClassTypeSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodSignature {
+    pub(crate) type_parameters: Vec<TypeParameter>,
+    pub(crate) parameters: Vec<JavaTypeSignature>,
+    // `None` for void
+    pub(crate) result: Option<JavaTypeSignature>,
+    pub(crate) throws: Vec<ReferenceTypeSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeParameter {
+    pub(crate) name: String,
+    pub(crate) class_bound: Option<ReferenceTypeSignature>,
+    pub(crate) interface_bounds: Vec<ReferenceTypeSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JavaTypeSignature {
+    Base(FieldType),
+    Reference(ReferenceTypeSignature),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceTypeSignature {
+    Class(ClassTypeSignature),
+    TypeVariable(String),
+    Array(Box<JavaTypeSignature>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassTypeSignature {
+    // binary name, inner classes joined with `$`
+    pub(crate) class_name: String,
+    pub(crate) type_arguments: Vec<TypeArgument>,
+    // only present when the outer class is parameterized, e.g. `LOuter<TT;>.Inner;`
+    pub(crate) outer: Option<Box<ClassTypeSignature>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeArgument {
+    Wildcard,
+    Exact(ReferenceTypeSignature),
+    Extends(ReferenceTypeSignature),
+    Super(ReferenceTypeSignature),
+}
+
+// displayed like `Type.getTypeName`, but with class names in internal form as `FieldType` does
+
+impl Display for JavaTypeSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JavaTypeSignature::Base(field_type) => write!(f, "{field_type}"),
+            JavaTypeSignature::Reference(signature) => write!(f, "{signature}"),
+        }
+    }
+}
+
+impl Display for ReferenceTypeSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReferenceTypeSignature::Class(signature) => write!(f, "{signature}"),
+            ReferenceTypeSignature::TypeVariable(name) => write!(f, "{name}"),
+            ReferenceTypeSignature::Array(element_type) => write!(f, "{element_type}[]"),
+        }
+    }
+}
+
+impl Display for ClassTypeSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.outer {
+            // e.g. `Outer<K>$Inner`
+            Some(outer) => {
+                let simple_name = &self.class_name[outer.class_name.len()..];
+                write!(f, "{outer}{simple_name}")?;
+            }
+            None => write!(f, "{}", self.class_name)?,
+        }
+        if let Some((first, rest)) = self.type_arguments.split_first() {
+            write!(f, "<{first}")?;
+            for type_argument in rest {
+                write!(f, ", {type_argument}")?;
+            }
+            write!(f, ">")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for TypeArgument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeArgument::Wildcard => write!(f, "?"),
+            TypeArgument::Exact(signature) => write!(f, "{signature}"),
+            TypeArgument::Extends(signature) => write!(f, "? extends {signature}"),
+            TypeArgument::Super(signature) => write!(f, "? super {signature}"),
+        }
+    }
+}
+
+/// The erased type as a signature, for members without a `Signature` attribute
+impl From<&FieldType> for JavaTypeSignature {
+    fn from(field_type: &FieldType) -> Self {
+        match field_type {
+            FieldType::Object(class_name) => {
+                JavaTypeSignature::Reference(ReferenceTypeSignature::Class(ClassTypeSignature {
+                    class_name: class_name.clone(),
+                    type_arguments: vec![],
+                    outer: None,
+                }))
+            }
+            FieldType::Array(element_type) => JavaTypeSignature::Reference(
+                ReferenceTypeSignature::Array(Box::new(element_type.as_ref().into())),
+            ),
+            base_type => JavaTypeSignature::Base(base_type.clone()),
+        }
+    }
+}
+
+pub fn parse_class_signature(input: &str) -> IResult<&str, ClassSignature> {
+    let (input, type_parameters) = opt(parse_type_parameters).parse(input)?;
+    let (input, super_class) = parse_class_type_signature(input)?;
+    let (input, interfaces) = many0(parse_class_type_signature).parse(input)?;
+
+    eof(input)?;
+    Ok((
+        input,
+        ClassSignature {
+            type_parameters: type_parameters.unwrap_or_default(),
+            super_class,
+            interfaces,
+        },
+    ))
+}
+
+pub fn parse_method_signature(input: &str) -> IResult<&str, MethodSignature> {
+    let (input, type_parameters) = opt(parse_type_parameters).parse(input)?;
+    let (input, parameters) =
+        delimited(char('('), many0(parse_java_type_signature), char(')')).parse(input)?;
+    let (input, result) = alt((
+        map(parse_void_type, |_| None),
+        map(parse_java_type_signature, Some),
+    ))
+    .parse(input)?;
+    let (input, throws) = many0(preceded(
+        char('^'),
+        alt((
+            map(parse_class_type_signature, ReferenceTypeSignature::Class),
+            parse_type_variable_signature,
+        )),
+    ))
+    .parse(input)?;
+
+    eof(input)?;
+    Ok((
+        input,
+        MethodSignature {
+            type_parameters: type_parameters.unwrap_or_default(),
+            parameters,
+            result,
+            throws,
+        },
+    ))
+}
+
+pub fn parse_field_signature(input: &str) -> IResult<&str, ReferenceTypeSignature> {
+    let (input, signature) = parse_reference_type_signature(input)?;
+
+    eof(input)?;
+    Ok((input, signature))
+}
+
+fn parse_identifier(input: &str) -> IResult<&str, &str> {
+    is_not(".;[/<>:").parse(input)
+}
+
+fn parse_type_parameters(input: &str) -> IResult<&str, Vec<TypeParameter>> {
+    delimited(char('<'), many1(parse_type_parameter), char('>')).parse(input)
+}
+
+fn parse_type_parameter(input: &str) -> IResult<&str, TypeParameter> {
+    let (input, name) = parse_identifier(input)?;
+    let (input, class_bound) =
+        preceded(char(':'), opt(parse_reference_type_signature)).parse(input)?;
+    let (input, interface_bounds) =
+        many0(preceded(char(':'), parse_reference_type_signature)).parse(input)?;
+
+    Ok((
+        input,
+        TypeParameter {
+            name: name.to_string(),
+            class_bound,
+            interface_bounds,
+        },
+    ))
+}
+
+fn parse_java_type_signature(input: &str) -> IResult<&str, JavaTypeSignature> {
+    alt((
+        map(parse_reference_type_signature, JavaTypeSignature::Reference),
+        map(parse_base_type, JavaTypeSignature::Base),
+    ))
+    .parse(input)
+}
+
+fn parse_reference_type_signature(input: &str) -> IResult<&str, ReferenceTypeSignature> {
+    alt((
+        map(parse_class_type_signature, ReferenceTypeSignature::Class),
+        parse_type_variable_signature,
+        parse_array_type_signature,
+    ))
+    .parse(input)
+}
+
+fn parse_class_type_signature(input: &str) -> IResult<&str, ClassTypeSignature> {
+    let (input, _) = char('L')(input)?;
+
+    // package specifier and the first simple class name
+    let (input, class_name) = is_not(".;[<>:").parse(input)?;
+    let (mut input, type_arguments) = opt(parse_type_arguments).parse(input)?;
+
+    let mut signature = ClassTypeSignature {
+        class_name: class_name.to_string(),
+        type_arguments: type_arguments.unwrap_or_default(),
+        outer: None,
+    };
+
+    while let Ok((rest, _)) = char::<_, nom::error::Error<&str>>('.')(input) {
+        let (rest, name) = parse_identifier(rest)?;
+        let (rest, type_arguments) = opt(parse_type_arguments).parse(rest)?;
+        let outer = (!signature.type_arguments.is_empty() || signature.outer.is_some())
+            .then(|| Box::new(signature.clone()));
+        signature = ClassTypeSignature {
+            class_name: format!("{}${name}", signature.class_name),
+            type_arguments: type_arguments.unwrap_or_default(),
+            outer,
+        };
+        input = rest;
+    }
+
+    let (input, _) = char(';')(input)?;
+
+    Ok((input, signature))
+}
+
+fn parse_type_arguments(input: &str) -> IResult<&str, Vec<TypeArgument>> {
+    delimited(char('<'), many1(parse_type_argument), char('>')).parse(input)
+}
+
+fn parse_type_argument(input: &str) -> IResult<&str, TypeArgument> {
+    alt((
+        map(char('*'), |_| TypeArgument::Wildcard),
+        map(
+            preceded(char('+'), parse_reference_type_signature),
+            TypeArgument::Extends,
+        ),
+        map(
+            preceded(char('-'), parse_reference_type_signature),
+            TypeArgument::Super,
+        ),
+        map(parse_reference_type_signature, TypeArgument::Exact),
+    ))
+    .parse(input)
+}
+
+fn parse_type_variable_signature(input: &str) -> IResult<&str, ReferenceTypeSignature> {
+    let (input, name) = delimited(char('T'), parse_identifier, char(';')).parse(input)?;
+    Ok((
+        input,
+        ReferenceTypeSignature::TypeVariable(name.to_string()),
+    ))
+}
+
+fn parse_array_type_signature(input: &str) -> IResult<&str, ReferenceTypeSignature> {
+    let (input, _) = char('[')(input)?;
+
+    let (input, element_type) = parse_java_type_signature(input)?;
+
+    Ok((input, ReferenceTypeSignature::Array(Box::new(element_type))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class_type(class_name: &str, type_arguments: Vec<TypeArgument>) -> ClassTypeSignature {
+        ClassTypeSignature {
+            class_name: class_name.to_string(),
+            type_arguments,
+            outer: None,
+        }
+    }
+
+    #[test]
+    fn test_class_signature_type_argument() {
+        // class StringList extends ArrayList<String>
+        let (_, signature) =
+            parse_class_signature("Ljava/util/ArrayList<Ljava/lang/String;>;").unwrap();
+        assert!(signature.type_parameters.is_empty());
+        assert!(signature.interfaces.is_empty());
+        assert_eq!(signature.super_class.class_name, "java/util/ArrayList");
+        assert_eq!(
+            signature.super_class.type_arguments,
+            vec![TypeArgument::Exact(ReferenceTypeSignature::Class(
+                class_type("java/lang/String", vec![])
+            ))]
+        );
+    }
+
+    #[test]
+    fn test_class_signature_type_parameters() {
+        // class Foo<K extends Comparable<? super K>, V> extends Outer<K>.Inner implements Map.Entry<K, V[]>
+        let (_, signature) = parse_class_signature(
+            "<K::Ljava/lang/Comparable<-TK;>;V:Ljava/lang/Object;>LOuter<TK;>.Inner;Ljava/util/Map$Entry<TK;[TV;>;",
+        )
+        .unwrap();
+        assert_eq!(
+            signature.type_parameters,
+            vec![
+                TypeParameter {
+                    name: "K".to_string(),
+                    class_bound: None,
+                    interface_bounds: vec![ReferenceTypeSignature::Class(class_type(
+                        "java/lang/Comparable",
+                        vec![TypeArgument::Super(ReferenceTypeSignature::TypeVariable(
+                            "K".to_string()
+                        ))]
+                    ))],
+                },
+                TypeParameter {
+                    name: "V".to_string(),
+                    class_bound: Some(ReferenceTypeSignature::Class(class_type(
+                        "java/lang/Object",
+                        vec![]
+                    ))),
+                    interface_bounds: vec![],
+                },
+            ]
+        );
+        assert_eq!(
+            signature.super_class,
+            ClassTypeSignature {
+                class_name: "Outer$Inner".to_string(),
+                type_arguments: vec![],
+                outer: Some(Box::new(class_type(
+                    "Outer",
+                    vec![TypeArgument::Exact(ReferenceTypeSignature::TypeVariable(
+                        "K".to_string()
+                    ))]
+                ))),
+            }
+        );
+        assert_eq!(
+            signature.interfaces,
+            vec![class_type(
+                "java/util/Map$Entry",
+                vec![
+                    TypeArgument::Exact(ReferenceTypeSignature::TypeVariable("K".to_string())),
+                    TypeArgument::Exact(ReferenceTypeSignature::Array(Box::new(
+                        JavaTypeSignature::Reference(ReferenceTypeSignature::TypeVariable(
+                            "V".to_string()
+                        ))
+                    ))),
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_malformed_field_descriptor() {
+        for descriptor in [
+            "",
+            "L;",
+            "X",
+            "[",
+            "V",
+            "[V",
+            "Ljava/lang/String",
+            "Ljava.lang.String;",
+            "Ljava//String;",
+            "L/String;",
+            "Ljava/lang/;",
+            "La;b;",
+            "II",
+        ] {
+            assert!(parse_field_descriptor(descriptor).is_err(), "{descriptor}");
+        }
+        for descriptor in ["(L;)V", "(X)V", "([)V", "()[", "(V)V"] {
+            assert!(parse_method_descriptor(descriptor).is_err(), "{descriptor}");
+        }
+
+        let (_, FieldDescriptor(field_type)) =
+            parse_field_descriptor("[[Ljava/util/Map$Entry;").unwrap();
+        assert_eq!(field_type.to_descriptor(), "[[Ljava/util/Map$Entry;");
+        let (_, FieldDescriptor(field_type)) = parse_field_descriptor("LFoo;").unwrap();
+        assert_eq!(field_type, FieldType::Object("Foo".to_string()));
+    }
+
+    #[test]
+    fn test_slot_count() {
+        for (descriptor, slots) in [
+            ("()V", 0),
+            ("(JD)V", 4),
+            ("(Ljava/lang/String;J[D)I", 4),
+            ("(I[JLjava/lang/Object;DZ)J", 6),
+            ("([[Ljava/lang/Long;)D", 1),
+        ] {
+            let (_, method_descriptor) = parse_method_descriptor(descriptor).unwrap();
+            assert_eq!(method_descriptor.arg_slot_count(), slots, "{descriptor}");
+        }
+        assert_eq!(FieldType::Double.slot_count(), 2);
+        assert_eq!(FieldType::Array(Box::new(FieldType::Long)).slot_count(), 1);
+    }
+
+    #[test]
+    fn test_method_descriptor_display() {
+        for (descriptor, display) in [
+            ("()V", "() -> void"),
+            ("(IJ)V", "(int, long, ) -> void"),
+            (
+                "([Ljava/lang/String;)[[D",
+                "(java/lang/String[], ) -> double[][]",
+            ),
+        ] {
+            let (_, method_descriptor) = parse_method_descriptor(descriptor).unwrap();
+            assert_eq!(method_descriptor.to_string(), display);
+            assert_eq!(method_descriptor.to_descriptor(), descriptor);
+        }
+    }
+
+    #[test]
+    fn test_field_signature() {
+        // List<String>
+        let (_, signature) = parse_field_signature("Ljava/util/List<Ljava/lang/String;>;").unwrap();
+        assert_eq!(signature.to_string(), "java/util/List<java/lang/String>");
+        // Map.Entry<? extends K, ?>[]
+        let (_, signature) = parse_field_signature("[Ljava/util/Map$Entry<+TK;*>;").unwrap();
+        assert_eq!(
+            signature.to_string(),
+            "java/util/Map$Entry<? extends K, ?>[]"
+        );
+        // Outer<K>.Inner<? super V>
+        let (_, signature) = parse_field_signature("LOuter<TK;>.Inner<-TV;>;").unwrap();
+        assert_eq!(signature.to_string(), "Outer<K>$Inner<? super V>");
+
+        assert!(parse_field_signature("I").is_err());
+        assert!(parse_field_signature("Ljava/util/List<Ljava/lang/String;>;I").is_err());
+    }
+
+    #[test]
+    fn test_method_signature() {
+        // <T extends Throwable> void run(List<? extends T>, int[]) throws T, IOException
+        let (_, signature) = parse_method_signature(
+            "<T:Ljava/lang/Throwable;>(Ljava/util/List<+TT;>;[I)V^TT;^Ljava/io/IOException;",
+        )
+        .unwrap();
+        assert_eq!(signature.type_parameters.len(), 1);
+        assert_eq!(
+            signature
+                .parameters
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["java/util/List<? extends T>", "int[]"]
+        );
+        assert_eq!(signature.result, None);
+        assert_eq!(
+            signature.throws,
+            vec![
+                ReferenceTypeSignature::TypeVariable("T".to_string()),
+                ReferenceTypeSignature::Class(class_type("java/io/IOException", vec![])),
+            ]
+        );
+
+        // T get()
+        let (_, signature) = parse_method_signature("()TT;").unwrap();
+        assert!(signature.parameters.is_empty());
+        assert_eq!(
+            signature.result,
+            Some(JavaTypeSignature::Reference(
+                ReferenceTypeSignature::TypeVariable("T".to_string())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_erased_signature() {
+        let (_, FieldDescriptor(field_type)) = parse_field_descriptor("[Ljava/util/List;").unwrap();
+        assert_eq!(
+            JavaTypeSignature::from(&field_type).to_string(),
+            "java/util/List[]"
+        );
+        assert_eq!(
+            JavaTypeSignature::from(&FieldType::Long).to_string(),
+            "long"
+        );
+    }
+}
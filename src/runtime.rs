@@ -5,8 +5,8 @@ mod inheritance;
 mod interpreter;
 mod native;
 mod structs;
+mod vm;
 
-use crate::runtime::global::BOOTSTRAP_CLASS_LOADER;
 pub use class_loader::*;
 pub use interpreter::*;
 use std::{
@@ -14,8 +14,10 @@ use std::{
     sync::RwLock,
 };
 pub(crate) use structs::*;
+pub use structs::{Class, Exception, NativeResult};
+pub use vm::*;
 
-use crate::runtime::{famous_classes::init_famous_classes, heap::Heap};
+use crate::runtime::heap::Heap;
 pub use native::*;
 
 struct VmEnv<'a> {
@@ -33,19 +35,17 @@ impl<'a> VmEnv<'a> {
     }
 }
 
-fn init_bootstrap_class_loader(modules: Vec<Box<dyn ModuleLoader + Send + Sync + 'static>>) {
-    let mut bootstrap_class_loader = BootstrapClassLoader::new();
-    for module in modules {
-        bootstrap_class_loader.add_module(module);
-    }
-    BOOTSTRAP_CLASS_LOADER.set(bootstrap_class_loader).unwrap()
+/// Waits until the threads started from java that are not daemons have finished, as the vm does
+/// before exiting once `main` returns.
+pub fn wait_for_non_daemon_threads() {
+    let (count, finished) = &interpreter::global::NON_DAEMON_THREADS;
+    let count = count.lock().unwrap();
+    drop(finished.wait_while(count, |count| *count > 0).unwrap());
 }
 
 pub fn genesis(java_home: impl AsRef<Path>, class_path: impl Into<PathBuf>) {
-    init_bootstrap_class_loader(vec![
-        Box::new(JModModule::new(java_home, "java.base")),
-        Box::new(ClassPathModule::new("main", class_path)),
-    ]);
-
-    init_famous_classes();
+    VmBuilder::new()
+        .java_home(java_home.as_ref())
+        .class_path(class_path)
+        .build();
 }
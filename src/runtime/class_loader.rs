@@ -1,6 +1,8 @@
 use crate::{
     class::{self, JavaStr},
-    consts::{ClassAccessFlag, FieldAccessFlag, MethodAccessFlag},
+    consts::{
+        ClassAccessFlag, FieldAccessFlag, InnerClassAccessFlag, MethodAccessFlag, RequiresFlag,
+    },
     descriptor::{
         self, FieldDescriptor, FieldType, MethodDescriptor, parse_field_descriptor,
         parse_method_descriptor, parse_return_type_descriptor,
@@ -8,10 +10,17 @@ use crate::{
     runtime::{
         self, Annotation, Const, CpClassInfo, CpNameAndTypeInfo, ElementValue, ElementValuePair,
         FieldInfo, FieldResolve, Fieldref, LocalVariable, MethodInfo, MethodResolve, Methodref,
-        Module, ModuleExport, NativeResult, Variable, VmEnv,
-        famous_classes::{CLASS_CLASS, STRING_CLASS},
-        global::{CLASS_TABLE, HEAP, STRING_TABLE},
-        structs::ClinitStatus,
+        Module, ModuleExport, ModuleOpen, ModuleProvide, ModuleRequire, NativeResult,
+        StackMapFrame, Variable, VerificationTypeInfo, VmEnv, VtableIndex,
+        famous_classes::{
+            ABSTRACT_METHOD_ERROR_CLASS, CLASS_CLASS, CLASS_FORMAT_ERROR_CLASS,
+            ILLEGAL_ACCESS_ERROR_CLASS, INCOMPATIBLE_CLASS_CHANGE_ERROR_CLASS,
+            NEGATIVE_ARRAY_SIZE_EXCEPTION_CLASS, STRING_CLASS,
+        },
+        global::{BOOTSTRAP_CLASS_LOADER, CLASS_TABLE, HEAP, STRING_TABLE, VERBOSE},
+        heap::Heap,
+        inheritance::{is_class_implements, is_same_or_sub_class_of},
+        structs::{ClinitStatus, put_array_index},
     },
 };
 use nom::{
@@ -21,22 +30,28 @@ use nom::{
     multi::count,
     number::complete::{be_u16, be_u32, u8},
 };
+use once_cell::sync::OnceCell;
 use parking_lot::ReentrantMutex;
 use std::{
     cell::Cell,
     collections::HashMap,
     convert::identity,
-    sync::{Arc, RwLock},
+    iter, ptr,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU32, Ordering},
+    },
 };
 
 mod bootstrap;
+mod verifier;
 
 use crate::runtime::{BootstrapMethod, MethodHandle, ReferenceKind};
 pub(super) use bootstrap::BootstrapClassLoader;
 pub use bootstrap::{ClassPathModule, JModModule, ModuleLoader};
 
-pub fn parse_class(class_file: &class::Class) -> runtime::Class {
-    let constant_pool = parse_constant_pool(&class_file.constant_pool);
+pub fn parse_class(class_file: &class::Class) -> NativeResult<runtime::Class> {
+    let constant_pool = parse_constant_pool(&class_file.constant_pool)?;
 
     let (mut static_fields, instance_fields): (Vec<_>, Vec<_>) = class_file
         .fields
@@ -58,7 +73,7 @@ pub fn parse_class(class_file: &class::Class) -> runtime::Class {
 
     let static_fields_var = allocate_static_fields(&mut static_fields);
 
-    runtime::Class {
+    Ok(runtime::Class {
         access_flags: class_file.access_flags,
         class_name: Arc::clone(&class_name),
         super_class: None,
@@ -72,7 +87,8 @@ pub fn parse_class(class_file: &class::Class) -> runtime::Class {
         static_fields: static_fields_var,
         clinit_call: ReentrantMutex::new(Cell::new(ClinitStatus::NotInit)),
         vtable: vec![],
-    }
+        has_finalizer: false,
+    })
 }
 
 pub fn gen_array_class(class_name: Arc<str>) -> runtime::Class {
@@ -91,6 +107,7 @@ pub fn gen_array_class(class_name: Arc<str>) -> runtime::Class {
         // array has no clinit
         clinit_call: ReentrantMutex::new(Cell::new(ClinitStatus::Init)),
         vtable: vec![],
+        has_finalizer: false,
     }
 }
 
@@ -110,10 +127,15 @@ pub fn gen_primitive_class(class_name: Arc<str>) -> runtime::Class {
         // primitive class has no clinit
         clinit_call: ReentrantMutex::new(Cell::new(ClinitStatus::Init)),
         vtable: vec![],
+        has_finalizer: false,
     }
 }
 
-fn parse_constant_pool(cp: &Vec<class::ConstantPoolInfo>) -> Vec<runtime::ConstantPoolInfo> {
+/// Entries may reference the ones after them, so they are all looked up in the class file
+/// constant pool `cp`
+fn parse_constant_pool(
+    cp: &[class::ConstantPoolInfo],
+) -> NativeResult<Vec<runtime::ConstantPoolInfo>> {
     let mut constant_pool = Vec::with_capacity(cp.len());
     let mut class_info_map = HashMap::new();
     for (i, cp_info) in cp.iter().enumerate() {
@@ -121,12 +143,19 @@ fn parse_constant_pool(cp: &Vec<class::ConstantPoolInfo>) -> Vec<runtime::Consta
             class_info_map.insert(
                 i as u16 + 1,
                 CpClassInfo {
-                    name: resolve_cp_utf8(cp, *name_index).to_str_arc(),
+                    name: resolve_cp_utf8(cp, *name_index)?.to_str_arc(),
                     class: Default::default(),
                 },
             );
         }
     }
+    let class_name = |class_index: u16| {
+        cp_entry(cp, class_index)?;
+        class_info_map
+            .get(&class_index)
+            .map(|class_info| Arc::clone(&class_info.name))
+            .ok_or_else(|| invalid_cp_entry(class_index, "Class"))
+    };
 
     for cp_info in cp {
         type Cpi = runtime::ConstantPoolInfo;
@@ -137,53 +166,57 @@ fn parse_constant_pool(cp: &Vec<class::ConstantPoolInfo>) -> Vec<runtime::Consta
             class::ConstantPoolInfo::Long(v) => Cpi::Long(*v),
             class::ConstantPoolInfo::Double(v) => Cpi::Double(*v),
             class::ConstantPoolInfo::Class { name_index } => Cpi::Class(CpClassInfo {
-                name: resolve_cp_utf8(cp, *name_index).to_str_arc(),
+                name: resolve_cp_utf8(cp, *name_index)?.to_str_arc(),
                 class: Default::default(),
             }),
             class::ConstantPoolInfo::String { string_index } => {
-                Cpi::String(resolve_cp_utf8(cp, *string_index))
+                Cpi::String(resolve_cp_utf8(cp, *string_index)?)
             }
             class::ConstantPoolInfo::Fieldref {
                 class_index,
                 name_and_type_index,
             } => Cpi::Fieldref(Fieldref {
-                class_name: Arc::clone(&class_info_map[class_index].name),
-                name_and_type: resolve_cp_name_and_type_field(cp, *name_and_type_index),
+                class_name: class_name(*class_index)?,
+                name_and_type: resolve_cp_name_and_type_field(cp, *name_and_type_index)?,
                 resolve: Default::default(),
             }),
             class::ConstantPoolInfo::Methodref {
                 class_index,
                 name_and_type_index,
             } => Cpi::Methodref(Methodref {
-                class_name: Arc::clone(&class_info_map[class_index].name),
-                name_and_type: resolve_cp_name_and_type_method(cp, *name_and_type_index),
+                class_name: class_name(*class_index)?,
+                name_and_type: resolve_cp_name_and_type_method(cp, *name_and_type_index)?,
                 resolve: Default::default(),
             }),
             class::ConstantPoolInfo::InterfaceMethodref {
                 class_index,
                 name_and_type_index,
             } => Cpi::InterfaceMethodref(Methodref {
-                class_name: Arc::clone(&class_info_map[class_index].name),
-                name_and_type: resolve_cp_name_and_type_method(cp, *name_and_type_index),
+                class_name: class_name(*class_index)?,
+                name_and_type: resolve_cp_name_and_type_method(cp, *name_and_type_index)?,
                 resolve: Default::default(),
             }),
             class::ConstantPoolInfo::NameAndType {
                 name_index,
                 descriptor_index,
-            } => Cpi::NameAndType(resolve_cp_name_and_type(cp, *name_index, *descriptor_index)),
+            } => Cpi::NameAndType(resolve_cp_name_and_type(
+                cp,
+                *name_index,
+                *descriptor_index,
+            )?),
             class::ConstantPoolInfo::Dynamic {
                 bootstrap_method_attr_index,
                 name_and_type_index,
             } => Cpi::Dynamic {
                 bootstrap_method_attr_index: *bootstrap_method_attr_index,
-                name_and_type: resolve_cp_name_and_type_field(cp, *name_and_type_index),
+                name_and_type: resolve_cp_name_and_type_field(cp, *name_and_type_index)?,
             },
             class::ConstantPoolInfo::InvokeDynamic {
                 bootstrap_method_attr_index,
                 name_and_type_index,
             } => Cpi::InvokeDynamic {
                 bootstrap_method_attr_index: *bootstrap_method_attr_index,
-                name_and_type: resolve_cp_name_and_type_method(cp, *name_and_type_index),
+                name_and_type: resolve_cp_name_and_type_method(cp, *name_and_type_index)?,
             },
             class::ConstantPoolInfo::MethodHandle {
                 reference_kind,
@@ -203,21 +236,22 @@ fn parse_constant_pool(cp: &Vec<class::ConstantPoolInfo>) -> Vec<runtime::Consta
                 },
                 reference_index: *reference_index,
             }),
-            // TODO: fill
-            class::ConstantPoolInfo::MethodType { .. } => Cpi::MethodType,
+            class::ConstantPoolInfo::MethodType { descriptor_index } => {
+                Cpi::MethodType(resolve_cp_method_descriptor(cp, *descriptor_index)?)
+            }
 
             class::ConstantPoolInfo::Module { name_index } => {
-                Cpi::Module(resolve_cp_utf8(cp, *name_index))
+                Cpi::Module(resolve_cp_utf8(cp, *name_index)?)
             }
             class::ConstantPoolInfo::Package { name_index } => {
-                Cpi::Package(resolve_cp_utf8(cp, *name_index))
+                Cpi::Package(resolve_cp_utf8(cp, *name_index)?)
             }
             class::ConstantPoolInfo::Empty => Cpi::Empty,
         };
         constant_pool.push(constant_pool_info);
     }
 
-    constant_pool
+    Ok(constant_pool)
 }
 
 fn parse_field(cp: &[runtime::ConstantPoolInfo], field: &class::FieldInfo) -> runtime::FieldInfo {
@@ -261,11 +295,37 @@ fn convert_attribute(
     }
 }
 
-fn resolve_cp_utf8(constant_pool: &[class::ConstantPoolInfo], index: u16) -> Arc<JavaStr> {
-    let class::ConstantPoolInfo::Utf8(string) = &constant_pool[index as usize - 1] else {
-        panic!("cannot find string {index}");
+/// The entry at `index` of the class file constant pool, checking the index is in range
+fn cp_entry(
+    constant_pool: &[class::ConstantPoolInfo],
+    index: u16,
+) -> NativeResult<&class::ConstantPoolInfo> {
+    (index as usize)
+        .checked_sub(1)
+        .and_then(|i| constant_pool.get(i))
+        .ok_or_else(|| {
+            runtime::Exception::new_vm_msg(
+                CLASS_FORMAT_ERROR_CLASS.get().expect("must have init"),
+                &format!("Invalid constant pool index {index}"),
+            )
+        })
+}
+
+fn invalid_cp_entry(index: u16, expected: &str) -> runtime::Exception {
+    runtime::Exception::new_vm_msg(
+        CLASS_FORMAT_ERROR_CLASS.get().expect("must have init"),
+        &format!("Invalid constant pool entry {index}, expected {expected}"),
+    )
+}
+
+fn resolve_cp_utf8(
+    constant_pool: &[class::ConstantPoolInfo],
+    index: u16,
+) -> NativeResult<Arc<JavaStr>> {
+    let class::ConstantPoolInfo::Utf8(string) = cp_entry(constant_pool, index)? else {
+        return Err(invalid_cp_entry(index, "Utf8"));
     };
-    Arc::clone(string)
+    Ok(Arc::clone(string))
 }
 
 fn resolve_runtime_cp_utf8(
@@ -303,61 +363,69 @@ fn resolve_cp_class(constant_pool: &[runtime::ConstantPoolInfo], class_index: u1
 fn resolve_cp_name_and_type_field(
     constant_pool: &[class::ConstantPoolInfo],
     index: u16,
-) -> CpNameAndTypeInfo<FieldDescriptor> {
+) -> NativeResult<CpNameAndTypeInfo<FieldDescriptor>> {
     let class::ConstantPoolInfo::NameAndType {
         name_index,
         descriptor_index,
-    } = &constant_pool[index as usize - 1]
+    } = cp_entry(constant_pool, index)?
     else {
-        panic!("cannot find name_and_type {index}");
+        return Err(invalid_cp_entry(index, "NameAndType"));
     };
 
-    let name = resolve_cp_utf8(constant_pool, *name_index);
-    let descriptor = resolve_cp_utf8(constant_pool, *descriptor_index);
+    let name = resolve_cp_utf8(constant_pool, *name_index)?;
+    let descriptor = resolve_cp_utf8(constant_pool, *descriptor_index)?;
 
     // TODO: unwrap
     let (_, descriptor) =
         descriptor::parse_field_descriptor(&descriptor.to_str()).expect("invalid descriptor");
 
-    CpNameAndTypeInfo::<FieldDescriptor> {
+    Ok(CpNameAndTypeInfo::<FieldDescriptor> {
         name: Arc::clone(&name),
         descriptor,
-    }
+    })
+}
+
+fn resolve_cp_method_descriptor(
+    constant_pool: &[class::ConstantPoolInfo],
+    index: u16,
+) -> NativeResult<MethodDescriptor> {
+    let descriptor = resolve_cp_utf8(constant_pool, index)?;
+
+    // TODO: unwrap
+    let (_, descriptor) =
+        descriptor::parse_method_descriptor(&descriptor.to_str()).expect("invalid descriptor");
+    Ok(descriptor)
 }
 
 fn resolve_cp_name_and_type_method(
     constant_pool: &[class::ConstantPoolInfo],
     index: u16,
-) -> CpNameAndTypeInfo<MethodDescriptor> {
+) -> NativeResult<CpNameAndTypeInfo<MethodDescriptor>> {
     let class::ConstantPoolInfo::NameAndType {
         name_index,
         descriptor_index,
-    } = &constant_pool[index as usize - 1]
+    } = cp_entry(constant_pool, index)?
     else {
-        panic!("cannot find name_and_type {index}");
+        return Err(invalid_cp_entry(index, "NameAndType"));
     };
 
-    let name = resolve_cp_utf8(constant_pool, *name_index);
-    let descriptor = resolve_cp_utf8(constant_pool, *descriptor_index);
+    let name = resolve_cp_utf8(constant_pool, *name_index)?;
+    let descriptor = resolve_cp_method_descriptor(constant_pool, *descriptor_index)?;
 
-    // TODO: unwrap
-    let (_, descriptor) =
-        descriptor::parse_method_descriptor(&descriptor.to_str()).expect("invalid descriptor");
-
-    CpNameAndTypeInfo::<MethodDescriptor> {
+    Ok(CpNameAndTypeInfo::<MethodDescriptor> {
         name: Arc::clone(&name),
         descriptor,
-    }
+    })
 }
 
 fn resolve_cp_name_and_type(
     constant_pool: &[class::ConstantPoolInfo],
     name_index: u16,
     descriptor_index: u16,
-) -> CpNameAndTypeInfo<Arc<JavaStr>> {
-    let name = resolve_cp_utf8(constant_pool, name_index);
-    let descriptor = resolve_cp_utf8(constant_pool, descriptor_index);
-    CpNameAndTypeInfo::<Arc<JavaStr>> { name, descriptor }
+) -> NativeResult<CpNameAndTypeInfo<Arc<JavaStr>>> {
+    let name = resolve_cp_utf8(constant_pool, name_index)?;
+    let descriptor = resolve_cp_utf8(constant_pool, descriptor_index)?;
+    Ok(CpNameAndTypeInfo::<Arc<JavaStr>> { name, descriptor })
 }
 
 fn resolve_constant_value(constant_pool: &[runtime::ConstantPoolInfo], index: u16) -> Const {
@@ -434,10 +502,26 @@ fn parse_attribute<'a>(
             ))
         }
         "Deprecated" => runtime::AttributeInfo::Deprecated,
-        // TODO: only used for verification
-        "StackMapTable" => runtime::AttributeInfo::StackMapTable(vec![]),
-        // TODO: checked exception only
-        "Exceptions" => runtime::AttributeInfo::Exceptions,
+        "StackMapTable" => {
+            let (number_of_entries, entries);
+            (input, number_of_entries) = be_u16(input)?;
+            (input, entries) =
+                count(parse_stack_map_frame(constant_pool), number_of_entries as _).parse(input)?;
+            runtime::AttributeInfo::StackMapTable(entries)
+        }
+        "Exceptions" => {
+            let (number_of_exceptions, exception_index_table);
+            (input, number_of_exceptions) = be_u16(input)?;
+            (input, exception_index_table) =
+                count(be_u16, number_of_exceptions as _).parse(input)?;
+
+            let exceptions = exception_index_table
+                .iter()
+                .map(|index| resolve_cp_class(constant_pool, *index).clone())
+                .collect();
+
+            runtime::AttributeInfo::Exceptions(exceptions)
+        }
         "SourceFile" => {
             let sourcefile_index;
             (input, sourcefile_index) = be_u16(input)?;
@@ -505,7 +589,16 @@ fn parse_attribute<'a>(
                     let (input, requires_index) = be_u16(input)?;
                     let (input, requires_flags) = be_u16(input)?;
                     let (input, requires_version_index) = be_u16(input)?;
-                    Ok((input, ()))
+                    Ok((
+                        input,
+                        ModuleRequire {
+                            requires: resolve_cp_module(constant_pool, requires_index),
+                            requires_flags: RequiresFlag::from_bits_retain(requires_flags),
+                            requires_version: (requires_version_index != 0).then(|| {
+                                resolve_runtime_cp_utf8(constant_pool, requires_version_index)
+                            }),
+                        },
+                    ))
                 },
                 requires_count as _,
             )
@@ -546,7 +639,17 @@ fn parse_attribute<'a>(
                     let (input, opens_to_count) = be_u16(input)?;
                     let (input, opens_to_index) =
                         count(be_u16, opens_to_count as _).parse(input)?;
-                    Ok((input, ()))
+                    Ok((
+                        input,
+                        ModuleOpen {
+                            opens: resolve_cp_package(constant_pool, opens_index),
+                            opens_flags,
+                            opens_to: opens_to_index
+                                .iter()
+                                .map(|index| resolve_cp_module(constant_pool, *index))
+                                .collect(),
+                        },
+                    ))
                 },
                 opens_count as _,
             )
@@ -564,14 +667,37 @@ fn parse_attribute<'a>(
                     let (input, provides_with_count) = be_u16(input)?;
                     let (input, provides_with_index) =
                         count(be_u16, provides_with_count as _).parse(input)?;
-                    Ok((input, ()))
+                    Ok((
+                        input,
+                        ModuleProvide {
+                            provides: Arc::clone(
+                                &resolve_cp_class(constant_pool, provides_index).name,
+                            ),
+                            provides_with: provides_with_index
+                                .iter()
+                                .map(|index| {
+                                    Arc::clone(&resolve_cp_class(constant_pool, *index).name)
+                                })
+                                .collect(),
+                        },
+                    ))
                 },
                 provides_count as _,
             )
             .parse(input)?;
             runtime::AttributeInfo::Module(Module {
-                // TODO:
+                name: resolve_cp_module(constant_pool, module_name_index),
+                flags: module_flags,
+                version: (module_version_index != 0)
+                    .then(|| resolve_runtime_cp_utf8(constant_pool, module_version_index)),
+                requires,
                 exports,
+                opens,
+                uses: uses_index
+                    .iter()
+                    .map(|index| Arc::clone(&resolve_cp_class(constant_pool, *index).name))
+                    .collect(),
+                provides,
             })
         }
         "ModulePackages" => {
@@ -615,8 +741,71 @@ fn parse_attribute<'a>(
                 target_platform_index,
             ))
         }
-        // TODO:
-        "InnerClasses" => runtime::AttributeInfo::InnerClasses,
+        "NestHost" => {
+            let host_class_index;
+            (input, host_class_index) = be_u16(input)?;
+            runtime::AttributeInfo::NestHost(
+                resolve_cp_class(constant_pool, host_class_index).clone(),
+            )
+        }
+        "NestMembers" => {
+            let (number_of_classes, classes);
+            (input, number_of_classes) = be_u16(input)?;
+            (input, classes) = count(be_u16, number_of_classes as _).parse(input)?;
+
+            let classes = classes
+                .iter()
+                .map(|index| resolve_cp_class(constant_pool, *index).clone())
+                .collect();
+
+            runtime::AttributeInfo::NestMembers(classes)
+        }
+        "PermittedSubclasses" => {
+            let (number_of_classes, classes);
+            (input, number_of_classes) = be_u16(input)?;
+            (input, classes) = count(be_u16, number_of_classes as _).parse(input)?;
+
+            let classes = classes
+                .iter()
+                .map(|index| resolve_cp_class(constant_pool, *index).clone())
+                .collect();
+
+            runtime::AttributeInfo::PermittedSubclasses(classes)
+        }
+        "InnerClasses" => {
+            let (number_of_classes, classes);
+            (input, number_of_classes) = be_u16(input)?;
+            (input, classes) =
+                count(parse_inner_class(constant_pool), number_of_classes as _).parse(input)?;
+            runtime::AttributeInfo::InnerClasses(classes)
+        }
+        "EnclosingMethod" => {
+            let (class_index, method_index);
+            (input, class_index) = be_u16(input)?;
+            (input, method_index) = be_u16(input)?;
+
+            // 0 if the class is not immediately enclosed by a method or constructor
+            let method = (method_index != 0).then(|| {
+                let runtime::ConstantPoolInfo::NameAndType(name_and_type) =
+                    &constant_pool[method_index as usize - 1]
+                else {
+                    panic!("cannot find name_and_type {method_index}");
+                };
+                name_and_type.clone()
+            });
+
+            runtime::AttributeInfo::EnclosingMethod {
+                class: resolve_cp_class(constant_pool, class_index).clone(),
+                method,
+            }
+        }
+        "Record" => {
+            let (components_count, components);
+            (input, components_count) = be_u16(input)?;
+            (input, components) =
+                count(parse_record_component(constant_pool), components_count as _).parse(input)?;
+            runtime::AttributeInfo::Record(components)
+        }
         _ => {
             // TODO:
             // eprintln!("Unknown attribute {:?}", attribute_name);
@@ -686,6 +875,7 @@ fn parse_code_attribute<'a>(
             code: code.into(),
             exception_table,
             attributes,
+            instructions: OnceCell::new(),
         }),
     ))
 }
@@ -717,6 +907,62 @@ fn parse_exception_table(
     }
 }
 
+fn parse_inner_class(
+    constant_pool: &[runtime::ConstantPoolInfo],
+) -> impl FnMut(&[u8]) -> IResult<&[u8], runtime::InnerClassInfo> + '_ {
+    move |input| {
+        let (input, inner_class_info_index) = be_u16(input)?;
+        let (input, outer_class_info_index) = be_u16(input)?;
+        let (input, inner_name_index) = be_u16(input)?;
+        let (input, inner_class_access_flags) = be_u16(input)?;
+
+        let outer_class = if outer_class_info_index == 0 {
+            None
+        } else {
+            Some(resolve_cp_class(constant_pool, outer_class_info_index))
+        };
+        let inner_name = if inner_name_index == 0 {
+            None
+        } else {
+            Some(resolve_runtime_cp_utf8(constant_pool, inner_name_index))
+        };
+
+        Ok((
+            input,
+            runtime::InnerClassInfo {
+                inner_class: resolve_cp_class(constant_pool, inner_class_info_index).clone(),
+                outer_class: outer_class.cloned(),
+                inner_name,
+                inner_class_access_flags: InnerClassAccessFlag::from_bits_retain(
+                    inner_class_access_flags,
+                ),
+            },
+        ))
+    }
+}
+
+fn parse_record_component(
+    constant_pool: &[runtime::ConstantPoolInfo],
+) -> impl FnMut(&[u8]) -> IResult<&[u8], runtime::RecordComponentInfo> + '_ {
+    move |input| {
+        let (input, name_index) = be_u16(input)?;
+        let (input, descriptor_index) = be_u16(input)?;
+        let descriptor = resolve_runtime_cp_utf8(constant_pool, descriptor_index);
+        // TODO: unwrap
+        let (_, descriptor) = parse_field_descriptor(&descriptor.to_str()).unwrap();
+        let (input, attributes) = parse_attributes(input, constant_pool)?;
+
+        Ok((
+            input,
+            runtime::RecordComponentInfo {
+                name: resolve_runtime_cp_utf8(constant_pool, name_index),
+                descriptor,
+                attributes,
+            },
+        ))
+    }
+}
+
 fn parse_annotation(
     constant_pool: &[runtime::ConstantPoolInfo],
 ) -> impl FnMut(&[u8]) -> IResult<&[u8], Annotation> + '_ {
@@ -833,6 +1079,133 @@ fn parse_constant_value<'a>(
     Ok((input, converter(value)))
 }
 
+fn parse_stack_map_frame(
+    constant_pool: &[runtime::ConstantPoolInfo],
+) -> impl FnMut(&[u8]) -> IResult<&[u8], StackMapFrame> + '_ {
+    move |input| {
+        let (input, frame_type) = u8(input)?;
+        match frame_type {
+            0..=63 => Ok((
+                input,
+                StackMapFrame::Same {
+                    offset_delta: frame_type as _,
+                },
+            )),
+            64..=127 => {
+                let (input, stack) = parse_verification_type_info(constant_pool)(input)?;
+                Ok((
+                    input,
+                    StackMapFrame::SameLocals1StackItem {
+                        offset_delta: frame_type as u16 - 64,
+                        stack,
+                    },
+                ))
+            }
+            247 => {
+                let (input, offset_delta) = be_u16(input)?;
+                let (input, stack) = parse_verification_type_info(constant_pool)(input)?;
+                Ok((
+                    input,
+                    StackMapFrame::SameLocals1StackItem {
+                        offset_delta,
+                        stack,
+                    },
+                ))
+            }
+            248..=250 => {
+                let (input, offset_delta) = be_u16(input)?;
+                Ok((
+                    input,
+                    StackMapFrame::Chop {
+                        offset_delta,
+                        k: 251 - frame_type,
+                    },
+                ))
+            }
+            251 => {
+                let (input, offset_delta) = be_u16(input)?;
+                Ok((input, StackMapFrame::Same { offset_delta }))
+            }
+            252..=254 => {
+                let (input, offset_delta) = be_u16(input)?;
+                let (input, locals) = count(
+                    parse_verification_type_info(constant_pool),
+                    frame_type as usize - 251,
+                )
+                .parse(input)?;
+                Ok((
+                    input,
+                    StackMapFrame::Append {
+                        offset_delta,
+                        locals,
+                    },
+                ))
+            }
+            255 => {
+                let (input, offset_delta) = be_u16(input)?;
+                let (input, number_of_locals) = be_u16(input)?;
+                let (input, locals) = count(
+                    parse_verification_type_info(constant_pool),
+                    number_of_locals as _,
+                )
+                .parse(input)?;
+                let (input, number_of_stack_items) = be_u16(input)?;
+                let (input, stack) = count(
+                    parse_verification_type_info(constant_pool),
+                    number_of_stack_items as _,
+                )
+                .parse(input)?;
+                Ok((
+                    input,
+                    StackMapFrame::Full {
+                        offset_delta,
+                        locals,
+                        stack,
+                    },
+                ))
+            }
+            // 128-246 are reserved
+            _ => Err(nom::Err::Error(error_position!(
+                input,
+                nom::error::ErrorKind::Tag
+            ))),
+        }
+    }
+}
+
+fn parse_verification_type_info(
+    constant_pool: &[runtime::ConstantPoolInfo],
+) -> impl FnMut(&[u8]) -> IResult<&[u8], VerificationTypeInfo> + '_ {
+    move |input| {
+        let (input, tag) = u8(input)?;
+        let info = match tag {
+            0 => VerificationTypeInfo::Top,
+            1 => VerificationTypeInfo::Integer,
+            2 => VerificationTypeInfo::Float,
+            3 => VerificationTypeInfo::Double,
+            4 => VerificationTypeInfo::Long,
+            5 => VerificationTypeInfo::Null,
+            6 => VerificationTypeInfo::UninitializedThis,
+            7 => {
+                let (input, cpool_index) = be_u16(input)?;
+                let class = resolve_cp_class(constant_pool, cpool_index);
+                return Ok((input, VerificationTypeInfo::Object(Arc::clone(&class.name))));
+            }
+            8 => {
+                let (input, offset) = be_u16(input)?;
+                return Ok((input, VerificationTypeInfo::Uninitialized(offset)));
+            }
+            _ => {
+                return Err(nom::Err::Error(error_position!(
+                    input,
+                    nom::error::ErrorKind::Tag
+                )));
+            }
+        };
+        Ok((input, info))
+    }
+}
+
 fn parse_local_variable(
     constant_pool: &[runtime::ConstantPoolInfo],
 ) -> impl FnMut(&[u8]) -> IResult<&[u8], LocalVariable> + '_ {
@@ -859,30 +1232,14 @@ fn parse_local_variable(
     }
 }
 
-fn allocate_static_fields(static_fields_info: &mut [FieldInfo]) -> Vec<RwLock<Variable>> {
+fn allocate_static_fields(static_fields_info: &mut [FieldInfo]) -> Vec<AtomicU32> {
     let mut static_fields = Vec::with_capacity(static_fields_info.len());
     for field in static_fields_info {
         field.index = static_fields.len() as _;
-        match field.descriptor.0 {
-            FieldType::Byte
-            | FieldType::Char
-            | FieldType::Short
-            | FieldType::Int
-            | FieldType::Boolean => static_fields.push(RwLock::new(Variable { int: 0 })),
-            FieldType::Double => {
-                let (a, b) = Variable::put_double(0.0);
-                static_fields.push(RwLock::new(a));
-                static_fields.push(RwLock::new(b));
-            }
-            FieldType::Float => static_fields.push(RwLock::new(Variable { float: 0.0 })),
-            FieldType::Long => {
-                let (a, b) = Variable::put_long(0);
-                static_fields.push(RwLock::new(a));
-                static_fields.push(RwLock::new(b));
-            }
-            FieldType::Object(_) | FieldType::Array(_) => {
-                static_fields.push(RwLock::new(Variable { reference: 0 }))
-            }
+        // all zero bits are the default value of every type
+        static_fields.push(AtomicU32::new(0));
+        if field.descriptor.0.is_long() {
+            static_fields.push(AtomicU32::new(0));
         }
     }
     static_fields
@@ -899,10 +1256,12 @@ fn resolve_static_field(
             if !(field.name == name_and_type.name && field.descriptor == name_and_type.descriptor) {
                 continue;
             }
-            println!(
-                "loaded field from other class: {:?} from {}.{}",
-                field_ref.name_and_type.name, class.class_name, field.index
-            );
+            if VERBOSE.load(Ordering::Relaxed) {
+                println!(
+                    "loaded field from other class: {:?} from {}.{}",
+                    field_ref.name_and_type.name, class.class_name, field.index
+                );
+            }
             return Some(FieldResolve::OtherClass {
                 class: Arc::clone(class),
                 index: field.index,
@@ -1031,15 +1390,20 @@ fn resolve_from_vtable(class: &Arc<runtime::Class>, method: &MethodInfo) -> isiz
     {
         -1
     } else {
-        // find in vtable
-        // TODO: package private dispatch
-        class
-            .vtable
-            .iter()
-            .enumerate()
-            .find(|(_, entry)| entry.name == method.name && entry.descriptor == method.descriptor)
-            .map(|(index, _)| index as isize)
-            .unwrap_or(-1)
+        // a package private method has a slot of its own besides the ones it overrides, which a
+        // method from another package may take over, see `build_vtable`
+        let own_slot = class.vtable.iter().rposition(|entry| {
+            matches!(entry.index, VtableIndex::InThisClass(index)
+                if ptr::eq(&class.methods[index], method))
+        });
+        // an interface method, implemented or inherited as miranda method by the class
+        own_slot
+            .or_else(|| {
+                class.vtable.iter().position(|entry| {
+                    entry.name == method.name && entry.descriptor == method.descriptor
+                })
+            })
+            .map_or(-1, |index| index as isize)
     }
 }
 
@@ -1060,9 +1424,18 @@ fn resolve_method_statically_inner(
             return Some(resolve);
         }
     }
-    // TODO: maximally-specific superinterface
 
-    None
+    let (interface, index) = resolve_superinterface_method(
+        class,
+        &method_ref.name_and_type.name,
+        &method_ref.name_and_type.descriptor,
+    )?;
+    let vtable_index = resolve_from_vtable(class, &interface.methods[index]);
+    Some(MethodResolve::OtherClass {
+        class: interface,
+        index,
+        vtable_index,
+    })
 }
 
 pub(in crate::runtime) fn resolve_method_statically(
@@ -1072,6 +1445,422 @@ pub(in crate::runtime) fn resolve_method_statically(
     resolve_method_statically_inner(class, method_ref, false)
 }
 
+/// Selects the method `invokespecial` in `current_class` invokes for `method_ref`, resolved to
+/// method `index` of `class` (JVMS 6.5 invokespecial). With `ACC_SUPER`, a method of a
+/// superclass is looked up again from the direct superclass, so it is not taken from above an
+/// override compiled in between.
+pub(in crate::runtime) fn select_special_method(
+    current_class: &Arc<runtime::Class>,
+    method_ref: &Methodref,
+    class: &Arc<runtime::Class>,
+    index: usize,
+) -> (Arc<runtime::Class>, usize) {
+    let Some(direct_super_class) = &current_class.super_class else {
+        return (Arc::clone(class), index);
+    };
+    let names_super_class =
+        iter::successors(Some(direct_super_class), |class| class.super_class.as_ref())
+            .any(|super_class| super_class.class_name == method_ref.class_name);
+    if !current_class.access_flags.contains(ClassAccessFlag::SUPER)
+        || !names_super_class
+        || method_ref.name_and_type.name.to_str() == "<init>"
+    {
+        return (Arc::clone(class), index);
+    }
+
+    // a default method, when no superclass declares one
+    let Some(selected) = resolve_method_in_class_only(direct_super_class, method_ref) else {
+        return (Arc::clone(class), index);
+    };
+    let (class, index, _) = selected.get_class_and_index(direct_super_class);
+    (Arc::clone(class), index)
+}
+
+/// The nest host of `class` (JVMS 5.4.4), a class whose `NestHost` cannot be validated is the
+/// host of its own nest
+fn nest_host(class: &Arc<runtime::Class>) -> Arc<runtime::Class> {
+    let host = class.attributes.iter().find_map(|attr| {
+        if let runtime::AttributeInfo::NestHost(host) = attr {
+            Some(host)
+        } else {
+            None
+        }
+    });
+    let Some(host) = host else {
+        return Arc::clone(class);
+    };
+    let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+    let Ok(host) = host.get_or_load_class(|| bootstrap_class_loader.resolve_class(&host.name))
+    else {
+        return Arc::clone(class);
+    };
+
+    let is_member = host.attributes.iter().any(|attr| {
+        matches!(attr, runtime::AttributeInfo::NestMembers(members)
+            if members.iter().any(|member| member.name == class.class_name))
+    });
+    if is_member && host.package_name() == class.package_name() {
+        host
+    } else {
+        Arc::clone(class)
+    }
+}
+
+/// Whether `a` and `b` are in the same nest, nestmates can access each other's private members
+pub(in crate::runtime) fn is_nestmate(a: &Arc<runtime::Class>, b: &Arc<runtime::Class>) -> bool {
+    a.class_name == b.class_name || nest_host(a).class_name == nest_host(b).class_name
+}
+
+/// The field at `index` of `class`, along with the class declaring it.
+///
+/// Instance fields inherited from super classes keep their indices in sub classes.
+fn declared_field(
+    class: &Arc<runtime::Class>,
+    index: usize,
+    is_static: bool,
+) -> Option<(&Arc<runtime::Class>, &FieldInfo)> {
+    if is_static {
+        let field = class.static_fields_info.iter().find(|f| f.index == index)?;
+        return Some((class, field));
+    }
+    if let Some(super_class) = &class.super_class
+        && super_class
+            .instance_fields_info
+            .iter()
+            .any(|f| f.index == index)
+    {
+        return declared_field(super_class, index, false);
+    }
+    let field = class
+        .instance_fields_info
+        .iter()
+        .find(|f| f.index == index)?;
+    Some((class, field))
+}
+
+/// Checks that the field resolved by `resolve` is accessible from `class`
+pub(in crate::runtime) fn check_field_access(
+    class: &Arc<runtime::Class>,
+    resolve: &FieldResolve,
+    is_static: bool,
+) -> NativeResult<()> {
+    let FieldResolve::OtherClass {
+        class: field_class,
+        index,
+    } = resolve
+    else {
+        return Ok(());
+    };
+    let Some((declaring_class, field)) = declared_field(field_class, *index, is_static) else {
+        return Ok(());
+    };
+    let access = MemberAccess::new(
+        field.access_flags.contains(FieldAccessFlag::PUBLIC),
+        field.access_flags.contains(FieldAccessFlag::PROTECTED),
+        field.access_flags.contains(FieldAccessFlag::PRIVATE),
+    );
+    if !is_member_accessible(class, declaring_class, access) {
+        return Err(illegal_access(
+            class,
+            access,
+            "field",
+            declaring_class,
+            &field.name,
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the method resolved by `resolve` is accessible from `class`
+pub(in crate::runtime) fn check_method_access(
+    class: &Arc<runtime::Class>,
+    resolve: &MethodResolve,
+) -> NativeResult<()> {
+    let MethodResolve::OtherClass {
+        class: declaring_class,
+        index,
+        ..
+    } = resolve
+    else {
+        return Ok(());
+    };
+    let method = &declaring_class.methods[*index];
+    let access = MemberAccess::new(
+        method.access_flags.contains(MethodAccessFlag::PUBLIC),
+        method.access_flags.contains(MethodAccessFlag::PROTECTED),
+        method.access_flags.contains(MethodAccessFlag::PRIVATE),
+    );
+    if !is_member_accessible(class, declaring_class, access) {
+        return Err(illegal_access(
+            class,
+            access,
+            "method",
+            declaring_class,
+            &method.name,
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MemberAccess {
+    Public,
+    Protected,
+    PackagePrivate,
+    Private,
+}
+
+impl MemberAccess {
+    fn new(is_public: bool, is_protected: bool, is_private: bool) -> Self {
+        if is_public {
+            MemberAccess::Public
+        } else if is_protected {
+            MemberAccess::Protected
+        } else if is_private {
+            MemberAccess::Private
+        } else {
+            MemberAccess::PackagePrivate
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            MemberAccess::Public => "public",
+            MemberAccess::Protected => "protected",
+            MemberAccess::PackagePrivate => "package-private",
+            MemberAccess::Private => "private",
+        }
+    }
+}
+
+/// Whether a member of `declaring_class` is accessible from `class`, see JVMS 5.4.4
+fn is_member_accessible(
+    class: &Arc<runtime::Class>,
+    declaring_class: &Arc<runtime::Class>,
+    access: MemberAccess,
+) -> bool {
+    // there is only the bootstrap class loader, so the run-time package is the package name
+    let same_package = || class.package_name() == declaring_class.package_name();
+    match access {
+        MemberAccess::Public => true,
+        MemberAccess::Protected => {
+            is_same_or_sub_class_of(class, declaring_class) || same_package()
+        }
+        MemberAccess::PackagePrivate => same_package(),
+        MemberAccess::Private => is_nestmate(class, declaring_class),
+    }
+}
+
+fn illegal_access(
+    class: &runtime::Class,
+    access: MemberAccess,
+    kind: &str,
+    declaring_class: &runtime::Class,
+    name: &JavaStr,
+) -> runtime::Exception {
+    runtime::Exception::new_vm_msg(
+        ILLEGAL_ACCESS_ERROR_CLASS.get().expect("must have init"),
+        &format!(
+            "class {} tried to access {} {kind} {}.{}",
+            class.class_name().replace('/', "."),
+            access.name(),
+            declaring_class.class_name().replace('/', "."),
+            name.to_str(),
+        ),
+    )
+}
+
+fn resolve_interface_method_inner(
+    class: &Arc<runtime::Class>,
+    method_ref: &Methodref,
+) -> Option<MethodResolve> {
+    for (index, method) in class.methods.iter().enumerate() {
+        if method_ref.is_signature_equal(method) {
+            return Some(MethodResolve::OtherClass {
+                class: Arc::clone(class),
+                index,
+                vtable_index: -1,
+            });
+        }
+    }
+    let (interface, index) = resolve_superinterface_method(
+        class,
+        &method_ref.name_and_type.name,
+        &method_ref.name_and_type.descriptor,
+    )?;
+    Some(MethodResolve::OtherClass {
+        class: interface,
+        index,
+        vtable_index: -1,
+    })
+}
+
+pub(in crate::runtime) fn resolve_interface_method(
+    class: &Arc<runtime::Class>,
+    method_ref: &Methodref,
+) -> Option<MethodResolve> {
+    if let Some(resolve) = resolve_interface_method_inner(class, method_ref) {
+        return Some(resolve);
+    }
+    // interface's super class is always Object
+    let super_class = class.super_class.as_ref()?;
+    resolve_method_in_class_only(super_class, method_ref)
+}
+
+/// The superinterfaces of `class`, which it and its super classes implement directly or through
+/// other interfaces, each once
+fn superinterfaces(class: &runtime::Class) -> Vec<Arc<runtime::Class>> {
+    fn collect(class: &runtime::Class, interfaces: &mut Vec<Arc<runtime::Class>>) {
+        for interface in &class.interfaces {
+            if !interfaces.iter().any(|i| Arc::ptr_eq(i, interface)) {
+                interfaces.push(Arc::clone(interface));
+                collect(interface, interfaces);
+            }
+        }
+        if let Some(super_class) = &class.super_class {
+            collect(super_class, interfaces);
+        }
+    }
+
+    let mut interfaces = vec![];
+    collect(class, &mut interfaces);
+    interfaces
+}
+
+/// The non-private, non-static methods of the superinterfaces of `class` with `name` and
+/// `descriptor`
+fn superinterface_methods(
+    class: &runtime::Class,
+    name: &JavaStr,
+    descriptor: &MethodDescriptor,
+) -> Vec<(Arc<runtime::Class>, usize)> {
+    superinterfaces(class)
+        .into_iter()
+        .filter_map(|interface| {
+            let index = interface.methods.iter().position(|m| {
+                !m.access_flags
+                    .intersects(MethodAccessFlag::PRIVATE | MethodAccessFlag::STATIC)
+                    && m.name.as_ref() == name
+                    && m.descriptor == *descriptor
+            })?;
+            Some((interface, index))
+        })
+        .collect()
+}
+
+/// The maximally-specific superinterface methods of `class` (JVMS 5.4.3.3), the ones whose
+/// interface is not a superinterface of another one with such a method
+fn maximally_specific_methods(
+    class: &runtime::Class,
+    name: &JavaStr,
+    descriptor: &MethodDescriptor,
+) -> Vec<(Arc<runtime::Class>, usize)> {
+    let methods = superinterface_methods(class, name, descriptor);
+    methods
+        .iter()
+        .filter(|(interface, _)| {
+            !methods.iter().any(|(other, _)| {
+                !Arc::ptr_eq(other, interface) && is_class_implements(other, interface)
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// Resolves a method of the superinterfaces of `class` (JVMS 5.4.3.3), the maximally-specific
+/// one that is not abstract if there is exactly one, otherwise any of them
+fn resolve_superinterface_method(
+    class: &runtime::Class,
+    name: &JavaStr,
+    descriptor: &MethodDescriptor,
+) -> Option<(Arc<runtime::Class>, usize)> {
+    let mut defaults: Vec<_> = maximally_specific_methods(class, name, descriptor)
+        .into_iter()
+        .filter(|(interface, index)| !is_abstract(&interface.methods[*index]))
+        .collect();
+    if defaults.len() == 1 {
+        return defaults.pop();
+    }
+    superinterface_methods(class, name, descriptor)
+        .into_iter()
+        .next()
+}
+
+fn is_abstract(method: &MethodInfo) -> bool {
+    method.access_flags.contains(MethodAccessFlag::ABSTRACT)
+}
+
+/// Selects the default method of `method` for an object of `class` (JVMS 5.4.6), the only
+/// maximally-specific superinterface method that is not abstract. `None` if all of them are
+/// abstract, `IncompatibleClassChangeError` if more than one is not.
+fn select_default_method(
+    class: &runtime::Class,
+    method: &MethodInfo,
+) -> NativeResult<Option<(Arc<runtime::Class>, usize)>> {
+    let mut defaults: Vec<_> = maximally_specific_methods(class, &method.name, &method.descriptor)
+        .into_iter()
+        .filter(|(interface, index)| !is_abstract(&interface.methods[*index]))
+        .collect();
+    if defaults.len() > 1 {
+        let conflicts: Vec<_> = defaults
+            .iter()
+            .map(|(interface, _)| {
+                format!(
+                    "{}.{}",
+                    interface.class_name.replace('/', "."),
+                    method.name.to_str()
+                )
+            })
+            .collect();
+        return Err(runtime::Exception::new_vm_msg(
+            INCOMPATIBLE_CLASS_CHANGE_ERROR_CLASS
+                .get()
+                .expect("must have init"),
+            &format!("Conflicting default methods: {}", conflicts.join(" ")),
+        ));
+    }
+    Ok(defaults.pop())
+}
+
+/// `AbstractMethodError` for invoking `method` on an object of `class`, which has no
+/// implementation of it
+pub(in crate::runtime) fn abstract_method_error(
+    class: &runtime::Class,
+    method: &MethodInfo,
+) -> runtime::Exception {
+    runtime::Exception::new_vm_msg(
+        ABSTRACT_METHOD_ERROR_CLASS.get().expect("must have init"),
+        &format!(
+            "{}.{}{}",
+            class.class_name().replace('/', "."),
+            method.name.to_str(),
+            method.descriptor.to_descriptor()
+        ),
+    )
+}
+
+/// Selects the method to invoke for an `invokeinterface` on an object of `class`, where `method`
+/// is the resolved interface method. `None` if there is no implementation of it.
+pub(in crate::runtime) fn select_interface_method(
+    class: &Arc<runtime::Class>,
+    method: &MethodInfo,
+) -> NativeResult<Option<(Arc<runtime::Class>, usize)>> {
+    let mut current = Some(class);
+    while let Some(class) = current {
+        let index = class.methods.iter().position(|m| {
+            !m.access_flags.contains(MethodAccessFlag::STATIC)
+                && m.name == method.name
+                && m.descriptor == method.descriptor
+        });
+        if let Some(index) = index {
+            return Ok(Some((Arc::clone(class), index)));
+        }
+        current = class.super_class.as_ref();
+    }
+
+    select_default_method(class, method)
+}
+
 pub(in crate::runtime) fn initialize_class(
     env: &VmEnv,
     class: &Arc<runtime::Class>,
@@ -1105,21 +1894,80 @@ pub(in crate::runtime) fn initialize_class(
 
     // execute clinit
     if let Some(clinit) = class.methods.iter().find(|m| m.name.to_str() == "<clinit>") {
-        println!("clinit found for {clinit:?}");
+        if VERBOSE.load(Ordering::Relaxed) {
+            println!("clinit found for {clinit:?}");
+        }
         let mut init_thread = env.get_thread().new_native_frame_group(None);
         init_thread.new_frame(
             Arc::clone(class),
             &clinit.name,
             &clinit.descriptor.parameters,
             0,
-        );
+        )?;
         init_thread.execute()?;
     }
-    println!("initialized {}", class.class_name);
+    if VERBOSE.load(Ordering::Relaxed) {
+        println!("initialized {}", class.class_name);
+    }
 
     Ok(())
 }
 
+/// Checks that the `ConstantValue` of each static field matches the type of the field, as hotspot
+/// does when parsing the class file. A reference field can only have a String constant.
+pub(in crate::runtime) fn check_constant_values(class: &runtime::Class) -> NativeResult<()> {
+    for field in &class.static_fields_info {
+        let const_value = field.attributes.iter().find_map(|attr| {
+            if let runtime::AttributeInfo::ConstantValue(value) = attr {
+                Some(value)
+            } else {
+                None
+            }
+        });
+        let Some(const_value) = const_value else {
+            continue;
+        };
+        let consistent = match (&field.descriptor.0, const_value) {
+            (FieldType::Object(class_name), Const::String(_))
+                if class_name == "java/lang/String" =>
+            {
+                true
+            }
+            (FieldType::Object(_) | FieldType::Array(_), Const::String(_)) => {
+                return Err(runtime::Exception::new_vm_msg(
+                    CLASS_FORMAT_ERROR_CLASS.get().expect("must have init"),
+                    &format!(
+                        "Bad string initial value in class file {}",
+                        class.class_name
+                    ),
+                ));
+            }
+            (
+                FieldType::Byte
+                | FieldType::Char
+                | FieldType::Short
+                | FieldType::Int
+                | FieldType::Boolean,
+                Const::Int(_),
+            )
+            | (FieldType::Long, Const::Long(_))
+            | (FieldType::Float, Const::Float(_))
+            | (FieldType::Double, Const::Double(_)) => true,
+            _ => false,
+        };
+        if !consistent {
+            return Err(runtime::Exception::new_vm_msg(
+                CLASS_FORMAT_ERROR_CLASS.get().expect("must have init"),
+                &format!(
+                    "Inconsistent constant value type in class file {}",
+                    class.class_name
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
 fn init_static_from_const_value(env: &VmEnv, class: &Arc<runtime::Class>) -> NativeResult<()> {
     for field in &class.static_fields_info {
         let const_value = field.attributes.iter().find_map(|attr| {
@@ -1132,7 +1980,7 @@ fn init_static_from_const_value(env: &VmEnv, class: &Arc<runtime::Class>) -> Nat
         let Some(const_value) = const_value else {
             continue;
         };
-        let mut static_var = class.static_fields[field.index as usize].write().unwrap();
+        let index = field.index as usize;
         use Const::*;
         match field.descriptor.0 {
             FieldType::Byte
@@ -1143,41 +1991,37 @@ fn init_static_from_const_value(env: &VmEnv, class: &Arc<runtime::Class>) -> Nat
                 let (Byte(a) | Char(a) | Int(a) | Short(a) | Boolean(a)) = const_value else {
                     panic!("unexpected const value");
                 };
-                static_var.int = *a;
+                class.set_static_field(index, Variable { int: *a });
             }
             FieldType::Double => {
                 let Double(a) = const_value else {
                     panic!("unexpected const value");
                 };
                 let (a, b) = Variable::put_double(*a);
-                *static_var = a;
-                *class.static_fields[(field.index + 1) as usize]
-                    .write()
-                    .unwrap() = b;
+                class.set_static_field(index, a);
+                class.set_static_field(index + 1, b);
             }
             FieldType::Float => {
                 let Float(a) = const_value else {
                     panic!("unexpected const value");
                 };
-                static_var.float = *a;
+                class.set_static_field(index, Variable { float: *a });
             }
             FieldType::Long => {
                 let Long(a) = const_value else {
                     panic!("unexpected const value");
                 };
                 let (a, b) = Variable::put_long(*a);
-                *static_var = a;
-                *class.static_fields[(field.index + 1) as usize]
-                    .write()
-                    .unwrap() = b;
+                class.set_static_field(index, a);
+                class.set_static_field(index + 1, b);
             }
-            FieldType::Object(ref class_name) => {
-                assert_eq!(class_name, "java/lang/String", "field must be String");
+            // only String fields, see check_constant_values
+            FieldType::Object(_) => {
                 let String(a) = const_value else {
                     panic!("unexpected const value");
                 };
                 let id = intern_string(a);
-                static_var.reference = id;
+                class.set_static_field(index, Variable { reference: id });
             }
             FieldType::Array(_) => {
                 panic!("cannot have const value for array");
@@ -1196,11 +2040,11 @@ pub(in crate::runtime) fn intern_string(str: &Arc<JavaStr>) -> u32 {
     );
 
     // TODO: jvm env for compact String
-    let (java_string_bytes, has_multi_byte) = Arc::clone(str).to_java_string_bytes_arc(true);
+    let (java_string_bytes, is_utf16) = Arc::clone(str).to_java_string_bytes_arc(true);
 
     HEAP.write().unwrap().intern_string(
         java_string_bytes,
-        has_multi_byte,
+        is_utf16,
         &mut STRING_TABLE.write().unwrap(),
     )
 }
@@ -1218,3 +2062,411 @@ pub(in crate::runtime) fn get_class_object(class: Arc<runtime::Class>) -> Native
         .unwrap()
         .get_class_object(class, &mut CLASS_TABLE.write().unwrap()))
 }
+
+/// Allocates an instance of `class` with all fields set to their default values.
+pub(in crate::runtime) fn new_instance(class: &Arc<runtime::Class>) -> u32 {
+    // the slot past the last field, long and double fields take two slots
+    let slot_count = class
+        .instance_fields_info
+        .last()
+        .map_or(0, |f| f.index + f.descriptor.0.slot_count());
+    let mut fields_types = Vec::with_capacity(slot_count);
+
+    for f in &class.instance_fields_info {
+        // slots are indexed by the fields in order, see `resolve_this_class_field_ref`
+        assert_eq!(
+            f.index,
+            fields_types.len(),
+            "field {} of {} is out of order",
+            f.name.to_str(),
+            class.class_name
+        );
+        for _ in 0..f.descriptor.0.slot_count() {
+            fields_types.push(&f.descriptor);
+        }
+    }
+    assert_eq!(fields_types.len(), slot_count);
+
+    let mut heap = HEAP.write().unwrap();
+    unsafe {
+        heap.allocate_object(fields_types.len(), Arc::clone(class), |i, v| {
+            use FieldType::*;
+            let var = match fields_types[i].0 {
+                Byte | Char | Int | Short | Boolean | Long => Variable { int: 0 },
+                Float | Double => Variable { float: 0.0 },
+                Object(_) | Array(_) => Variable { reference: 0 },
+            };
+
+            v.write(var);
+        })
+    }
+}
+
+/// Allocates an array of the array class `class` with `count` elements set to their default values.
+pub(in crate::runtime) fn new_array(
+    heap: &mut Heap,
+    class: Arc<runtime::Class>,
+    count: usize,
+) -> u32 {
+    let (_, FieldDescriptor(element_type)) =
+        parse_field_descriptor(&class.class_name[1..]).expect("invalid array class name");
+    match element_type {
+        FieldType::Boolean | FieldType::Byte => heap.allocate_array::<i8>(count, class),
+        FieldType::Char => heap.allocate_array::<u16>(count, class),
+        FieldType::Short => heap.allocate_array::<i16>(count, class),
+        FieldType::Int => heap.allocate_array::<i32>(count, class),
+        FieldType::Float => heap.allocate_array::<f32>(count, class),
+        FieldType::Long => heap.allocate_array::<i64>(count, class),
+        FieldType::Double => heap.allocate_array::<f64>(count, class),
+        FieldType::Object(_) | FieldType::Array(_) => heap.allocate_array::<u32>(count, class),
+    }
+}
+
+/// Allocates an array of the array class `class_name` like `multianewarray`, `dims` are the
+/// lengths of its leading dimensions and the innermost allocated arrays hold default values.
+pub(in crate::runtime) fn new_multi_array(
+    heap: &RwLock<Heap>,
+    class_name: &str,
+    dims: &[i32],
+) -> NativeResult<u32> {
+    debug_assert!(
+        !dims.is_empty() && class_name.starts_with(&"[".repeat(dims.len())),
+        "array class dimension not enough"
+    );
+    if dims.iter().any(|&dim| dim < 0) {
+        return Err(runtime::Exception::new_vm(
+            NEGATIVE_ARRAY_SIZE_EXCEPTION_CLASS
+                .get()
+                .expect("must have init"),
+        ));
+    }
+    // resolved before locking the heap, the class of the array at each dimension
+    let loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+    let classes = (0..dims.len())
+        .map(|i| loader.resolve_class(&class_name[i..]))
+        .collect::<NativeResult<Vec<_>>>()?;
+    Ok(new_multi_array_dim(
+        &mut heap.write().unwrap(),
+        &classes,
+        dims,
+    ))
+}
+
+fn new_multi_array_dim(heap: &mut Heap, classes: &[Arc<runtime::Class>], dims: &[i32]) -> u32 {
+    let count = dims[0] as usize;
+    if dims.len() == 1 {
+        return new_array(heap, Arc::clone(&classes[0]), count);
+    }
+    let id = heap.allocate_array::<u32>(count, Arc::clone(&classes[0]));
+    let array_obj = heap.get(id);
+    for i in 0..count {
+        let element = new_multi_array_dim(heap, &classes[1..], &dims[1..]);
+        // SAFETY: array is newly allocated and not yet visible to other threads
+        unsafe { put_array_index(array_obj.as_ref(), i, element) };
+    }
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JModModule, ModuleLoader, parse_class, parse_constant_pool};
+    use crate::{
+        class::{self, JavaStr},
+        descriptor::FieldType,
+        runtime::{self, Exception, ReferenceKind, native::boot_vm},
+    };
+    use std::env;
+
+    fn utf8(s: &str) -> class::ConstantPoolInfo {
+        class::ConstantPoolInfo::Utf8(JavaStr::from_str(s).into())
+    }
+
+    #[test]
+    fn test_parse_method_handle() {
+        type Cpi = class::ConstantPoolInfo;
+        let cp = vec![
+            /* 1 */ utf8("Foo"),
+            /* 2 */ Cpi::Class { name_index: 1 },
+            /* 3 */ utf8("bar"),
+            /* 4 */ utf8("()V"),
+            /* 5 */
+            Cpi::NameAndType {
+                name_index: 3,
+                descriptor_index: 4,
+            },
+            /* 6 */
+            Cpi::Methodref {
+                class_index: 2,
+                name_and_type_index: 5,
+            },
+            /* 7 */
+            Cpi::MethodHandle {
+                reference_kind: 6,
+                reference_index: 6,
+            },
+            /* 8 */ utf8("baz"),
+            /* 9 */ utf8("I"),
+            /* 10 */
+            Cpi::NameAndType {
+                name_index: 8,
+                descriptor_index: 9,
+            },
+            /* 11 */
+            Cpi::Fieldref {
+                class_index: 2,
+                name_and_type_index: 10,
+            },
+            /* 12 */
+            Cpi::MethodHandle {
+                reference_kind: 1,
+                reference_index: 11,
+            },
+            /* 13 */
+            Cpi::InterfaceMethodref {
+                class_index: 2,
+                name_and_type_index: 5,
+            },
+            /* 14 */
+            Cpi::MethodHandle {
+                reference_kind: 9,
+                reference_index: 13,
+            },
+        ];
+        let constant_pool = parse_constant_pool(&cp).unwrap();
+
+        let handle = |index: usize| {
+            let runtime::ConstantPoolInfo::MethodHandle(handle) = &constant_pool[index - 1] else {
+                panic!("#{index} is not a method handle");
+            };
+            *handle
+        };
+
+        let invoke_static = handle(7);
+        assert!(matches!(
+            invoke_static.reference_kind,
+            ReferenceKind::InvokeStatic
+        ));
+        assert_eq!(invoke_static.reference_index, 6);
+        let runtime::ConstantPoolInfo::Methodref(method) = &constant_pool[5] else {
+            panic!("#6 is not a methodref");
+        };
+        assert_eq!(&*method.class_name, "Foo");
+
+        let get_field = handle(12);
+        assert!(matches!(get_field.reference_kind, ReferenceKind::GetField));
+        assert_eq!(get_field.reference_index, 11);
+
+        let invoke_interface = handle(14);
+        assert!(matches!(
+            invoke_interface.reference_kind,
+            ReferenceKind::InvokeInterface
+        ));
+        assert_eq!(invoke_interface.reference_index, 13);
+    }
+
+    #[test]
+    fn test_parse_dynamic() {
+        type Cpi = class::ConstantPoolInfo;
+        let cp = vec![
+            /* 1 */ utf8("run"),
+            /* 2 */ utf8("()Ljava/lang/Runnable;"),
+            /* 3 */
+            Cpi::NameAndType {
+                name_index: 1,
+                descriptor_index: 2,
+            },
+            /* 4 */
+            Cpi::InvokeDynamic {
+                bootstrap_method_attr_index: 0,
+                name_and_type_index: 3,
+            },
+            /* 5 */ utf8("()V"),
+            /* 6 */
+            Cpi::MethodType {
+                descriptor_index: 5,
+            },
+            /* 7 */ utf8("value"),
+            /* 8 */ utf8("J"),
+            /* 9 */
+            Cpi::NameAndType {
+                name_index: 7,
+                descriptor_index: 8,
+            },
+            /* 10 */
+            Cpi::Dynamic {
+                bootstrap_method_attr_index: 1,
+                name_and_type_index: 9,
+            },
+        ];
+        let constant_pool = parse_constant_pool(&cp).unwrap();
+
+        let runtime::ConstantPoolInfo::InvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type,
+        } = &constant_pool[3]
+        else {
+            panic!("#4 is not invokedynamic");
+        };
+        assert_eq!(*bootstrap_method_attr_index, 0);
+        assert_eq!(name_and_type.name.to_str(), "run");
+        assert!(name_and_type.descriptor.parameters.is_empty());
+        assert_eq!(
+            name_and_type.descriptor.return_type,
+            Some(FieldType::Object("java/lang/Runnable".to_string()))
+        );
+
+        let runtime::ConstantPoolInfo::MethodType(descriptor) = &constant_pool[5] else {
+            panic!("#6 is not a method type");
+        };
+        assert!(descriptor.parameters.is_empty());
+        assert_eq!(descriptor.return_type, None);
+
+        let runtime::ConstantPoolInfo::Dynamic {
+            bootstrap_method_attr_index,
+            name_and_type,
+        } = &constant_pool[9]
+        else {
+            panic!("#10 is not a dynamic constant");
+        };
+        assert_eq!(*bootstrap_method_attr_index, 1);
+        assert_eq!(name_and_type.name.to_str(), "value");
+        assert_eq!(name_and_type.descriptor.0, FieldType::Long);
+    }
+
+    #[test]
+    fn test_parse_forward_references() {
+        type Cpi = class::ConstantPoolInfo;
+        let cp = vec![
+            /* 1 */
+            Cpi::Methodref {
+                class_index: 2,
+                name_and_type_index: 4,
+            },
+            /* 2 */ Cpi::Class { name_index: 3 },
+            /* 3 */ utf8("Foo"),
+            /* 4 */
+            Cpi::NameAndType {
+                name_index: 5,
+                descriptor_index: 6,
+            },
+            /* 5 */ utf8("bar"),
+            /* 6 */ utf8("(J)I"),
+        ];
+        let constant_pool = parse_constant_pool(&cp).unwrap();
+
+        let runtime::ConstantPoolInfo::Methodref(method_ref) = &constant_pool[0] else {
+            panic!("#1 is not a method");
+        };
+        assert_eq!(method_ref.class_name.as_ref(), "Foo");
+        assert_eq!(method_ref.name_and_type.name.to_str(), "bar");
+        assert_eq!(
+            method_ref.name_and_type.descriptor.parameters,
+            [FieldType::Long]
+        );
+        assert_eq!(
+            method_ref.name_and_type.descriptor.return_type,
+            Some(FieldType::Int)
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_cp_index() {
+        if !boot_vm() {
+            return;
+        }
+        type Cpi = class::ConstantPoolInfo;
+        let error_message = |cp: &[Cpi]| {
+            let Err(Exception::VmException {
+                exception_type,
+                message,
+            }) = parse_constant_pool(cp)
+            else {
+                panic!("must be rejected");
+            };
+            assert_eq!(exception_type.class_name(), "java/lang/ClassFormatError");
+            message
+        };
+
+        let methodref = |class_index, name_and_type_index| Cpi::Methodref {
+            class_index,
+            name_and_type_index,
+        };
+        // the entry under test is #5
+        let with_entry = |entry| {
+            vec![
+                /* 1 */ utf8("Foo"),
+                /* 2 */ Cpi::Class { name_index: 1 },
+                /* 3 */ utf8("()V"),
+                /* 4 */
+                Cpi::NameAndType {
+                    name_index: 3,
+                    descriptor_index: 3,
+                },
+                entry,
+            ]
+        };
+
+        assert_eq!(
+            error_message(&with_entry(methodref(2, 6))),
+            "Invalid constant pool index 6"
+        );
+        assert_eq!(
+            error_message(&with_entry(Cpi::String { string_index: 0 })),
+            "Invalid constant pool index 0"
+        );
+        assert_eq!(
+            error_message(&with_entry(methodref(1, 4))),
+            "Invalid constant pool entry 1, expected Class"
+        );
+        assert_eq!(
+            error_message(&with_entry(methodref(2, 3))),
+            "Invalid constant pool entry 3, expected NameAndType"
+        );
+        assert_eq!(
+            error_message(&with_entry(Cpi::Class { name_index: 2 })),
+            "Invalid constant pool entry 2, expected Utf8"
+        );
+    }
+
+    #[test]
+    fn test_bootstrap_methods() {
+        let Some(java_home) = env::var_os("JAVA_HOME") else {
+            return;
+        };
+        // java.base concatenates strings inline, other modules through invokedynamic
+        let sql = JModModule::new(&java_home, "java.sql");
+        let class =
+            parse_class(&sql.get_class_file("java/sql/SQLException.class").unwrap()).unwrap();
+        let bootstrap_methods = class.bootstrap_methods();
+        assert!(!bootstrap_methods.is_empty());
+        for bootstrap_method in bootstrap_methods {
+            let handle = &bootstrap_method.bootstrap_method;
+            assert!(matches!(handle.reference_kind, ReferenceKind::InvokeStatic));
+            let runtime::ConstantPoolInfo::Methodref(method_ref) =
+                class.get_constant(handle.reference_index)
+            else {
+                panic!("bootstrap method must be a method");
+            };
+            assert_eq!(
+                method_ref.class_name.as_ref(),
+                "java/lang/invoke/StringConcatFactory"
+            );
+            assert_eq!(
+                method_ref.name_and_type.name.to_str(),
+                "makeConcatWithConstants"
+            );
+
+            // the recipe, with \u{1} marking the dynamic arguments
+            let [recipe] = bootstrap_method.bootstrap_arguments[..] else {
+                panic!("makeConcatWithConstants takes only the recipe");
+            };
+            let runtime::ConstantPoolInfo::String(recipe) = class.get_constant(recipe) else {
+                panic!("recipe must be a string");
+            };
+            assert!(recipe.to_str().contains('\u{1}'));
+        }
+
+        let sql_data = parse_class(&sql.get_class_file("java/sql/SQLData.class").unwrap()).unwrap();
+        assert!(sql_data.bootstrap_methods().is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use dashmap::DashMap;
 use once_cell::sync::OnceCell;
 use std::{
+    cell::Cell,
     collections::{HashMap, HashSet},
     fmt::Debug,
     fs::{self, File},
@@ -8,30 +9,39 @@ use std::{
     mem,
     ops::Deref,
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, atomic::Ordering},
 };
 use zip::{ZipArchive, read::ZipFile};
 
 use crate::{
-    class::{self, parser},
-    consts::{ClassAccessFlag, MethodAccessFlag},
-    descriptor::{FieldDescriptor, FieldType, parse_field_descriptor},
+    class::{self, JavaStr, parser},
+    consts::{ClassAccessFlag, MethodAccessFlag, RequiresFlag},
+    descriptor::{FieldDescriptor, FieldType, MethodDescriptor, parse_field_descriptor},
     runtime,
     runtime::{
-        AttributeInfo, FieldResolve, MethodResolve, NativeResult, VtableEntry, VtableIndex,
+        AttributeInfo, Exception, FieldResolve, MethodResolve, Module, NativeResult, VtableEntry,
+        VtableIndex,
         class_loader::{
-            resolve_cp_class, resolve_from_vtable, resolve_method_statically_inner,
-            resolve_static_field, resolve_static_method_inner,
+            check_constant_values, maximally_specific_methods, resolve_cp_class,
+            resolve_from_vtable, resolve_method_statically_inner, resolve_static_field,
+            resolve_static_method_inner, superinterfaces,
+            verifier::{verify_categories, verify_class, verify_constant_references},
+        },
+        famous_classes::{
+            CLONEABLE_CLASS, INCOMPATIBLE_CLASS_CHANGE_ERROR_CLASS, LINKAGE_ERROR_CLASS,
+            NO_CLASS_DEF_FOUND_ERROR_CLASS, OBJECT_CLASS, SERIALIZABLE_CLASS,
         },
-        famous_classes::{CLONEABLE_CLASS, OBJECT_CLASS, SERIALIZABLE_CLASS},
         gen_array_class, gen_primitive_class,
+        global::{VERBOSE, VERIFY_CATEGORIES},
+        interpreter::instructions as inst,
     },
 };
 
 #[derive(Debug)]
 pub(in crate::runtime) struct BootstrapClassLoader {
     modules: Vec<Box<dyn ModuleLoader + Send + Sync + 'static>>,
-    package_to_module: HashMap<String, usize>,
+    // modules contributing to a package, in the order they are added
+    package_to_module: HashMap<String, Vec<usize>>,
     // TODO: use Arc<String>
     class_registry: DashMap<String, Arc<OnceCell<Arc<runtime::Class>>>>,
 }
@@ -39,8 +49,12 @@ pub(in crate::runtime) struct BootstrapClassLoader {
 pub trait ModuleLoader: Debug {
     fn packages(&self) -> Vec<Arc<str>>;
     fn name(&self) -> &str;
-    // must end with .class
-    fn get_class_file(&self, class_name: &str) -> OwnedOrRef<'_, class::Class>;
+    // must end with .class, `None` if the class is not in this module
+    fn get_class_file(&self, class_name: &str) -> Option<OwnedOrRef<'_, class::Class>>;
+    // `None` for an unnamed module, which reads every module
+    fn module_info(&self) -> Option<&Module> {
+        None
+    }
 }
 
 impl BootstrapClassLoader {
@@ -54,7 +68,9 @@ impl BootstrapClassLoader {
     pub fn add_module(&mut self, module: Box<dyn ModuleLoader + Send + Sync + 'static>) {
         for package in module.packages() {
             self.package_to_module
-                .insert(package.to_string(), self.modules.len());
+                .entry(package.to_string())
+                .or_default()
+                .push(self.modules.len());
         }
         self.modules.push(module);
     }
@@ -79,6 +95,128 @@ impl BootstrapClassLoader {
         Ok(Arc::clone(class))
     }
 
+    /// Defines the class `class_name` from the class file `bytes`, like `ClassLoader.defineClass`.
+    ///
+    /// Throws `LinkageError` if a class of the same name is already defined.
+    pub(in crate::runtime) fn define_class_from_bytes(
+        &self,
+        class_name: &str,
+        bytes: &[u8],
+    ) -> NativeResult<Arc<runtime::Class>> {
+        let class_file = parser::class_file(bytes)?;
+        let class_cell = Arc::clone(
+            self.class_registry
+                .entry(class_name.to_string())
+                .or_default()
+                .value(),
+        );
+
+        let mut defined = false;
+        let class = class_cell.get_or_try_init(|| {
+            defined = true;
+            self.link_class(class_name, &class_file)
+        })?;
+        if !defined {
+            return Err(Exception::new_vm_msg(
+                LINKAGE_ERROR_CLASS.get().expect("must have init"),
+                &format!(
+                    "duplicate class definition for {}",
+                    class_name.replace('/', ".")
+                ),
+            ));
+        }
+
+        Ok(Arc::clone(class))
+    }
+
+    /// Whether the module of `from` reads the module of `to`, i.e. `to` is the same module, is
+    /// required by it, or is required transitively by a module it reads.
+    ///
+    /// Classes in no module and in unnamed modules read every module.
+    pub(in crate::runtime) fn can_read(&self, from: &runtime::Class, to: &runtime::Class) -> bool {
+        let (Some(from), Some(to)) = (self.module_of(from), self.module_of(to)) else {
+            return true;
+        };
+        if from == to {
+            return true;
+        }
+        let Some(from_info) = self.modules[from].module_info() else {
+            return true;
+        };
+        let to_name = self.modules[to].name();
+
+        let mut visited = HashSet::new();
+        let mut pending: Vec<_> = from_info
+            .requires
+            .iter()
+            .map(|require| require.requires.to_str())
+            .collect();
+        while let Some(name) = pending.pop() {
+            if name == to_name {
+                return true;
+            }
+            if !visited.insert(name.to_string()) {
+                continue;
+            }
+            // readability is implied through `requires transitive` of the modules read
+            let Some(info) = self
+                .modules
+                .iter()
+                .find(|module| module.name() == name)
+                .and_then(|module| module.module_info())
+            else {
+                continue;
+            };
+            pending.extend(
+                info.requires
+                    .iter()
+                    .filter(|require| require.requires_flags.contains(RequiresFlag::TRANSITIVE))
+                    .map(|require| require.requires.to_str()),
+            );
+        }
+        false
+    }
+
+    /// Logs if `from` resolves `to` across modules that do not read each other
+    pub(in crate::runtime) fn check_readable(&self, from: &runtime::Class, to: &runtime::Class) {
+        // TODO: throw IllegalAccessError once modules other than java.base are loaded
+        if !self.can_read(from, to) && VERBOSE.load(Ordering::Relaxed) {
+            println!(
+                "{} cannot access {}: module {} does not read module {}",
+                from.class_name,
+                to.class_name,
+                self.module_of(from)
+                    .map_or("", |id| self.modules[id].name()),
+                self.module_of(to).map_or("", |id| self.modules[id].name()),
+            );
+        }
+    }
+
+    fn module_of(&self, class: &runtime::Class) -> Option<usize> {
+        if class.class_name.starts_with('[') {
+            return None;
+        }
+        self.package_to_module
+            .get(class.package_name())
+            .and_then(|module_ids| module_ids.first().copied())
+    }
+
+    /// Names of the classes defined so far, including array and primitive classes, sorted.
+    pub(in crate::runtime) fn loaded_class_names(&self) -> Vec<Arc<str>> {
+        let mut class_names: Vec<_> = self
+            .class_registry
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .value()
+                    .get()
+                    .map(|class| Arc::clone(&class.class_name))
+            })
+            .collect();
+        class_names.sort();
+        class_names
+    }
+
     pub(in crate::runtime) fn resolve_primitive_class(
         &self,
         class_name: &str,
@@ -113,7 +251,8 @@ impl BootstrapClassLoader {
             FieldType::Object(class_name) => {
                 self.resolve_object_array_class(&self.resolve_class(&class_name)?)
             }
-            FieldType::Array(_) => self.resolve_array_class_with_field_type(*element),
+            FieldType::Array(_) => self
+                .resolve_object_array_class(&self.resolve_array_class_with_field_type(*element)?),
         }
     }
 
@@ -121,34 +260,66 @@ impl BootstrapClassLoader {
         &self,
         field_type: &FieldType,
     ) -> NativeResult<Arc<runtime::Class>> {
-        let class_name_string = "[".to_string() + &field_type.to_descriptor();
-        let class_name: Arc<str> = Arc::from(class_name_string.as_str());
-        let class_cell = Arc::clone(
-            self.class_registry
-                .entry(class_name_string)
-                .or_default()
-                .value(),
-        );
-        let class = class_cell.get_or_try_init(|| self.define_array(class_name, None))?;
-
-        // array has no clinit
-        Ok(Arc::clone(class))
+        let class_name = match field_type {
+            FieldType::Byte => "[B",
+            FieldType::Char => "[C",
+            FieldType::Double => "[D",
+            FieldType::Float => "[F",
+            FieldType::Int => "[I",
+            FieldType::Long => "[J",
+            FieldType::Short => "[S",
+            FieldType::Boolean => "[Z",
+            FieldType::Object(_) | FieldType::Array(_) => panic!("must be primitive type"),
+        };
+        self.resolve_array_class(class_name, None)
     }
 
     pub(in crate::runtime) fn resolve_object_array_class(
         &self,
         ele_class: &Arc<runtime::Class>,
     ) -> NativeResult<Arc<runtime::Class>> {
-        let class_name_string = format!("[L{};", ele_class.class_name);
-        let class_name: Arc<str> = Arc::from(class_name_string.as_str());
+        thread_local! {
+            static CLASS_NAME: Cell<String> = const { Cell::new(String::new()) };
+        }
+        // taken rather than borrowed, defining the array class may resolve another one
+        let mut class_name = CLASS_NAME.take();
+        class_name.clear();
+        if ele_class.is_array() {
+            class_name.push('[');
+            class_name.push_str(&ele_class.class_name);
+        } else {
+            class_name.push_str("[L");
+            class_name.push_str(&ele_class.class_name);
+            class_name.push(';');
+        }
+        let class = self.resolve_array_class(&class_name, Some(ele_class));
+        CLASS_NAME.set(class_name);
+        class
+    }
+
+    /// Looks up the registry with the borrowed name first, and only allocates the name to define
+    /// the class, as array classes are resolved on every `newarray` and `anewarray`.
+    fn resolve_array_class(
+        &self,
+        class_name: &str,
+        ele_class: Option<&Arc<runtime::Class>>,
+    ) -> NativeResult<Arc<runtime::Class>> {
+        let defined = self
+            .class_registry
+            .get(class_name)
+            .and_then(|class_cell| class_cell.get().map(Arc::clone));
+        if let Some(class) = defined {
+            return Ok(class);
+        }
+
         let class_cell = Arc::clone(
             self.class_registry
-                .entry(class_name_string)
+                .entry(class_name.to_string())
                 .or_default()
                 .value(),
         );
         let class =
-            class_cell.get_or_try_init(|| self.define_array(class_name, Some(ele_class)))?;
+            class_cell.get_or_try_init(|| self.define_array(Arc::from(class_name), ele_class))?;
 
         // array has no clinit
         Ok(Arc::clone(class))
@@ -160,49 +331,91 @@ impl BootstrapClassLoader {
         } else {
             ""
         };
-        // TODO: unwrap
-        let module_id = self.package_to_module.get(package).unwrap();
-        let module = &self.modules[*module_id];
+        let file_name = name.to_string() + ".class";
+        let class_file = self
+            .package_to_module
+            .get(package)
+            .into_iter()
+            .flatten()
+            .find_map(|module_id| self.modules[*module_id].get_class_file(&file_name))
+            .ok_or_else(|| {
+                Exception::new_vm_msg(
+                    NO_CLASS_DEF_FOUND_ERROR_CLASS
+                        .get()
+                        .expect("must have init"),
+                    name,
+                )
+            })?;
+        self.link_class(name, &class_file)
+    }
 
-        let class_file = &module.get_class_file(&(name.to_string() + ".class"));
-        let mut class = runtime::parse_class(class_file);
+    /// Creates the class `name` from its class file, after loading its super class and interfaces
+    fn link_class(
+        &self,
+        name: &str,
+        class_file: &class::Class,
+    ) -> NativeResult<Arc<runtime::Class>> {
+        let mut class = runtime::parse_class(class_file)?;
+        if class.class_name.as_ref() != name {
+            return Err(Exception::new_vm_msg(
+                NO_CLASS_DEF_FOUND_ERROR_CLASS
+                    .get()
+                    .expect("must have init"),
+                &format!("{name} (wrong name: {})", class.class_name),
+            ));
+        }
+        check_constant_values(&class)?;
+        verify_constant_references(&class)?;
+        // older class files have no StackMapTable and need verification by type inference
+        if class_file.major_version >= 50 {
+            verify_class(&class)?;
+        }
+        if VERIFY_CATEGORIES.load(Ordering::Relaxed) {
+            verify_categories(&class)?;
+        }
         self.load_super_class(&mut class, class_file.super_class)?;
         self.load_interfaces(&mut class, &class_file.interfaces)?;
 
         Self::resolve_this_class_field_ref(&mut class);
         Self::build_vtable(&mut class);
+        Self::detect_finalizer(&mut class);
 
         let class = Arc::new(class);
         Self::resolve_this_class_field_ref_static(&class);
         Self::resolve_this_class_method_ref_static(&class);
         Self::resolve_this_class_method_ref(&class);
 
-        println!("defined {name}");
-
-        println!("vtable:");
-        for entry in &class.vtable {
-            print!(
-                "{}.{}: ",
-                entry
-                    .root_class
-                    .as_ref()
-                    .map(|c| c.class_name.as_ref())
-                    .unwrap_or(""),
-                entry.name.to_str()
-            );
-            match &entry.index {
-                VtableIndex::InThisClass(index) => {
-                    println!("{index}");
-                }
-                VtableIndex::OtherClass { class, index } => {
-                    println!("{}: {index}", class.class_name);
-                }
-                VtableIndex::OtherInterface { class, index } => {
-                    println!("{}: {index}", class.class_name);
+        if VERBOSE.load(Ordering::Relaxed) {
+            println!("defined {name}");
+
+            println!("vtable:");
+            for entry in &class.vtable {
+                print!(
+                    "{}.{}: ",
+                    entry
+                        .root_class
+                        .as_ref()
+                        .map(|c| c.class_name.as_ref())
+                        .unwrap_or(""),
+                    entry.name.to_str()
+                );
+                match &entry.index {
+                    VtableIndex::InThisClass(index) => {
+                        println!("{index}");
+                    }
+                    VtableIndex::OtherClass { class, index } => {
+                        println!("{}: {index}", class.class_name);
+                    }
+                    VtableIndex::OtherInterface { class, index } => {
+                        println!("{}: {index}", class.class_name);
+                    }
+                    VtableIndex::DefaultConflict { class, index } => {
+                        println!("{}: {index} (conflict)", class.class_name);
+                    }
                 }
             }
+            println!();
         }
-        println!();
 
         Ok(class)
     }
@@ -222,7 +435,8 @@ impl BootstrapClassLoader {
             .interfaces
             .push(self.resolve_class("java/io/Serializable")?);
         class.array_element_type = ele_class.map(Arc::clone);
-        // TODO: vtable
+        // Object's methods, invokevirtual on an array like `clone` dispatches through them
+        Self::build_vtable(&mut class);
 
         Ok(Arc::new(class))
     }
@@ -234,6 +448,8 @@ impl BootstrapClassLoader {
         }
         let super_class = resolve_cp_class(&class.constant_pool, class_index);
         let loaded = self.resolve_class(&super_class.name)?;
+        self.check_readable(class, &loaded);
+        Self::check_permitted_subclass(class, &loaded)?;
         super_class.set_class(&loaded);
         class.super_class.replace(Arc::clone(&loaded));
         Ok(())
@@ -242,32 +458,54 @@ impl BootstrapClassLoader {
         for index in interfaces {
             let interface = resolve_cp_class(&class.constant_pool, *index);
             let loaded = self.resolve_class(&interface.name)?;
+            self.check_readable(class, &loaded);
+            Self::check_permitted_subclass(class, &loaded)?;
             interface.set_class(&loaded);
             class.interfaces.push(loaded);
         }
         Ok(())
     }
+    /// Checks that `class` is permitted to directly extend or implement `super_class` if it is
+    /// sealed (JVMS 5.3.5)
+    fn check_permitted_subclass(
+        class: &runtime::Class,
+        super_class: &runtime::Class,
+    ) -> NativeResult<()> {
+        let Some(permitted) = super_class.permitted_subclasses() else {
+            return Ok(());
+        };
+        if permitted.iter().any(|c| c.name == class.class_name) {
+            return Ok(());
+        }
+        let inherits = if super_class
+            .access_flags
+            .contains(ClassAccessFlag::INTERFACE)
+        {
+            "implement sealed interface"
+        } else {
+            "inherit from sealed class"
+        };
+        Err(Exception::new_vm_msg(
+            INCOMPATIBLE_CLASS_CHANGE_ERROR_CLASS
+                .get()
+                .expect("must have init"),
+            &format!(
+                "class {} cannot {inherits} {}",
+                class.class_name, super_class.class_name
+            ),
+        ))
+    }
     fn resolve_this_class_field_ref(class: &mut runtime::Class) {
         // allocates field index for instance fields
         let mut instance_field_num = class
             .super_class
             .as_ref()
             .and_then(|s| s.instance_fields_info.last())
-            .map(|f| {
-                1 + if f.descriptor.0.is_long() {
-                    f.index + 1
-                } else {
-                    f.index
-                }
-            })
+            .map(|f| f.index + f.descriptor.0.slot_count())
             .unwrap_or(0);
         for field_info in class.instance_fields_info.iter_mut() {
             field_info.index = instance_field_num;
-            if field_info.descriptor.0.is_long() {
-                instance_field_num += 2;
-            } else {
-                instance_field_num += 1;
-            }
+            instance_field_num += field_info.descriptor.0.slot_count();
         }
 
         // set up map, with fields in current class overwriting fields in super class
@@ -462,6 +700,86 @@ impl BootstrapClassLoader {
         }
     }
 
+    fn detect_finalizer(class: &mut runtime::Class) {
+        let finalizer = class.methods.iter().find(|m| {
+            m.name.to_str() == "finalize"
+                && m.descriptor.parameters.is_empty()
+                && m.descriptor.return_type.is_none()
+                && !m.access_flags.contains(MethodAccessFlag::STATIC)
+        });
+        class.has_finalizer = match finalizer {
+            // a finalize that only returns, like Object's, need not run
+            Some(finalizer) => !finalizer.attributes.iter().any(
+                |attr| matches!(attr, AttributeInfo::Code(code) if *code.code == [inst::RETURN]),
+            ),
+            None => class.super_class.as_ref().is_some_and(|s| s.has_finalizer),
+        };
+    }
+
+    /// Whether a method of `class` overrides the one in `slot` of the vtable of its super class,
+    /// given the same name and descriptor (JVMS 5.4.5).
+    ///
+    /// Overriding is transitive, so it is enough to override any of the methods the slot has held
+    /// down the hierarchy, e.g. a package private method is overridden from another package if it
+    /// overrides a public one.
+    fn overrides_slot(class: &runtime::Class, slot: usize) -> bool {
+        let mut current = class.super_class.as_deref();
+        // the vtable of a class starts with the one of its super class
+        while let Some(super_class) = current
+            && let Some(entry) = super_class.vtable.get(slot)
+        {
+            let (declaring_class, index) = match &entry.index {
+                VtableIndex::InThisClass(index) => (super_class, *index),
+                VtableIndex::OtherClass { class, index }
+                | VtableIndex::OtherInterface { class, index }
+                | VtableIndex::DefaultConflict { class, index } => (class.as_ref(), *index),
+            };
+            let method = &declaring_class.methods[index];
+            if method
+                .access_flags
+                .intersects(MethodAccessFlag::PUBLIC | MethodAccessFlag::PROTECTED)
+                || declaring_class.package_name() == class.package_name()
+            {
+                return true;
+            }
+            current = super_class.super_class.as_deref();
+        }
+        false
+    }
+
+    /// The vtable entry of a superinterface method of `class` that no class implements, the only
+    /// maximally-specific default method, a conflict if there are more than one, or an abstract
+    /// method if there are none
+    fn select_interface_entry(
+        class: &runtime::Class,
+        name: &JavaStr,
+        descriptor: &MethodDescriptor,
+    ) -> VtableIndex {
+        let methods = maximally_specific_methods(class, name, descriptor);
+        let mut defaults = methods.iter().filter(|(interface, index)| {
+            !interface.methods[*index]
+                .access_flags
+                .contains(MethodAccessFlag::ABSTRACT)
+        });
+        match (defaults.next(), defaults.next()) {
+            (Some((interface, index)), None) => VtableIndex::OtherInterface {
+                class: Arc::clone(interface),
+                index: *index,
+            },
+            (Some((interface, index)), Some(_)) => VtableIndex::DefaultConflict {
+                class: Arc::clone(interface),
+                index: *index,
+            },
+            (None, _) => {
+                let (interface, index) = methods.first().expect("must be a superinterface method");
+                VtableIndex::OtherInterface {
+                    class: Arc::clone(interface),
+                    index: *index,
+                }
+            }
+        }
+    }
+
     fn build_vtable(class: &mut runtime::Class) {
         if let Some(super_class) = &class.super_class {
             // super class's vtable goes first
@@ -487,7 +805,7 @@ impl BootstrapClassLoader {
 
         let mut overrode_methods = HashSet::new();
 
-        for entry in &mut vtable {
+        for (slot, entry) in vtable.iter_mut().enumerate() {
             // check for overrides
             let (super_class, index) = match &entry.index {
                 VtableIndex::InThisClass(index) => {
@@ -502,7 +820,8 @@ impl BootstrapClassLoader {
                     (class, index)
                 }
                 VtableIndex::OtherClass { class, index } => (class as &_, *index),
-                VtableIndex::OtherInterface { class, index } => (class as &_, *index),
+                VtableIndex::OtherInterface { class, index }
+                | VtableIndex::DefaultConflict { class, index } => (class as &_, *index),
             };
 
             entry
@@ -513,13 +832,7 @@ impl BootstrapClassLoader {
 
             // skip non overridable
             // private and final method will not be in vtable
-            // TODO: check transitive overridable
-            if !super_method.access_flags.contains(MethodAccessFlag::PUBLIC)
-                && !super_method
-                    .access_flags
-                    .contains(MethodAccessFlag::PROTECTED)
-                && super_class.package_name() != class.package_name()
-            {
+            if !Self::overrides_slot(class, slot) {
                 continue;
             }
 
@@ -561,20 +874,22 @@ impl BootstrapClassLoader {
             }
         }
 
-        // TODO: interface hierarchy
+        // methods of the superinterfaces that no class in the hierarchy implements, selected again
+        // as this class may implement more specific interfaces than its super class
+        for entry in &mut vtable {
+            if let VtableIndex::OtherInterface { .. } | VtableIndex::DefaultConflict { .. } =
+                entry.index
+            {
+                entry.index = Self::select_interface_entry(class, &entry.name, &entry.descriptor);
+            }
+        }
         // put interface methods
-        for interface in &class.interfaces {
-            for (i, interface_method) in interface.methods.iter().enumerate() {
+        for interface in superinterfaces(class) {
+            for interface_method in &interface.methods {
                 // private/static method is not inheritable
                 if interface_method
                     .access_flags
-                    .contains(MethodAccessFlag::PRIVATE)
-                {
-                    continue;
-                }
-                if interface_method
-                    .access_flags
-                    .contains(MethodAccessFlag::STATIC)
+                    .intersects(MethodAccessFlag::PRIVATE | MethodAccessFlag::STATIC)
                 {
                     continue;
                 }
@@ -583,17 +898,23 @@ impl BootstrapClassLoader {
                     interface_method.name.to_java_string(),
                     interface_method.descriptor.clone(),
                 );
-                if method_map.contains_key(&key) {
+                if method_map.contains_key(&key)
+                    || vtable.iter().any(|entry| {
+                        entry.name == interface_method.name
+                            && entry.descriptor == interface_method.descriptor
+                    })
+                {
                     continue;
                 }
                 vtable.push(VtableEntry {
-                    root_class: Some(Arc::clone(interface)),
+                    root_class: Some(Arc::clone(&interface)),
                     name: Arc::clone(&interface_method.name),
                     descriptor: interface_method.descriptor.clone(),
-                    index: VtableIndex::OtherInterface {
-                        class: Arc::clone(interface),
-                        index: i,
-                    },
+                    index: Self::select_interface_entry(
+                        class,
+                        &interface_method.name,
+                        &interface_method.descriptor,
+                    ),
                 });
             }
         }
@@ -625,7 +946,7 @@ impl JModModule {
         let module_info = Self::get_class_bytes(&mut module_info_file);
         drop(module_info_file);
         let module_info = parser::class_file(&module_info).unwrap();
-        let module_info = runtime::parse_class(&module_info);
+        let module_info = runtime::parse_class(&module_info).unwrap();
 
         JModModule {
             name: module_name,
@@ -661,15 +982,25 @@ impl ModuleLoader for JModModule {
         &self.name
     }
 
-    fn get_class_file(&self, class_name: &str) -> OwnedOrRef<'_, class::Class> {
+    fn module_info(&self) -> Option<&Module> {
+        self.module_info
+            .attributes
+            .iter()
+            .find_map(|attr| match attr {
+                AttributeInfo::Module(module) => Some(module),
+                _ => None,
+            })
+    }
+
+    fn get_class_file(&self, class_name: &str) -> Option<OwnedOrRef<'_, class::Class>> {
         let mut archive = self.zip_file.lock().unwrap();
-        let mut class_file = archive.by_name(&format!("classes/{class_name}")).unwrap();
+        let mut class_file = archive.by_name(&format!("classes/{class_name}")).ok()?;
         let class_bytes = Self::get_class_bytes(&mut class_file);
         drop(class_file);
         drop(archive);
 
         let class_file = parser::class_file(&class_bytes).expect(class_name);
-        class_file.into()
+        Some(class_file.into())
     }
 }
 
@@ -734,11 +1065,11 @@ impl ModuleLoader for ClassPathModule {
         &self.name
     }
 
-    fn get_class_file(&self, class_name: &str) -> OwnedOrRef<'_, class::Class> {
+    fn get_class_file(&self, class_name: &str) -> Option<OwnedOrRef<'_, class::Class>> {
+        let class_file = fs::read(self.base_path.join(class_name)).ok()?;
         // TODO: unwrap
-        let class_file = fs::read(self.base_path.join(class_name)).unwrap();
         let class_file = parser::class_file(&class_file).unwrap();
-        class_file.into()
+        Some(class_file.into())
     }
 }
 
@@ -769,3 +1100,149 @@ impl<'a, T> From<&'a T> for OwnedOrRef<'a, T> {
         OwnedOrRef::Ref(r)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{BootstrapClassLoader, JModModule, ModuleLoader};
+    use crate::{
+        consts::RequiresFlag,
+        descriptor::FieldType,
+        runtime::{self, global::BOOTSTRAP_CLASS_LOADER, native::boot_vm},
+    };
+    use std::{env, sync::Arc};
+
+    #[test]
+    fn test_resolve_array_class() {
+        if !boot_vm() {
+            return;
+        }
+        let loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+
+        let ints = loader
+            .resolve_primitive_array_class(&FieldType::Int)
+            .unwrap();
+        assert_eq!(ints.class_name(), "[I");
+        assert!(Arc::ptr_eq(&ints, &loader.resolve_class("[I").unwrap()));
+
+        let string = loader.resolve_class("java/lang/String").unwrap();
+        let strings = loader.resolve_object_array_class(&string).unwrap();
+        assert_eq!(strings.class_name(), "[Ljava/lang/String;");
+        assert!(Arc::ptr_eq(
+            &strings,
+            &loader.resolve_object_array_class(&string).unwrap()
+        ));
+        assert!(Arc::ptr_eq(
+            &strings,
+            &loader.resolve_class("[Ljava/lang/String;").unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_module_info() {
+        let Some(java_home) = env::var_os("JAVA_HOME") else {
+            return;
+        };
+        let rowset = JModModule::new(&java_home, "java.sql.rowset");
+        let module = rowset.module_info().unwrap();
+        assert_eq!(module.name.to_str(), "java.sql.rowset");
+        assert!(module.version.is_some());
+
+        let mut requires: Vec<_> = module
+            .requires
+            .iter()
+            .map(|require| {
+                (
+                    require.requires.to_str().into_owned(),
+                    require.requires_flags,
+                    require.requires_version.clone(),
+                )
+            })
+            .collect();
+        requires.sort_by(|a, b| a.0.cmp(&b.0));
+        // the jdk does not record the versions of the modules required
+        assert_eq!(
+            requires,
+            [
+                ("java.base".to_string(), RequiresFlag::MANDATED, None),
+                ("java.logging".to_string(), RequiresFlag::TRANSITIVE, None),
+                ("java.naming".to_string(), RequiresFlag::TRANSITIVE, None),
+                ("java.sql".to_string(), RequiresFlag::TRANSITIVE, None),
+            ]
+        );
+        assert_eq!(
+            module.uses.iter().map(AsRef::as_ref).collect::<Vec<_>>(),
+            ["javax/sql/rowset/RowSetFactory"]
+        );
+
+        let logging = JModModule::new(&java_home, "java.logging");
+        let provides = &logging.module_info().unwrap().provides;
+        assert_eq!(provides.len(), 1);
+        assert_eq!(
+            provides[0].provides.as_ref(),
+            "jdk/internal/logger/DefaultLoggerFinder"
+        );
+        assert_eq!(
+            provides[0]
+                .provides_with
+                .iter()
+                .map(AsRef::as_ref)
+                .collect::<Vec<_>>(),
+            ["sun/util/logging/internal/LoggingProviderImpl"]
+        );
+
+        let unsupported = JModModule::new(&java_home, "jdk.unsupported");
+        let mut opens: Vec<_> = unsupported
+            .module_info()
+            .unwrap()
+            .opens
+            .iter()
+            .map(|open| {
+                assert_eq!(open.opens_flags, 0);
+                assert!(open.opens_to.is_empty());
+                open.opens.to_str().into_owned()
+            })
+            .collect();
+        opens.sort();
+        assert_eq!(opens, ["sun/misc", "sun/reflect"]);
+    }
+
+    #[test]
+    fn test_can_read() {
+        let Some(java_home) = env::var_os("JAVA_HOME") else {
+            return;
+        };
+        let mut loader = BootstrapClassLoader::new();
+        for module in [
+            "java.base",
+            "java.sql.rowset",
+            "java.sql",
+            "java.xml",
+            "java.naming",
+            "java.security.sasl",
+        ] {
+            loader.add_module(Box::new(JModModule::new(&java_home, module)));
+        }
+        // only the name is needed to find the module of a class
+        let class = |name: &str| runtime::gen_primitive_class(Arc::from(name));
+        let object = class("java/lang/Object");
+        let rowset = class("javax/sql/rowset/RowSetFactory");
+        let sql = class("java/sql/Connection");
+        let xml = class("javax/xml/XMLConstants");
+        let naming = class("javax/naming/Context");
+        let sasl = class("javax/security/sasl/Sasl");
+        let no_module = class("Main");
+
+        assert!(loader.can_read(&rowset, &rowset));
+        assert!(loader.can_read(&rowset, &object));
+        assert!(loader.can_read(&rowset, &sql));
+        // java.sql requires transitive java.xml
+        assert!(loader.can_read(&rowset, &xml));
+        assert!(loader.can_read(&naming, &sasl));
+        // java.naming requires java.security.sasl, but not transitively
+        assert!(!loader.can_read(&rowset, &sasl));
+        assert!(!loader.can_read(&sql, &naming));
+        assert!(!loader.can_read(&object, &sql));
+        assert!(loader.can_read(&no_module, &sql));
+        assert!(loader.can_read(&sql, &no_module));
+    }
+}
@@ -0,0 +1,1179 @@
+use crate::{
+    consts::MethodAccessFlag,
+    descriptor::{FieldType, MethodDescriptor},
+    runtime::{
+        self, AttributeInfo, CodeAttribute, ConstantPoolInfo, Exception, MethodInfo, NativeResult,
+        StackMapFrame, VerificationTypeInfo, famous_classes::VERIFY_ERROR_CLASS,
+        interpreter::instructions as inst,
+    },
+};
+use std::sync::Arc;
+
+// Verification by type checking (JVMS 4.10.1), against the StackMapTable of each method.
+// Only the shape of the frames is checked for now: operand stack depths at every instruction
+// and branch target, and the size of the locals of every stack map frame. Of the types, only
+// uninitialized `this` is tracked, for the rules of instance initialization methods.
+
+pub(super) fn verify_class(class: &runtime::Class) -> NativeResult<()> {
+    for_each_code(class, |verifier| verifier.verify())
+}
+
+/// Checks that the constant pool operands of the instructions of every method are in range and of
+/// the kind the instruction takes (JVMS 4.9.1), for class files of every version, so the
+/// interpreter can look them up without checking
+pub(super) fn verify_constant_references(class: &runtime::Class) -> NativeResult<()> {
+    for_each_code(class, |verifier| verifier.verify_constant_references())
+}
+
+/// Checks that every instruction takes the values on the operand stack by their computational
+/// type category (JVMS 2.11.1), so that e.g. `pop` never takes half of a long, by abstract
+/// interpretation over the categories alone. Runs for class files of every version, when enabled
+/// with `VmBuilder::verify_categories`
+pub(super) fn verify_categories(class: &runtime::Class) -> NativeResult<()> {
+    for_each_code(class, |verifier| verifier.verify_categories())
+}
+
+// runs `verify` on each method with a Code attribute
+fn for_each_code(
+    class: &runtime::Class,
+    verify: impl Fn(&MethodVerifier) -> NativeResult<()>,
+) -> NativeResult<()> {
+    for method in &class.methods {
+        let code = method.attributes.iter().find_map(|attr| match attr {
+            AttributeInfo::Code(code) => Some(code),
+            _ => None,
+        });
+        if let Some(code) = code {
+            verify(&MethodVerifier::new(class, method, code))?;
+        }
+    }
+    Ok(())
+}
+
+// computational type category of a value on the operand stack, 2 for long and double
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Category {
+    One,
+    Two,
+}
+
+impl Category {
+    fn of(field_type: &FieldType) -> Self {
+        if field_type.is_long() {
+            Category::Two
+        } else {
+            Category::One
+        }
+    }
+
+    fn slots(self) -> usize {
+        match self {
+            Category::One => 1,
+            Category::Two => 2,
+        }
+    }
+
+    fn slots_of(categories: &[Category]) -> usize {
+        categories.iter().map(|category| category.slots()).sum()
+    }
+}
+
+// state of the abstract interpretation of `MethodVerifier::verify_categories`
+struct CategoryFlow {
+    // whether an instruction starts at each offset
+    instructions: Vec<bool>,
+    // categories on the operand stack before each instruction, once it is reached
+    stacks: Vec<Option<Vec<Category>>>,
+    // instructions reached but not interpreted yet
+    pending: Vec<usize>,
+}
+
+impl CategoryFlow {
+    /// Hands the operand stack at `from` to the instruction at `target`, which must agree with
+    /// the categories it is reached with from elsewhere.
+    fn merge(
+        &mut self,
+        verifier: &MethodVerifier,
+        from: usize,
+        target: isize,
+        stack: &[Category],
+    ) -> NativeResult<()> {
+        let Some(target) = usize::try_from(target)
+            .ok()
+            .filter(|target| self.instructions.get(*target) == Some(&true))
+        else {
+            return Err(verifier.error(from, "illegal target of jump or branch"));
+        };
+        match &self.stacks[target] {
+            None => {
+                self.stacks[target] = Some(stack.to_vec());
+                self.pending.push(target);
+            }
+            Some(reached) if reached != stack => {
+                return Err(verifier.error(
+                    from,
+                    &format!("inconsistent operand stack categories at {target}"),
+                ));
+            }
+            Some(_) => {}
+        }
+        Ok(())
+    }
+}
+
+// locals and operand stack of a stack map frame, replayed from the StackMapTable
+struct FrameTypes {
+    locals: Vec<VerificationTypeInfo>,
+    stack: Vec<VerificationTypeInfo>,
+}
+
+impl FrameTypes {
+    fn stack_depth(&self) -> usize {
+        self.stack
+            .iter()
+            .map(VerificationTypeInfo::slot_count)
+            .sum()
+    }
+
+    // the type of each slot, the second slot of a long or double being top
+    fn slots(types: &[VerificationTypeInfo]) -> Vec<&VerificationTypeInfo> {
+        types
+            .iter()
+            .flat_map(|info| {
+                let second = (info.slot_count() == 2).then_some(&VerificationTypeInfo::Top);
+                std::iter::once(info).chain(second)
+            })
+            .collect()
+    }
+}
+
+struct MethodVerifier<'a> {
+    class: &'a runtime::Class,
+    method: &'a MethodInfo,
+    code: &'a CodeAttribute,
+}
+
+impl<'a> MethodVerifier<'a> {
+    fn new(class: &'a runtime::Class, method: &'a MethodInfo, code: &'a CodeAttribute) -> Self {
+        Self {
+            class,
+            method,
+            code,
+        }
+    }
+
+    fn error(&self, pc: usize, message: &str) -> Exception {
+        Exception::new_vm_at(
+            VERIFY_ERROR_CLASS.get().expect("must have init"),
+            self.class,
+            &self.method.name.to_str(),
+            pc,
+            message,
+        )
+    }
+
+    fn verify(&self) -> NativeResult<()> {
+        let code = &self.code.code;
+        let mut instructions = vec![false; code.len()];
+        let mut pc = 0;
+        while pc < code.len() {
+            instructions[pc] = true;
+            pc += self.instruction_length(pc)?;
+        }
+        if pc != code.len() {
+            return Err(self.error(pc, "truncated instruction"));
+        }
+
+        let frames = self.stack_map_frames(&instructions)?;
+        // stack depth of the stack map frame at each offset
+        let frame_at = |target: usize| {
+            frames
+                .get(target)
+                .and_then(Option::as_ref)
+                .map(FrameTypes::stack_depth)
+        };
+
+        for item in &self.code.exception_table {
+            let handler_pc = item.handler_pc as usize;
+            if item.start_pc >= item.end_pc || item.end_pc as usize > code.len() {
+                return Err(self.error(handler_pc, "illegal exception handler range"));
+            }
+            if frame_at(handler_pc) != Some(1) {
+                return Err(self.error(
+                    handler_pc,
+                    "expecting a stack map frame with the exception at the handler",
+                ));
+            }
+        }
+
+        let max_stack = self.code.max_stack as usize;
+        let mut depth = 0;
+        // whether the instruction can be reached from the previous one
+        let mut falls_through = true;
+        let mut pc = 0;
+        while pc < code.len() {
+            match frame_at(pc) {
+                Some(frame_depth) if falls_through && frame_depth != depth => {
+                    return Err(self.error(
+                        pc,
+                        &format!(
+                            "inconsistent stack depth {depth}, stack map frame has {frame_depth}"
+                        ),
+                    ));
+                }
+                Some(frame_depth) => depth = frame_depth,
+                None if !falls_through => {
+                    return Err(self.error(pc, "expecting a stack map frame"));
+                }
+                None => {}
+            }
+
+            let (pop, push) = self.stack_effect(pc)?;
+            if depth < pop {
+                return Err(self.error(pc, "operand stack underflow"));
+            }
+            depth -= pop;
+
+            for target in self.branch_targets(pc) {
+                let frame_depth = usize::try_from(target)
+                    .ok()
+                    .filter(|target| instructions.get(*target) == Some(&true))
+                    .and_then(frame_at);
+                let Some(frame_depth) = frame_depth else {
+                    return Err(self.error(
+                        pc,
+                        &format!("expecting a stack map frame at branch target {target}"),
+                    ));
+                };
+                if frame_depth != depth {
+                    return Err(self.error(
+                        pc,
+                        &format!(
+                            "inconsistent stack depth {depth} at branch target {target}, \
+                             stack map frame has {frame_depth}"
+                        ),
+                    ));
+                }
+            }
+
+            depth += push;
+            if depth > max_stack {
+                return Err(self.error(pc, "operand stack overflow"));
+            }
+
+            falls_through = Self::falls_through(code[pc]);
+            pc += self.instruction_length(pc)?;
+        }
+        if falls_through {
+            return Err(self.error(pc, "falling off the end of the code"));
+        }
+
+        if self.initializes_this() {
+            self.verify_uninitialized_this(&frames)?;
+        }
+        Ok(())
+    }
+
+    // whether `this` is uninitialized in the method until it calls another `<init>`, which is
+    // every instance initialization method except the one of Object
+    fn initializes_this(&self) -> bool {
+        self.method.name.to_str() == "<init>" && &*self.class.class_name != "java/lang/Object"
+    }
+
+    /// Checks the uses of uninitialized `this` in an instance initialization method: it may only
+    /// be stored, moved, compared, have the fields of this class put, or have another `<init>`
+    /// invoked on it, which initializes it, and the method must not return before that.
+    ///
+    /// Tracks which slots of the locals and operand stack hold it, alongside the stack depths
+    /// `verify` has already checked, so the operand stack never underflows here.
+    fn verify_uninitialized_this(&self, frames: &[Option<FrameTypes>]) -> NativeResult<()> {
+        let code = &self.code.code;
+        let mut locals = vec![false; self.code.max_locals as usize];
+        if let Some(this) = locals.first_mut() {
+            *this = true;
+        }
+        let mut stack = vec![];
+        // whether `this` has not been initialized yet
+        let mut uninitialized = true;
+        let mut falls_through = true;
+        let mut pc = 0;
+        while pc < code.len() {
+            if let Some(frame) = &frames[pc] {
+                if falls_through {
+                    self.match_uninitialized_this(pc, None, frame, &locals, &stack, uninitialized)?;
+                }
+                locals = FrameTypes::slots(&frame.locals)
+                    .into_iter()
+                    .map(|info| *info == VerificationTypeInfo::UninitializedThis)
+                    .collect();
+                uninitialized = locals.contains(&true);
+                locals.resize(self.code.max_locals as usize, false);
+                stack = FrameTypes::slots(&frame.stack)
+                    .into_iter()
+                    .map(|info| *info == VerificationTypeInfo::UninitializedThis)
+                    .collect();
+            }
+            for item in &self.code.exception_table {
+                if (item.start_pc as usize..item.end_pc as usize).contains(&pc) {
+                    let handler_pc = item.handler_pc as usize;
+                    let frame = frames[handler_pc]
+                        .as_ref()
+                        .expect("verify checks the handlers");
+                    // the handler is entered with only the exception on the operand stack
+                    self.match_uninitialized_this(
+                        pc,
+                        Some(handler_pc as isize),
+                        frame,
+                        &locals,
+                        &[false],
+                        uninitialized,
+                    )?;
+                }
+            }
+
+            self.apply_uninitialized_this(pc, &mut locals, &mut stack, &mut uninitialized)?;
+            for target in self.branch_targets(pc) {
+                let frame = frames[target as usize]
+                    .as_ref()
+                    .expect("verify checks the branch targets");
+                self.match_uninitialized_this(
+                    pc,
+                    Some(target),
+                    frame,
+                    &locals,
+                    &stack,
+                    uninitialized,
+                )?;
+            }
+
+            falls_through = Self::falls_through(code[pc]);
+            pc += self.instruction_length(pc)?;
+        }
+        Ok(())
+    }
+
+    fn apply_uninitialized_this(
+        &self,
+        pc: usize,
+        locals: &mut [bool],
+        stack: &mut Vec<bool>,
+        uninitialized: &mut bool,
+    ) -> NativeResult<()> {
+        let code = &self.code.code;
+        let used = || self.error(pc, "uninitialized this used before calling <init>");
+        // the local variable loaded or stored, and the instruction as if not wide or `_<n>`
+        let (op, index) = match code[pc] {
+            inst::WIDE => (code[pc + 1], self.u16_at(pc + 2) as usize),
+            op @ (inst::ILOAD..=inst::ALOAD | inst::ISTORE..=inst::ASTORE) => {
+                (op, code[pc + 1] as usize)
+            }
+            op @ inst::ILOAD_0..=inst::ALOAD_3 => (
+                inst::ILOAD + (op - inst::ILOAD_0) / 4,
+                ((op - inst::ILOAD_0) % 4) as usize,
+            ),
+            op @ inst::ISTORE_0..=inst::ASTORE_3 => (
+                inst::ISTORE + (op - inst::ISTORE_0) / 4,
+                ((op - inst::ISTORE_0) % 4) as usize,
+            ),
+            op => (op, 0),
+        };
+        let local_slots = match op {
+            inst::ILOAD..=inst::ALOAD | inst::ISTORE..=inst::ASTORE => {
+                if matches!(op, inst::LLOAD | inst::DLOAD | inst::LSTORE | inst::DSTORE) {
+                    2
+                } else {
+                    1
+                }
+            }
+            _ => 0,
+        };
+        if index + local_slots > locals.len() {
+            return Err(self.error(pc, "illegal local variable index"));
+        }
+
+        // slots of the stack from the bottom up, before and after
+        let permute = |pop: usize, order: &[usize], stack: &mut Vec<bool>| {
+            let popped = stack.split_off(stack.len() - pop);
+            stack.extend(order.iter().map(|slot| popped[*slot]));
+        };
+        match op {
+            inst::ALOAD => stack.push(locals[index]),
+            inst::ASTORE => locals[index] = stack.pop().expect("checked by verify"),
+            inst::POP => permute(1, &[], stack),
+            inst::POP2 => permute(2, &[], stack),
+            inst::DUP => permute(1, &[0, 0], stack),
+            inst::DUP_X1 => permute(2, &[1, 0, 1], stack),
+            inst::DUP_X2 => permute(3, &[2, 0, 1, 2], stack),
+            inst::DUP2 => permute(2, &[0, 1, 0, 1], stack),
+            inst::DUP2_X1 => permute(3, &[1, 2, 0, 1, 2], stack),
+            inst::DUP2_X2 => permute(4, &[2, 3, 0, 1, 2, 3], stack),
+            inst::SWAP => permute(2, &[1, 0], stack),
+            // uninitialized this is a reference like any other to these
+            inst::IFNULL
+            | inst::IFNONNULL
+            | inst::IF_ACMPEQ
+            | inst::IF_ACMPNE
+            | inst::MONITORENTER
+            | inst::MONITOREXIT
+            | inst::AASTORE => {
+                let (pop, _) = self.stack_effect(pc)?;
+                stack.truncate(stack.len() - pop);
+            }
+            inst::PUTFIELD => {
+                let ConstantPoolInfo::Fieldref(field_ref) =
+                    self.constant(pc, self.u16_at(pc + 1))?
+                else {
+                    return Err(self.error(pc, "expecting a field reference"));
+                };
+                let value = field_ref.name_and_type.descriptor.0.slot_count();
+                if stack.split_off(stack.len() - value).contains(&true) {
+                    return Err(used());
+                }
+                // the fields of this class can be put before calling <init>, e.g. `this$0`
+                let object = stack.pop().expect("checked by verify");
+                if object && field_ref.class_name != self.class.class_name {
+                    return Err(used());
+                }
+            }
+            inst::INVOKESPECIAL => {
+                let (args, ret) = self.method_categories(pc)?;
+                if stack
+                    .split_off(stack.len() - Category::slots_of(&args))
+                    .contains(&true)
+                {
+                    return Err(used());
+                }
+                if stack.pop().expect("checked by verify") {
+                    let (ConstantPoolInfo::Methodref(method_ref)
+                    | ConstantPoolInfo::InterfaceMethodref(method_ref)) =
+                        self.constant(pc, self.u16_at(pc + 1))?
+                    else {
+                        return Err(self.error(pc, "expecting a method reference"));
+                    };
+                    if method_ref.name_and_type.name.to_str() != "<init>" {
+                        return Err(used());
+                    }
+                    locals.iter_mut().for_each(|this| *this = false);
+                    stack.iter_mut().for_each(|this| *this = false);
+                    *uninitialized = false;
+                }
+                stack.resize(stack.len() + Category::slots_of(&ret), false);
+            }
+            inst::RETURN if *uninitialized => {
+                return Err(self.error(pc, "constructor must call super() or this() before return"));
+            }
+            _ => {
+                let (pop, push) = self.stack_effect(pc)?;
+                if stack.split_off(stack.len() - pop).contains(&true) {
+                    return Err(used());
+                }
+                stack.resize(stack.len() + push, false);
+                // overwritten by another value
+                if (inst::ISTORE..=inst::DSTORE).contains(&op) {
+                    locals[index..index + local_slots].fill(false);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that uninitialized `this` is in the same slots as in the stack map frame, which is
+    /// at `target`, or at `pc` itself when falling through to it.
+    fn match_uninitialized_this(
+        &self,
+        pc: usize,
+        target: Option<isize>,
+        frame: &FrameTypes,
+        locals: &[bool],
+        stack: &[bool],
+        uninitialized: bool,
+    ) -> NativeResult<()> {
+        let matches = |slots: &[bool], frame_slots: Vec<&VerificationTypeInfo>| {
+            slots
+                .iter()
+                .enumerate()
+                .all(|(index, this)| match frame_slots.get(index) {
+                    None | Some(VerificationTypeInfo::Top) => true,
+                    Some(VerificationTypeInfo::UninitializedThis) => *this,
+                    Some(_) => !*this,
+                })
+        };
+        let frame_locals = FrameTypes::slots(&frame.locals);
+        // the frame is of an uninitialized this if any of its locals is
+        let frame_uninitialized = frame_locals.contains(&&VerificationTypeInfo::UninitializedThis);
+        if matches(locals, frame_locals)
+            && matches(stack, FrameTypes::slots(&frame.stack))
+            && (!uninitialized || frame_uninitialized)
+        {
+            return Ok(());
+        }
+        Err(self.error(
+            pc,
+            &match target {
+                Some(target) => {
+                    format!("uninitialized this does not match the stack map frame at {target}")
+                }
+                None => "uninitialized this does not match the stack map frame".to_string(),
+            },
+        ))
+    }
+
+    // whether the instruction after `op` can be reached from it, jsr aside
+    fn falls_through(op: u8) -> bool {
+        !matches!(
+            op,
+            inst::GOTO
+                | inst::GOTO_W
+                | inst::TABLESWITCH
+                | inst::LOOKUPSWITCH
+                | inst::IRETURN
+                | inst::LRETURN
+                | inst::FRETURN
+                | inst::DRETURN
+                | inst::ARETURN
+                | inst::RETURN
+                | inst::ATHROW
+                | inst::RET
+        )
+    }
+
+    fn verify_categories(&self) -> NativeResult<()> {
+        let code = &self.code.code;
+        if code.is_empty() {
+            return Err(self.error(0, "falling off the end of the code"));
+        }
+        let mut instructions = vec![false; code.len()];
+        let mut pc = 0;
+        while pc < code.len() {
+            instructions[pc] = true;
+            pc += self.instruction_length(pc)?;
+        }
+        if pc != code.len() {
+            return Err(self.error(pc, "truncated instruction"));
+        }
+
+        let mut flow = CategoryFlow {
+            stacks: vec![None; code.len()],
+            pending: vec![0],
+            instructions,
+        };
+        flow.stacks[0] = Some(vec![]);
+        for item in &self.code.exception_table {
+            let handler_pc = item.handler_pc as usize;
+            flow.merge(self, handler_pc, handler_pc as isize, &[Category::One])?;
+        }
+
+        while let Some(pc) = flow.pending.pop() {
+            let mut stack = flow.stacks[pc].clone().expect("pending must be reached");
+            let next = pc + self.instruction_length(pc)?;
+            if matches!(code[pc], inst::JSR | inst::JSR_W) {
+                let offset = if code[pc] == inst::JSR {
+                    self.u16_at(pc + 1) as i16 as isize
+                } else {
+                    self.i32_at(pc + 1)? as isize
+                };
+                let mut subroutine = stack.clone();
+                subroutine.push(Category::One);
+                flow.merge(self, pc, pc as isize + offset, &subroutine)?;
+                // the subroutine is taken to return with the operand stack of the jsr
+                flow.merge(self, pc, next as isize, &stack)?;
+                continue;
+            }
+
+            self.apply_categories(pc, &mut stack)?;
+            for target in self.branch_targets(pc) {
+                flow.merge(self, pc, target, &stack)?;
+            }
+            if Self::falls_through(code[pc]) {
+                if next == code.len() {
+                    return Err(self.error(pc, "falling off the end of the code"));
+                }
+                flow.merge(self, pc, next as isize, &stack)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_categories(&self, pc: usize, stack: &mut Vec<Category>) -> NativeResult<()> {
+        // slots of the value on top, and of the value it is duplicated or swapped under
+        let (value, under) = match self.code.code[pc] {
+            inst::POP => (1, 0),
+            inst::POP2 => (2, 0),
+            inst::DUP => (1, 0),
+            inst::DUP_X1 => (1, 1),
+            inst::DUP_X2 => (1, 2),
+            inst::DUP2 => (2, 0),
+            inst::DUP2_X1 => (2, 1),
+            inst::DUP2_X2 => (2, 2),
+            inst::SWAP => (1, 1),
+            _ => {
+                let (pop, push) = self.category_effect(pc)?;
+                for expected in pop.into_iter().rev() {
+                    match stack.pop() {
+                        None => return Err(self.error(pc, "operand stack underflow")),
+                        Some(actual) if actual != expected => {
+                            return Err(self.error(
+                                pc,
+                                &format!(
+                                    "expecting a category {} value on the operand stack",
+                                    expected.slots()
+                                ),
+                            ));
+                        }
+                        Some(_) => {}
+                    }
+                }
+                stack.extend(push);
+                return Ok(());
+            }
+        };
+
+        let values = self.split_off_slots(pc, stack, value)?;
+        match self.code.code[pc] {
+            inst::POP | inst::POP2 => {}
+            inst::SWAP => {
+                let unders = self.split_off_slots(pc, stack, under)?;
+                stack.extend(values);
+                stack.extend(unders);
+            }
+            _ => {
+                let unders = self.split_off_slots(pc, stack, under)?;
+                stack.extend(&values);
+                stack.extend(unders);
+                stack.extend(values);
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes the values making up the top `slots` of the operand stack, which must not split one.
+    fn split_off_slots(
+        &self,
+        pc: usize,
+        stack: &mut Vec<Category>,
+        slots: usize,
+    ) -> NativeResult<Vec<Category>> {
+        let mut taken = 0;
+        let mut len = stack.len();
+        while taken < slots {
+            let Some(category) = len.checked_sub(1).map(|index| stack[index]) else {
+                return Err(self.error(pc, "operand stack underflow"));
+            };
+            taken += category.slots();
+            len -= 1;
+        }
+        if taken != slots {
+            return Err(self.error(pc, "splitting a category 2 value on the operand stack"));
+        }
+        Ok(stack.split_off(len))
+    }
+
+    /// Replays the StackMapTable from the frame implied by the method descriptor.
+    fn stack_map_frames(&self, instructions: &[bool]) -> NativeResult<Vec<Option<FrameTypes>>> {
+        let mut frames: Vec<_> = instructions.iter().map(|_| None).collect();
+        let Some(stack_map_table) = self.code.attributes.iter().find_map(|attr| match attr {
+            AttributeInfo::StackMapTable(frames) => Some(frames),
+            _ => None,
+        }) else {
+            return Ok(frames);
+        };
+
+        let mut locals = self.initial_locals();
+        let mut offset = None;
+        for frame in stack_map_table {
+            let frame_offset = match offset {
+                None => frame.offset_delta() as usize,
+                Some(offset) => offset + frame.offset_delta() as usize + 1,
+            };
+            offset = Some(frame_offset);
+            if instructions.get(frame_offset) != Some(&true) {
+                return Err(self.error(
+                    frame_offset,
+                    "stack map frame is not at the start of an instruction",
+                ));
+            }
+
+            let stack = match frame {
+                StackMapFrame::Same { .. } => vec![],
+                StackMapFrame::SameLocals1StackItem { stack, .. } => vec![stack.clone()],
+                StackMapFrame::Chop { k, .. } => {
+                    let Some(len) = locals.len().checked_sub(*k as usize) else {
+                        return Err(self.error(frame_offset, "chopping too many locals"));
+                    };
+                    locals.truncate(len);
+                    vec![]
+                }
+                StackMapFrame::Append {
+                    locals: appended, ..
+                } => {
+                    locals.extend_from_slice(appended);
+                    vec![]
+                }
+                StackMapFrame::Full {
+                    locals: full_locals,
+                    stack,
+                    ..
+                } => {
+                    locals = full_locals.clone();
+                    stack.clone()
+                }
+            };
+            let frame = FrameTypes {
+                locals: locals.clone(),
+                stack,
+            };
+
+            let locals_size: usize = locals.iter().map(VerificationTypeInfo::slot_count).sum();
+            if locals_size > self.code.max_locals as usize {
+                return Err(self.error(frame_offset, "stack map frame exceeds max_locals"));
+            }
+            if frame.stack_depth() > self.code.max_stack as usize {
+                return Err(self.error(frame_offset, "stack map frame exceeds max_stack"));
+            }
+            frames[frame_offset] = Some(frame);
+        }
+        Ok(frames)
+    }
+
+    fn initial_locals(&self) -> Vec<VerificationTypeInfo> {
+        let mut locals = vec![];
+        if !self.method.access_flags.contains(MethodAccessFlag::STATIC) {
+            // the super class is not loaded yet, so Object is told apart by name
+            if self.initializes_this() {
+                locals.push(VerificationTypeInfo::UninitializedThis);
+            } else {
+                locals.push(VerificationTypeInfo::Object(Arc::clone(
+                    &self.class.class_name,
+                )));
+            }
+        }
+        for param in &self.method.descriptor.parameters {
+            locals.push(match param {
+                FieldType::Byte
+                | FieldType::Char
+                | FieldType::Int
+                | FieldType::Short
+                | FieldType::Boolean => VerificationTypeInfo::Integer,
+                FieldType::Float => VerificationTypeInfo::Float,
+                FieldType::Long => VerificationTypeInfo::Long,
+                FieldType::Double => VerificationTypeInfo::Double,
+                FieldType::Object(class_name) => {
+                    VerificationTypeInfo::Object(class_name.as_str().into())
+                }
+                FieldType::Array(_) => VerificationTypeInfo::Object(param.to_descriptor().into()),
+            });
+        }
+        locals
+    }
+
+    fn u16_at(&self, pc: usize) -> u16 {
+        u16::from_be_bytes([self.code.code[pc], self.code.code[pc + 1]])
+    }
+
+    fn i32_at(&self, pc: usize) -> NativeResult<i32> {
+        let bytes = self
+            .code
+            .code
+            .get(pc..pc + 4)
+            .ok_or_else(|| self.error(pc, "truncated instruction"))?;
+        Ok(i32::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    // the operands of tableswitch and lookupswitch are 4-byte aligned
+    fn switch_operands(pc: usize) -> usize {
+        (pc + 4) & !3
+    }
+
+    fn instruction_length(&self, pc: usize) -> NativeResult<usize> {
+        let length = match self.code.code[pc] {
+            inst::BIPUSH
+            | inst::LDC
+            | inst::ILOAD
+            | inst::LLOAD
+            | inst::FLOAD
+            | inst::DLOAD
+            | inst::ALOAD
+            | inst::ISTORE
+            | inst::LSTORE
+            | inst::FSTORE
+            | inst::DSTORE
+            | inst::ASTORE
+            | inst::RET
+            | inst::NEWARRAY => 2,
+            inst::SIPUSH
+            | inst::LDC_W
+            | inst::LDC2_W
+            | inst::IINC
+            | inst::IFEQ..=inst::JSR
+            | inst::GETSTATIC..=inst::INVOKESTATIC
+            | inst::NEW
+            | inst::ANEWARRAY
+            | inst::CHECKCAST
+            | inst::INSTANCEOF
+            | inst::IFNULL
+            | inst::IFNONNULL => 3,
+            inst::MULTIANEWARRAY => 4,
+            inst::INVOKEINTERFACE | inst::INVOKEDYNAMIC | inst::GOTO_W | inst::JSR_W => 5,
+            inst::WIDE => match self.code.code.get(pc + 1) {
+                Some(&inst::IINC) => 6,
+                _ => 4,
+            },
+            inst::TABLESWITCH => {
+                let operands = Self::switch_operands(pc);
+                let low = self.i32_at(operands + 4)?;
+                let high = self.i32_at(operands + 8)?;
+                if low > high {
+                    return Err(self.error(pc, "tableswitch low is greater than high"));
+                }
+                operands - pc + 12 + 4 * (high as i64 - low as i64 + 1) as usize
+            }
+            inst::LOOKUPSWITCH => {
+                let operands = Self::switch_operands(pc);
+                let npairs = self.i32_at(operands + 4)?;
+                if npairs < 0 {
+                    return Err(self.error(pc, "lookupswitch npairs is negative"));
+                }
+                operands - pc + 8 + 8 * npairs as usize
+            }
+            _ => 1,
+        };
+        Ok(length)
+    }
+
+    fn branch_targets(&self, pc: usize) -> Vec<isize> {
+        let code = &self.code.code;
+        let branch = |offset: isize| pc as isize + offset;
+        match code[pc] {
+            inst::IFEQ..=inst::IF_ACMPNE | inst::GOTO | inst::IFNULL | inst::IFNONNULL => {
+                vec![branch(self.u16_at(pc + 1) as i16 as isize)]
+            }
+            inst::GOTO_W => vec![branch(self.i32_at(pc + 1).unwrap() as isize)],
+            inst::TABLESWITCH | inst::LOOKUPSWITCH => {
+                let operands = Self::switch_operands(pc);
+                let offset = |at: usize| branch(self.i32_at(at).unwrap() as isize);
+                let mut targets = vec![offset(operands)];
+                if code[pc] == inst::TABLESWITCH {
+                    let low = self.i32_at(operands + 4).unwrap() as i64;
+                    let high = self.i32_at(operands + 8).unwrap() as i64;
+                    let count = (high - low + 1) as usize;
+                    targets.extend((0..count).map(|i| offset(operands + 12 + 4 * i)));
+                } else {
+                    let npairs = self.i32_at(operands + 4).unwrap() as usize;
+                    targets.extend((0..npairs).map(|i| offset(operands + 12 + 8 * i)));
+                }
+                targets
+            }
+            _ => vec![],
+        }
+    }
+
+    fn verify_constant_references(&self) -> NativeResult<()> {
+        let code = &self.code.code;
+        let mut pc = 0;
+        while pc < code.len() {
+            let length = self.instruction_length(pc)?;
+            if pc + length > code.len() {
+                return Err(self.error(pc, "truncated instruction"));
+            }
+            self.verify_constant_operand(pc)?;
+            pc += length;
+        }
+        Ok(())
+    }
+
+    fn verify_constant_operand(&self, pc: usize) -> NativeResult<()> {
+        let code = &self.code.code;
+        let index = match code[pc] {
+            inst::LDC => code[pc + 1] as u16,
+            inst::LDC_W
+            | inst::LDC2_W
+            | inst::GETSTATIC..=inst::INVOKEDYNAMIC
+            | inst::NEW
+            | inst::ANEWARRAY
+            | inst::CHECKCAST
+            | inst::INSTANCEOF
+            | inst::MULTIANEWARRAY => self.u16_at(pc + 1),
+            _ => return Ok(()),
+        };
+        let constant = self.constant(pc, index)?;
+        let (valid, message) = match code[pc] {
+            inst::LDC | inst::LDC_W => (
+                matches!(
+                    constant,
+                    ConstantPoolInfo::Integer(_)
+                        | ConstantPoolInfo::Float(_)
+                        | ConstantPoolInfo::String(_)
+                        | ConstantPoolInfo::Class(_)
+                        | ConstantPoolInfo::MethodHandle(_)
+                        | ConstantPoolInfo::MethodType(_)
+                ) || matches!(
+                    constant,
+                    ConstantPoolInfo::Dynamic { name_and_type, .. }
+                        if name_and_type.descriptor.0.slot_count() == 1
+                ),
+                "illegal constant type for ldc",
+            ),
+            inst::LDC2_W => (
+                matches!(
+                    constant,
+                    ConstantPoolInfo::Long(_) | ConstantPoolInfo::Double(_)
+                ) || matches!(
+                    constant,
+                    ConstantPoolInfo::Dynamic { name_and_type, .. }
+                        if name_and_type.descriptor.0.slot_count() == 2
+                ),
+                "illegal constant type for ldc2_w",
+            ),
+            inst::GETSTATIC..=inst::PUTFIELD => (
+                matches!(constant, ConstantPoolInfo::Fieldref(_)),
+                "expecting a field reference",
+            ),
+            inst::INVOKEVIRTUAL => (
+                matches!(constant, ConstantPoolInfo::Methodref(_)),
+                "expecting a method reference",
+            ),
+            // interface methods are invoked by invokespecial and invokestatic since java 8
+            inst::INVOKESPECIAL | inst::INVOKESTATIC => (
+                matches!(
+                    constant,
+                    ConstantPoolInfo::Methodref(_) | ConstantPoolInfo::InterfaceMethodref(_)
+                ),
+                "expecting a method reference",
+            ),
+            inst::INVOKEINTERFACE => (
+                matches!(constant, ConstantPoolInfo::InterfaceMethodref(_)),
+                "expecting an interface method reference",
+            ),
+            inst::INVOKEDYNAMIC => (
+                matches!(constant, ConstantPoolInfo::InvokeDynamic { .. }),
+                "expecting a dynamically-computed call site",
+            ),
+            _ => (
+                matches!(constant, ConstantPoolInfo::Class(_)),
+                "expecting a class",
+            ),
+        };
+        if !valid {
+            return Err(self.error(pc, message));
+        }
+        Ok(())
+    }
+
+    /// Slots popped and pushed by the instruction at `pc`.
+    fn stack_effect(&self, pc: usize) -> NativeResult<(usize, usize)> {
+        let effect = match self.code.code[pc] {
+            inst::POP => (1, 0),
+            inst::POP2 => (2, 0),
+            inst::DUP => (1, 2),
+            inst::DUP_X1 => (2, 3),
+            inst::DUP_X2 => (3, 4),
+            inst::DUP2 => (2, 4),
+            inst::DUP2_X1 => (3, 5),
+            inst::DUP2_X2 => (4, 6),
+            inst::SWAP => (2, 2),
+            inst::JSR | inst::JSR_W | inst::RET => {
+                return Err(self.error(pc, "jsr and ret are not allowed with stack map frames"));
+            }
+            _ => {
+                let (pop, push) = self.category_effect(pc)?;
+                (Category::slots_of(&pop), Category::slots_of(&push))
+            }
+        };
+        Ok(effect)
+    }
+
+    /// Categories of the values popped, from the bottom of the stack up, and pushed by the
+    /// instruction at `pc`, which is not one of the stack manipulation instructions `pop` to
+    /// `swap`, as those take values of either category.
+    fn category_effect(&self, pc: usize) -> NativeResult<(Vec<Category>, Vec<Category>)> {
+        use Category::{One, Two};
+        let code = &self.code.code;
+        let effect = match code[pc] {
+            inst::NOP | inst::IINC | inst::GOTO | inst::GOTO_W | inst::RETURN | inst::RET => {
+                (vec![], vec![])
+            }
+            inst::ACONST_NULL
+            | inst::ICONST_M1..=inst::ICONST_5
+            | inst::FCONST_0..=inst::FCONST_2
+            | inst::BIPUSH
+            | inst::SIPUSH
+            | inst::ILOAD
+            | inst::FLOAD
+            | inst::ALOAD
+            | inst::ILOAD_0..=inst::ILOAD_3
+            | inst::FLOAD_0..=inst::FLOAD_3
+            | inst::ALOAD_0..=inst::ALOAD_3
+            | inst::NEW
+            | inst::JSR
+            | inst::JSR_W => (vec![], vec![One]),
+            inst::LCONST_0
+            | inst::LCONST_1
+            | inst::DCONST_0
+            | inst::DCONST_1
+            | inst::LLOAD
+            | inst::DLOAD
+            | inst::LLOAD_0..=inst::LLOAD_3
+            | inst::DLOAD_0..=inst::DLOAD_3 => (vec![], vec![Two]),
+            inst::LDC => (vec![], vec![self.ldc_category(pc, code[pc + 1] as u16)?]),
+            inst::LDC_W | inst::LDC2_W => {
+                (vec![], vec![self.ldc_category(pc, self.u16_at(pc + 1))?])
+            }
+            inst::IALOAD
+            | inst::FALOAD
+            | inst::AALOAD
+            | inst::BALOAD
+            | inst::CALOAD
+            | inst::SALOAD => (vec![One, One], vec![One]),
+            inst::LALOAD | inst::DALOAD => (vec![One, One], vec![Two]),
+            inst::ISTORE
+            | inst::FSTORE
+            | inst::ASTORE
+            | inst::ISTORE_0..=inst::ISTORE_3
+            | inst::FSTORE_0..=inst::FSTORE_3
+            | inst::ASTORE_0..=inst::ASTORE_3 => (vec![One], vec![]),
+            inst::LSTORE
+            | inst::DSTORE
+            | inst::LSTORE_0..=inst::LSTORE_3
+            | inst::DSTORE_0..=inst::DSTORE_3 => (vec![Two], vec![]),
+            inst::IASTORE
+            | inst::FASTORE
+            | inst::AASTORE
+            | inst::BASTORE
+            | inst::CASTORE
+            | inst::SASTORE => (vec![One, One, One], vec![]),
+            inst::LASTORE | inst::DASTORE => (vec![One, One, Two], vec![]),
+            inst::IADD
+            | inst::ISUB
+            | inst::IMUL
+            | inst::IDIV
+            | inst::IREM
+            | inst::ISHL
+            | inst::ISHR
+            | inst::IUSHR
+            | inst::IAND
+            | inst::IOR
+            | inst::IXOR
+            | inst::FADD
+            | inst::FSUB
+            | inst::FMUL
+            | inst::FDIV
+            | inst::FREM
+            | inst::FCMPL
+            | inst::FCMPG => (vec![One, One], vec![One]),
+            inst::LADD
+            | inst::LSUB
+            | inst::LMUL
+            | inst::LDIV
+            | inst::LREM
+            | inst::LAND
+            | inst::LOR
+            | inst::LXOR
+            | inst::DADD
+            | inst::DSUB
+            | inst::DMUL
+            | inst::DDIV
+            | inst::DREM => (vec![Two, Two], vec![Two]),
+            inst::LSHL | inst::LSHR | inst::LUSHR => (vec![Two, One], vec![Two]),
+            inst::LCMP | inst::DCMPL | inst::DCMPG => (vec![Two, Two], vec![One]),
+            inst::INEG
+            | inst::FNEG
+            | inst::I2F
+            | inst::F2I
+            | inst::I2B
+            | inst::I2C
+            | inst::I2S
+            | inst::NEWARRAY
+            | inst::ANEWARRAY
+            | inst::ARRAYLENGTH
+            | inst::CHECKCAST
+            | inst::INSTANCEOF => (vec![One], vec![One]),
+            inst::LNEG | inst::DNEG | inst::L2D | inst::D2L => (vec![Two], vec![Two]),
+            inst::I2L | inst::I2D | inst::F2L | inst::F2D => (vec![One], vec![Two]),
+            inst::L2I | inst::L2F | inst::D2I | inst::D2F => (vec![Two], vec![One]),
+            inst::IFEQ..=inst::IFLE
+            | inst::IFNULL
+            | inst::IFNONNULL
+            | inst::TABLESWITCH
+            | inst::LOOKUPSWITCH
+            | inst::IRETURN
+            | inst::FRETURN
+            | inst::ARETURN
+            | inst::ATHROW
+            | inst::MONITORENTER
+            | inst::MONITOREXIT => (vec![One], vec![]),
+            inst::IF_ICMPEQ..=inst::IF_ACMPNE => (vec![One, One], vec![]),
+            inst::LRETURN | inst::DRETURN => (vec![Two], vec![]),
+            inst::GETSTATIC => (vec![], vec![self.field_category(pc)?]),
+            inst::PUTSTATIC => (vec![self.field_category(pc)?], vec![]),
+            inst::GETFIELD => (vec![One], vec![self.field_category(pc)?]),
+            inst::PUTFIELD => (vec![One, self.field_category(pc)?], vec![]),
+            inst::INVOKEVIRTUAL | inst::INVOKESPECIAL | inst::INVOKEINTERFACE => {
+                let (mut args, ret) = self.method_categories(pc)?;
+                args.insert(0, One);
+                (args, ret)
+            }
+            inst::INVOKESTATIC | inst::INVOKEDYNAMIC => self.method_categories(pc)?,
+            inst::MULTIANEWARRAY => (vec![One; code[pc + 3] as usize], vec![One]),
+            inst::WIDE => match code.get(pc + 1) {
+                Some(
+                    &(inst::ILOAD
+                    | inst::FLOAD
+                    | inst::ALOAD
+                    | inst::LLOAD
+                    | inst::DLOAD
+                    | inst::ISTORE
+                    | inst::FSTORE
+                    | inst::ASTORE
+                    | inst::LSTORE
+                    | inst::DSTORE
+                    | inst::IINC),
+                ) => self.category_effect(pc + 1)?,
+                _ => return Err(self.error(pc, "illegal instruction after wide")),
+            },
+            op => return Err(self.error(pc, &format!("unknown instruction {op}"))),
+        };
+        Ok(effect)
+    }
+
+    fn constant(&self, pc: usize, index: u16) -> NativeResult<&'a ConstantPoolInfo> {
+        index
+            .checked_sub(1)
+            .and_then(|index| self.class.constant_pool.get(index as usize))
+            .ok_or_else(|| self.error(pc, &format!("illegal constant pool index {index}")))
+    }
+
+    fn ldc_category(&self, pc: usize, index: u16) -> NativeResult<Category> {
+        let category = match self.constant(pc, index)? {
+            ConstantPoolInfo::Long(_) | ConstantPoolInfo::Double(_) => Category::Two,
+            ConstantPoolInfo::Dynamic { name_and_type, .. } => {
+                Category::of(&name_and_type.descriptor.0)
+            }
+            _ => Category::One,
+        };
+        if (category == Category::Two) != (self.code.code[pc] == inst::LDC2_W) {
+            return Err(self.error(pc, "illegal constant type for ldc"));
+        }
+        Ok(category)
+    }
+
+    fn field_category(&self, pc: usize) -> NativeResult<Category> {
+        let ConstantPoolInfo::Fieldref(field_ref) = self.constant(pc, self.u16_at(pc + 1))? else {
+            return Err(self.error(pc, "expecting a field reference"));
+        };
+        Ok(Category::of(&field_ref.name_and_type.descriptor.0))
+    }
+
+    fn method_categories(&self, pc: usize) -> NativeResult<(Vec<Category>, Vec<Category>)> {
+        let descriptor: &MethodDescriptor = match self.constant(pc, self.u16_at(pc + 1))? {
+            ConstantPoolInfo::Methodref(method_ref)
+            | ConstantPoolInfo::InterfaceMethodref(method_ref) => {
+                &method_ref.name_and_type.descriptor
+            }
+            ConstantPoolInfo::InvokeDynamic { name_and_type, .. } => &name_and_type.descriptor,
+            _ => return Err(self.error(pc, "expecting a method reference")),
+        };
+        let args = descriptor.parameters.iter().map(Category::of).collect();
+        let ret = descriptor.return_type.iter().map(Category::of).collect();
+        Ok((args, ret))
+    }
+}
@@ -17,6 +17,8 @@ pub(super) static BYTE_ARRAY_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static CLONEABLE_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static SERIALIZABLE_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static SYSTEM_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static REFERENCE_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static THREAD_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 
 // exceptions
 pub(super) static THROWABLE_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
@@ -27,8 +29,16 @@ pub(super) static ARRAY_STORE_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::
 pub(super) static LINKAGE_ERROR_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static CLASS_CAST_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static CLASS_FORMAT_ERROR_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static NO_CLASS_DEF_FOUND_ERROR_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static NO_SUCH_METHOD_ERROR_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static NO_SUCH_FIELD_ERROR_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static ABSTRACT_METHOD_ERROR_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static INCOMPATIBLE_CLASS_CHANGE_ERROR_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static ILLEGAL_ACCESS_ERROR_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static IO_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static VERIFY_ERROR_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static INTERNAL_ERROR_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static STACK_OVERFLOW_ERROR_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 
 pub(super) static NULL_POINTER_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static CLONE_NOT_SUPPORTED_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
@@ -36,6 +46,11 @@ pub(super) static INDEX_OUT_OF_BOUND_EXCEPTION_CLASS: OnceLock<Arc<Class>> = Onc
 pub(super) static ARRAY_INDEX_OUT_OF_BOUND_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static ARITHMETIC_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static NEGATIVE_ARRAY_SIZE_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static ILLEGAL_ARGUMENT_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static INVOCATION_TARGET_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static INSTANTIATION_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static NO_SUCH_METHOD_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
+pub(super) static CLASS_NOT_FOUND_EXCEPTION_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 
 pub(super) static BOOLEAN_TYPE_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static CHAR_TYPE_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
@@ -47,6 +62,22 @@ pub(super) static INT_TYPE_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static LONG_TYPE_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 pub(super) static VOID_TYPE_CLASS: OnceLock<Arc<Class>> = OnceLock::new();
 
+/// The primitive class of `field_type` like `int.class`, `None` for reference types
+pub(super) fn primitive_type_class(field_type: &FieldType) -> Option<&'static Arc<Class>> {
+    let primitive = match field_type {
+        FieldType::Boolean => &BOOLEAN_TYPE_CLASS,
+        FieldType::Byte => &BYTE_TYPE_CLASS,
+        FieldType::Char => &CHAR_TYPE_CLASS,
+        FieldType::Short => &SHORT_TYPE_CLASS,
+        FieldType::Int => &INT_TYPE_CLASS,
+        FieldType::Long => &LONG_TYPE_CLASS,
+        FieldType::Float => &FLOAT_TYPE_CLASS,
+        FieldType::Double => &DOUBLE_TYPE_CLASS,
+        FieldType::Object(_) | FieldType::Array(_) => return None,
+    };
+    Some(primitive.get().expect("must have init"))
+}
+
 pub(super) fn init_famous_classes() {
     let bootstrap = BOOTSTRAP_CLASS_LOADER.get().unwrap();
 
@@ -88,7 +119,7 @@ pub(super) fn init_famous_classes() {
         )
         .expect("must not be set");
 
-    let bootstrap_thread = runtime::Thread::new(1024);
+    let bootstrap_thread = runtime::Thread::new(0);
     let env = VmEnv::new(&bootstrap_thread, &HEAP);
 
     register_natives();
@@ -100,6 +131,8 @@ pub(super) fn init_famous_classes() {
 
     resolve_famous!(SERIALIZABLE_CLASS, "java/io/Serializable");
     resolve_famous!(SYSTEM_CLASS, "java/lang/System");
+    resolve_famous!(REFERENCE_CLASS, "java/lang/ref/Reference");
+    resolve_famous!(THREAD_CLASS, "java/lang/Thread");
 
     resolve_famous!(THROWABLE_CLASS, "java/lang/Throwable");
     resolve_famous!(ERROR_CLASS, "java/lang/Error");
@@ -109,9 +142,23 @@ pub(super) fn init_famous_classes() {
     resolve_famous!(LINKAGE_ERROR_CLASS, "java/lang/LinkageError");
     resolve_famous!(CLASS_CAST_EXCEPTION_CLASS, "java/lang/ClassCastException");
     resolve_famous!(CLASS_FORMAT_ERROR_CLASS, "java/lang/ClassFormatError");
+    resolve_famous!(
+        NO_CLASS_DEF_FOUND_ERROR_CLASS,
+        "java/lang/NoClassDefFoundError"
+    );
 
     resolve_famous!(NO_SUCH_METHOD_ERROR_CLASS, "java/lang/NoSuchMethodError");
     resolve_famous!(NO_SUCH_FIELD_ERROR_CLASS, "java/lang/NoSuchFieldError");
+    resolve_famous!(ABSTRACT_METHOD_ERROR_CLASS, "java/lang/AbstractMethodError");
+    resolve_famous!(
+        INCOMPATIBLE_CLASS_CHANGE_ERROR_CLASS,
+        "java/lang/IncompatibleClassChangeError"
+    );
+    resolve_famous!(ILLEGAL_ACCESS_ERROR_CLASS, "java/lang/IllegalAccessError");
+    resolve_famous!(IO_EXCEPTION_CLASS, "java/io/IOException");
+    resolve_famous!(VERIFY_ERROR_CLASS, "java/lang/VerifyError");
+    resolve_famous!(INTERNAL_ERROR_CLASS, "java/lang/InternalError");
+    resolve_famous!(STACK_OVERFLOW_ERROR_CLASS, "java/lang/StackOverflowError");
 
     resolve_famous!(
         NULL_POINTER_EXCEPTION_CLASS,
@@ -134,4 +181,24 @@ pub(super) fn init_famous_classes() {
         NEGATIVE_ARRAY_SIZE_EXCEPTION_CLASS,
         "java/lang/NegativeArraySizeException"
     );
+    resolve_famous!(
+        ILLEGAL_ARGUMENT_EXCEPTION_CLASS,
+        "java/lang/IllegalArgumentException"
+    );
+    resolve_famous!(
+        INVOCATION_TARGET_EXCEPTION_CLASS,
+        "java/lang/reflect/InvocationTargetException"
+    );
+    resolve_famous!(
+        INSTANTIATION_EXCEPTION_CLASS,
+        "java/lang/InstantiationException"
+    );
+    resolve_famous!(
+        NO_SUCH_METHOD_EXCEPTION_CLASS,
+        "java/lang/NoSuchMethodException"
+    );
+    resolve_famous!(
+        CLASS_NOT_FOUND_EXCEPTION_CLASS,
+        "java/lang/ClassNotFoundException"
+    );
 }
@@ -1,13 +1,17 @@
-use crate::runtime::{
-    ArrayType, Class, Object, SpecialStringObject, StringTable, StringTableEntry, Variable,
-    heap::reflection::{ClassTable, SpecialClassObject},
-    structs::ObjectMonitor,
+use crate::{
+    descriptor::{FieldDescriptor, parse_field_descriptor},
+    runtime::{
+        ArrayType, Class, Object, SpecialStringObject, StringTable, StringTableEntry, Variable,
+        famous_classes::primitive_type_class,
+        heap::reflection::{ClassTable, SpecialClassObject},
+        structs::ObjectMonitor,
+    },
 };
 use std::{
     alloc::{Layout, alloc},
     cell::UnsafeCell,
     ptr::addr_of_mut,
-    sync::Arc,
+    sync::{Arc, OnceLock},
 };
 
 pub mod reflection;
@@ -15,7 +19,10 @@ pub mod string_table;
 
 pub struct Heap {
     heap: Vec<Option<Arc<Box<HeapObject>>>>,
+    // index past the end of the heap, only grows when no freed slot is left
     next_id: u32,
+    // indexes of the freed slots, reused before growing the heap
+    free_slots: Vec<u32>,
     special_heap: SpecialHeap,
 }
 
@@ -26,13 +33,20 @@ impl Heap {
         Heap {
             heap: vec![],
             next_id: 0,
+            free_slots: vec![],
             special_heap: SpecialHeap {
                 heap: vec![],
                 next_id: 0,
+                free_slots: vec![],
             },
         }
     }
 
+    /// Reserves room for at least `additional` more objects.
+    pub fn reserve(&mut self, additional: usize) {
+        self.heap.reserve(additional);
+    }
+
     /// # Safety
     ///
     /// `init_fields` must write legal `Variable`
@@ -81,13 +95,16 @@ impl Heap {
         allocate_id_for_obj(
             &mut self.heap,
             &mut self.next_id,
+            &mut self.free_slots,
             Box::into_raw(Box::new(unsafe { Box::from_raw(ptr) })),
         )
     }
 
     pub fn deallocate(&mut self, id: u32) {
-        self.heap[(id - 1) as usize].take();
-        self.next_id = id;
+        let index = id - 1;
+        if self.heap[index as usize].take().is_some() {
+            self.free_slots.push(index);
+        }
     }
 
     pub(in crate::runtime) fn get(&self, id: u32) -> Arc<dyn Object> {
@@ -137,6 +154,7 @@ impl Heap {
         allocate_id_for_obj(
             &mut self.heap,
             &mut self.next_id,
+            &mut self.free_slots,
             Box::into_raw(Box::new(unsafe { Box::from_raw(ptr) })),
         )
     }
@@ -163,6 +181,7 @@ impl Heap {
         let bytes_id = allocate_id_for_obj(
             &mut self.special_heap.heap,
             &mut self.special_heap.next_id,
+            &mut self.special_heap.free_slots,
             Box::into_raw(bytes_obj),
         ) | Self::MAX_OBJECT_ID;
 
@@ -170,20 +189,20 @@ impl Heap {
             monitor: ObjectMonitor::new(),
             bytes_id,
             bytes: Arc::clone(&string),
-            hash: 0,
+            hash: OnceLock::new(),
             has_multi_bytes,
         });
 
         let string_id = allocate_id_for_obj(
             &mut self.special_heap.heap,
             &mut self.special_heap.next_id,
+            &mut self.special_heap.free_slots,
             Box::into_raw(string_obj),
         ) | Self::MAX_OBJECT_ID;
 
         let table_entry = StringTableEntry {
             string_id,
             bytes_id,
-            hash: 0,
             has_multi_bytes: false,
         };
 
@@ -197,6 +216,20 @@ impl Heap {
         if let Some(entry) = class_table.map.get(&class_name) {
             return *entry;
         }
+        let component_type = if class.is_array() {
+            // primitive arrays have no element class
+            let element = match &class.array_element_type {
+                Some(element) => Arc::clone(element),
+                None => {
+                    let (_, FieldDescriptor(element)) =
+                        parse_field_descriptor(&class.class_name[1..]).expect("array class name");
+                    Arc::clone(primitive_type_class(&element).expect("must be primitive"))
+                }
+            };
+            self.get_class_object(element, class_table)
+        } else {
+            0
+        };
         assert!(
             self.special_heap.next_id < Self::MAX_OBJECT_ID - 1,
             "heap oom"
@@ -207,11 +240,17 @@ impl Heap {
             monitor: ObjectMonitor::default(),
             name_str: Default::default(),
             package_name_str: Default::default(),
+            generic_info: Default::default(),
+            enum_constants: Default::default(),
+            enum_constant_directory: Default::default(),
+            reflection_data: Default::default(),
+            component_type,
         });
 
         let class_id = allocate_id_for_obj(
             &mut self.special_heap.heap,
             &mut self.special_heap.next_id,
+            &mut self.special_heap.free_slots,
             Box::into_raw(class_obj),
         ) | Self::MAX_OBJECT_ID;
 
@@ -230,23 +269,29 @@ impl Default for Heap {
 pub struct SpecialHeap {
     heap: Vec<Option<Arc<dyn SpecialObject + Sync + Send>>>,
     next_id: u32,
+    free_slots: Vec<u32>,
 }
 
 fn allocate_id_for_obj<T: ?Sized>(
     heap: &mut Vec<Option<Arc<T>>>,
     next_id: &mut u32,
+    free_slots: &mut Vec<u32>,
     object_ptr: *mut T,
 ) -> u32 {
-    let id = *next_id;
     let object = unsafe { Some(Box::from_raw(object_ptr).into()) };
-    if heap.len() <= id as usize {
-        heap.resize_with(id as usize + 1, || None);
-    }
-    heap[id as usize] = object;
-    while (*next_id as usize) < heap.len() && heap[*next_id as usize].is_some() {
-        *next_id += 1;
-    }
-    id + 1
+    let index = match free_slots.pop() {
+        Some(index) => {
+            heap[index as usize] = object;
+            index
+        }
+        None => {
+            let index = *next_id;
+            heap.push(object);
+            *next_id += 1;
+            index
+        }
+    };
+    index + 1
 }
 #[repr(C)]
 #[derive(Debug)]
@@ -411,6 +456,29 @@ mod tests {
         heap.deallocate(id);
     }
 
+    #[test]
+    fn test_reuse_freed_ids() {
+        let mut heap = Heap::new();
+        let ids: Vec<_> = (0..10)
+            .map(|_| heap.allocate_array::<i32>(1, get_class()))
+            .collect();
+        assert_eq!(ids, (1..=10).collect::<Vec<_>>());
+
+        for id in [2, 9, 5] {
+            heap.deallocate(id);
+        }
+        // freeing twice doesn't hand out the id twice
+        heap.deallocate(5);
+
+        let mut reused: Vec<_> = (0..3)
+            .map(|_| heap.allocate_array::<i32>(1, get_class()))
+            .collect();
+        reused.sort();
+        assert_eq!(reused, [2, 5, 9]);
+        assert_eq!(heap.next_id, 10);
+        assert_eq!(heap.allocate_array::<i32>(1, get_class()), 11);
+    }
+
     fn get_class() -> Arc<Class> {
         let class = gen_array_class(Arc::from("[I"));
 
@@ -1,3 +1,4 @@
+use crate::runtime::structs::ObjectMonitor;
 use crate::{
     class::JavaStr,
     runtime::{Class, Object, Variable, famous_classes::CLASS_CLASS, heap::SpecialObject},
@@ -9,7 +10,6 @@ use std::{
         atomic::{AtomicU32, Ordering::Relaxed},
     },
 };
-use crate::runtime::structs::ObjectMonitor;
 
 pub struct ClassTable {
     pub(in crate::runtime) map: HashMap<Arc<str>, u32>,
@@ -29,6 +29,13 @@ pub struct SpecialClassObject {
     pub(in crate::runtime) monitor: ObjectMonitor,
     pub(in crate::runtime) name_str: AtomicU32,
     pub(super) package_name_str: AtomicU32,
+    pub(super) generic_info: AtomicU32,
+    pub(super) enum_constants: AtomicU32,
+    pub(super) enum_constant_directory: AtomicU32,
+    /// the `SoftReference` to the cached reflective members, see `Class.reflectionData`
+    pub(super) reflection_data: AtomicU32,
+    /// class object of the element type for array classes, read by `getComponentType`
+    pub(super) component_type: u32,
 }
 
 impl Object for SpecialClassObject {
@@ -47,6 +54,19 @@ impl Object for SpecialClassObject {
         if field.name.as_ref() == JavaStr::from_str("packageName").as_ref() {
             // SAFETY: class verification guarantees that the field is a String
             self.package_name_str.store(unsafe { v.reference }, Relaxed);
+        } else if field.name.as_ref() == JavaStr::from_str("genericInfo").as_ref() {
+            // SAFETY: class verification guarantees that the field is a ClassRepository
+            self.generic_info.store(unsafe { v.reference }, Relaxed);
+        } else if field.name.as_ref() == JavaStr::from_str("enumConstants").as_ref() {
+            // SAFETY: class verification guarantees that the field is an array
+            self.enum_constants.store(unsafe { v.reference }, Relaxed);
+        } else if field.name.as_ref() == JavaStr::from_str("enumConstantDirectory").as_ref() {
+            // SAFETY: class verification guarantees that the field is a Map
+            self.enum_constant_directory
+                .store(unsafe { v.reference }, Relaxed);
+        } else if field.name.as_ref() == JavaStr::from_str("reflectionData").as_ref() {
+            // SAFETY: class verification guarantees that the field is a SoftReference
+            self.reflection_data.store(unsafe { v.reference }, Relaxed);
         } else {
             panic!("invalid field");
         }
@@ -74,6 +94,29 @@ impl Object for SpecialClassObject {
         } else if field.name.as_ref() == JavaStr::from_str("classLoader").as_ref() {
             // TODO: always bootstrap loader
             Variable { reference: 0 }
+        } else if field.name.as_ref() == JavaStr::from_str("module").as_ref() {
+            // TODO: no module objects yet
+            Variable { reference: 0 }
+        } else if field.name.as_ref() == JavaStr::from_str("genericInfo").as_ref() {
+            Variable {
+                reference: self.generic_info.load(Relaxed),
+            }
+        } else if field.name.as_ref() == JavaStr::from_str("enumConstants").as_ref() {
+            Variable {
+                reference: self.enum_constants.load(Relaxed),
+            }
+        } else if field.name.as_ref() == JavaStr::from_str("enumConstantDirectory").as_ref() {
+            Variable {
+                reference: self.enum_constant_directory.load(Relaxed),
+            }
+        } else if field.name.as_ref() == JavaStr::from_str("reflectionData").as_ref() {
+            Variable {
+                reference: self.reflection_data.load(Relaxed),
+            }
+        } else if field.name.as_ref() == JavaStr::from_str("componentType").as_ref() {
+            Variable {
+                reference: self.component_type,
+            }
         } else {
             panic!("invalid field");
         }
@@ -94,6 +137,10 @@ impl Object for SpecialClassObject {
     fn get_monitor(&self) -> &ObjectMonitor {
         &self.monitor
     }
+
+    fn as_class_object(&self) -> Option<&SpecialClassObject> {
+        Some(self)
+    }
 }
 
 impl SpecialObject for SpecialClassObject {}
@@ -7,7 +7,10 @@ use crate::{
         structs::ObjectMonitor,
     },
 };
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
 
 pub struct StringTable {
     pub(in crate::runtime) map: HashMap<Arc<[u8]>, StringTableEntry>,
@@ -19,13 +22,31 @@ impl StringTable {
             map: Default::default(),
         }
     }
+
+    /// Returns the interned string with contents `bytes`, interning the heap string `string_id`
+    /// backed by `bytes_id` if there is none yet
+    pub(in crate::runtime) fn intern_heap_string(
+        &mut self,
+        bytes: Arc<[u8]>,
+        has_multi_bytes: bool,
+        string_id: u32,
+        bytes_id: u32,
+    ) -> u32 {
+        self.map
+            .entry(bytes)
+            .or_insert(StringTableEntry {
+                string_id,
+                bytes_id,
+                has_multi_bytes,
+            })
+            .string_id
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct StringTableEntry {
     pub(in crate::runtime) string_id: u32,
     pub(in crate::runtime) bytes_id: u32,
-    pub(in crate::runtime) hash: i32,
     pub(in crate::runtime) has_multi_bytes: bool,
 }
 
@@ -39,7 +60,8 @@ pub enum SpecialStringObject {
         monitor: ObjectMonitor,
         bytes_id: u32,
         bytes: Arc<[u8]>,
-        hash: i32,
+        /// `String.hashCode`, computed when the `hash` field is first read
+        hash: OnceLock<i32>,
         has_multi_bytes: bool,
     },
 }
@@ -63,6 +85,7 @@ impl Object for SpecialStringObject {
     unsafe fn get_field(&self, index: usize) -> Variable {
         let SpecialStringObject::String {
             bytes_id,
+            bytes,
             hash,
             has_multi_bytes,
             ..
@@ -77,6 +100,7 @@ impl Object for SpecialStringObject {
             .iter()
             .find(|f| f.index == index as _)
             .expect("invalid field");
+        let hash = || *hash.get_or_init(|| string_hash_code(bytes, *has_multi_bytes));
 
         if field.name.as_ref() == JavaStr::from_str("value").as_ref() {
             Variable {
@@ -87,10 +111,10 @@ impl Object for SpecialStringObject {
                 int: if *has_multi_bytes { 1 } else { 0 },
             }
         } else if field.name.as_ref() == JavaStr::from_str("hash").as_ref() {
-            Variable { int: *hash }
+            Variable { int: hash() }
         } else if field.name.as_ref() == JavaStr::from_str("hashIsZero").as_ref() {
             Variable {
-                int: if *hash == 0 { 1 } else { 0 },
+                int: if hash() == 0 { 1 } else { 0 },
             }
         } else {
             panic!("invalid field");
@@ -141,3 +165,17 @@ impl SpecialStringObject {
         }
     }
 }
+
+/// `String.hashCode` of the `value` `bytes`, which are UTF16 in native byte order if `utf16`,
+/// otherwise LATIN1
+fn string_hash_code(bytes: &[u8], utf16: bool) -> i32 {
+    let hash = |hash: i32, c: u16| hash.wrapping_mul(31).wrapping_add(c as i32);
+    if utf16 {
+        bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+            .fold(0, hash)
+    } else {
+        bytes.iter().map(|&b| b as u16).fold(0, hash)
+    }
+}
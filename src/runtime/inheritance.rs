@@ -37,8 +37,8 @@ pub(in crate::runtime) fn is_assignable_to(source: &Arc<Class>, target: &Arc<Cla
     } else {
         // source is class
         if target.access_flags.contains(ClassAccessFlag::INTERFACE) {
-            // target is interface
-            is_class_implements(source, target)
+            // target is interface, which source is too for the elements of interface arrays
+            source.class_name == target.class_name || is_class_implements(source, target)
         } else {
             is_same_or_sub_class_of(source, target)
         }
@@ -47,7 +47,10 @@ pub(in crate::runtime) fn is_assignable_to(source: &Arc<Class>, target: &Arc<Cla
 
 pub(in crate::runtime) fn is_class_implements(class: &Arc<Class>, interface: &Arc<Class>) -> bool {
     for class_intf in &class.interfaces {
-        if class_intf.class_name == interface.class_name {
+        // interfaces list their super interfaces in `interfaces`
+        if class_intf.class_name == interface.class_name
+            || is_class_implements(class_intf, interface)
+        {
             return true;
         }
     }
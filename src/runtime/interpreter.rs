@@ -1,30 +1,35 @@
+pub(in crate::runtime) mod decoder;
 mod frame;
 pub(crate) mod global;
-mod instructions;
+pub(in crate::runtime) mod instructions;
+mod string_concat;
 
 use crate::{
-    descriptor::{self, FieldType, parse_field_descriptor},
+    consts::MethodAccessFlag,
+    descriptor::{self, FieldType},
     runtime::{
-        self, ArrayType, AttributeInfo, Class, ConstantPoolInfo, CpClassInfo, Exception,
-        FieldResolve, MethodResolve, Methodref, NativeEnv, NativeResult, NativeVariable,
-        ReferenceKind, VmEnv,
+        self, ArrayType, Class, ConstantPoolInfo, CpClassInfo, Exception, FieldResolve,
+        MethodResolve, NativeEnv, NativeResult, NativeVariable, ReferenceKind, VmEnv,
         class_loader::{
-            get_class_object, initialize_class, intern_string, resolve_field,
-            resolve_method_statically, resolve_static_method,
+            abstract_method_error, check_field_access, check_method_access, get_class_object,
+            initialize_class, intern_string, new_array, new_instance, new_multi_array,
+            resolve_field, resolve_interface_method, resolve_method_statically,
+            resolve_static_method, select_interface_method, select_special_method,
         },
         famous_classes::{
-            ARITHMETIC_EXCEPTION_CLASS, ARRAY_INDEX_OUT_OF_BOUND_EXCEPTION_CLASS,
-            ARRAY_STORE_EXCEPTION_CLASS, CLASS_CAST_EXCEPTION_CLASS,
+            ARITHMETIC_EXCEPTION_CLASS, ARRAY_STORE_EXCEPTION_CLASS, CLASS_CAST_EXCEPTION_CLASS,
+            INCOMPATIBLE_CLASS_CHANGE_ERROR_CLASS, INTERNAL_ERROR_CLASS,
             NEGATIVE_ARRAY_SIZE_EXCEPTION_CLASS, NO_SUCH_FIELD_ERROR_CLASS,
-            NO_SUCH_METHOD_ERROR_CLASS, NULL_POINTER_EXCEPTION_CLASS,
+            NO_SUCH_METHOD_ERROR_CLASS, NULL_POINTER_EXCEPTION_CLASS, VERIFY_ERROR_CLASS,
         },
         global::BOOTSTRAP_CLASS_LOADER,
         heap::Heap,
         inheritance::{get_array_len, get_array_type, is_assignable_to},
         native::NATIVE_FUNCTIONS,
-        structs::{get_array_index, put_array_index},
+        structs::{get_array_index_checked, put_array_index_checked},
     },
 };
+use decoder::{LookupSwitch, TableSwitch};
 pub use frame::*;
 use std::{
     cmp::Ordering,
@@ -85,54 +90,43 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                 }
             };
         }
-        let mut wide = false;
-
         use instructions as inst;
+        // not borrowed from the frame, which the instructions modify
+        let code = Arc::clone(&self.frame.code);
         loop {
-            let op = self.frame.code[*self.pc];
+            let instruction = &code[*self.pc];
+            let op = instruction.opcode;
             match op {
                 // load
                 inst::ALOAD_0 | inst::ILOAD_0 | inst::FLOAD_0 => {
-                    self.load_n(0);
+                    except!(self.load_n(0));
                 }
                 inst::LLOAD_0 | inst::DLOAD_0 => {
-                    self.load_n_long(0);
+                    except!(self.load_n_long(0));
                 }
                 inst::ALOAD_1 | inst::ILOAD_1 | inst::FLOAD_1 => {
-                    self.load_n(1);
+                    except!(self.load_n(1));
                 }
                 inst::LLOAD_1 | inst::DLOAD_1 => {
-                    self.load_n_long(1);
+                    except!(self.load_n_long(1));
                 }
                 inst::ALOAD_2 | inst::ILOAD_2 | inst::FLOAD_2 => {
-                    self.load_n(2);
+                    except!(self.load_n(2));
                 }
                 inst::LLOAD_2 | inst::DLOAD_2 => {
-                    self.load_n_long(2);
+                    except!(self.load_n_long(2));
                 }
                 inst::ALOAD_3 | inst::ILOAD_3 | inst::FLOAD_3 => {
-                    self.load_n(3);
+                    except!(self.load_n(3));
                 }
                 inst::LLOAD_3 | inst::DLOAD_3 => {
-                    self.load_n_long(3);
+                    except!(self.load_n_long(3));
                 }
                 inst::ALOAD | inst::ILOAD | inst::FLOAD => {
-                    let index = if wide {
-                        wide = false;
-                        self.get_u16_args() as usize
-                    } else {
-                        self.get_u8_args() as usize
-                    };
-                    self.load_n(index);
+                    except!(self.load_n(instruction.index() as usize));
                 }
                 inst::LLOAD | inst::DLOAD => {
-                    let index = if wide {
-                        wide = false;
-                        self.get_u16_args() as usize
-                    } else {
-                        self.get_u8_args() as usize
-                    };
-                    self.load_n_long(index);
+                    except!(self.load_n_long(instruction.index() as usize));
                 }
                 inst::AALOAD => {
                     let value = except!(self.arr_load::<u32>());
@@ -169,28 +163,28 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
 
                 // store
                 inst::ASTORE_0 | inst::ISTORE_0 | inst::FSTORE_0 => {
-                    self.store_n(0);
+                    except!(self.store_n(0));
                 }
                 inst::LSTORE_0 | inst::DSTORE_0 => {
-                    self.store_n_long(0);
+                    except!(self.store_n_long(0));
                 }
                 inst::ASTORE_1 | inst::ISTORE_1 | inst::FSTORE_1 => {
-                    self.store_n(1);
+                    except!(self.store_n(1));
                 }
                 inst::LSTORE_1 | inst::DSTORE_1 => {
-                    self.store_n_long(1);
+                    except!(self.store_n_long(1));
                 }
                 inst::ASTORE_2 | inst::ISTORE_2 | inst::FSTORE_2 => {
-                    self.store_n(2);
+                    except!(self.store_n(2));
                 }
                 inst::LSTORE_2 | inst::DSTORE_2 => {
-                    self.store_n_long(2);
+                    except!(self.store_n_long(2));
                 }
                 inst::ASTORE_3 | inst::ISTORE_3 | inst::FSTORE_3 => {
-                    self.store_n(3);
+                    except!(self.store_n(3));
                 }
                 inst::LSTORE_3 | inst::DSTORE_3 => {
-                    self.store_n_long(3);
+                    except!(self.store_n_long(3));
                 }
                 inst::AASTORE => {
                     // SAFETY: rely on class file checking to ensure correct type
@@ -203,8 +197,14 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     except!(self.arr_store(value));
                 }
                 inst::BASTORE => {
-                    let value = self.pop_int() as i8;
-                    except!(self.arr_store(value));
+                    let value = self.pop_int();
+                    // boolean arrays share bastore with byte arrays, but only hold 0 and 1
+                    let value = if self.is_boolean_array_below_index() {
+                        value & 1
+                    } else {
+                        value
+                    };
+                    except!(self.arr_store(value as i8));
                 }
                 inst::CASTORE => {
                     let value = self.pop_int() as u16;
@@ -228,22 +228,10 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                 }
 
                 inst::ASTORE | inst::ISTORE | inst::FSTORE => {
-                    let index = if wide {
-                        wide = false;
-                        self.get_u16_args() as usize
-                    } else {
-                        self.get_u8_args() as usize
-                    };
-                    self.store_n(index);
+                    except!(self.store_n(instruction.index() as usize));
                 }
                 inst::LSTORE | inst::DSTORE => {
-                    let index = if wide {
-                        wide = false;
-                        self.get_u16_args() as usize
-                    } else {
-                        self.get_u8_args() as usize
-                    };
-                    self.store_n_long(index);
+                    except!(self.store_n_long(instruction.index() as usize));
                 }
 
                 // array
@@ -308,29 +296,22 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                 inst::ACONST_NULL => {
                     self.frame.stack.push(Variable { reference: 0 });
                 }
-                inst::BIPUSH => {
-                    let byte = self.get_i8_args();
-                    self.frame.stack.push(Variable { int: byte as i32 });
-                }
-                inst::SIPUSH => {
-                    let short = self.get_i16_args();
-                    self.frame.stack.push(Variable { int: short as i32 });
+                inst::BIPUSH | inst::SIPUSH => {
+                    self.iconst(instruction.constant());
                 }
 
-                inst::LDC => {
-                    let index = self.get_u8_args() as u16;
-                    except!(self.ldc(index));
-                }
-                inst::LDC_W => {
-                    let index = self.get_u16_args();
-                    except!(self.ldc(index));
+                inst::LDC | inst::LDC_W => {
+                    except!(self.ldc(instruction.index()));
                 }
                 inst::LDC2_W => {
-                    let index = self.get_u16_args();
-                    self.ldc2(index);
+                    except!(self.ldc2(instruction.index()));
                 }
 
                 // stacks
+                // the stack holds slots, and a long or double is two adjacent slots that always
+                // move together. so each form of the dup and pop instructions is the same slot
+                // shuffle, e.g. dup_x2 on `value3, value2, value1` moves the same slots as on
+                // `value2 (category 2), value1`, and dup2 on a long duplicates both its halves
                 inst::DUP => {
                     self.frame.stack.push(
                         *self
@@ -359,10 +340,10 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                 inst::DUP2 => {
                     let v1 = self.frame.stack.pop().unwrap();
                     let v2 = self.frame.stack.pop().unwrap();
-                    self.frame.stack.push(v1);
                     self.frame.stack.push(v2);
                     self.frame.stack.push(v1);
                     self.frame.stack.push(v2);
+                    self.frame.stack.push(v1);
                 }
                 inst::DUP2_X1 => {
                     let v1 = self.frame.stack.pop().unwrap();
@@ -427,13 +408,13 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     let a = self.pop_int();
                     let b = self.pop_int();
                     self.frame.stack.push(Variable {
-                        int: b.wrapping_add(-a),
+                        int: b.wrapping_sub(a),
                     });
                 }
                 inst::LSUB => {
                     let a = self.pop_long();
                     let b = self.pop_long();
-                    self.push_long(b.wrapping_add(-a));
+                    self.push_long(b.wrapping_sub(a));
                 }
                 inst::FSUB => {
                     let a = self.pop_float();
@@ -470,8 +451,9 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     let a = self.pop_int();
                     let b = self.pop_int();
                     if a == 0 {
-                        return Next::Exception(Exception::new_vm(
+                        return Next::Exception(Exception::new_vm_msg(
                             ARITHMETIC_EXCEPTION_CLASS.get().expect("must have init"),
+                            "/ by zero",
                         ));
                     }
                     self.push_int(b.wrapping_div(a))
@@ -480,8 +462,9 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     let a = self.pop_long();
                     let b = self.pop_long();
                     if a == 0 {
-                        return Next::Exception(Exception::new_vm(
+                        return Next::Exception(Exception::new_vm_msg(
                             ARITHMETIC_EXCEPTION_CLASS.get().expect("must have init"),
+                            "/ by zero",
                         ));
                     }
                     self.push_long(b.wrapping_div(a));
@@ -501,8 +484,9 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     let a = self.pop_int();
                     let b = self.pop_int();
                     if a == 0 {
-                        return Next::Exception(Exception::new_vm(
+                        return Next::Exception(Exception::new_vm_msg(
                             ARITHMETIC_EXCEPTION_CLASS.get().expect("must have init"),
+                            "/ by zero",
                         ));
                     }
                     self.frame.stack.push(Variable {
@@ -513,12 +497,16 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     let a = self.pop_long();
                     let b = self.pop_long();
                     if a == 0 {
-                        return Next::Exception(Exception::new_vm(
+                        return Next::Exception(Exception::new_vm_msg(
                             ARITHMETIC_EXCEPTION_CLASS.get().expect("must have init"),
+                            "/ by zero",
                         ));
                     }
                     self.push_long(b.wrapping_rem(a));
                 }
+                // % on floats truncates like C fmod, which matches frem and drem also for NaN,
+                // infinities and zeros: x % 0 and inf % y are NaN, x % inf is x, and a zero or
+                // finite result takes the sign of the dividend
                 inst::FREM => {
                     let a = self.pop_float();
                     let b = self.pop_float();
@@ -547,14 +535,14 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                 }
 
                 inst::IINC => {
-                    let (index, con) = if wide {
-                        wide = false;
-                        (self.get_u16_args() as usize, self.get_u16_args() as i32)
-                    } else {
-                        (self.get_u8_args() as usize, self.get_u8_args() as i32)
-                    };
+                    let (index, con) = instruction.iinc();
+                    let index = index as usize;
+                    except!(self.check_local_index(index, 1));
                     // SAFETY: rely on class file checking to ensure correct type
-                    unsafe { self.frame.locals[index].int += con };
+                    unsafe {
+                        let local = &mut self.frame.locals[index].int;
+                        *local = local.wrapping_add(con as i32);
+                    }
                 }
                 inst::ISHL => {
                     let v2 = self.pop_int();
@@ -574,17 +562,17 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                 inst::LSHL => {
                     let v2 = self.pop_int();
                     let v1 = self.pop_long();
-                    self.push_long(v1 << (v2 & 0x1F));
+                    self.push_long(v1 << (v2 & 0x3F));
                 }
                 inst::LSHR => {
                     let v2 = self.pop_int();
                     let v1 = self.pop_long();
-                    self.push_long(v1 >> (v2 & 0x1F));
+                    self.push_long(v1 >> (v2 & 0x3F));
                 }
                 inst::LUSHR => {
                     let v2 = self.pop_int();
                     let v1 = self.pop_long();
-                    self.push_long(((v1 as u64) >> (v2 & 0x1F)) as i64);
+                    self.push_long(((v1 as u64) >> (v2 & 0x3F)) as i64);
                 }
 
                 inst::IAND => {
@@ -649,6 +637,7 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     let v = self.pop_int();
                     self.push_double(v as f64);
                 }
+                // keeps the low 32 bits
                 inst::L2I => {
                     let v = self.pop_long();
                     self.push_int(v as i32);
@@ -661,6 +650,8 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     let v = self.pop_long();
                     self.push_double(v as f64);
                 }
+                // `as` rounds toward zero, saturates out of range values and infinities, and turns NaN
+                // into 0, as f2i, f2l, d2i and d2l do
                 inst::F2I => {
                     let v = self.pop_float();
                     self.push_int(v as i32);
@@ -706,7 +697,7 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     // SAFETY: rely on class file checking to ensure correct type
                     let a = unsafe { self.frame.stack.pop().unwrap().reference };
                     let b = unsafe { self.frame.stack.pop().unwrap().reference };
-                    if self.goto(a == b) {
+                    if except!(self.goto(instruction.target(), a == b)) {
                         continue;
                     }
                 }
@@ -714,176 +705,162 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     // SAFETY: rely on class file checking to ensure correct type
                     let a = unsafe { self.frame.stack.pop().unwrap().reference };
                     let b = unsafe { self.frame.stack.pop().unwrap().reference };
-                    if self.goto(a != b) {
+                    if except!(self.goto(instruction.target(), a != b)) {
                         continue;
                     }
                 }
                 inst::IF_ICMPEQ => {
                     let v2 = self.pop_int();
                     let v1 = self.pop_int();
-                    if self.goto(v1 == v2) {
+                    if except!(self.goto(instruction.target(), v1 == v2)) {
                         continue;
                     }
                 }
                 inst::IF_ICMPNE => {
                     let v2 = self.pop_int();
                     let v1 = self.pop_int();
-                    if self.goto(v1 != v2) {
+                    if except!(self.goto(instruction.target(), v1 != v2)) {
                         continue;
                     }
                 }
                 inst::IF_ICMPLT => {
                     let v2 = self.pop_int();
                     let v1 = self.pop_int();
-                    if self.goto(v1 < v2) {
+                    if except!(self.goto(instruction.target(), v1 < v2)) {
                         continue;
                     }
                 }
                 inst::IF_ICMPGT => {
                     let v2 = self.pop_int();
                     let v1 = self.pop_int();
-                    if self.goto(v1 > v2) {
+                    if except!(self.goto(instruction.target(), v1 > v2)) {
                         continue;
                     }
                 }
                 inst::IF_ICMPLE => {
                     let v2 = self.pop_int();
                     let v1 = self.pop_int();
-                    if self.goto(v1 <= v2) {
+                    if except!(self.goto(instruction.target(), v1 <= v2)) {
                         continue;
                     }
                 }
                 inst::IF_ICMPGE => {
                     let v2 = self.pop_int();
                     let v1 = self.pop_int();
-                    if self.goto(v1 >= v2) {
+                    if except!(self.goto(instruction.target(), v1 >= v2)) {
                         continue;
                     }
                 }
                 inst::IFEQ => {
                     let v2 = 0;
                     let v1 = self.pop_int();
-                    if self.goto(v1 == v2) {
+                    if except!(self.goto(instruction.target(), v1 == v2)) {
                         continue;
                     }
                 }
                 inst::IFNE => {
                     let v2 = 0;
                     let v1 = self.pop_int();
-                    if self.goto(v1 != v2) {
+                    if except!(self.goto(instruction.target(), v1 != v2)) {
                         continue;
                     }
                 }
                 inst::IFLT => {
                     let v2 = 0;
                     let v1 = self.pop_int();
-                    if self.goto(v1 < v2) {
+                    if except!(self.goto(instruction.target(), v1 < v2)) {
                         continue;
                     }
                 }
                 inst::IFGT => {
                     let v2 = 0;
                     let v1 = self.pop_int();
-                    if self.goto(v1 > v2) {
+                    if except!(self.goto(instruction.target(), v1 > v2)) {
                         continue;
                     }
                 }
                 inst::IFLE => {
                     let v2 = 0;
                     let v1 = self.pop_int();
-                    if self.goto(v1 <= v2) {
+                    if except!(self.goto(instruction.target(), v1 <= v2)) {
                         continue;
                     }
                 }
                 inst::IFGE => {
                     let v2 = 0;
                     let v1 = self.pop_int();
-                    if self.goto(v1 >= v2) {
+                    if except!(self.goto(instruction.target(), v1 >= v2)) {
                         continue;
                     }
                 }
                 inst::IFNULL => {
                     let a = unsafe { self.frame.stack.pop().unwrap().reference };
-                    if self.goto(a == 0) {
+                    if except!(self.goto(instruction.target(), a == 0)) {
                         continue;
                     }
                 }
                 inst::IFNONNULL => {
                     let a = unsafe { self.frame.stack.pop().unwrap().reference };
-                    if self.goto(a != 0) {
+                    if except!(self.goto(instruction.target(), a != 0)) {
                         continue;
                     }
                 }
-                inst::GOTO => {
-                    self.goto(true);
-                    continue;
-                }
-                inst::GOTO_W => {
-                    self.goto_w();
+                inst::GOTO | inst::GOTO_W => {
+                    except!(self.jump(instruction.target()));
                     continue;
                 }
-                inst::JSR => {
-                    let offset = self.get_i16_args();
-                    self.frame.stack.push(Variable {
-                        return_address: *self.pc as _,
-                    });
-                    *self.pc = self.pc.wrapping_add_signed(offset as _);
-                }
-                inst::JSR_W => {
-                    let offset = self.get_i32_args();
+                inst::JSR | inst::JSR_W => {
+                    let return_address = *self.pc + 1;
+                    except!(self.jump(instruction.target()));
                     self.frame.stack.push(Variable {
-                        return_address: *self.pc as _,
+                        return_address: return_address as _,
                     });
-                    *self.pc = self.pc.wrapping_add_signed(offset as _);
+                    continue;
                 }
                 inst::RET => {
-                    let index = if wide {
-                        wide = false;
-                        self.get_u16_args() as usize
-                    } else {
-                        self.get_u8_args() as usize
-                    };
-                    // SAFETY: rely on class file checking to ensure correct type
-                    let return_pc = unsafe { self.frame.locals[index].return_address };
-                    *self.pc = return_pc as _;
+                    let index = instruction.index() as usize;
+                    except!(self.check_local_index(index, 1));
+                    *self.pc = except!(self.return_address(index));
+                    continue;
                 }
                 inst::LOOKUPSWITCH => {
-                    self.lookup_switch();
+                    except!(self.lookup_switch(instruction.lookup_switch()));
                     continue;
                 }
                 inst::TABLESWITCH => {
-                    self.table_switch();
+                    except!(self.table_switch(instruction.table_switch()));
                     continue;
                 }
 
                 // oop
                 inst::NEW => {
-                    except!(self.new_object());
+                    except!(self.new_object(instruction.index()));
                 }
                 inst::NEWARRAY => {
-                    except!(self.new_array());
+                    except!(self.new_array(instruction.constant()));
                 }
                 inst::ANEWARRAY => {
-                    except!(self.new_object_array());
+                    except!(self.new_object_array(instruction.index()));
                 }
                 inst::MULTIANEWARRAY => {
-                    except!(self.new_multi_object_array());
+                    let (cp_index, dimensions) = instruction.multi_array();
+                    except!(self.new_multi_object_array(cp_index, dimensions));
                 }
 
                 inst::PUTFIELD => {
-                    except!(self.put_field());
+                    except!(self.put_field(instruction.index()));
                 }
                 inst::GETFIELD => {
-                    except!(self.get_field());
+                    except!(self.get_field(instruction.index()));
                 }
                 inst::GETSTATIC => {
-                    except!(self.get_static());
+                    except!(self.get_static(instruction.index()));
                 }
                 inst::PUTSTATIC => {
-                    except!(self.put_static());
+                    except!(self.put_static(instruction.index()));
                 }
                 inst::CHECKCAST => {
-                    let cp_index = self.get_u16_args();
+                    let cp_index = instruction.index();
                     let runtime::ConstantPoolInfo::Class(cp_class) =
                         self.frame.class.get_constant(cp_index)
                     else {
@@ -902,7 +879,7 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     }
                 }
                 inst::INSTANCEOF => {
-                    let cp_index = self.get_u16_args();
+                    let cp_index = instruction.index();
                     let runtime::ConstantPoolInfo::Class(cp_class) =
                         self.frame.class.get_constant(cp_index)
                     else {
@@ -926,7 +903,7 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                 // call
                 // TODO: do monitor ops for synchronized
                 inst::INVOKESPECIAL | inst::INVOKEVIRTUAL => {
-                    let cp_index = self.get_u16_args();
+                    let cp_index = instruction.index();
                     // extend class's lifetime to avoid borrowing self
                     let runtime::ConstantPoolInfo::Methodref(method_ref) =
                         self.frame.class.get_constant(cp_index)
@@ -934,7 +911,7 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                         panic!("invalid constant type {cp_index}");
                     };
 
-                    let param_size = method_ref.name_and_type.descriptor.parameters.len();
+                    let param_size = method_ref.name_and_type.descriptor.arg_slot_count();
                     // SAFETY: rely on class file checking to ensure correct type
                     let this = unsafe {
                         self.frame.stack[self.frame.stack.len() - param_size - 1].reference
@@ -962,17 +939,76 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                             vtable_index,
                         } => (class, index, vtable_index),
                     };
+                    let (static_class, index) = if op == inst::INVOKESPECIAL {
+                        select_special_method(&self.frame.class, method_ref, static_class, index)
+                    } else {
+                        (Arc::clone(static_class), index)
+                    };
 
                     return Next::InvokeSpecial {
-                        static_class: Arc::clone(static_class),
+                        static_class,
                         index,
                         vtable_index,
                         is_virtual: op == inst::INVOKEVIRTUAL,
                         this,
                     };
                 }
+                inst::INVOKEINTERFACE => {
+                    // count is the slots taken by the arguments, including this
+                    let (cp_index, count) = instruction.invoke_interface();
+                    let runtime::ConstantPoolInfo::InterfaceMethodref(method_ref) =
+                        self.frame.class.get_constant(cp_index)
+                    else {
+                        panic!("invalid constant type {cp_index}");
+                    };
+
+                    // SAFETY: rely on class file checking to ensure correct type
+                    let this = unsafe {
+                        self.frame.stack[self.frame.stack.len() - count as usize].reference
+                    };
+                    if this == 0 {
+                        return Next::Exception(Exception::new_vm(
+                            NULL_POINTER_EXCEPTION_CLASS.get().expect("must have init"),
+                        ));
+                    }
+
+                    let resolve = except!(
+                        method_ref
+                            .resolve
+                            .get_or_try_init(|| self.resolve_interface_method(method_ref))
+                    );
+                    let (interface, index, _) = resolve.get_class_and_index(&self.frame.class);
+                    let resolved_method = &interface.methods[index];
+
+                    let (class, index) = if resolved_method
+                        .access_flags
+                        .contains(MethodAccessFlag::PRIVATE)
+                    {
+                        (Arc::clone(interface), index)
+                    } else {
+                        let this_class =
+                            Arc::clone(self.heap.read().unwrap().get(this).get_class());
+                        let Some(selected) =
+                            except!(select_interface_method(&this_class, resolved_method))
+                        else {
+                            return Next::Exception(abstract_method_error(
+                                &this_class,
+                                resolved_method,
+                            ));
+                        };
+                        selected
+                    };
+
+                    return Next::InvokeSpecial {
+                        static_class: class,
+                        index,
+                        vtable_index: -1,
+                        is_virtual: false,
+                        this,
+                    };
+                }
                 inst::INVOKESTATIC => {
-                    let cp_index = self.get_u16_args();
+                    let cp_index = instruction.index();
                     let method_ref = match self.frame.class.get_constant(cp_index) {
                         ConstantPoolInfo::Methodref(method_ref) => method_ref,
                         ConstantPoolInfo::InterfaceMethodref(method_ref) => method_ref,
@@ -998,31 +1034,19 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     };
                 }
                 inst::INVOKEDYNAMIC => {
-                    let cp_index = self.get_u16_args();
-                    // skip two zeros
-                    self.get_u8_args();
-                    self.get_u8_args();
+                    let cp_index = instruction.index();
+                    let class = Arc::clone(&self.frame.class);
                     let runtime::ConstantPoolInfo::InvokeDynamic {
                         bootstrap_method_attr_index,
                         name_and_type,
-                    } = self.frame.class.get_constant(cp_index)
+                    } = class.get_constant(cp_index)
                     else {
                         panic!("invalid constant type {cp_index}");
                     };
 
                     // FIXME: the following is special resolve, should first resovle method handle
-                    let mut bootstrap_methods = None;
-                    for attr in &self.frame.class.attributes {
-                        if let AttributeInfo::BootstrapMethods(b) = attr {
-                            bootstrap_methods = Some(b);
-                            break;
-                        }
-                    }
-                    let Some(bootstrap_methods) = bootstrap_methods else {
-                        panic!("cannot find bootstrap methods");
-                    };
                     let bootstrap_method =
-                        &bootstrap_methods[*bootstrap_method_attr_index as usize];
+                        &class.bootstrap_methods()[*bootstrap_method_attr_index as usize];
 
                     match bootstrap_method.bootstrap_method.reference_kind {
                         // ReferenceKind::GetField => {}
@@ -1031,37 +1055,42 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                         // ReferenceKind::PutStatic => {}
                         // ReferenceKind::InvokeVirtual => {}
                         ReferenceKind::InvokeStatic => {
-                            let runtime::ConstantPoolInfo::Methodref(method_ref) = self
-                                .frame
-                                .class
+                            let runtime::ConstantPoolInfo::Methodref(method_ref) = class
                                 .get_constant(bootstrap_method.bootstrap_method.reference_index)
                             else {
                                 panic!("invalid constant type {cp_index}");
                             };
-
-                            let resolved_bootstrap_method = except!(
-                                method_ref
-                                    .resolve
-                                    .get_or_try_init(|| self.resolve_static_method(method_ref))
-                            );
-                            let (cls, index, _) =
-                                resolved_bootstrap_method.get_class_and_index(&self.frame.class);
-                            let bootstrap_method_info = &cls.methods[index];
-
-                            except!(initialize_class(&self.new_vm_env(), cls));
-
-                            let mut bootstrap_method_thread =
-                                self.next_native_thread.new_native_frame_group(None);
-                            bootstrap_method_thread.new_frame(
-                                Arc::clone(cls),
-                                &bootstrap_method_info.name,
-                                &bootstrap_method_info.descriptor.parameters,
-                                0,
-                            );
-                            except!(bootstrap_method_thread.execute());
-                            dbg!(unsafe {
-                                bootstrap_method_thread.top_frame.unwrap().stack[0].reference
-                            });
+                            // without method handles, the call site is run without linking
+                            if string_concat::is_string_concat(method_ref) {
+                                except!(self.string_concat(
+                                    &class,
+                                    bootstrap_method,
+                                    &name_and_type.descriptor
+                                ));
+                            } else {
+                                let resolved_bootstrap_method =
+                                    except!(method_ref.resolve.get_or_try_init(|| {
+                                        self.resolve_static_method(method_ref)
+                                    }));
+                                let (cls, index, _) = resolved_bootstrap_method
+                                    .get_class_and_index(&self.frame.class);
+                                let bootstrap_method_info = &cls.methods[index];
+
+                                except!(initialize_class(&self.new_vm_env(), cls));
+
+                                let mut bootstrap_method_thread =
+                                    self.next_native_thread.new_native_frame_group(None);
+                                except!(bootstrap_method_thread.new_frame(
+                                    Arc::clone(cls),
+                                    &bootstrap_method_info.name,
+                                    &bootstrap_method_info.descriptor.parameters,
+                                    0,
+                                ));
+                                except!(bootstrap_method_thread.execute());
+                                dbg!(unsafe {
+                                    bootstrap_method_thread.top_frame.unwrap().stack[0].reference
+                                });
+                            }
                         }
                         // ReferenceKind::InvokeSpecial => {}
                         // ReferenceKind::NewInvokeSpecial => {}
@@ -1097,8 +1126,13 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     };
                 }
 
+                // the decoder folds wide into the instruction it modifies, so this is a wide
+                // followed by an instruction that has no wide form
                 inst::WIDE => {
-                    wide = true;
+                    return Next::Exception(self.new_vm_exception_at_pc(
+                        VERIFY_ERROR_CLASS.get().expect("must have init"),
+                        "illegal instruction after wide",
+                    ));
                 }
 
                 // misc
@@ -1113,7 +1147,6 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                 }
 
                 inst::MONITORENTER => {
-                    // TODO: support sync methods
                     // TODO: monitor enter/exit on Object.wait etc
                     // SAFETY: rely on class file checking to ensure correct type
                     let obj_ref = unsafe { self.frame.stack.pop().unwrap().reference };
@@ -1126,7 +1159,6 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                     obj.get_monitor().enter();
                 }
                 inst::MONITOREXIT => {
-                    // TODO: support sync methods
                     // SAFETY: rely on class file checking to ensure correct type
                     let obj_ref = unsafe { self.frame.stack.pop().unwrap().reference };
                     if obj_ref == 0 {
@@ -1145,8 +1177,10 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
 
                 inst::NOP => {}
                 _ => {
-                    // skip unknown instructions
-                    eprintln!("unknown instruction: {op}");
+                    return Next::Exception(self.new_vm_exception_at_pc(
+                        INTERNAL_ERROR_CLASS.get().expect("must have init"),
+                        &format!("unknown instruction {op}"),
+                    ));
                 }
             }
 
@@ -1155,89 +1189,102 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
     }
 
     #[inline]
-    fn load_n(&mut self, n: usize) {
+    fn load_n(&mut self, n: usize) -> NativeResult<()> {
+        self.check_local_index(n, 1)?;
         self.frame.stack.push(self.frame.locals[n]);
+        Ok(())
     }
 
     #[inline]
-    fn load_n_long(&mut self, n: usize) {
+    fn load_n_long(&mut self, n: usize) -> NativeResult<()> {
+        self.check_local_index(n, 2)?;
         self.frame.stack.push(self.frame.locals[n]);
         self.frame.stack.push(self.frame.locals[n + 1]);
+        Ok(())
     }
 
     #[inline]
-    fn store_n(&mut self, n: usize) {
+    fn store_n(&mut self, n: usize) -> NativeResult<()> {
+        self.check_local_index(n, 1)?;
         let v = self.frame.stack.pop().unwrap();
 
+        // skipped locals are zeroed, so that ret on one of them reads no return address
         if self.frame.locals.len() < n + 1 {
-            self.frame.locals.resize(n + 1, Variable { void: () });
+            self.frame.locals.resize(n + 1, Variable { int: 0 });
         }
         self.frame.locals[n] = v;
+        Ok(())
     }
 
     #[inline]
-    fn store_n_long(&mut self, n: usize) {
+    fn store_n_long(&mut self, n: usize) -> NativeResult<()> {
+        self.check_local_index(n, 2)?;
         let v2 = self.frame.stack.pop().unwrap();
         let v1 = self.frame.stack.pop().unwrap();
         if self.frame.locals.len() < n + 2 {
-            self.frame.locals.resize(n + 2, Variable { void: () });
+            self.frame.locals.resize(n + 2, Variable { int: 0 });
         }
         self.frame.locals[n] = v1;
         self.frame.locals[n + 1] = v2;
+        Ok(())
     }
 
-    #[inline]
-    fn iconst(&mut self, i: i32) {
-        self.frame.stack.push(Variable { int: i });
-    }
-
-    #[inline]
-    fn fconst(&mut self, f: f32) {
-        self.frame.stack.push(Variable { float: f });
+    /// The return address in the local `n` for `ret`, which must be the instruction after a
+    /// `jsr`. Locals are untagged, so an int that happens to be such an index is not caught
+    fn return_address(&self, n: usize) -> NativeResult<usize> {
+        // SAFETY: every slot of locals is initialized, and all variants are 4 bytes
+        let return_pc = self
+            .frame
+            .locals
+            .get(n)
+            .map_or(0, |v| unsafe { v.return_address } as usize);
+        let after_jsr = return_pc < self.frame.code.len()
+            && return_pc
+                .checked_sub(1)
+                .and_then(|jsr| self.frame.code.get(jsr))
+                .is_some_and(|jsr| matches!(jsr.opcode, instructions::JSR | instructions::JSR_W));
+        if !after_jsr {
+            return Err(self.new_vm_exception_at_pc(
+                VERIFY_ERROR_CLASS.get().expect("must have init"),
+                &format!("local variable {n} does not hold a return address"),
+            ));
+        }
+        Ok(return_pc)
     }
 
+    /// `size` is 2 for long and double locals
     #[inline]
-    fn get_u8_args(&mut self) -> u8 {
-        let byte = self.frame.code[*self.pc + 1];
-        *self.pc += 1;
-        byte
-    }
-    #[inline]
-    fn get_i8_args(&mut self) -> i8 {
-        let byte = self.frame.code[*self.pc + 1] as i8;
-        *self.pc += 1;
-        byte
+    fn check_local_index(&self, n: usize, size: usize) -> NativeResult<()> {
+        if n + size > self.frame.max_locals {
+            return Err(self.new_vm_exception_at_pc(
+                VERIFY_ERROR_CLASS.get().expect("must have init"),
+                &format!(
+                    "local variable index {n} out of max_locals {}",
+                    self.frame.max_locals
+                ),
+            ));
+        }
+        Ok(())
     }
 
     #[inline]
-    fn get_u16_args(&mut self) -> u16 {
-        let byte1 = self.frame.code[*self.pc + 1] as u16;
-        let byte2 = self.frame.code[*self.pc + 2] as u16;
-        *self.pc += 2;
-        (byte1 << 8) | byte2
+    fn iconst(&mut self, i: i32) {
+        self.frame.stack.push(Variable { int: i });
     }
 
     #[inline]
-    fn get_i16_args(&mut self) -> i16 {
-        self.get_u16_args() as _
+    fn fconst(&mut self, f: f32) {
+        self.frame.stack.push(Variable { float: f });
     }
 
-    #[inline]
-    fn get_i32_args(&mut self) -> i32 {
-        let byte1 = self.frame.code[*self.pc + 1] as i32;
-        let byte2 = self.frame.code[*self.pc + 2] as i32;
-        let byte3 = self.frame.code[*self.pc + 3] as i32;
-        let byte4 = self.frame.code[*self.pc + 4] as i32;
-        *self.pc += 4;
-        (byte1 << 24) | (byte2 << 16) | (byte3 << 8) | byte4
-    }
-    #[inline]
-    fn get_i32_args_from(&self, pc: usize) -> i32 {
-        let byte1 = self.frame.code[pc + 1] as i32;
-        let byte2 = self.frame.code[pc + 2] as i32;
-        let byte3 = self.frame.code[pc + 3] as i32;
-        let byte4 = self.frame.code[pc + 4] as i32;
-        (byte1 << 24) | (byte2 << 16) | (byte3 << 8) | byte4
+    fn new_vm_exception_at_pc(&self, exception_type: &Arc<Class>, message: &str) -> Exception {
+        Exception::new_vm_at(
+            exception_type,
+            &self.frame.class,
+            &self.frame.method_name,
+            self.frame.code[*self.pc].offset as usize,
+            message,
+        )
     }
 
     #[inline]
@@ -1295,8 +1342,7 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
         self.frame.stack.push(lower);
     }
 
-    fn new_object(&mut self) -> NativeResult<()> {
-        let cp_index = self.get_u16_args();
+    fn new_object(&mut self, cp_index: u16) -> NativeResult<()> {
         let runtime::ConstantPoolInfo::Class(cp_info) = self.frame.class.get_constant(cp_index)
         else {
             panic!("invalid constant type {cp_index}");
@@ -1304,39 +1350,12 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
         let new_class = self.resolve_class(cp_info)?;
         initialize_class(&self.new_vm_env(), &new_class)?;
 
-        let max_size = new_class
-            .instance_fields_info
-            .last()
-            .map(|f| f.index + 1)
-            .unwrap_or(0);
-        let mut fields_types = Vec::with_capacity(max_size as _);
-
-        for f in &new_class.instance_fields_info {
-            if f.descriptor.0.is_long() {
-                fields_types.push(&f.descriptor);
-            }
-            fields_types.push(&f.descriptor);
-        }
-
-        let mut heap = self.heap.write().unwrap();
-        let id = unsafe {
-            heap.allocate_object(fields_types.len(), Arc::clone(&new_class), |i, v| {
-                use FieldType::*;
-                let var = match fields_types[i].0 {
-                    Byte | Char | Int | Short | Boolean | Long => Variable { int: 0 },
-                    Float | Double => Variable { float: 0.0 },
-                    Object(_) | Array(_) => Variable { reference: 0 },
-                };
-
-                v.write(var);
-            })
-        };
+        let id = new_instance(&new_class);
         self.frame.stack.push(Variable { reference: id });
         Ok(())
     }
 
-    fn new_array(&mut self) -> NativeResult<()> {
-        let atype = self.get_i8_args();
+    fn new_array(&mut self, atype: i32) -> NativeResult<()> {
         let count = self.pop_int();
         if count < 0 {
             return Err(Exception::new_vm(
@@ -1369,32 +1388,12 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
         let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
         let new_class = bootstrap_class_loader.resolve_primitive_array_class(&arr_type)?;
 
-        let mut heap = self.heap.write().unwrap();
-
-        let id = match arr_type {
-            FieldType::Boolean | FieldType::Byte => {
-                heap.allocate_array::<i8>(count as _, new_class)
-            }
-            // char
-            FieldType::Char => heap.allocate_array::<u16>(count as _, new_class),
-            // float
-            FieldType::Float => heap.allocate_array::<f32>(count as _, new_class),
-            // double
-            FieldType::Double => heap.allocate_array::<f64>(count as _, new_class),
-            // short
-            FieldType::Short => heap.allocate_array::<i16>(count as _, new_class),
-            // int
-            FieldType::Int => heap.allocate_array::<i32>(count as _, new_class),
-            // long
-            FieldType::Long => heap.allocate_array::<i64>(count as _, new_class),
-            _ => panic!("invalid array type {atype}"),
-        };
+        let id = new_array(&mut self.heap.write().unwrap(), new_class, count as _);
         self.frame.stack.push(Variable { reference: id });
         Ok(())
     }
 
-    fn new_object_array(&mut self) -> NativeResult<()> {
-        let cp_index = self.get_u16_args();
+    fn new_object_array(&mut self, cp_index: u16) -> NativeResult<()> {
         let runtime::ConstantPoolInfo::Class(cp_info) = self.frame.class.get_constant(cp_index)
         else {
             panic!("invalid constant type {cp_index}");
@@ -1421,21 +1420,11 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
         Ok(())
     }
 
-    fn new_multi_object_array(&mut self) -> NativeResult<()> {
-        let cp_index = self.get_u16_args();
-        let dimensions = self.get_u8_args();
+    fn new_multi_object_array(&mut self, cp_index: u16, dimensions: u8) -> NativeResult<()> {
         debug_assert!(dimensions >= 1);
         let mut dims = vec![0; dimensions as usize];
         for i in 0..dimensions {
-            let dim = self.pop_int();
-            if dim < 0 {
-                return Err(Exception::new_vm(
-                    NEGATIVE_ARRAY_SIZE_EXCEPTION_CLASS
-                        .get()
-                        .expect("must have init"),
-                ));
-            }
-            dims[(dimensions - i - 1) as usize] = dim;
+            dims[(dimensions - i - 1) as usize] = self.pop_int();
         }
 
         // this is array type with dim >= dimensions
@@ -1443,52 +1432,15 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
         else {
             panic!("invalid constant type {cp_index}");
         };
-        debug_assert!(
-            cp_info.name.starts_with(&"[".repeat(dimensions as usize)),
-            "array class dimension not enough"
-        );
-        let id = Self::new_multi_object_array_dim(
-            &cp_info.name,
-            &dims,
-            &mut self.heap.write().unwrap(),
-        )?;
+        let id = new_multi_array(self.heap, &cp_info.name, &dims)?;
 
         self.frame.stack.push(Variable { reference: id });
         Ok(())
     }
 
-    fn new_multi_object_array_dim(
-        arr_class_name: &str,
-        dim: &[i32],
-        heap: &mut Heap,
-    ) -> NativeResult<u32> {
-        let element_class_name = &arr_class_name[1..];
-        let (_, filed_type) =
-            parse_field_descriptor(element_class_name).expect("invalid arr class name");
-
-        let loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
-        let class = loader.resolve_class(arr_class_name)?;
-
-        let count = dim[0] as usize;
-        let id = heap.allocate_array::<u32>(count, class);
-        let array_obj = heap.get(id);
-        for i in 0..count {
-            if dim.len() == 1 {
-                let size = filed_type.0.get_field_type_size();
-                unsafe { array_obj.put_array_index_raw(i, &vec![0; size], size) }
-            } else {
-                let element =
-                    Self::new_multi_object_array_dim(element_class_name, &dim[1..], heap)?;
-                unsafe {
-                    put_array_index(array_obj.as_ref(), i, element);
-                }
-            }
-        }
-        Ok(id)
-    }
-
-    fn put_field(&mut self) -> NativeResult<()> {
-        let (index, is_long) = self.resolve_instance_field()?;
+    fn put_field(&mut self, cp_index: u16) -> NativeResult<()> {
+        // resolution errors take priority over the NullPointerException of a null receiver
+        let (index, is_long) = self.resolve_instance_field(cp_index)?;
         let v1;
         let mut v2 = None;
         if is_long {
@@ -1515,8 +1467,9 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
         Ok(())
     }
 
-    fn get_field(&mut self) -> NativeResult<()> {
-        let (index, is_long) = self.resolve_instance_field()?;
+    fn get_field(&mut self, cp_index: u16) -> NativeResult<()> {
+        // resolution errors take priority over the NullPointerException of a null receiver
+        let (index, is_long) = self.resolve_instance_field(cp_index)?;
 
         let this = unsafe { self.frame.stack.pop().unwrap().reference };
         if this == 0 {
@@ -1538,8 +1491,7 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
         Ok(())
     }
 
-    fn resolve_instance_field(&mut self) -> NativeResult<(usize, bool)> {
-        let cp_index = self.get_u16_args();
+    fn resolve_instance_field(&mut self, cp_index: u16) -> NativeResult<(usize, bool)> {
         let runtime::ConstantPoolInfo::Fieldref(
             field_ref @ runtime::Fieldref {
                 name_and_type,
@@ -1558,8 +1510,8 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
         Ok((index, is_long))
     }
 
-    fn get_static(&mut self) -> NativeResult<()> {
-        let (class, index, is_long) = self.resolve_static_field()?;
+    fn get_static(&mut self, cp_index: u16) -> NativeResult<()> {
+        let (class, index, is_long) = self.resolve_static_field(cp_index)?;
         initialize_class(&self.new_vm_env(), &class)?;
 
         self.frame.stack.push(class.get_static_field(index));
@@ -1570,8 +1522,8 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
         Ok(())
     }
 
-    fn put_static(&mut self) -> NativeResult<()> {
-        let (class, index, is_long) = self.resolve_static_field()?;
+    fn put_static(&mut self, cp_index: u16) -> NativeResult<()> {
+        let (class, index, is_long) = self.resolve_static_field(cp_index)?;
         initialize_class(&self.new_vm_env(), &class)?;
 
         if is_long {
@@ -1583,8 +1535,10 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
         Ok(())
     }
 
-    fn resolve_static_field(&mut self) -> Result<(Arc<Class>, usize, bool), Exception> {
-        let cp_index = self.get_u16_args();
+    fn resolve_static_field(
+        &mut self,
+        cp_index: u16,
+    ) -> Result<(Arc<Class>, usize, bool), Exception> {
         let runtime::ConstantPoolInfo::Fieldref(
             field_ref @ runtime::Fieldref {
                 name_and_type,
@@ -1625,17 +1579,26 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                 self.frame.stack.push(Variable { reference: id });
             }
             runtime::ConstantPoolInfo::MethodHandle { .. } => todo!(),
-            runtime::ConstantPoolInfo::MethodType => todo!(),
+            runtime::ConstantPoolInfo::MethodType(_) => todo!(),
             runtime::ConstantPoolInfo::Dynamic { .. } => todo!(),
+            runtime::ConstantPoolInfo::Long(_) | runtime::ConstantPoolInfo::Double(_) => {
+                return Err(self.new_vm_exception_at_pc(
+                    VERIFY_ERROR_CLASS.get().expect("must have init"),
+                    "illegal constant type for ldc",
+                ));
+            }
             _ => {
-                panic!("ldc error, invalid constant type");
+                return Err(self.new_vm_exception_at_pc(
+                    VERIFY_ERROR_CLASS.get().expect("must have init"),
+                    "illegal constant type for ldc",
+                ));
             }
         }
         Ok(())
     }
 
     #[inline]
-    fn ldc2(&mut self, index: u16) {
+    fn ldc2(&mut self, index: u16) -> NativeResult<()> {
         match self.frame.class.get_constant(index) {
             runtime::ConstantPoolInfo::Long(l) => {
                 self.push_long(*l);
@@ -1644,9 +1607,13 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                 self.push_double(*d);
             }
             _ => {
-                panic!("ldc2 error, invalid constant type");
+                return Err(self.new_vm_exception_at_pc(
+                    VERIFY_ERROR_CLASS.get().expect("must have init"),
+                    "illegal constant type for ldc2_w",
+                ));
             }
         }
+        Ok(())
     }
 
     #[inline]
@@ -1674,18 +1641,25 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
     }
 
     #[inline]
-    fn goto(&mut self, jump: bool) -> bool {
-        let offset = self.get_i16_args();
+    fn goto(&mut self, target: usize, jump: bool) -> NativeResult<bool> {
         if jump {
-            *self.pc = self.pc.wrapping_add_signed((offset - 2) as isize);
-            return true;
+            self.jump(target)?;
         }
-        false
+        Ok(jump)
     }
 
-    fn goto_w(&mut self) {
-        let offset = self.get_i32_args();
-        *self.pc = self.pc.wrapping_add_signed((offset - 4) as isize);
+    /// `target` is an instruction index, which the decoder leaves out of range when the branch
+    /// offset doesn't point at an instruction of the method
+    #[inline]
+    fn jump(&mut self, target: usize) -> NativeResult<()> {
+        if target >= self.frame.code.len() {
+            return Err(self.new_vm_exception_at_pc(
+                VERIFY_ERROR_CLASS.get().expect("must have init"),
+                "illegal target of jump or branch",
+            ));
+        }
+        *self.pc = target;
+        Ok(())
     }
 
     fn invoke_native(&mut self) -> NativeResult<()> {
@@ -1705,55 +1679,50 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
 
         for field in &param_descriptor {
             // SAFETY: rely on class file checking to ensure correct type
-            let arg = unsafe {
-                match field {
-                    FieldType::Byte => NativeVariable::Byte(locals[i].get_int() as _),
-                    FieldType::Char => NativeVariable::Char(locals[i].get_int() as _),
-                    FieldType::Double => {
-                        let double = Variable::get_double(locals[i], locals[i + 1]);
-                        i += 1;
-                        NativeVariable::Double(double)
-                    }
-                    FieldType::Float => NativeVariable::Float(locals[i].float),
-                    FieldType::Int => NativeVariable::Int(locals[i].get_int()),
-                    FieldType::Long => {
-                        let long = Variable::get_long(locals[i], locals[i + 1]);
-                        i += 1;
-                        NativeVariable::Long(long)
-                    }
-                    FieldType::Object(_) | FieldType::Array(_) => {
-                        NativeVariable::Reference(locals[i].reference)
-                    }
-                    FieldType::Short => NativeVariable::Short(locals[i].get_int() as _),
-                    FieldType::Boolean => NativeVariable::Boolean(locals[i].get_int() == 1),
-                }
-            };
-            i += 1;
-            args.push(arg);
+            args.push(unsafe { NativeVariable::from_local(field, locals, &mut i) });
         }
 
+        let key = (class_name, method_name, param_descriptor);
         let method = *NATIVE_FUNCTIONS
-            .get(&(class_name, method_name, param_descriptor))
-            .expect("cannot find native method");
-
+            .get(&key)
+            .unwrap_or_else(|| panic!("cannot find native method {}.{}", key.0, key.1));
+
+        // skip the caller of the native method too, e.g. `Class.forName` for `getCallerClass`
+        let caller = self
+            .frame
+            .previous_frame
+            .as_ref()
+            .and_then(|f| f.previous_frame.as_ref())
+            .filter(|f| !f.is_dummy())
+            .map(|f| Arc::clone(&f.class));
         let ret = method(NativeEnv {
             args,
             heap: self.heap,
             class: Arc::clone(&self.frame.class),
+            caller,
+            thread_id: self.next_native_thread.thread_id(),
+            thread: &self.next_native_thread,
         })?;
-        // TODO: check actual return type
-        let stack = &mut self.frame.stack;
-        match ret {
-            None => {}
-            Some(NativeVariable::Byte(b)) => self.iconst(b as _),
-            Some(NativeVariable::Boolean(b)) => self.iconst(b as _),
-            Some(NativeVariable::Char(c)) => self.iconst(c as _),
-            Some(NativeVariable::Short(s)) => self.iconst(s as _),
-            Some(NativeVariable::Int(i)) => self.iconst(i),
-            Some(NativeVariable::Long(l)) => self.push_long(l),
-            Some(NativeVariable::Float(f)) => self.fconst(f),
-            Some(NativeVariable::Double(d)) => self.push_double(d),
-            Some(NativeVariable::Reference(r)) => stack.push(Variable { reference: r }),
+        // a native returning the wrong variant would leave the stack unbalanced for the caller
+        if cfg!(debug_assertions) {
+            let is_declared_type = match (&ret, &self.frame.return_type) {
+                (None, None) => true,
+                (Some(ret), Some(return_type)) => ret.is_of_type(return_type),
+                _ => false,
+            };
+            assert!(
+                is_declared_type,
+                "native method {}.{} returned {ret:?}, but its return type is {}",
+                key.0,
+                key.1,
+                self.frame
+                    .return_type
+                    .as_ref()
+                    .map_or("void".to_string(), ToString::to_string)
+            );
+        }
+        if let Some(ret) = ret {
+            ret.push_onto(&mut self.frame.stack);
         }
         Ok(())
     }
@@ -1770,20 +1739,12 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
 
         let field_type = get_array_type(arr_object.get_class()).expect("not an array");
         let type_size = field_type.get_field_type_size();
-        let arr_len = arr_object.get_array_size(type_size);
         // check array type
         if type_size != size_of::<T>() {
             panic!("invalid array type");
         }
-        // check array size
-        if index >= arr_len as _ {
-            return Err(Exception::new_vm(
-                ARRAY_INDEX_OUT_OF_BOUND_EXCEPTION_CLASS
-                    .get()
-                    .expect("must have init"),
-            ));
-        }
-        Ok(unsafe { get_array_index::<T, _>(arr_object.as_ref(), index as _) })
+        // SAFETY: the element size is checked
+        unsafe { get_array_index_checked::<T, _>(arr_object.as_ref(), index) }
     }
 
     fn arr_store<T: ArrayType>(&mut self, value: T) -> NativeResult<()> {
@@ -1799,7 +1760,6 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
 
         let field_type = get_array_type(arr_object.get_class()).expect("not an array");
         let type_size = field_type.get_field_type_size();
-        let arr_len = arr_object.get_array_size(type_size);
         // check array type
         // TODO: check for object type
         if type_size != size_of::<T>() {
@@ -1807,54 +1767,35 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
                 ARRAY_STORE_EXCEPTION_CLASS.get().expect("must have init"),
             ));
         }
-        // check array size
-        if index >= arr_len as _ {
-            return Err(Exception::new_vm(
-                ARRAY_INDEX_OUT_OF_BOUND_EXCEPTION_CLASS
-                    .get()
-                    .expect("must have init"),
-            ));
-        }
+        // SAFETY: the element size is checked
+        unsafe { put_array_index_checked(arr_object.as_ref(), index, value) }
+    }
 
-        unsafe {
-            // SAFETY: must be array
-            put_array_index(arr_object.as_ref(), index as _, value);
-        }
-        Ok(())
+    /// Whether the array an `xastore` stores into, below the index on the operand stack, is a
+    /// `boolean[]`
+    fn is_boolean_array_below_index(&self) -> bool {
+        // SAFETY: rely on class file checking to ensure correct type
+        let arr = unsafe { self.frame.stack[self.frame.stack.len() - 2].reference };
+        // null is left for arr_store to throw
+        arr != 0 && self.heap.read().unwrap().get(arr).get_class().class_name() == "[Z"
     }
 
-    fn lookup_switch(&mut self) {
-        let start_pc = *self.pc;
-        *self.pc = (*self.pc & 4) + 3;
-        let default = self.get_i32_args();
-        let npairs = self.get_i32_args();
+    fn lookup_switch(&mut self, switch: &LookupSwitch) -> NativeResult<()> {
         let key = self.pop_int();
-
-        for _ in 0..npairs {
-            let mat = self.get_i32_args();
-            let offset = self.get_i32_args();
-            if key == mat {
-                *self.pc = start_pc.wrapping_add_signed(offset as isize);
-                return;
-            }
-        }
-        *self.pc = start_pc.wrapping_add_signed(default as isize);
+        let target = switch
+            .pairs
+            .iter()
+            .find(|(mat, _)| *mat == key)
+            .map_or(switch.default, |(_, target)| *target);
+        self.jump(target)
     }
-    fn table_switch(&mut self) {
-        let start_pc = *self.pc;
-        *self.pc = (*self.pc & 4) + 3;
-        let default = self.get_i32_args();
-        let low = self.get_i32_args();
-        let high = self.get_i32_args();
-        let index = self.pop_int();
 
-        if index < low || index > high {
-            *self.pc = start_pc.wrapping_add_signed(default as isize);
-            return;
-        }
-        let pos = index - low;
-        let offset = self.get_i32_args_from(*self.pc + 4 * pos as usize);
-        *self.pc = start_pc.wrapping_add_signed(offset as isize);
+    fn table_switch(&mut self, switch: &TableSwitch) -> NativeResult<()> {
+        let index = self.pop_int();
+        let target = usize::try_from(index as i64 - switch.low as i64)
+            .ok()
+            .and_then(|pos| switch.targets.get(pos));
+        self.jump(*target.unwrap_or(&switch.default))
     }
 
     fn resolve_class(&self, class: &CpClassInfo) -> NativeResult<Arc<Class>> {
@@ -1862,7 +1803,11 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
             Ok(Arc::clone(&self.frame.class))
         } else {
             let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
-            class.get_or_load_class(|| bootstrap_class_loader.resolve_class(&class.name))
+            class.get_or_load_class(|| {
+                let resolved = bootstrap_class_loader.resolve_class(&class.name)?;
+                bootstrap_class_loader.check_readable(&self.frame.class, &resolved);
+                Ok(resolved)
+            })
         }
     }
 
@@ -1873,9 +1818,24 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
     ) -> NativeResult<FieldResolve> {
         let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
         let class = bootstrap_class_loader.resolve_class(&field_ref.class_name)?;
-        resolve_field(&class, field_ref, is_static).ok_or_else(|| {
+        let resolve = resolve_field(&class, field_ref, is_static).ok_or_else(|| {
+            if resolve_field(&class, field_ref, !is_static).is_some() {
+                return Exception::new_vm_msg(
+                    INCOMPATIBLE_CLASS_CHANGE_ERROR_CLASS
+                        .get()
+                        .expect("must have init"),
+                    &format!(
+                        "Expected {} field {}.{}",
+                        if is_static { "static" } else { "non-static" },
+                        field_ref.class_name.replace('/', "."),
+                        field_ref.name_and_type.name.to_str()
+                    ),
+                );
+            }
             Exception::new_vm(NO_SUCH_FIELD_ERROR_CLASS.get().expect("must have init"))
-        })
+        })?;
+        check_field_access(&self.frame.class, &resolve, is_static)?;
+        Ok(resolve)
     }
 
     fn resolve_static_method(
@@ -1884,9 +1844,37 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
     ) -> NativeResult<MethodResolve> {
         let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
         let class = bootstrap_class_loader.resolve_class(&method_ref.class_name)?;
-        resolve_static_method(&class, method_ref).ok_or_else(|| {
+        let resolve = resolve_static_method(&class, method_ref).ok_or_else(|| {
+            if resolve_method_statically(&class, method_ref).is_some() {
+                return Exception::new_vm_msg(
+                    INCOMPATIBLE_CLASS_CHANGE_ERROR_CLASS
+                        .get()
+                        .expect("must have init"),
+                    &format!(
+                        "Expected static method {}.{}{}",
+                        method_ref.class_name.replace('/', "."),
+                        method_ref.name_and_type.name.to_str(),
+                        method_ref.name_and_type.descriptor.to_descriptor()
+                    ),
+                );
+            }
+            Exception::new_vm(NO_SUCH_METHOD_ERROR_CLASS.get().expect("must have init"))
+        })?;
+        check_method_access(&self.frame.class, &resolve)?;
+        Ok(resolve)
+    }
+
+    fn resolve_interface_method(
+        &self,
+        method_ref: &runtime::Methodref,
+    ) -> NativeResult<MethodResolve> {
+        let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+        let class = bootstrap_class_loader.resolve_class(&method_ref.class_name)?;
+        let resolve = resolve_interface_method(&class, method_ref).ok_or_else(|| {
             Exception::new_vm(NO_SUCH_METHOD_ERROR_CLASS.get().expect("must have init"))
-        })
+        })?;
+        check_method_access(&self.frame.class, &resolve)?;
+        Ok(resolve)
     }
 
     fn resolve_method_statically(
@@ -1895,9 +1883,24 @@ impl<'t, 'f> InterpreterEnv<'t, 'f> {
     ) -> NativeResult<MethodResolve> {
         let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
         let class = bootstrap_class_loader.resolve_class(&method_ref.class_name)?;
-        resolve_method_statically(&class, method_ref).ok_or_else(|| {
+        let resolve = resolve_method_statically(&class, method_ref).ok_or_else(|| {
+            if resolve_static_method(&class, method_ref).is_some() {
+                return Exception::new_vm_msg(
+                    INCOMPATIBLE_CLASS_CHANGE_ERROR_CLASS
+                        .get()
+                        .expect("must have init"),
+                    &format!(
+                        "Expecting non-static method {}.{}{}",
+                        method_ref.class_name.replace('/', "."),
+                        method_ref.name_and_type.name.to_str(),
+                        method_ref.name_and_type.descriptor.to_descriptor()
+                    ),
+                );
+            }
             Exception::new_vm(NO_SUCH_METHOD_ERROR_CLASS.get().expect("must have init"))
-        })
+        })?;
+        check_method_access(&self.frame.class, &resolve)?;
+        Ok(resolve)
     }
 
     fn new_vm_env(&self) -> VmEnv<'_> {
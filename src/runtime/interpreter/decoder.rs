@@ -0,0 +1,433 @@
+use crate::runtime::interpreter::instructions as inst;
+
+// Decoding of the code of a method for the interpreter, done once on the first invocation so
+// that the interpreter loop indexes instructions instead of parsing operands, computing switch
+// padding and branch offsets every time an instruction runs.
+//
+// The program counter of a frame is an index into the decoded instructions. The exception table
+// and error messages still refer to byte offsets, which every instruction keeps.
+
+/// Branch target that is outside of the code, or not at the start of an instruction.
+const INVALID_TARGET: usize = usize::MAX;
+
+/// An instruction with its operands decoded, and its branch targets resolved to indexes of the
+/// decoded instructions.
+///
+/// Takes 24 bytes, where most instructions take 1 to 3 bytes of code, and is kept along the raw
+/// code for as long as the class is loaded. Methods that are never invoked are not decoded.
+#[derive(Debug, Clone)]
+pub(crate) struct DecodedInstruction {
+    /// The instruction modified by a `wide` prefix, which is folded into it.
+    pub(crate) opcode: u8,
+    pub(crate) offset: u32,
+    pub(crate) operands: Operands,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Operands {
+    None,
+    /// Local variable index, or constant pool index.
+    Index(u16),
+    /// Value of `bipush` and `sipush`, or `atype` of `newarray`.
+    Const(i32),
+    Iinc {
+        index: u16,
+        value: i16,
+    },
+    Branch(usize),
+    TableSwitch(Box<TableSwitch>),
+    LookupSwitch(Box<LookupSwitch>),
+    MultiArray {
+        index: u16,
+        dimensions: u8,
+    },
+    InvokeInterface {
+        index: u16,
+        /// Slots taken by the arguments, including the receiver.
+        count: u8,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TableSwitch {
+    pub(crate) default: usize,
+    pub(crate) low: i32,
+    pub(crate) targets: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct LookupSwitch {
+    pub(crate) default: usize,
+    pub(crate) pairs: Vec<(i32, usize)>,
+}
+
+impl DecodedInstruction {
+    #[inline]
+    pub(crate) fn index(&self) -> u16 {
+        match self.operands {
+            Operands::Index(index) | Operands::InvokeInterface { index, .. } => index,
+            _ => self.missing_operands(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn constant(&self) -> i32 {
+        match self.operands {
+            Operands::Const(value) => value,
+            _ => self.missing_operands(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn target(&self) -> usize {
+        match self.operands {
+            Operands::Branch(target) => target,
+            _ => self.missing_operands(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn iinc(&self) -> (u16, i16) {
+        match self.operands {
+            Operands::Iinc { index, value } => (index, value),
+            _ => self.missing_operands(),
+        }
+    }
+
+    pub(crate) fn table_switch(&self) -> &TableSwitch {
+        match &self.operands {
+            Operands::TableSwitch(switch) => switch,
+            _ => self.missing_operands(),
+        }
+    }
+
+    pub(crate) fn lookup_switch(&self) -> &LookupSwitch {
+        match &self.operands {
+            Operands::LookupSwitch(switch) => switch,
+            _ => self.missing_operands(),
+        }
+    }
+
+    pub(crate) fn multi_array(&self) -> (u16, u8) {
+        match self.operands {
+            Operands::MultiArray { index, dimensions } => (index, dimensions),
+            _ => self.missing_operands(),
+        }
+    }
+
+    pub(crate) fn invoke_interface(&self) -> (u16, u8) {
+        match self.operands {
+            Operands::InvokeInterface { index, count } => (index, count),
+            _ => self.missing_operands(),
+        }
+    }
+
+    // instructions are decoded with all their operands, so only asking for the operands of
+    // another kind of instruction ends up here
+    #[cold]
+    fn missing_operands(&self) -> ! {
+        panic!(
+            "instruction {} at {} has no such operands",
+            self.opcode, self.offset
+        )
+    }
+}
+
+/// Decodes the instructions of `code`, or returns the offset of the instruction cut short by the
+/// end of the code.
+pub(crate) fn decode(code: &[u8]) -> Result<Vec<DecodedInstruction>, usize> {
+    let mut instructions = vec![];
+    // index of the instruction starting at each offset
+    let mut indexes = vec![INVALID_TARGET; code.len()];
+    let mut reader = Reader { code, pos: 0 };
+    while reader.pos < code.len() {
+        let offset = reader.pos;
+        indexes[offset] = instructions.len();
+        let (opcode, operands) = decode_next(&mut reader).ok_or(offset)?;
+        instructions.push(DecodedInstruction {
+            opcode,
+            offset: offset as u32,
+            operands,
+        });
+    }
+
+    // branch targets are decoded as offsets, now that every instruction has an index
+    let resolve = |target: &mut usize| {
+        *target = indexes.get(*target).copied().unwrap_or(INVALID_TARGET);
+    };
+    for instruction in &mut instructions {
+        match &mut instruction.operands {
+            Operands::Branch(target) => resolve(target),
+            Operands::TableSwitch(switch) => {
+                resolve(&mut switch.default);
+                switch.targets.iter_mut().for_each(resolve);
+            }
+            Operands::LookupSwitch(switch) => {
+                resolve(&mut switch.default);
+                switch
+                    .pairs
+                    .iter_mut()
+                    .for_each(|(_, target)| resolve(target));
+            }
+            _ => {}
+        }
+    }
+    Ok(instructions)
+}
+
+struct Reader<'a> {
+    code: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.code.get(self.pos..self.pos + N)?.try_into().ok()?;
+        self.pos += N;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes().map(u8::from_be_bytes)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes().map(u16::from_be_bytes)
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.bytes().map(i32::from_be_bytes)
+    }
+}
+
+/// Decodes the instruction at the reader, returns `None` if it is truncated.
+fn decode_next(reader: &mut Reader) -> Option<(u8, Operands)> {
+    let offset = reader.pos;
+    let opcode = reader.u8()?;
+    let branch = |delta: i32| {
+        offset
+            .checked_add_signed(delta as isize)
+            .unwrap_or(INVALID_TARGET)
+    };
+
+    let operands = match opcode {
+        inst::ILOAD
+        | inst::LLOAD
+        | inst::FLOAD
+        | inst::DLOAD
+        | inst::ALOAD
+        | inst::ISTORE
+        | inst::LSTORE
+        | inst::FSTORE
+        | inst::DSTORE
+        | inst::ASTORE
+        | inst::RET
+        | inst::LDC => Operands::Index(reader.u8()? as u16),
+        inst::LDC_W
+        | inst::LDC2_W
+        | inst::GETSTATIC..=inst::INVOKESTATIC
+        | inst::NEW
+        | inst::ANEWARRAY
+        | inst::CHECKCAST
+        | inst::INSTANCEOF => Operands::Index(reader.u16()?),
+        inst::INVOKEINTERFACE => {
+            let operands = Operands::InvokeInterface {
+                index: reader.u16()?,
+                count: reader.u8()?,
+            };
+            // zero
+            reader.u8()?;
+            operands
+        }
+        inst::INVOKEDYNAMIC => {
+            let index = reader.u16()?;
+            // two zeros
+            reader.u16()?;
+            Operands::Index(index)
+        }
+        inst::BIPUSH => Operands::Const(reader.u8()? as i8 as i32),
+        inst::SIPUSH => Operands::Const(reader.u16()? as i16 as i32),
+        inst::NEWARRAY => Operands::Const(reader.u8()? as i32),
+        inst::IINC => Operands::Iinc {
+            index: reader.u8()? as u16,
+            value: reader.u8()? as i8 as i16,
+        },
+        inst::IFEQ..=inst::JSR | inst::IFNULL | inst::IFNONNULL => {
+            Operands::Branch(branch(reader.u16()? as i16 as i32))
+        }
+        inst::GOTO_W | inst::JSR_W => Operands::Branch(branch(reader.i32()?)),
+        inst::TABLESWITCH | inst::LOOKUPSWITCH => {
+            // the operands are 4-byte aligned
+            reader.pos = (offset + 4) & !3;
+            let default = branch(reader.i32()?);
+            if opcode == inst::TABLESWITCH {
+                let low = reader.i32()?;
+                let high = reader.i32()?;
+                let targets = (low as i64..=high as i64)
+                    .map(|_| reader.i32().map(branch))
+                    .collect::<Option<_>>()?;
+                Operands::TableSwitch(Box::new(TableSwitch {
+                    default,
+                    low,
+                    targets,
+                }))
+            } else {
+                let npairs = reader.i32()?;
+                let pairs = (0..npairs)
+                    .map(|_| Some((reader.i32()?, branch(reader.i32()?))))
+                    .collect::<Option<_>>()?;
+                Operands::LookupSwitch(Box::new(LookupSwitch { default, pairs }))
+            }
+        }
+        inst::MULTIANEWARRAY => Operands::MultiArray {
+            index: reader.u16()?,
+            dimensions: reader.u8()?,
+        },
+        inst::WIDE => {
+            let modified = reader.code.get(reader.pos).copied();
+            match modified {
+                Some(
+                    modified @ (inst::ILOAD
+                    | inst::LLOAD
+                    | inst::FLOAD
+                    | inst::DLOAD
+                    | inst::ALOAD
+                    | inst::ISTORE
+                    | inst::LSTORE
+                    | inst::FSTORE
+                    | inst::DSTORE
+                    | inst::ASTORE
+                    | inst::RET),
+                ) => {
+                    reader.pos += 1;
+                    return Some((modified, Operands::Index(reader.u16()?)));
+                }
+                Some(inst::IINC) => {
+                    reader.pos += 1;
+                    let operands = Operands::Iinc {
+                        index: reader.u16()?,
+                        value: reader.u16()? as i16,
+                    };
+                    return Some((inst::IINC, operands));
+                }
+                // a prefix that modifies nothing, rejected when it runs. the next instruction is
+                // decoded on its own
+                _ => Operands::None,
+            }
+        }
+        _ => Operands::None,
+    };
+    Some((opcode, operands))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_branch_targets() {
+        // 0: iconst_0, 1: ifeq +8, 4: goto_w -2, 9: return
+        let code = [
+            inst::ICONST_0,
+            inst::IFEQ,
+            0x00,
+            0x08,
+            inst::GOTO_W,
+            0xFF,
+            0xFF,
+            0xFF,
+            0xFE,
+            inst::RETURN,
+        ];
+        let instructions = decode(&code).unwrap();
+        let offsets: Vec<_> = instructions.iter().map(|i| i.offset).collect();
+        assert_eq!(offsets, [0, 1, 4, 9]);
+        assert_eq!(instructions[1].target(), 3);
+        // into the operands of the ifeq
+        assert_eq!(instructions[2].target(), INVALID_TARGET);
+    }
+
+    #[test]
+    fn test_decode_switch_padding() {
+        for start in 0..4 {
+            let mut code = vec![inst::NOP; start];
+            code.push(inst::TABLESWITCH);
+            code.resize(code.len().next_multiple_of(4), 0);
+            let switch_offset = start as i32;
+            // default to the return after the switch, case 1 back to the switch itself
+            let end = code.len() as i32 + 16;
+            for operand in [end - switch_offset, 1, 1, 0] {
+                code.extend(operand.to_be_bytes());
+            }
+            code.push(inst::RETURN);
+
+            let instructions = decode(&code).unwrap();
+            assert_eq!(instructions.len(), start + 2, "start {start}");
+            let switch = instructions[start].table_switch();
+            assert_eq!(switch.default, start + 1, "start {start}");
+            assert_eq!(switch.low, 1);
+            assert_eq!(switch.targets, [start]);
+        }
+    }
+
+    #[test]
+    fn test_decode_lookup_switch() {
+        let mut code = vec![inst::ICONST_0, inst::LOOKUPSWITCH, 0, 0];
+        // default to the first return, -1 to the second, 7 into the operands
+        for operand in [27_i32, 2, -1, 28, 7, 5] {
+            code.extend(operand.to_be_bytes());
+        }
+        code.push(inst::RETURN);
+        code.push(inst::RETURN);
+
+        let instructions = decode(&code).unwrap();
+        let switch = instructions[1].lookup_switch();
+        assert_eq!(instructions[2].offset, 28);
+        assert_eq!(switch.default, 2);
+        assert_eq!(switch.pairs, [(-1, 3), (7, INVALID_TARGET)]);
+    }
+
+    #[test]
+    fn test_decode_wide() {
+        let code = [
+            inst::WIDE,
+            inst::ILOAD,
+            0x01,
+            0x00,
+            inst::WIDE,
+            inst::IINC,
+            0x01,
+            0x00,
+            0xFF,
+            0x9C,
+            inst::WIDE,
+            inst::NOP,
+        ];
+        let instructions = decode(&code).unwrap();
+        assert_eq!(instructions[0].opcode, inst::ILOAD);
+        assert_eq!(instructions[0].index(), 256);
+        assert_eq!(instructions[1].opcode, inst::IINC);
+        assert_eq!(instructions[1].iinc(), (256, -100));
+        assert_eq!(instructions[2].opcode, inst::WIDE);
+        assert_eq!(instructions[3].opcode, inst::NOP);
+        assert_eq!(instructions[3].offset, 11);
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        assert_eq!(
+            decode(&[inst::ICONST_0, inst::SIPUSH, 0x01]).unwrap_err(),
+            1
+        );
+        // the jump offsets of the switch end early
+        let mut code = vec![inst::TABLESWITCH, 0, 0, 0];
+        for operand in [16_i32, 0, 1, 16] {
+            code.extend(operand.to_be_bytes());
+        }
+        assert_eq!(decode(&code).unwrap_err(), 0);
+        assert_eq!(
+            decode(&[inst::WIDE, inst::IINC, 0x00, 0x01]).unwrap_err(),
+            0
+        );
+    }
+}
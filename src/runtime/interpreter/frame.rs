@@ -1,24 +1,36 @@
 use crate::{
     class::JavaStr,
     consts::{ClassAccessFlag, MethodAccessFlag},
-    descriptor::{FieldType, ReturnType},
+    descriptor::{FieldType, MethodDescriptor, ReturnType},
     runtime,
     runtime::{
-        CodeAttribute, Exception, ExceptionTableItem, NativeResult, VmEnv, VtableIndex,
-        class_loader::initialize_class,
+        CodeAttribute, Exception, ExceptionTableItem, NativeResult, NativeVariable, VmEnv,
+        VtableIndex,
+        class_loader::{
+            abstract_method_error, get_class_object, initialize_class, intern_string, new_instance,
+            select_interface_method,
+        },
+        famous_classes::{
+            NO_SUCH_METHOD_ERROR_CLASS, STACK_OVERFLOW_ERROR_CLASS, VERIFY_ERROR_CLASS,
+        },
         global::BOOTSTRAP_CLASS_LOADER,
         inheritance::is_same_or_sub_class_of,
-        interpreter::{InterpreterEnv, Next, global, instructions},
+        interpreter::{InterpreterEnv, Next, decoder::DecodedInstruction, global, instructions},
     },
 };
+use once_cell::sync::OnceCell;
 use std::{
     fmt::{Debug, Formatter},
+    iter,
     sync::{
         Arc,
         atomic::{AtomicUsize, Ordering},
     },
 };
 
+// slots of the frames of a frame group when `Thread::new` is given 0
+pub(in crate::runtime) const DEFAULT_MAX_FRAME_SIZE: usize = 1 << 16;
+
 pub struct Thread<'t> {
     pub(in crate::runtime) top_frame: Option<Frame>,
     max_frame_size: usize,
@@ -28,39 +40,93 @@ pub struct Thread<'t> {
 
 pub struct Frame {
     pub(in crate::runtime) class: Arc<runtime::Class>,
-    pub(super) code: Arc<[u8]>,
+    pub(super) code: Arc<[DecodedInstruction]>,
     pub(super) return_type: ReturnType,
     pub(super) locals: Vec<Variable>,
+    pub(super) max_locals: usize,
     pub(super) stack: Vec<Variable>,
     pub(in crate::runtime) previous_frame: Option<Box<Frame>>,
     pub(in crate::runtime) method_name: String,
     pub(super) param_descriptor: Vec<FieldType>,
     pub(super) is_static: bool,
     pub(super) exception_table: Vec<ExceptionTableItem>,
+    // slots of the locals and operand stacks of this frame and the ones below it in the group
+    pub(super) frame_size: usize,
+    // the object whose monitor a synchronized method entered, exited when the frame is popped
+    pub(super) monitor: Option<u32>,
+    // for the copies of `clone_dummy`, the offset in the code of the instruction the frame is at
+    pub(super) offset: Option<u32>,
 }
 
 impl Frame {
-    pub(in crate::runtime) fn clone_dummy(&self) -> Frame {
-        Frame {
-            class: Arc::clone(&self.class),
-            code: Arc::new([]),
-            return_type: self.return_type.clone(),
-            locals: vec![],
-            stack: vec![],
-            previous_frame: self
-                .previous_frame
-                .as_ref()
-                .map(|f| Box::new(f.clone_dummy())),
-            method_name: self.method_name.clone(),
-            param_descriptor: self.param_descriptor.clone(),
-            is_static: self.is_static,
-            exception_table: vec![],
+    /// Copies the frames of the group down from this one, at instruction `pc`, without their
+    /// code, locals and operand stacks.
+    pub(in crate::runtime) fn clone_dummy(&self, pc: usize) -> Frame {
+        // built from the bottom up rather than recursively, the group may be deep
+        let frames: Vec<_> =
+            iter::successors(Some(self), |f| f.previous_frame.as_deref()).collect();
+        // the callers are at the invoke before the return address of their callee
+        let mut pc = Some(pc);
+        let offsets: Vec<_> = frames
+            .iter()
+            .map(|f| {
+                if f.is_dummy() {
+                    pc = None;
+                    return f.offset;
+                }
+                let offset = pc.and_then(|pc| f.code.get(pc)).map(|i| i.offset);
+                // SAFETY: the first two must be return address
+                let (upper, lower) =
+                    unsafe { (f.stack[0].return_address, f.stack[1].return_address) };
+                // the bottom frames of a group are called with no return address
+                pc = (((upper as usize) << 32) | lower as usize).checked_sub(1);
+                offset
+            })
+            .collect();
+        let mut previous_frame = None;
+        for (f, offset) in frames.into_iter().zip(offsets).rev() {
+            previous_frame = Some(Box::new(Frame {
+                class: Arc::clone(&f.class),
+                code: Arc::new([]),
+                return_type: f.return_type.clone(),
+                locals: vec![],
+                max_locals: 0,
+                stack: vec![],
+                previous_frame,
+                method_name: f.method_name.clone(),
+                param_descriptor: f.param_descriptor.clone(),
+                is_static: f.is_static,
+                exception_table: vec![],
+                frame_size: f.frame_size,
+                monitor: None,
+                offset,
+            }));
         }
+        *previous_frame.expect("self is in the frames")
     }
 
-    fn is_dummy(&self) -> bool {
+    pub(super) fn is_dummy(&self) -> bool {
         self.code.is_empty()
     }
+
+    // called once the frame is popped, by a return or an exception it does not catch
+    fn exit_monitor(&mut self) {
+        if let Some(monitor) = self.monitor.take() {
+            let object = global::HEAP.read().unwrap().get(monitor);
+            // SAFETY: entered by current thread when the frame was pushed
+            unsafe { object.get_monitor().exit() }
+        }
+    }
+}
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        // unlinks the frames below one by one, dropping them recursively may overflow the stack
+        let mut previous_frame = self.previous_frame.take();
+        while let Some(mut frame) = previous_frame {
+            previous_frame = frame.previous_frame.take();
+        }
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -97,10 +163,57 @@ impl Variable {
     #[inline]
     pub unsafe fn get_long(pre: Self, suf: Self) -> i64 {
         let upper = unsafe { pre.get_int() as i64 };
-        let lower = unsafe { suf.get_int() as i64 };
+        let lower = unsafe { suf.get_int() as u32 as i64 };
         (upper << 32) | lower
     }
 
+    /// # Safety
+    ///
+    /// should ensure the underlying type is float
+    #[inline]
+    pub unsafe fn get_float(self) -> f32 {
+        unsafe { self.float }
+    }
+
+    /// # Safety
+    ///
+    /// should ensure the underlying type is reference
+    #[inline]
+    pub unsafe fn get_reference(self) -> u32 {
+        unsafe { self.reference }
+    }
+
+    /// Formats the value as `field_type`, which the caller knows from a descriptor or the stack
+    /// map, as the union does not tell. A slot of a long or double is one half of it.
+    ///
+    /// # Safety
+    ///
+    /// should ensure the underlying type is `field_type`
+    pub unsafe fn debug_as(self, field_type: &FieldType) -> String {
+        // SAFETY: the caller ensures the type
+        unsafe {
+            match field_type {
+                FieldType::Boolean => (self.int != 0).to_string(),
+                FieldType::Byte | FieldType::Short | FieldType::Int => self.int.to_string(),
+                FieldType::Char => match char::from_u32(self.int as u16 as u32) {
+                    Some(c) => format!("{c:?}"),
+                    // a surrogate
+                    None => format!("'\\u{:04x}'", self.int as u16),
+                },
+                FieldType::Float => format!("{:?}f", self.float),
+                FieldType::Long | FieldType::Double => {
+                    format!("{field_type} half {:#010x}", self.int as u32)
+                }
+                FieldType::Object(_) | FieldType::Array(_) if self.reference == 0 => {
+                    "null".to_string()
+                }
+                FieldType::Object(_) | FieldType::Array(_) => {
+                    format!("{field_type}@{:#x}", self.reference)
+                }
+            }
+        }
+    }
+
     #[inline]
     pub fn put_long(long: i64) -> (Variable, Variable) {
         let lower = long as i32;
@@ -113,8 +226,8 @@ impl Variable {
     /// should ensure the underlying type is double
     #[inline]
     pub unsafe fn get_double(pre: Self, suf: Self) -> f64 {
-        let upper = unsafe { pre.get_int() as u64 };
-        let lower = unsafe { suf.get_int() as u64 };
+        let upper = unsafe { pre.get_int() as u32 as u64 };
+        let lower = unsafe { suf.get_int() as u32 as u64 };
         f64::from_bits((upper << 32) | lower)
     }
 
@@ -128,6 +241,9 @@ impl Variable {
 }
 
 impl Thread<'_> {
+    /// `max_frame_size` bounds the slots of the locals and operand stacks of the frames of a frame
+    /// group, beyond which a call throws `StackOverflowError`, 0 takes a default. Native methods
+    /// run the java code they call in a frame group of its own.
     pub fn new(max_frame_size: usize) -> Self {
         static COUNTER: AtomicUsize = AtomicUsize::new(0);
 
@@ -145,18 +261,30 @@ impl Thread<'_> {
 
         Thread {
             top_frame: None,
-            max_frame_size,
+            max_frame_size: if max_frame_size == 0 {
+                DEFAULT_MAX_FRAME_SIZE
+            } else {
+                max_frame_size
+            },
             thread_id,
             previous_thread: None,
         }
     }
 
+    pub(in crate::runtime) fn thread_id(&self) -> usize {
+        self.thread_id
+    }
+
     pub fn new_main_frame(
         &mut self,
         main_class: &str,
         method_name: &str,
         param_descriptor: &[FieldType],
     ) {
+        // the thread running main is the main thread created when the vm is built
+        if let Some(&main_thread) = global::MAIN_THREAD_OBJECT.get() {
+            global::THREAD_OBJECTS.insert(self.thread_id, main_thread);
+        }
         let loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
         let main_class = loader
             .resolve_class(main_class)
@@ -167,7 +295,8 @@ impl Thread<'_> {
             &JavaStr::from_str(method_name),
             param_descriptor,
             0,
-        );
+        )
+        .expect("main frame must fit in the stack");
     }
     pub(in crate::runtime) fn new_frame(
         &mut self,
@@ -175,18 +304,19 @@ impl Thread<'_> {
         method_name: &JavaStr,
         param_descriptor: &[FieldType],
         return_address: usize,
-    ) {
+    ) -> NativeResult<()> {
         let top_frame = &mut self.top_frame;
         let Some(method_info) = class.resolve_method(method_name, param_descriptor) else {
             panic!("{method_name:?}");
         };
         Self::new_frame_with_method_info(
             top_frame,
+            self.max_frame_size,
             Arc::clone(&class),
             method_info,
             return_address,
             false,
-        );
+        )
     }
 
     pub fn new_native_frame_group(&self, frame: Option<Frame>) -> Thread<'_> {
@@ -198,32 +328,49 @@ impl Thread<'_> {
         }
     }
 
-    fn new_frame_resolved(
-        top_frame: &mut Option<Frame>,
+    /// Calls method `index` of `class` from `frame`, whose instruction at `pc` is the invoke,
+    /// or throws `StackOverflowError` there if the new frame does not fit.
+    fn invoke_resolved(
+        &mut self,
+        frame: Frame,
         class: Arc<runtime::Class>,
         index: usize,
-        return_address: usize,
+        pc: &mut usize,
         need_this: bool,
-    ) {
+    ) -> NativeResult<()> {
         let method_info = class
             .methods
             .get(index)
             .unwrap_or_else(|| panic!("method not found {index}"));
-        Self::new_frame_with_method_info(
-            top_frame,
+        self.top_frame = Some(frame);
+        let pushed = Self::new_frame_with_method_info(
+            &mut self.top_frame,
+            self.max_frame_size,
             Arc::clone(&class),
             method_info,
-            return_address,
+            *pc + 1,
             need_this,
         );
+        match pushed {
+            Ok(()) => {
+                *pc = 0;
+                Ok(())
+            }
+            Err(exception) => {
+                let frame = self.top_frame.take().expect("the caller is kept");
+                self.handle_exception(exception, frame, pc)
+            }
+        }
     }
+
     fn new_frame_with_method_info(
         top_frame: &mut Option<Frame>,
+        max_frame_size: usize,
         class: Arc<runtime::Class>,
         method_info: &runtime::MethodInfo,
         return_address: usize,
         need_this: bool,
-    ) {
+    ) -> NativeResult<()> {
         // find code attribute
         let mut code_attribute = None;
         for attr in &method_info.attributes {
@@ -256,10 +403,11 @@ impl Thread<'_> {
 
             native_code_attribute = CodeAttribute {
                 max_stack: 2,
-                max_locals: method_info.descriptor.parameters.len() as u16,
+                max_locals: (method_info.descriptor.arg_slot_count() + need_this as usize) as u16,
                 code: Arc::new([instructions::INVOKENATIVE, return_inst]),
                 exception_table: vec![],
                 attributes: vec![],
+                instructions: OnceCell::new(),
             };
             code_attribute = Some(&native_code_attribute)
         }
@@ -270,23 +418,43 @@ impl Thread<'_> {
             );
         };
 
+        let frame_size = top_frame.as_ref().map_or(0, |frame| frame.frame_size)
+            + code.max_locals as usize
+            + code.max_stack as usize;
+        if frame_size > max_frame_size {
+            return Err(Exception::new_vm(
+                STACK_OVERFLOW_ERROR_CLASS.get().expect("must have init"),
+            ));
+        }
+
+        let instructions = code.instructions().map_err(|pc| {
+            Exception::new_vm_at(
+                VERIFY_ERROR_CLASS.get().expect("must have init"),
+                &class,
+                &method_info.name.to_str(),
+                pc,
+                "truncated instruction",
+            )
+        })?;
+        let monitor = if !method_info
+            .access_flags
+            .contains(MethodAccessFlag::SYNCHRONIZED)
+        {
+            None
+        } else if need_this {
+            let previous_frame = top_frame.as_ref().expect("this is passed by the caller");
+            let param_size = method_info.descriptor.arg_slot_count() + 1;
+            // SAFETY: this is the first argument
+            Some(unsafe { previous_frame.stack[previous_frame.stack.len() - param_size].reference })
+        } else {
+            Some(get_class_object(Arc::clone(&class))?)
+        };
+
         let mut previous_frame = top_frame.take();
         let mut locals = Vec::with_capacity(code.max_locals as _);
         if let Some(previous_frame) = previous_frame.as_mut() {
-            let mut param_size = 0;
-            for param in &method_info.descriptor.parameters {
-                match param {
-                    FieldType::Long | FieldType::Double => {
-                        param_size += 2;
-                    }
-                    _ => {
-                        param_size += 1;
-                    }
-                }
-            }
-            if need_this {
-                param_size += 1;
-            }
+            // the arguments keep their stack order, so longs and doubles stay upper half first
+            let param_size = method_info.descriptor.arg_slot_count() + need_this as usize;
             for v in previous_frame
                 .stack
                 .drain((previous_frame.stack.len() - param_size)..)
@@ -296,8 +464,9 @@ impl Thread<'_> {
         }
 
         let mut frame = Frame {
-            code: Arc::clone(&code.code),
+            code: Arc::clone(instructions),
             locals,
+            max_locals: code.max_locals as usize,
             stack: Vec::with_capacity(code.max_stack as usize + 2),
             return_type: method_info.descriptor.return_type.clone(),
             class,
@@ -306,7 +475,14 @@ impl Thread<'_> {
             param_descriptor: method_info.descriptor.parameters.to_vec(),
             is_static: !need_this,
             exception_table: code.exception_table.clone(),
+            frame_size,
+            monitor,
+            offset: None,
         };
+        if let Some(monitor) = monitor {
+            let object = global::HEAP.read().unwrap().get(monitor);
+            object.get_monitor().enter();
+        }
 
         // return address
         let lower = return_address as u32;
@@ -319,6 +495,99 @@ impl Thread<'_> {
         });
 
         *top_frame = Some(frame);
+        Ok(())
+    }
+
+    /// Initializes `class_name` if needed, then runs the static method to completion and returns
+    /// its result, or `None` for void methods.
+    pub fn invoke_static(
+        &mut self,
+        class_name: &str,
+        method_name: &str,
+        descriptor: &MethodDescriptor,
+        args: &[NativeVariable],
+    ) -> NativeResult<Option<NativeVariable>> {
+        let class = BOOTSTRAP_CLASS_LOADER
+            .get()
+            .unwrap()
+            .resolve_class(class_name)?;
+        initialize_class(&VmEnv::new(self, &global::HEAP), &class)?;
+
+        let Some(method_info) = class
+            .resolve_method(&JavaStr::from_str(method_name), &descriptor.parameters)
+            .filter(|m| {
+                m.access_flags.contains(MethodAccessFlag::STATIC)
+                    && m.descriptor.return_type == descriptor.return_type
+            })
+        else {
+            return Err(Exception::new_vm_msg(
+                NO_SUCH_METHOD_ERROR_CLASS.get().expect("must have init"),
+                &format!("{class_name}.{method_name}{descriptor}"),
+            ));
+        };
+
+        self.invoke_method(Arc::clone(&class), method_info, args)
+    }
+
+    /// Runs the method to completion in a new frame group and returns its result.
+    ///
+    /// `args` includes `this` for instance methods.
+    pub(in crate::runtime) fn invoke_method(
+        &self,
+        class: Arc<runtime::Class>,
+        method_info: &runtime::MethodInfo,
+        args: &[NativeVariable],
+    ) -> NativeResult<Option<NativeVariable>> {
+        if method_info
+            .access_flags
+            .contains(MethodAccessFlag::ABSTRACT)
+        {
+            return Err(abstract_method_error(&class, method_info));
+        }
+        // the caller of the method, collects the return value
+        let mut dummy_frame = Frame {
+            class: Arc::clone(&class),
+            code: Arc::new([]),
+            return_type: None,
+            locals: vec![],
+            max_locals: 0,
+            stack: Vec::with_capacity(args.len() * 2),
+            previous_frame: None,
+            method_name: method_info.name.to_str().into_owned(),
+            param_descriptor: vec![],
+            is_static: true,
+            exception_table: vec![],
+            frame_size: 0,
+            monitor: None,
+            offset: None,
+        };
+        for &arg in args {
+            arg.push_onto(&mut dummy_frame.stack);
+        }
+
+        let mut thread = self.new_native_frame_group(Some(dummy_frame));
+        let need_this = !method_info.access_flags.contains(MethodAccessFlag::STATIC);
+        Self::new_frame_with_method_info(
+            &mut thread.top_frame,
+            thread.max_frame_size,
+            class,
+            method_info,
+            0,
+            need_this,
+        )?;
+        thread.execute()?;
+
+        let stack = &thread
+            .top_frame
+            .as_ref()
+            .expect("dummy frame is kept")
+            .stack;
+        let Some(return_type) = &method_info.descriptor.return_type else {
+            return Ok(None);
+        };
+        // SAFETY: the method returns a value of its return type
+        let ret = unsafe { NativeVariable::from_local(return_type, stack, &mut 0) };
+        Ok(Some(ret))
     }
 
     pub fn top_frame(&mut self) -> Option<&mut Frame> {
@@ -329,22 +598,25 @@ impl Thread<'_> {
         let mut pc = 0;
         while let Some(mut frame) = self.top_frame.take() {
             if frame.is_dummy() {
+                // keep it for the caller to read the return value
+                self.top_frame = Some(frame);
                 break;
             }
 
-            let native_frame_group = self.new_native_frame_group(Some(frame.clone_dummy()));
+            let native_frame_group = self.new_native_frame_group(Some(frame.clone_dummy(pc)));
             let mut env =
                 InterpreterEnv::new(&mut pc, &mut frame, &global::HEAP, native_frame_group);
             let next = env.execute();
 
             match next {
                 Next::Return { return_pc, v1, v2 } => {
+                    frame.exit_monitor();
                     let (is_void, is_long) = match frame.return_type {
                         Some(FieldType::Long | FieldType::Double) => (false, true),
                         Some(_) => (false, false),
                         None => (true, false),
                     };
-                    self.top_frame = frame.previous_frame.map(|f| *f);
+                    self.top_frame = frame.previous_frame.take().map(|f| *f);
                     pc = return_pc;
                     if let Some(ref mut frame) = self.top_frame
                         && !is_void
@@ -355,15 +627,19 @@ impl Thread<'_> {
                         }
                     }
 
-                    print!(
-                        "return from {}.{}({:?})",
-                        frame.class.class_name, frame.method_name, frame.param_descriptor
-                    );
-                    if !is_void {
-                        if is_long {
+                    if global::VERBOSE.load(Ordering::Relaxed) {
+                        print!(
+                            "return from {}.{}({:?})",
+                            frame.class.class_name, frame.method_name, frame.param_descriptor
+                        );
+                        // SAFETY: the values returned are of the return type
+                        if let Some(FieldType::Double) = frame.return_type {
+                            print!(" with {:?}", unsafe { Variable::get_double(v1, v2) });
+                        } else if is_long {
                             print!(" with {}L", unsafe { Variable::get_long(v1, v2) });
-                        } else if let Some(FieldType::Object(cls)) = frame.return_type
+                        } else if let Some(FieldType::Object(cls)) = &frame.return_type
                             && cls == "java/lang/String"
+                            && unsafe { v1.reference } != 0
                         {
                             let str_ref = unsafe { v1.reference };
                             let obj = global::HEAP.read().unwrap().get(str_ref);
@@ -374,11 +650,11 @@ impl Thread<'_> {
                             for i in 0..len {
                                 print!("{}", unsafe { obj.get_array_index_raw(i, 1)[0] as char })
                             }
-                        } else {
-                            print!(" with {}", unsafe { v1.int });
+                        } else if let Some(return_type) = &frame.return_type {
+                            print!(" with {}", unsafe { v1.debug_as(return_type) });
                         }
+                        println!();
                     }
-                    println!();
                 }
                 Next::Exception(exception) => {
                     self.handle_exception(exception, frame, &mut pc)?;
@@ -390,9 +666,7 @@ impl Thread<'_> {
                     is_virtual,
                     this,
                 } => {
-                    self.top_frame = Some(frame);
-
-                    if !is_virtual || vtable_index < 0 {
+                    let (class, index) = if !is_virtual || vtable_index < 0 {
                         if cfg!(debug_assertions) && is_virtual {
                             let statically_resolved_method = &static_class.methods[index];
                             assert!(
@@ -405,47 +679,54 @@ impl Thread<'_> {
                                     || static_class.access_flags.contains(ClassAccessFlag::FINAL)
                             );
                         }
-                        println!("invokespecial {}.{}", static_class.class_name, index);
-                        // invokespecial
-                        Self::new_frame_resolved(
-                            &mut self.top_frame,
-                            static_class,
-                            index,
-                            pc + 1,
-                            true,
-                        );
+                        if global::VERBOSE.load(Ordering::Relaxed) {
+                            println!("invokespecial {}.{}", static_class.class_name, index);
+                        }
+                        (static_class, index)
                     } else {
                         let this_obj = global::HEAP.read().unwrap().get(this);
                         let this_class = this_obj.get_class();
                         let vtable_entry = &this_class.vtable[vtable_index as usize];
-                        let (class, method) = match &vtable_entry.index {
-                            VtableIndex::InThisClass(index) => {
-                                (this_class, &this_class.methods[*index])
-                            }
-                            VtableIndex::OtherClass { class, index } => {
-                                (class, &class.methods[*index])
-                            }
-                            VtableIndex::OtherInterface { class, index } => {
-                                (class, &class.methods[*index])
+                        let (class, index) = match &vtable_entry.index {
+                            VtableIndex::InThisClass(index) => (this_class, *index),
+                            VtableIndex::OtherClass { class, index } => (class, *index),
+                            VtableIndex::OtherInterface { class, index } => (class, *index),
+                            VtableIndex::DefaultConflict { class, index } => {
+                                // throws IncompatibleClassChangeError
+                                let exception =
+                                    select_interface_method(this_class, &class.methods[*index])
+                                        .expect_err("default methods must conflict");
+                                self.handle_exception(exception, frame, &mut pc)?;
+                                continue;
                             }
                         };
-                        println!("invokevirtual {}.{:?}", this_class.class_name, method.name);
-
-                        Self::new_frame_with_method_info(
-                            &mut self.top_frame,
-                            Arc::clone(&class),
-                            method,
-                            pc + 1,
-                            true,
-                        );
+                        if global::VERBOSE.load(Ordering::Relaxed) {
+                            println!(
+                                "invokevirtual {}.{:?}",
+                                this_class.class_name, class.methods[index].name
+                            );
+                        }
+                        (Arc::clone(class), index)
+                    };
+
+                    // e.g. a miranda method inherited from an interface without implementation
+                    if class.methods[index]
+                        .access_flags
+                        .contains(MethodAccessFlag::ABSTRACT)
+                    {
+                        let receiver =
+                            Arc::clone(global::HEAP.read().unwrap().get(this).get_class());
+                        let exception = abstract_method_error(&receiver, &class.methods[index]);
+                        self.handle_exception(exception, frame, &mut pc)?;
+                    } else {
+                        self.invoke_resolved(frame, class, index, &mut pc, true)?;
                     }
-                    pc = 0;
                 }
                 Next::InvokeStatic { class, index } => {
-                    self.top_frame = Some(frame);
-                    Self::new_frame_resolved(&mut self.top_frame, class, index, pc + 1, false);
-                    pc = 0;
-                    self.print_frames();
+                    self.invoke_resolved(frame, class, index, &mut pc, false)?;
+                    if global::VERBOSE.load(Ordering::Relaxed) {
+                        self.print_frames();
+                    }
                 }
             }
         }
@@ -454,61 +735,154 @@ impl Thread<'_> {
 
     fn handle_exception(
         &mut self,
-        exception: Exception,
+        mut exception: Exception,
         mut frame: Frame,
         pc: &mut usize,
     ) -> NativeResult<()> {
-        // TODO: if this return exception, attach the original stack
-        let (exp_class, obj_ref) = match exception {
-            // TODO: change to UserException, put stack in
-            Exception::VmException {
-                ref exception_type, ..
-            } => (Arc::clone(exception_type), 0),
-            Exception::UserException(obj_ref) => (
-                Arc::clone(global::HEAP.read().unwrap().get(obj_ref).get_class()),
-                obj_ref,
-            ),
-        };
+        // a loop rather than recursion, the stack may be deep, e.g. on StackOverflowError
+        'unwind: loop {
+            // TODO: if this return exception, attach the original stack
+            let (exp_class, obj_ref) = match exception {
+                // TODO: change to UserException, put stack in
+                Exception::VmException {
+                    ref exception_type, ..
+                } => (Arc::clone(exception_type), 0),
+                Exception::UserException(obj_ref) => (
+                    Arc::clone(global::HEAP.read().unwrap().get(obj_ref).get_class()),
+                    obj_ref,
+                ),
+                Exception::Exit(_) => return Err(exception),
+            };
 
-        let mut handler = -1;
-        for item in &frame.exception_table {
-            if !(item.start_pc as usize <= *pc && *pc < item.end_pc as usize) {
-                continue;
-            }
-            if let Some(cp_class) = &item.catch_type {
-                let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
-                let handler_class = cp_class
-                    .get_or_load_class(|| bootstrap_class_loader.resolve_class(&cp_class.name))?;
-                if !is_same_or_sub_class_of(&exp_class, &handler_class) {
+            // the exception table refers to offsets in the code
+            let offset = frame.code[*pc].offset as u16;
+            let mut handler = -1;
+            for item in &frame.exception_table {
+                if !(item.start_pc <= offset && offset < item.end_pc) {
                     continue;
                 }
-                handler = item.handler_pc as i32;
+                if let Some(cp_class) = &item.catch_type {
+                    let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+                    let handler_class = match cp_class
+                        .get_or_load_class(|| bootstrap_class_loader.resolve_class(&cp_class.name))
+                    {
+                        Ok(handler_class) => handler_class,
+                        Err(error @ Exception::Exit(_)) => return Err(error),
+                        Err(error) => {
+                            // like hotspot, the loading error replaces the exception in flight, and
+                            // its handler is looked up from the handler that cannot be loaded
+                            if global::VERBOSE.load(Ordering::Relaxed) {
+                                println!(
+                                    "{} in flight is replaced by the error loading handler class {}",
+                                    exp_class.class_name, cp_class.name
+                                );
+                            }
+                            *pc = instruction_index(&frame.code, item.handler_pc);
+                            exception = error;
+                            continue 'unwind;
+                        }
+                    };
+                    if !is_same_or_sub_class_of(&exp_class, &handler_class) {
+                        continue;
+                    }
+                    handler = item.handler_pc as i32;
+                } else {
+                    handler = item.handler_pc as i32;
+                    break;
+                }
+            }
+            if handler == -1 {
+                frame.exit_monitor();
+                if let Some(previous_frame) = frame.previous_frame.take()
+                    && !previous_frame.is_dummy()
+                {
+                    // return address
+                    // SAFETY: the first two must be return address
+                    let upper = unsafe { frame.stack[0].return_address } as usize;
+                    let lower = unsafe { frame.stack[1].return_address } as usize;
+                    // the return address is the next instruction, look up the handler at the invoke
+                    *pc = ((upper << 32) | lower) - 1;
+
+                    frame = *previous_frame;
+                    continue 'unwind;
+                }
+                return Err(exception);
             } else {
-                handler = item.handler_pc as i32;
-                break;
+                let obj_ref = match &exception {
+                    Exception::VmException {
+                        exception_type,
+                        message,
+                    } => self.new_exception_object(exception_type, message)?,
+                    Exception::UserException(_) => obj_ref,
+                    Exception::Exit(_) => unreachable!("exit is never caught"),
+                };
+                *pc = instruction_index(&frame.code, handler as u16);
+                // keep the return address at the bottom of the stack
+                frame.stack.truncate(2);
+                frame.stack.push(Variable { reference: obj_ref });
+                self.top_frame = Some(frame);
+                return Ok(());
             }
         }
-        if handler == -1 {
-            if let Some(frame) = self.top_frame.take()
-                && !frame.is_dummy()
-            {
-                // return address
-                // SAFETY: the first two must be return address
-                let upper = unsafe { frame.stack[0].return_address } as usize;
-                let lower = unsafe { frame.stack[1].return_address } as usize;
-                *pc = (upper << 32) | lower;
+    }
 
-                return self.handle_exception(exception, frame, pc);
-            }
-            return Err(exception);
+    /// Materializes a vm exception as a throwable, with `message` as its `detailMessage`.
+    pub(in crate::runtime) fn new_exception_object(
+        &self,
+        exception_type: &Arc<runtime::Class>,
+        message: &str,
+    ) -> NativeResult<u32> {
+        initialize_class(&VmEnv::new(self, &global::HEAP), exception_type)?;
+        let obj_ref = new_instance(exception_type);
+        let message_ref = if message.is_empty() {
+            0
         } else {
-            *pc = handler as usize;
-            frame.stack.clear();
-            frame.stack.push(Variable { reference: obj_ref });
-            self.top_frame = Some(frame);
-        }
+            intern_string(&JavaStr::from_str(message).into())
+        };
 
-        Ok(())
+        let constructor = exception_type
+            .resolve_method(
+                &JavaStr::from_str("<init>"),
+                &[FieldType::Object("java/lang/String".to_string())],
+            )
+            .expect("throwable must have constructor with message");
+        self.invoke_method(
+            Arc::clone(exception_type),
+            constructor,
+            &[
+                NativeVariable::Reference(obj_ref),
+                NativeVariable::Reference(message_ref),
+            ],
+        )?;
+        Ok(obj_ref)
+    }
+
+    /// The methods on the stack from the top, by their class and index in its methods, with the
+    /// offset in their code of the instruction each is at. Only the frames copied for the native
+    /// frame groups are seen, so this is for the frame group of a native method.
+    pub(in crate::runtime) fn backtrace(&self) -> Vec<(Arc<runtime::Class>, usize, u32)> {
+        let mut backtrace = vec![];
+        for thread in iter::successors(Some(self), |t| t.previous_thread) {
+            let frames =
+                iter::successors(thread.top_frame.as_ref(), |f| f.previous_frame.as_deref());
+            for frame in frames {
+                // e.g. the caller made up by `invoke_method`
+                let Some(offset) = frame.offset else {
+                    continue;
+                };
+                let index = frame
+                    .class
+                    .methods
+                    .iter()
+                    .position(|method| {
+                        method.name.to_str() == frame.method_name
+                            && method.descriptor.parameters == frame.param_descriptor
+                    })
+                    .expect("frame must run a method of its class");
+                backtrace.push((Arc::clone(&frame.class), index, offset));
+            }
+        }
+        backtrace
     }
 
     pub fn print_frames(&self) {
@@ -551,3 +925,196 @@ impl Frame {
         self.locals.push(Variable { reference });
     }
 }
+
+// the index in `code` of the exception handler at `handler_pc`
+fn instruction_index(code: &[DecodedInstruction], handler_pc: u16) -> usize {
+    code.binary_search_by_key(&(handler_pc as u32), |instruction| instruction.offset)
+        .expect("exception handler must be at the start of an instruction")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::native::boot_vm;
+
+    #[test]
+    fn test_long_and_int_args_drained_into_locals() {
+        if !boot_vm() {
+            return;
+        }
+        let long_class = BOOTSTRAP_CLASS_LOADER
+            .get()
+            .unwrap()
+            .resolve_class("java/lang/Long")
+            .unwrap();
+        // static String toString(long i, int radix)
+        let method_info = long_class
+            .resolve_method(
+                &JavaStr::from_str("toString"),
+                &[FieldType::Long, FieldType::Int],
+            )
+            .unwrap();
+        let mut caller = Frame {
+            class: Arc::clone(&long_class),
+            code: Arc::new([]),
+            return_type: None,
+            locals: vec![],
+            max_locals: 0,
+            stack: vec![],
+            previous_frame: None,
+            method_name: "caller".to_string(),
+            param_descriptor: vec![],
+            is_static: true,
+            exception_table: vec![],
+            frame_size: 0,
+            monitor: None,
+            offset: None,
+        };
+        // a value of the caller below the arguments, which is not drained
+        caller.stack.push(Variable { int: 7 });
+        let (upper, lower) = Variable::put_long(0x1234_5678_9abc_def0);
+        caller.stack.extend([upper, lower, Variable { int: -16 }]);
+
+        let mut top_frame = Some(caller);
+        Thread::new_frame_with_method_info(
+            &mut top_frame,
+            DEFAULT_MAX_FRAME_SIZE,
+            Arc::clone(&long_class),
+            method_info,
+            0,
+            false,
+        )
+        .unwrap();
+        let frame = top_frame.unwrap();
+
+        // SAFETY: the locals are a long and an int, the caller stack holds an int
+        unsafe {
+            assert_eq!(frame.locals.len(), 3);
+            assert_eq!(
+                Variable::get_long(frame.locals[0], frame.locals[1]),
+                0x1234_5678_9abc_def0
+            );
+            assert_eq!(frame.locals[2].get_int(), -16);
+
+            let caller = frame.previous_frame.as_ref().unwrap();
+            assert_eq!(caller.stack.len(), 1);
+            assert_eq!(caller.stack[0].get_int(), 7);
+        }
+    }
+
+    #[test]
+    fn test_truncated_code() {
+        if !boot_vm() {
+            return;
+        }
+        let long_class = BOOTSTRAP_CLASS_LOADER
+            .get()
+            .unwrap()
+            .resolve_class("java/lang/Long")
+            .unwrap();
+        // static long reverse(long i), with a Code attribute ending in the middle of an sipush
+        let reverse = long_class
+            .resolve_method(&JavaStr::from_str("reverse"), &[FieldType::Long])
+            .unwrap();
+        let method_info = runtime::MethodInfo {
+            access_flags: reverse.access_flags,
+            name: Arc::clone(&reverse.name),
+            descriptor: reverse.descriptor.clone(),
+            attributes: vec![runtime::AttributeInfo::Code(CodeAttribute {
+                max_stack: 2,
+                max_locals: 2,
+                code: Arc::new([instructions::ICONST_0, instructions::SIPUSH, 0x01]),
+                exception_table: vec![],
+                attributes: vec![],
+                instructions: OnceCell::new(),
+            })],
+        };
+        let (upper, lower) = Variable::put_long(1);
+        let mut top_frame = Some(Frame {
+            class: Arc::clone(&long_class),
+            code: Arc::new([]),
+            return_type: None,
+            locals: vec![],
+            max_locals: 0,
+            stack: vec![upper, lower],
+            previous_frame: None,
+            method_name: "caller".to_string(),
+            param_descriptor: vec![],
+            is_static: true,
+            exception_table: vec![],
+            frame_size: 0,
+            monitor: None,
+            offset: None,
+        });
+
+        let Err(Exception::VmException {
+            exception_type,
+            message,
+        }) = Thread::new_frame_with_method_info(
+            &mut top_frame,
+            DEFAULT_MAX_FRAME_SIZE,
+            Arc::clone(&long_class),
+            &method_info,
+            0,
+            false,
+        )
+        else {
+            panic!("truncated code must not run");
+        };
+        assert_eq!(exception_type.class_name.as_ref(), "java/lang/VerifyError");
+        assert_eq!(message, "java/lang/Long.reverse @1: truncated instruction");
+        // the caller is kept to throw it from
+        assert_eq!(top_frame.unwrap().stack.len(), 2);
+    }
+
+    #[test]
+    fn test_long_and_double_words() {
+        // the lower word has its sign bit set, which must not spread into the upper one
+        for long in [0xffff_ffff, 0x1234_5678_9abc_def0, -1, i64::MIN, i64::MAX] {
+            let (upper, lower) = Variable::put_long(long);
+            // SAFETY: the variables hold a long
+            assert_eq!(unsafe { Variable::get_long(upper, lower) }, long);
+        }
+        for double in [
+            f64::from_bits(0x8000_0000_8000_0000),
+            f64::from_bits(0x0000_0001_ffff_ffff),
+            -0.0,
+            -1.5,
+            f64::MIN_POSITIVE,
+            f64::NEG_INFINITY,
+        ] {
+            let (upper, lower) = Variable::put_double(double);
+            // SAFETY: the variables hold a double
+            let actual = unsafe { Variable::get_double(upper, lower) };
+            assert_eq!(actual.to_bits(), double.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_debug_as() {
+        let string = FieldType::Object("java/lang/String".to_string());
+        let ints = FieldType::Array(Box::new(FieldType::Int));
+        let (upper, lower) = Variable::put_long(-2);
+        // SAFETY: each variable is formatted as the type it holds
+        unsafe {
+            assert_eq!(Variable { int: -7 }.debug_as(&FieldType::Int), "-7");
+            assert_eq!(Variable { int: 1 }.debug_as(&FieldType::Boolean), "true");
+            assert_eq!(Variable { int: 0x41 }.debug_as(&FieldType::Char), "'A'");
+            assert_eq!(
+                Variable { int: 0xd800 }.debug_as(&FieldType::Char),
+                "'\\ud800'"
+            );
+            assert_eq!(Variable { float: 1.5 }.debug_as(&FieldType::Float), "1.5f");
+            assert_eq!(Variable { float: 1.5 }.get_float(), 1.5);
+            assert_eq!(Variable { reference: 0 }.debug_as(&string), "null");
+            assert_eq!(
+                Variable { reference: 0x2a }.debug_as(&string),
+                "java/lang/String@0x2a"
+            );
+            assert_eq!(Variable { reference: 0x2a }.get_reference(), 0x2a);
+            assert_eq!(Variable { reference: 3 }.debug_as(&ints), "int[]@0x3");
+            assert_eq!(upper.debug_as(&FieldType::Long), "long half 0xffffffff");
+            assert_eq!(lower.debug_as(&FieldType::Long), "long half 0xfffffffe");
+        }
+    }
+}
@@ -1,7 +1,10 @@
-use std::sync::{LazyLock, OnceLock, RwLock};
+use std::sync::{Condvar, LazyLock, Mutex, OnceLock, RwLock, atomic::AtomicBool};
+
+use dashmap::DashMap;
 
 use crate::runtime::{StringTable, class_loader::BootstrapClassLoader, heap::Heap};
 use crate::runtime::heap::reflection::ClassTable;
+use crate::runtime::native::initial_system_properties;
 
 pub(in crate::runtime) static HEAP: RwLock<Heap> = RwLock::new(Heap::new());
 pub(in crate::runtime) static STRING_TABLE: LazyLock<RwLock<StringTable>> =
@@ -11,3 +14,28 @@ pub(in crate::runtime) static CLASS_TABLE: LazyLock<RwLock<ClassTable>> =
 
 pub(in crate::runtime) static BOOTSTRAP_CLASS_LOADER: OnceLock<BootstrapClassLoader> =
     OnceLock::new();
+
+// whether `assert` statements are enabled
+pub(in crate::runtime) static ASSERTION_STATUS: AtomicBool = AtomicBool::new(false);
+
+// whether to trace class loading and method calls to stdout
+pub(in crate::runtime) static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+// whether to check the categories of the operand stack values when linking classes
+pub(in crate::runtime) static VERIFY_CATEGORIES: AtomicBool = AtomicBool::new(false);
+
+// `java.lang.Thread` objects of the runtime threads by thread id, created on first use
+pub(in crate::runtime) static THREAD_OBJECTS: LazyLock<DashMap<usize, u32>> =
+    LazyLock::new(DashMap::new);
+
+// threads started from java that are not daemons and have not finished yet, with the condvar
+// notified when one finishes
+pub(in crate::runtime) static NON_DAEMON_THREADS: (Mutex<usize>, Condvar) =
+    (Mutex::new(0), Condvar::new());
+
+// `java.lang.Thread` object of the main thread, created when the vm is built
+pub(in crate::runtime) static MAIN_THREAD_OBJECT: OnceLock<u32> = OnceLock::new();
+
+// system properties handed to `jdk.internal.util.SystemProps`
+pub(in crate::runtime) static SYSTEM_PROPERTIES: LazyLock<DashMap<String, String>> =
+    LazyLock::new(initial_system_properties);
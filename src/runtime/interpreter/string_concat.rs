@@ -0,0 +1,182 @@
+//! String concatenation call sites, which javac compiles the `+` operator on strings into, linked
+//! by `java.lang.invoke.StringConcatFactory`
+
+use super::{InterpreterEnv, Variable};
+use crate::{
+    class::JavaStr,
+    descriptor::{FieldType, MethodDescriptor},
+    runtime::{
+        self, BootstrapMethod, Methodref, NativeResult, NativeVariable,
+        famous_classes::STRING_CLASS,
+        native::{get_string_chars, new_string},
+    },
+};
+use std::sync::Arc;
+
+/// Replaced by the next dynamic argument in the recipe
+const TAG_ARG: u16 = 1;
+/// Replaced by the next static argument after the recipe
+const TAG_CONST: u16 = 2;
+
+/// Whether the bootstrap method is `StringConcatFactory.makeConcat` or `makeConcatWithConstants`,
+/// whose call sites are run by `string_concat` instead of the linked method handle
+pub(super) fn is_string_concat(method_ref: &Methodref) -> bool {
+    &*method_ref.class_name == "java/lang/invoke/StringConcatFactory"
+        && matches!(
+            &*method_ref.name_and_type.name.to_str(),
+            "makeConcat" | "makeConcatWithConstants"
+        )
+}
+
+impl InterpreterEnv<'_, '_> {
+    /// Pops the arguments of the call site of type `descriptor` and pushes their concatenation
+    /// following the recipe of `bootstrap_method`
+    pub(super) fn string_concat(
+        &mut self,
+        class: &runtime::Class,
+        bootstrap_method: &BootstrapMethod,
+        descriptor: &MethodDescriptor,
+    ) -> NativeResult<()> {
+        let mut args = Vec::with_capacity(descriptor.parameters.len());
+        for param in descriptor.parameters.iter().rev() {
+            let arg = match param {
+                FieldType::Boolean => NativeVariable::Boolean(self.pop_int() != 0),
+                FieldType::Char => NativeVariable::Char(self.pop_int() as _),
+                FieldType::Byte | FieldType::Short | FieldType::Int => {
+                    NativeVariable::Int(self.pop_int())
+                }
+                FieldType::Long => NativeVariable::Long(self.pop_long()),
+                FieldType::Float => NativeVariable::Float(self.pop_float()),
+                FieldType::Double => NativeVariable::Double(self.pop_double()),
+                FieldType::Object(_) | FieldType::Array(_) => {
+                    // SAFETY: rely on class file checking to ensure correct type
+                    NativeVariable::Reference(unsafe { self.frame.stack.pop().unwrap().reference })
+                }
+            };
+            args.push(arg);
+        }
+        args.reverse();
+
+        // makeConcat has no recipe, all the arguments are concatenated
+        let (recipe, constants) = match bootstrap_method.bootstrap_arguments.split_first() {
+            Some((&recipe, constants)) => {
+                let runtime::ConstantPoolInfo::String(recipe) = class.get_constant(recipe) else {
+                    panic!("invalid constant type {recipe}");
+                };
+                (recipe.to_str().encode_utf16().collect(), constants)
+            }
+            None => (vec![TAG_ARG; args.len()], &[][..]),
+        };
+
+        let mut args = args.iter().zip(&descriptor.parameters);
+        let mut constants = constants.iter();
+        let mut chars = Vec::with_capacity(recipe.len());
+        for c in recipe {
+            match c {
+                TAG_ARG => {
+                    let (arg, param) = args.next().expect("recipe has more arguments");
+                    chars.extend(self.arg_to_chars(arg, param)?);
+                }
+                TAG_CONST => {
+                    let &index = constants.next().expect("recipe has more constants");
+                    let constant = match class.get_constant(index) {
+                        runtime::ConstantPoolInfo::String(s) => s.to_str().into_owned(),
+                        runtime::ConstantPoolInfo::Integer(i) => i.to_string(),
+                        runtime::ConstantPoolInfo::Long(l) => l.to_string(),
+                        runtime::ConstantPoolInfo::Float(f) => float_to_string(*f as f64, *f),
+                        runtime::ConstantPoolInfo::Double(d) => float_to_string(*d, *d),
+                        _ => panic!("invalid constant type {index}"),
+                    };
+                    chars.extend(constant.encode_utf16());
+                }
+                c => chars.push(c),
+            }
+        }
+
+        let string = new_string(self.heap, &chars);
+        self.frame.stack.push(Variable { reference: string });
+        Ok(())
+    }
+
+    /// The chars of `String.valueOf(arg)`
+    fn arg_to_chars(&self, arg: &NativeVariable, param: &FieldType) -> NativeResult<Vec<u16>> {
+        let string = match *arg {
+            NativeVariable::Boolean(b) => b.to_string(),
+            NativeVariable::Char(c) => return Ok(vec![c]),
+            NativeVariable::Int(i) => i.to_string(),
+            NativeVariable::Long(l) => l.to_string(),
+            NativeVariable::Float(f) => float_to_string(f as f64, f),
+            NativeVariable::Double(d) => float_to_string(d, d),
+            NativeVariable::Reference(0) => "null".to_string(),
+            NativeVariable::Reference(string) if matches!(param, FieldType::Object(name) if name == "java/lang/String") =>
+            {
+                return Ok(get_string_chars(self.heap, string));
+            }
+            NativeVariable::Reference(reference) => {
+                let string_class = STRING_CLASS.get().expect("string class should be defined");
+                let value_of = string_class
+                    .resolve_method(
+                        &JavaStr::from_str("valueOf"),
+                        &[FieldType::Object("java/lang/Object".to_string())],
+                    )
+                    .expect("String.valueOf(Object) must exist");
+                let string = self.next_native_thread.invoke_method(
+                    Arc::clone(string_class),
+                    value_of,
+                    &[NativeVariable::Reference(reference)],
+                )?;
+                let string = string.expect("String.valueOf returns a string").get_ref();
+                return Ok(get_string_chars(self.heap, string));
+            }
+            NativeVariable::Byte(_) | NativeVariable::Short(_) => {
+                unreachable!("popped as int")
+            }
+        };
+        Ok(string.encode_utf16().collect())
+    }
+}
+
+/// Formats like `Double.toString`, the shortest digits that tell the value apart from its
+/// neighbours of the type `float` is printed with
+fn float_to_string<F: std::fmt::LowerExp>(value: f64, float: F) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    let sign = if value.is_sign_negative() { "-" } else { "" };
+    if value == 0.0 {
+        return format!("{sign}0.0");
+    }
+
+    // d.ddde-n, without the sign
+    let mut scientific = format!("{float:e}");
+    if !scientific.contains('.') {
+        // like java, a single digit is replaced by the closest two, e.g. 4.9E-324 rather than
+        // 5.0E-324
+        scientific = format!("{float:.1e}");
+    }
+    let scientific = scientific.trim_start_matches('-');
+    let (mantissa, exponent) = scientific.split_once('e').expect("must have exponent");
+    let digits = mantissa.replace('.', "");
+    let digits = digits.trim_end_matches('0');
+    let exponent: i32 = exponent.parse().expect("must be integer");
+
+    if (1e-3..1e7).contains(&value.abs()) {
+        if exponent < 0 {
+            let zeros = "0".repeat((-exponent - 1) as usize);
+            format!("{sign}0.{zeros}{digits}")
+        } else {
+            let point = exponent as usize + 1;
+            let digits = format!("{digits:0<point$}");
+            let (integer, fraction) = digits.split_at(point);
+            let fraction = if fraction.is_empty() { "0" } else { fraction };
+            format!("{sign}{integer}.{fraction}")
+        }
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let rest = if rest.is_empty() { "0" } else { rest };
+        format!("{sign}{first}.{rest}E{exponent}")
+    }
+}
@@ -2,25 +2,56 @@ mod class;
 mod object;
 mod double;
 mod float;
+mod math;
 mod string;
 mod system;
 mod internal_misc_cds;
+mod internal_misc_scoped_memory_access;
+mod internal_misc_signal;
+mod internal_misc_unsafe;
+mod internal_misc_vm;
+mod internal_util_system_props;
+mod lang_class_loader;
+mod lang_reflect_array;
+mod lang_ref_reference;
+mod lang_runtime;
+mod lang_thread;
+mod lang_shutdown;
+mod security_access_controller;
+mod io_file_descriptor;
+mod io_file_input_stream;
+mod io_file_output_stream;
+mod internal_reflect;
+mod throwable;
 
 use crate::{
     descriptor::FieldType,
     runtime,
-    runtime::{NativeResult, heap::Heap},
+    runtime::{NativeResult, Thread, Variable, heap::Heap},
 };
 use dashmap::DashMap;
 use std::sync::{Arc, LazyLock, RwLock};
 
+pub(in crate::runtime) use internal_util_system_props::initial_system_properties;
+pub(in crate::runtime) use lang_thread::new_main_thread_object;
+pub(in crate::runtime) use string::{get_string_chars, new_string};
+pub(in crate::runtime) use system::initialize_system;
+
 pub type NativeFunction = fn(NativeEnv) -> NativeResult<Option<NativeVariable>>;
 
-pub struct NativeEnv {
+pub struct NativeEnv<'a> {
     pub args: Vec<NativeVariable>,
     pub heap: &'static RwLock<Heap>,
     pub class: Arc<runtime::Class>,
+    /// Class of the method that invoked the native method's caller, the one
+    /// `Reflection.getCallerClass` returns; `None` if there is no such interpreted frame
+    pub caller: Option<Arc<runtime::Class>>,
+    /// Id of the runtime thread the native method runs on
+    pub thread_id: usize,
+    /// The frame group of the native method, with copies of the frames below it
+    pub(in crate::runtime) thread: &'a Thread<'a>,
 }
+#[derive(Debug, Clone, Copy)]
 pub enum NativeVariable {
     Boolean(bool),
     Byte(i8),
@@ -95,6 +126,88 @@ impl NativeVariable {
             _ => panic!("get_ref: invalid type"),
         }
     }
+
+    /// Reads the value of `field_type` at `locals[*index]`, and moves `index` past its slots
+    ///
+    /// # Safety
+    ///
+    /// should ensure the slots hold a value of `field_type`
+    pub unsafe fn from_local(
+        field_type: &FieldType,
+        locals: &[Variable],
+        index: &mut usize,
+    ) -> Self {
+        let i = *index;
+        // SAFETY: ensured by the caller
+        let variable = unsafe {
+            match field_type {
+                FieldType::Boolean => NativeVariable::Boolean(locals[i].get_int() != 0),
+                FieldType::Byte => NativeVariable::Byte(locals[i].get_int() as _),
+                FieldType::Char => NativeVariable::Char(locals[i].get_int() as _),
+                FieldType::Short => NativeVariable::Short(locals[i].get_int() as _),
+                FieldType::Int => NativeVariable::Int(locals[i].get_int()),
+                FieldType::Long => {
+                    NativeVariable::Long(Variable::get_long(locals[i], locals[i + 1]))
+                }
+                FieldType::Float => NativeVariable::Float(locals[i].float),
+                FieldType::Double => {
+                    NativeVariable::Double(Variable::get_double(locals[i], locals[i + 1]))
+                }
+                FieldType::Object(_) | FieldType::Array(_) => {
+                    NativeVariable::Reference(locals[i].reference)
+                }
+            }
+        };
+        *index += variable.slots();
+        variable
+    }
+
+    /// Pushes the value as the interpreter keeps it, taking two slots for long and double
+    pub fn push_onto(self, stack: &mut Vec<Variable>) {
+        match self {
+            NativeVariable::Boolean(b) => stack.push(Variable { int: b as _ }),
+            NativeVariable::Byte(b) => stack.push(Variable { int: b as _ }),
+            NativeVariable::Char(c) => stack.push(Variable { int: c as _ }),
+            NativeVariable::Short(s) => stack.push(Variable { int: s as _ }),
+            NativeVariable::Int(int) => stack.push(Variable { int }),
+            NativeVariable::Long(l) => {
+                let (upper, lower) = Variable::put_long(l);
+                stack.extend([upper, lower]);
+            }
+            NativeVariable::Float(float) => stack.push(Variable { float }),
+            NativeVariable::Double(d) => {
+                let (upper, lower) = Variable::put_double(d);
+                stack.extend([upper, lower]);
+            }
+            NativeVariable::Reference(reference) => stack.push(Variable { reference }),
+        }
+    }
+
+    /// Whether the value is of `field_type`, without widening to int like the operand stack does
+    pub fn is_of_type(&self, field_type: &FieldType) -> bool {
+        matches!(
+            (self, field_type),
+            (NativeVariable::Boolean(_), FieldType::Boolean)
+                | (NativeVariable::Byte(_), FieldType::Byte)
+                | (NativeVariable::Char(_), FieldType::Char)
+                | (NativeVariable::Short(_), FieldType::Short)
+                | (NativeVariable::Int(_), FieldType::Int)
+                | (NativeVariable::Long(_), FieldType::Long)
+                | (NativeVariable::Float(_), FieldType::Float)
+                | (NativeVariable::Double(_), FieldType::Double)
+                | (
+                    NativeVariable::Reference(_),
+                    FieldType::Object(_) | FieldType::Array(_)
+                )
+        )
+    }
+
+    fn slots(&self) -> usize {
+        match self {
+            NativeVariable::Long(_) | NativeVariable::Double(_) => 2,
+            _ => 1,
+        }
+    }
 }
 
 // key: class_name, method_name, method_descriptor
@@ -105,13 +218,229 @@ pub(in crate::runtime) static NATIVE_FUNCTIONS: LazyLock<DashMap<Key, NativeFunc
 pub(in crate::runtime) fn register_natives() {
     object::register_natives();
     system::register_natives();
+    math::register_natives();
     string::register_natives();
     class::register_natives();
     double::register_natives();
     float::register_natives();
     internal_misc_cds::register_natives();
+    internal_misc_scoped_memory_access::register_natives();
+    internal_misc_signal::register_natives();
+    internal_misc_unsafe::register_natives();
+    internal_misc_vm::register_natives();
+    internal_util_system_props::register_natives();
+    lang_class_loader::register_natives();
+    lang_reflect_array::register_natives();
+    lang_ref_reference::register_natives();
+    lang_runtime::register_natives();
+    lang_thread::register_natives();
+    lang_shutdown::register_natives();
+    security_access_controller::register_natives();
+    io_file_descriptor::register_natives();
+    io_file_input_stream::register_natives();
+    io_file_output_stream::register_natives();
+    internal_reflect::register_natives();
+    throwable::register_natives();
 }
 
 fn native_nop(_: NativeEnv) -> NativeResult<Option<NativeVariable>> {
     Ok(None)
 }
+
+#[cfg(test)]
+pub(super) fn call_native(function: NativeFunction, args: Vec<NativeVariable>) -> NativeVariable {
+    try_call_native(function, args)
+        .unwrap()
+        .expect("must return value")
+}
+
+#[cfg(test)]
+pub(super) fn try_call_native(
+    function: NativeFunction,
+    args: Vec<NativeVariable>,
+) -> NativeResult<Option<NativeVariable>> {
+    use crate::runtime::{gen_primitive_class, global::HEAP};
+
+    let thread = Thread::new(0);
+    function(NativeEnv {
+        args,
+        heap: &HEAP,
+        class: Arc::new(gen_primitive_class(Arc::from("void"))),
+        caller: None,
+        thread_id: thread.thread_id(),
+        thread: &thread,
+    })
+}
+
+/// Boots the vm from `JAVA_HOME` once, for natives that need classes from the JDK.
+///
+/// Returns `false` when `JAVA_HOME` is not set, so tests can be skipped on machines without a JDK.
+#[cfg(test)]
+pub(super) fn boot_vm() -> bool {
+    use crate::runtime::VmBuilder;
+    use std::{env, sync::OnceLock};
+
+    static BOOTED: OnceLock<bool> = OnceLock::new();
+    *BOOTED.get_or_init(|| {
+        let Some(java_home) = env::var_os("JAVA_HOME") else {
+            eprintln!("JAVA_HOME is not set, skipping");
+            return false;
+        };
+        VmBuilder::new().java_home(java_home).build();
+        true
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_round_trip() {
+        let values = [
+            NativeVariable::Reference(42),
+            NativeVariable::Long(-0x1234_5678_9abc_def0),
+            NativeVariable::Boolean(true),
+            NativeVariable::Double(-1.5),
+            NativeVariable::Char(0xffff),
+            NativeVariable::Long(i64::MIN),
+            NativeVariable::Byte(-1),
+            NativeVariable::Short(i16::MIN),
+            NativeVariable::Int(-7),
+            NativeVariable::Float(f32::MAX),
+            NativeVariable::Reference(0),
+        ];
+        let types = [
+            FieldType::Object("java/lang/Object".to_string()),
+            FieldType::Long,
+            FieldType::Boolean,
+            FieldType::Double,
+            FieldType::Char,
+            FieldType::Long,
+            FieldType::Byte,
+            FieldType::Short,
+            FieldType::Int,
+            FieldType::Float,
+            FieldType::Array(Box::new(FieldType::Int)),
+        ];
+
+        let mut locals = vec![];
+        for value in values {
+            value.push_onto(&mut locals);
+        }
+        assert_eq!(locals.len(), types.len() + 3);
+
+        let mut index = 0;
+        // SAFETY: the locals are pushed from the values of the types
+        let read: Vec<_> = types
+            .iter()
+            .map(|t| unsafe { NativeVariable::from_local(t, &locals, &mut index) })
+            .collect();
+        assert_eq!(index, locals.len());
+
+        assert_eq!(read[0].get_ref(), 42);
+        assert_eq!(read[1].get_long(), -0x1234_5678_9abc_def0);
+        assert!(read[2].get_boolean());
+        assert_eq!(read[3].get_double(), -1.5);
+        assert_eq!(read[4].get_char(), 0xffff);
+        assert_eq!(read[5].get_long(), i64::MIN);
+        assert_eq!(read[6].get_byte(), -1);
+        assert_eq!(read[7].get_short(), i16::MIN);
+        assert_eq!(read[8].get_int(), -7);
+        assert_eq!(read[9].get_float(), f32::MAX);
+        assert_eq!(read[10].get_ref(), 0);
+    }
+
+    // the check is skipped in release builds
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_native_return_type_mismatch() {
+        use crate::{
+            descriptor::parse_method_descriptor,
+            runtime::{Thread, global::BOOTSTRAP_CLASS_LOADER},
+        };
+        use std::panic::{self, AssertUnwindSafe};
+
+        fn misbehave(_: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+            Ok(Some(NativeVariable::Int(1)))
+        }
+
+        if !boot_vm() {
+            return;
+        }
+        // a class of its own with `public static native String misbehave()`, so no other test
+        // calls the misbehaving native
+        let class_name = "NativeReturnTypeMismatch";
+        let utf8 = |constant: &str| {
+            [
+                &[1][..],
+                &(constant.len() as u16).to_be_bytes(),
+                constant.as_bytes(),
+            ]
+            .concat()
+        };
+        let class_file = [
+            &0xCAFEBABE_u32.to_be_bytes()[..],
+            // java 8, 6 constants
+            &[0, 0, 0, 52, 0, 7],
+            &utf8(class_name),
+            &[7, 0, 1],
+            &utf8("java/lang/Object"),
+            &[7, 0, 3],
+            &utf8("misbehave"),
+            &utf8("()Ljava/lang/String;"),
+            // public, this class #2, super class #4, no interfaces or fields, 1 method
+            &[0x00, 0x21, 0x00, 0x02, 0x00, 0x04, 0, 0, 0, 0, 0, 1],
+            // public static native, no attributes
+            &[0x01, 0x09, 0x00, 0x05, 0x00, 0x06, 0, 0],
+            // no class attributes
+            &[0, 0],
+        ]
+        .concat();
+        BOOTSTRAP_CLASS_LOADER
+            .get()
+            .expect("must have init")
+            .define_class_from_bytes(class_name, &class_file)
+            .expect("valid class file");
+        NATIVE_FUNCTIONS.insert(
+            (class_name.to_string(), "misbehave".to_string(), vec![]),
+            misbehave,
+        );
+
+        let (_, descriptor) =
+            parse_method_descriptor("()Ljava/lang/String;").expect("valid descriptor");
+        let panic = panic::catch_unwind(AssertUnwindSafe(|| {
+            Thread::new(0).invoke_static(class_name, "misbehave", &descriptor, &[])
+        }))
+        .expect_err("the mismatch must be caught");
+        let message = panic
+            .downcast_ref::<String>()
+            .expect("panics with a formatted message");
+        assert_eq!(
+            message,
+            "native method NativeReturnTypeMismatch.misbehave returned Some(Int(1)), \
+             but its return type is java/lang/String"
+        );
+    }
+
+    #[test]
+    fn test_long_takes_two_slots() {
+        let mut locals = vec![];
+        NativeVariable::Long(0x1_0000_0002).push_onto(&mut locals);
+        NativeVariable::Int(3).push_onto(&mut locals);
+
+        // SAFETY: the slots hold the pushed long and int
+        unsafe {
+            assert_eq!(locals[0].get_int(), 1);
+            assert_eq!(locals[1].get_int(), 2);
+
+            let mut index = 0;
+            let long = NativeVariable::from_local(&FieldType::Long, &locals, &mut index);
+            assert_eq!(long.get_long(), 0x1_0000_0002);
+            assert_eq!(index, 2);
+            let int = NativeVariable::from_local(&FieldType::Int, &locals, &mut index);
+            assert_eq!(int.get_int(), 3);
+            assert_eq!(index, 3);
+        }
+    }
+}
@@ -1,29 +1,38 @@
 use crate::{
     class::JavaStr,
-    descriptor::FieldType,
+    consts::{ClassAccessFlag, FieldAccessFlag, InnerClassAccessFlag, MethodAccessFlag},
+    descriptor::{FieldType, ReturnType},
     runtime::{
-        NativeEnv, NativeResult, NativeVariable,
+        AttributeInfo, Class, Exception, FieldInfo, MethodInfo, NativeEnv, NativeResult,
+        NativeVariable,
         NativeVariable::{Boolean, Reference},
-        SpecialStringObject,
-        class_loader::{get_class_object, intern_string},
-        famous_classes::INT_TYPE_CLASS,
-        heap::reflection::SpecialClassObject,
-        native::NATIVE_FUNCTIONS,
+        Thread, Variable, VmEnv,
+        class_loader::{get_class_object, initialize_class, intern_string, new_instance},
+        famous_classes::{
+            CLASS_CLASS, CLASS_NOT_FOUND_EXCEPTION_CLASS, INT_TYPE_CLASS,
+            NO_CLASS_DEF_FOUND_ERROR_CLASS, NULL_POINTER_EXCEPTION_CLASS, OBJECT_CLASS,
+            primitive_type_class,
+        },
+        global::{ASSERTION_STATUS, BOOTSTRAP_CLASS_LOADER},
+        inheritance::is_assignable_to,
+        native::{NATIVE_FUNCTIONS, string::get_string},
+        structs::put_array_index,
     },
 };
-use std::{
-    any::Any,
-    sync::{Arc, atomic::Ordering::Relaxed},
-};
+use std::sync::{Arc, atomic::Ordering::Relaxed};
 use crate::runtime::famous_classes::{BOOLEAN_TYPE_CLASS, BYTE_TYPE_CLASS, CHAR_TYPE_CLASS, SHORT_TYPE_CLASS, FLOAT_TYPE_CLASS, DOUBLE_TYPE_CLASS, LONG_TYPE_CLASS, VOID_TYPE_CLASS};
 
+pub(super) fn get_class(env: &NativeEnv, class_ref: u32) -> Arc<Class> {
+    let class_obj = env.heap.read().unwrap().get(class_ref);
+    let class_obj = class_obj.as_class_object().expect("must be class object");
+    Arc::clone(&class_obj.class)
+}
+
 // private native String initClassName();
 fn init_class_name(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
     let this = env.args[0].get_ref();
     let this_obj = env.heap.read().unwrap().get(this);
-    let class_obj = (&this_obj as &dyn Any)
-        .downcast_ref::<SpecialClassObject>()
-        .expect("must be class object");
+    let class_obj = this_obj.as_class_object().expect("must be class object");
     let class_binary_name = class_obj.class.class_name.replace("/", ".");
     let name_str = intern_string(&JavaStr::from_str(&class_binary_name).into());
 
@@ -34,22 +43,14 @@ fn init_class_name(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
 
 // private static native boolean desiredAssertionStatus0(Class<?> clazz);
 fn desired_assertion_status0(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
-    // TODO: from config
-    Ok(Some(Boolean(true)))
+    Ok(Some(Boolean(ASSERTION_STATUS.load(Relaxed))))
 }
 
 // static native Class<?> getPrimitiveClass(String name);
 fn get_primitive_class(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
-    let name_ref = env.args[0].get_ref();
-    let object_name = env.heap.read().unwrap().get(name_ref);
-    let string_name = (&object_name as &dyn Any)
-        .downcast_ref::<SpecialStringObject>()
-        .expect("must be string object");
-
-    // TODO: exception
-    let class_name = str::from_utf8(string_name.get_bytes()).expect("error");
+    let class_name = get_string(&env, env.args[0].get_ref());
 
-    let class = match class_name {
+    let class = match class_name.as_str() {
         "boolean" => BOOLEAN_TYPE_CLASS.get().expect("must have init"),
         "byte" => BYTE_TYPE_CLASS.get().expect("must have init"),
         "char" => CHAR_TYPE_CLASS.get().expect("must have init"),
@@ -66,6 +67,637 @@ fn get_primitive_class(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
     Ok(Some(Reference(get_class_object(Arc::clone(class))?)))
 }
 
+// private static native Class<?> forName0(String name, boolean initialize, ClassLoader loader,
+//                                         Class<?> caller) throws ClassNotFoundException;
+fn for_name0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let name = env.args[0].get_ref();
+    if name == 0 {
+        return Err(Exception::new_vm(
+            NULL_POINTER_EXCEPTION_CLASS.get().expect("must have init"),
+        ));
+    }
+    let name = get_string(&env, name);
+    let initialize = env.args[1].get_boolean();
+    // only the bootstrap class loader exists, `loader` and `caller` are not consulted
+
+    let class_not_found = || {
+        Exception::new_vm_msg(
+            CLASS_NOT_FOUND_EXCEPTION_CLASS
+                .get()
+                .expect("must have init"),
+            &name,
+        )
+    };
+    // binary names are dotted, `java/lang/String` is not a valid one
+    if name.contains('/') {
+        return Err(class_not_found());
+    }
+    let class = match BOOTSTRAP_CLASS_LOADER
+        .get()
+        .unwrap()
+        .resolve_class(&name.replace('.', "/"))
+    {
+        Ok(class) if is_primitive(&class) => return Err(class_not_found()),
+        Ok(class) => class,
+        Err(Exception::VmException { exception_type, .. })
+            if Arc::ptr_eq(
+                &exception_type,
+                NO_CLASS_DEF_FOUND_ERROR_CLASS
+                    .get()
+                    .expect("must have init"),
+            ) =>
+        {
+            return Err(class_not_found());
+        }
+        Err(e) => return Err(e),
+    };
+    if initialize {
+        let thread = Thread::new(0);
+        initialize_class(&VmEnv::new(&thread, env.heap), &class)?;
+    }
+    Ok(Some(Reference(get_class_object(class)?)))
+}
+
+// public native boolean isArray();
+fn is_array(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    Ok(Some(Boolean(class.is_array())))
+}
+
+// public native boolean isPrimitive();
+fn is_primitive_class(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    Ok(Some(Boolean(is_primitive(&class))))
+}
+
+// public native boolean isInstance(Object obj);
+fn is_instance(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    let obj = env.args[1].get_ref();
+    if obj == 0 {
+        return Ok(Some(Boolean(false)));
+    }
+    let obj_class = Arc::clone(env.heap.read().unwrap().get(obj).get_class());
+    Ok(Some(Boolean(is_assignable_to(&obj_class, &class))))
+}
+
+// public native boolean isAssignableFrom(Class<?> cls);
+fn is_assignable_from(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    let other = env.args[1].get_ref();
+    if other == 0 {
+        return Err(Exception::new_vm(
+            NULL_POINTER_EXCEPTION_CLASS.get().expect("must have init"),
+        ));
+    }
+    let other = get_class(&env, other);
+    // a primitive class is only assignable from itself
+    if is_primitive(&class) || is_primitive(&other) {
+        return Ok(Some(Boolean(Arc::ptr_eq(&class, &other))));
+    }
+    Ok(Some(Boolean(is_assignable_to(&other, &class))))
+}
+
+// public native Class<? super T> getSuperclass();
+fn get_superclass(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    if class.access_flags.contains(ClassAccessFlag::INTERFACE) {
+        return Ok(Some(Reference(0)));
+    }
+    let super_class = match &class.super_class {
+        Some(super_class) => get_class_object(Arc::clone(super_class))?,
+        None => 0,
+    };
+    Ok(Some(Reference(super_class)))
+}
+
+// public native int getModifiers();
+fn get_modifiers(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    Ok(Some(NativeVariable::Int(class_modifiers(&class) as _)))
+}
+
+/// The modifiers of `class` as `Class.getModifiers` returns, member classes take theirs from the
+/// `InnerClasses` attribute
+fn class_modifiers(class: &Class) -> u16 {
+    let abstract_final = (ClassAccessFlag::ABSTRACT | ClassAccessFlag::FINAL).bits();
+    if is_primitive(class) {
+        return ClassAccessFlag::PUBLIC.bits() | abstract_final;
+    }
+    if class.is_array() {
+        // arrays of primitive types are public
+        let element_modifiers = class
+            .array_element_type
+            .as_deref()
+            .map_or(ClassAccessFlag::PUBLIC.bits(), class_modifiers);
+        let access = (InnerClassAccessFlag::PUBLIC
+            | InnerClassAccessFlag::PRIVATE
+            | InnerClassAccessFlag::PROTECTED)
+            .bits();
+        return (element_modifiers & access) | abstract_final;
+    }
+    let modifiers = class
+        .inner_class_info()
+        .map_or(class.access_flags.bits(), |info| {
+            info.inner_class_access_flags.bits()
+        });
+    modifiers & !ClassAccessFlag::SUPER.bits()
+}
+
+// private native String getSimpleBinaryName0();
+fn get_simple_binary_name0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    let inner_name = class
+        .inner_class_info()
+        .and_then(|info| info.inner_name.as_ref());
+    Ok(Some(Reference(inner_name.map_or(0, intern_string))))
+}
+
+// private native Class<?> getDeclaringClass0();
+fn get_declaring_class0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    // only member classes have an outer class, local and anonymous classes have an enclosing one
+    let Some(outer_class) = class
+        .inner_class_info()
+        .and_then(|info| info.outer_class.as_ref())
+    else {
+        return Ok(Some(Reference(0)));
+    };
+    let outer_class = outer_class.get_or_load_class(|| {
+        BOOTSTRAP_CLASS_LOADER
+            .get()
+            .unwrap()
+            .resolve_class(&outer_class.name)
+    })?;
+    Ok(Some(Reference(get_class_object(outer_class)?)))
+}
+
+// private native Object[] getEnclosingMethod0();
+fn get_enclosing_method0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    let Some((enclosing_class, method)) = class.attributes.iter().find_map(|attr| match attr {
+        AttributeInfo::EnclosingMethod { class, method } => Some((class, method)),
+        _ => None,
+    }) else {
+        return Ok(Some(Reference(0)));
+    };
+    let enclosing_class = enclosing_class.get_or_load_class(|| {
+        BOOTSTRAP_CLASS_LOADER
+            .get()
+            .unwrap()
+            .resolve_class(&enclosing_class.name)
+    })?;
+    // the name and descriptor are null outside of a method or constructor
+    let (name, descriptor) = method.as_ref().map_or((0, 0), |method| {
+        (
+            intern_string(&method.name),
+            intern_string(&method.descriptor),
+        )
+    });
+    Ok(Some(Reference(new_reference_array(
+        &env,
+        OBJECT_CLASS.get().expect("must have init"),
+        &[get_class_object(enclosing_class)?, name, descriptor],
+    )?)))
+}
+
+// private native Class<?>[] getInterfaces0();
+fn get_interfaces0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    let mut interfaces = Vec::with_capacity(class.interfaces.len());
+    for interface in &class.interfaces {
+        interfaces.push(get_class_object(Arc::clone(interface))?);
+    }
+    Ok(Some(Reference(new_reference_array(
+        &env,
+        CLASS_CLASS.get().expect("must have init"),
+        &interfaces,
+    )?)))
+}
+
+// private native String getGenericSignature0();
+fn get_generic_signature0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    Ok(Some(Reference(signature_of(&class.attributes))))
+}
+
+// private native Field[]       getDeclaredFields0(boolean publicOnly);
+fn get_declared_fields0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    let public_only = env.args[1].get_boolean();
+
+    let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+    let field_class = bootstrap_class_loader.resolve_class("java/lang/reflect/Field")?;
+    // TODO: initialize Field, AccessibleObject's initializer needs natives that are not there yet
+
+    // instance_fields_info starts with the fields inherited from super classes
+    let inherited = class
+        .super_class
+        .as_ref()
+        .map_or(0, |s| s.instance_fields_info.len());
+    let fields: Vec<_> = class.instance_fields_info[inherited..]
+        .iter()
+        .chain(&class.static_fields_info)
+        .filter(|f| !public_only || f.access_flags.contains(FieldAccessFlag::PUBLIC))
+        .collect();
+
+    let mut field_objects = Vec::with_capacity(fields.len());
+    for field_info in fields {
+        field_objects.push(new_field_object(&env, &field_class, &class, field_info)?);
+    }
+
+    Ok(Some(Reference(new_reference_array(
+        &env,
+        &field_class,
+        &field_objects,
+    )?)))
+}
+
+// private native Method[]      getDeclaredMethods0(boolean publicOnly);
+fn get_declared_methods0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    let public_only = env.args[1].get_boolean();
+
+    let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+    let method_class = bootstrap_class_loader.resolve_class("java/lang/reflect/Method")?;
+    // TODO: initialize Method, AccessibleObject's initializer needs natives that are not there yet
+
+    let mut method_objects = vec![];
+    for (slot, method_info) in class.methods.iter().enumerate() {
+        let name = method_info.name.to_str();
+        if name == "<init>" || name == "<clinit>" {
+            continue;
+        }
+        if public_only && !method_info.access_flags.contains(MethodAccessFlag::PUBLIC) {
+            continue;
+        }
+        method_objects.push(new_method_object(
+            &env,
+            &method_class,
+            &class,
+            slot,
+            method_info,
+        )?);
+    }
+
+    Ok(Some(Reference(new_reference_array(
+        &env,
+        &method_class,
+        &method_objects,
+    )?)))
+}
+
+// private native Constructor<T>[] getDeclaredConstructors0(boolean publicOnly);
+fn get_declared_constructors0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    let public_only = env.args[1].get_boolean();
+
+    let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+    let constructor_class =
+        bootstrap_class_loader.resolve_class("java/lang/reflect/Constructor")?;
+    // TODO: initialize Constructor, AccessibleObject's initializer needs natives that are not
+    // there yet
+
+    let mut constructor_objects = vec![];
+    // interfaces and primitive classes have no constructors, array classes have no methods
+    for (slot, method_info) in class.methods.iter().enumerate() {
+        if method_info.name.to_str() != "<init>" {
+            continue;
+        }
+        if public_only && !method_info.access_flags.contains(MethodAccessFlag::PUBLIC) {
+            continue;
+        }
+        constructor_objects.push(new_reflection_object(
+            &env,
+            &constructor_class,
+            &[
+                (
+                    "clazz",
+                    Variable {
+                        reference: get_class_object(Arc::clone(&class))?,
+                    },
+                ),
+                ("slot", Variable { int: slot as _ }),
+                (
+                    "parameterTypes",
+                    Variable {
+                        reference: new_class_array(&env, &method_info.descriptor.parameters)?,
+                    },
+                ),
+                (
+                    "exceptionTypes",
+                    Variable {
+                        reference: new_class_array(&env, &method_info.exception_types())?,
+                    },
+                ),
+                (
+                    "modifiers",
+                    Variable {
+                        int: method_info.access_flags.bits() as _,
+                    },
+                ),
+                (
+                    "signature",
+                    Variable {
+                        reference: signature_of(&method_info.attributes),
+                    },
+                ),
+            ],
+        ));
+    }
+
+    Ok(Some(Reference(new_reference_array(
+        &env,
+        &constructor_class,
+        &constructor_objects,
+    )?)))
+}
+
+// private native boolean isRecord0();
+fn is_record0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    Ok(Some(Boolean(class.record_components().is_some())))
+}
+
+// private native RecordComponent[] getRecordComponents0();
+fn get_record_components0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[0].get_ref());
+    let Some(components) = class.record_components() else {
+        return Ok(Some(Reference(0)));
+    };
+
+    let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+    let component_class =
+        bootstrap_class_loader.resolve_class("java/lang/reflect/RecordComponent")?;
+    let method_class = bootstrap_class_loader.resolve_class("java/lang/reflect/Method")?;
+
+    let mut component_objects = Vec::with_capacity(components.len());
+    for component in components {
+        let field_type = &component.descriptor.0;
+        // the accessor is the method of the same name taking nothing and returning the component
+        let accessor = class.methods.iter().enumerate().find(|(_, m)| {
+            m.name == component.name
+                && m.descriptor.parameters.is_empty()
+                && m.descriptor.return_type.as_ref() == Some(field_type)
+        });
+        let accessor = match accessor {
+            Some((slot, method_info)) => {
+                new_method_object(&env, &method_class, &class, slot, method_info)?
+            }
+            None => 0,
+        };
+        // TODO: annotations and type annotations are not kept as raw bytes, so they are left null
+        component_objects.push(new_reflection_object(
+            &env,
+            &component_class,
+            &[
+                (
+                    "clazz",
+                    Variable {
+                        reference: get_class_object(Arc::clone(&class))?,
+                    },
+                ),
+                (
+                    "name",
+                    Variable {
+                        reference: intern_string(&component.name),
+                    },
+                ),
+                (
+                    "type",
+                    Variable {
+                        reference: get_class_object(field_type_class(field_type)?)?,
+                    },
+                ),
+                (
+                    "accessor",
+                    Variable {
+                        reference: accessor,
+                    },
+                ),
+                (
+                    "signature",
+                    Variable {
+                        reference: signature_of(&component.attributes),
+                    },
+                ),
+            ],
+        ));
+    }
+
+    Ok(Some(Reference(new_reference_array(
+        &env,
+        &component_class,
+        &component_objects,
+    )?)))
+}
+
+/// Allocates a `java.lang.reflect.Field` describing `field_info` of `declaring_class`
+fn new_field_object(
+    env: &NativeEnv,
+    field_class: &Arc<Class>,
+    declaring_class: &Arc<Class>,
+    field_info: &FieldInfo,
+) -> NativeResult<u32> {
+    let access_flags = field_info.access_flags;
+    let is_static = access_flags.contains(FieldAccessFlag::STATIC);
+    // TODO: final fields of hidden classes are trusted as well
+    let trusted_final = access_flags.contains(FieldAccessFlag::FINAL)
+        && (is_static || declaring_class.record_components().is_some());
+
+    Ok(new_reflection_object(
+        env,
+        field_class,
+        &[
+            (
+                "clazz",
+                Variable {
+                    reference: get_class_object(Arc::clone(declaring_class))?,
+                },
+            ),
+            (
+                "slot",
+                Variable {
+                    int: field_info.index as _,
+                },
+            ),
+            (
+                "name",
+                Variable {
+                    reference: intern_string(&field_info.name),
+                },
+            ),
+            (
+                "type",
+                Variable {
+                    reference: get_class_object(field_type_class(&field_info.descriptor.0)?)?,
+                },
+            ),
+            (
+                "modifiers",
+                Variable {
+                    int: access_flags.bits() as _,
+                },
+            ),
+            (
+                "trustedFinal",
+                Variable {
+                    int: trusted_final as _,
+                },
+            ),
+            (
+                "signature",
+                Variable {
+                    reference: signature_of(&field_info.attributes),
+                },
+            ),
+        ],
+    ))
+}
+
+/// Allocates a `java.lang.reflect.Method` describing `method_info`, the `slot`-th method of
+/// `declaring_class`
+fn new_method_object(
+    env: &NativeEnv,
+    method_class: &Arc<Class>,
+    declaring_class: &Arc<Class>,
+    slot: usize,
+    method_info: &MethodInfo,
+) -> NativeResult<u32> {
+    let return_type = return_type_class(&method_info.descriptor.return_type)?;
+
+    Ok(new_reflection_object(
+        env,
+        method_class,
+        &[
+            (
+                "clazz",
+                Variable {
+                    reference: get_class_object(Arc::clone(declaring_class))?,
+                },
+            ),
+            ("slot", Variable { int: slot as _ }),
+            (
+                "name",
+                Variable {
+                    reference: intern_string(&method_info.name),
+                },
+            ),
+            (
+                "returnType",
+                Variable {
+                    reference: get_class_object(return_type)?,
+                },
+            ),
+            (
+                "parameterTypes",
+                Variable {
+                    reference: new_class_array(env, &method_info.descriptor.parameters)?,
+                },
+            ),
+            (
+                "exceptionTypes",
+                Variable {
+                    reference: new_class_array(env, &method_info.exception_types())?,
+                },
+            ),
+            (
+                "modifiers",
+                Variable {
+                    int: method_info.access_flags.bits() as _,
+                },
+            ),
+            (
+                "signature",
+                Variable {
+                    reference: signature_of(&method_info.attributes),
+                },
+            ),
+        ],
+    ))
+}
+
+/// Allocates an instance of a reflection class, filling in its fields directly as hotspot does
+/// instead of running the constructor
+fn new_reflection_object(env: &NativeEnv, class: &Arc<Class>, fields: &[(&str, Variable)]) -> u32 {
+    let id = new_instance(class);
+    let obj = env.heap.read().unwrap().get(id);
+    for (name, v) in fields {
+        let field = class.find_field_info(name, false).expect("must have field");
+        // SAFETY: obj is newly allocated and not yet visible to other threads
+        unsafe { obj.put_field(field.index, *v) };
+    }
+    id
+}
+
+/// Allocates an array of `element_class` holding `elements`
+pub(super) fn new_reference_array(
+    env: &NativeEnv,
+    element_class: &Arc<Class>,
+    elements: &[u32],
+) -> NativeResult<u32> {
+    let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+    let array_class = bootstrap_class_loader.resolve_object_array_class(element_class)?;
+    let mut heap = env.heap.write().unwrap();
+    let array = heap.allocate_array::<u32>(elements.len(), array_class);
+    let array_obj = heap.get(array);
+    for (i, &element) in elements.iter().enumerate() {
+        // SAFETY: array is newly allocated and not yet visible to other threads
+        unsafe { put_array_index(array_obj.as_ref(), i, element) };
+    }
+    Ok(array)
+}
+
+/// Allocates a `Class[]` holding the classes mirrored by `field_types`
+fn new_class_array(env: &NativeEnv, field_types: &[FieldType]) -> NativeResult<u32> {
+    let mut classes = Vec::with_capacity(field_types.len());
+    for field_type in field_types {
+        classes.push(get_class_object(field_type_class(field_type)?)?);
+    }
+    new_reference_array(env, CLASS_CLASS.get().expect("must have init"), &classes)
+}
+
+/// The interned generic signature in `attributes`, or null if there is none
+fn signature_of(attributes: &[AttributeInfo]) -> u32 {
+    let signature = attributes.iter().find_map(|attr| {
+        if let AttributeInfo::Signature(signature) = attr {
+            Some(signature)
+        } else {
+            None
+        }
+    });
+    signature.map_or(0, intern_string)
+}
+
+/// Whether `class` is one of the primitive classes like `int.class`, including `void.class`
+pub(super) fn is_primitive(class: &Class) -> bool {
+    class.super_class.is_none()
+        && matches!(
+            class.class_name(),
+            "boolean" | "byte" | "char" | "short" | "int" | "long" | "float" | "double" | "void"
+        )
+}
+
+/// The class mirrored by `field_type`, with primitive types mapped to their primitive classes
+pub(super) fn field_type_class(field_type: &FieldType) -> NativeResult<Arc<Class>> {
+    if let Some(primitive) = primitive_type_class(field_type) {
+        return Ok(Arc::clone(primitive));
+    }
+    let bootstrap_class_loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+    match field_type {
+        FieldType::Object(class_name) => bootstrap_class_loader.resolve_class(class_name),
+        _ => bootstrap_class_loader.resolve_class(&field_type.to_descriptor()),
+    }
+}
+
+/// The class mirrored by `return_type`, which is `void.class` for methods returning nothing
+fn return_type_class(return_type: &ReturnType) -> NativeResult<Arc<Class>> {
+    match return_type {
+        Some(return_type) => field_type_class(return_type),
+        None => Ok(Arc::clone(VOID_TYPE_CLASS.get().expect("must have init"))),
+    }
+}
+
 fn native_class_register_natives(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
     NATIVE_FUNCTIONS.insert(
         (
@@ -90,7 +722,144 @@ fn native_class_register_natives(_env: NativeEnv) -> NativeResult<Option<NativeV
             "getPrimitiveClass".to_string(),
             vec![FieldType::Object("java/lang/String".to_string())],
         ),
-        desired_assertion_status0,
+        get_primitive_class,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "forName0".to_string(),
+            vec![
+                FieldType::Object("java/lang/String".to_string()),
+                FieldType::Boolean,
+                FieldType::Object("java/lang/ClassLoader".to_string()),
+                FieldType::Object("java/lang/Class".to_string()),
+            ],
+        ),
+        for_name0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "getSuperclass".to_string(),
+            vec![],
+        ),
+        get_superclass,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "getModifiers".to_string(),
+            vec![],
+        ),
+        get_modifiers,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "getInterfaces0".to_string(),
+            vec![],
+        ),
+        get_interfaces0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "getSimpleBinaryName0".to_string(),
+            vec![],
+        ),
+        get_simple_binary_name0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "getDeclaringClass0".to_string(),
+            vec![],
+        ),
+        get_declaring_class0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "getEnclosingMethod0".to_string(),
+            vec![],
+        ),
+        get_enclosing_method0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        ("java/lang/Class".to_string(), "isArray".to_string(), vec![]),
+        is_array,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "isPrimitive".to_string(),
+            vec![],
+        ),
+        is_primitive_class,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "isInstance".to_string(),
+            vec![FieldType::Object("java/lang/Object".to_string())],
+        ),
+        is_instance,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "isAssignableFrom".to_string(),
+            vec![FieldType::Object("java/lang/Class".to_string())],
+        ),
+        is_assignable_from,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "getGenericSignature0".to_string(),
+            vec![],
+        ),
+        get_generic_signature0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "getDeclaredFields0".to_string(),
+            vec![FieldType::Boolean],
+        ),
+        get_declared_fields0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "getDeclaredMethods0".to_string(),
+            vec![FieldType::Boolean],
+        ),
+        get_declared_methods0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "getDeclaredConstructors0".to_string(),
+            vec![FieldType::Boolean],
+        ),
+        get_declared_constructors0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "isRecord0".to_string(),
+            vec![],
+        ),
+        is_record0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Class".to_string(),
+            "getRecordComponents0".to_string(),
+            vec![],
+        ),
+        get_record_components0,
     );
 
     Ok(None)
@@ -106,3 +875,227 @@ pub(super) fn register_natives() {
         native_class_register_natives,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{
+        global::HEAP,
+        native::{boot_vm, call_native},
+        structs::get_array_index,
+    };
+
+    fn class_object(class_name: &str) -> u32 {
+        let class = BOOTSTRAP_CLASS_LOADER
+            .get()
+            .unwrap()
+            .resolve_class(class_name)
+            .unwrap();
+        get_class_object(class).unwrap()
+    }
+
+    fn array_elements(array: u32) -> Vec<u32> {
+        let array = HEAP.read().unwrap().get(array);
+        (0..array.get_array_size(size_of::<u32>()))
+            .map(|i| unsafe { get_array_index::<u32, _>(array.as_ref(), i) })
+            .collect()
+    }
+
+    fn get_field(obj: u32, name: &str) -> Variable {
+        let obj = HEAP.read().unwrap().get(obj);
+        let index = obj.get_class().find_field_info(name, false).unwrap().index;
+        unsafe { obj.get_field(index) }
+    }
+
+    fn class_name_of(class_ref: u32) -> String {
+        let class_obj = HEAP.read().unwrap().get(class_ref);
+        class_obj
+            .as_class_object()
+            .unwrap()
+            .class
+            .class_name()
+            .to_string()
+    }
+
+    /// Finds the reflection object named `name`, names are compared by their interned string
+    fn find(objects: &[u32], name: &str) -> Option<u32> {
+        let name = intern_string(&JavaStr::from_str(name).into());
+        objects
+            .iter()
+            .copied()
+            .find(|&obj| unsafe { get_field(obj, "name").reference } == name)
+    }
+
+    #[test]
+    fn test_get_declared_fields() {
+        if !boot_vm() {
+            return;
+        }
+        let declared_fields = |public_only| {
+            let args = vec![
+                Reference(class_object("java/lang/Boolean")),
+                Boolean(public_only),
+            ];
+            array_elements(call_native(get_declared_fields0, args).get_ref())
+        };
+
+        let fields = declared_fields(false);
+        assert_eq!(fields.len(), 5);
+        for name in ["TRUE", "FALSE", "TYPE", "serialVersionUID", "value"] {
+            assert!(find(&fields, name).is_some(), "{name}");
+        }
+        let value = find(&fields, "value").unwrap();
+        assert_eq!(unsafe { get_field(value, "modifiers").int }, 0x0012);
+        assert_eq!(
+            class_name_of(unsafe { get_field(value, "type").reference }),
+            "boolean"
+        );
+        let true_ = find(&fields, "TRUE").unwrap();
+        assert_eq!(unsafe { get_field(true_, "modifiers").int }, 0x0019);
+        assert_eq!(
+            class_name_of(unsafe { get_field(true_, "type").reference }),
+            "java/lang/Boolean"
+        );
+        let type_ = find(&fields, "TYPE").unwrap();
+        assert_eq!(
+            class_name_of(unsafe { get_field(type_, "type").reference }),
+            "java/lang/Class"
+        );
+
+        let public_fields = declared_fields(true);
+        assert_eq!(public_fields.len(), 3);
+        assert!(find(&public_fields, "value").is_none());
+    }
+
+    #[test]
+    fn test_get_modifiers() {
+        if !boot_vm() {
+            return;
+        }
+        let modifiers =
+            |class_object| call_native(get_modifiers, vec![Reference(class_object)]).get_int();
+        // public final, without ACC_SUPER
+        assert_eq!(modifiers(class_object("java/lang/String")), 0x0011);
+        // public static final enum, from InnerClasses
+        assert_eq!(modifiers(class_object("java/lang/Thread$State")), 0x4019);
+        // public abstract final
+        assert_eq!(modifiers(class_object("[Ljava/lang/Thread$State;")), 0x0411);
+        assert_eq!(modifiers(class_object("[I")), 0x0411);
+        let int_class = get_class_object(Arc::clone(INT_TYPE_CLASS.get().unwrap())).unwrap();
+        assert_eq!(modifiers(int_class), 0x0411);
+    }
+
+    #[test]
+    fn test_get_declared_methods() {
+        if !boot_vm() {
+            return;
+        }
+        let args = vec![
+            Reference(class_object("java/lang/Appendable")),
+            Boolean(false),
+        ];
+        let methods = array_elements(call_native(get_declared_methods0, args).get_ref());
+        // append(CharSequence), append(CharSequence, int, int) and append(char)
+        assert_eq!(methods.len(), 3);
+
+        let with_range = methods
+            .iter()
+            .copied()
+            .find(|&method| {
+                array_elements(unsafe { get_field(method, "parameterTypes").reference }).len() == 3
+            })
+            .unwrap();
+        let parameter_types: Vec<_> =
+            array_elements(unsafe { get_field(with_range, "parameterTypes").reference })
+                .into_iter()
+                .map(class_name_of)
+                .collect();
+        assert_eq!(parameter_types, ["java/lang/CharSequence", "int", "int"]);
+        assert_eq!(
+            class_name_of(unsafe { get_field(with_range, "returnType").reference }),
+            "java/lang/Appendable"
+        );
+        assert_eq!(unsafe { get_field(with_range, "modifiers").int }, 0x0401);
+        // every append is declared to throw IOException
+        for method in methods {
+            let exception_types: Vec<_> =
+                array_elements(unsafe { get_field(method, "exceptionTypes").reference })
+                    .into_iter()
+                    .map(class_name_of)
+                    .collect();
+            assert_eq!(exception_types, ["java/io/IOException"]);
+        }
+    }
+
+    #[test]
+    fn test_void_return_type() {
+        if !boot_vm() {
+            return;
+        }
+        let args = vec![
+            Reference(class_object("java/lang/Runnable")),
+            Boolean(false),
+        ];
+        let methods = array_elements(call_native(get_declared_methods0, args).get_ref());
+        let run = find(&methods, "run").unwrap();
+        let return_type = unsafe { get_field(run, "returnType").reference };
+        assert_eq!(class_name_of(return_type), "void");
+
+        // Void.TYPE is initialized from getPrimitiveClass("void")
+        let void_name = intern_string(&JavaStr::from_str("void").into());
+        let void_class = call_native(get_primitive_class, vec![Reference(void_name)]).get_ref();
+        assert_eq!(return_type, void_class);
+    }
+
+    #[test]
+    fn test_get_interfaces() {
+        if !boot_vm() {
+            return;
+        }
+        let interface_names = |class_name: &str| -> Vec<_> {
+            let args = vec![Reference(class_object(class_name))];
+            array_elements(call_native(get_interfaces0, args).get_ref())
+                .into_iter()
+                .map(class_name_of)
+                .collect()
+        };
+        assert_eq!(
+            interface_names("java/util/ArrayList"),
+            [
+                "java/util/List",
+                "java/util/RandomAccess",
+                "java/lang/Cloneable",
+                "java/io/Serializable"
+            ]
+        );
+        // only the directly implemented ones
+        assert_eq!(
+            interface_names("java/util/AbstractList"),
+            ["java/util/List"]
+        );
+        assert_eq!(interface_names("java/util/List"), ["java/util/Collection"]);
+        assert_eq!(
+            interface_names("[Ljava/lang/String;"),
+            ["java/lang/Cloneable", "java/io/Serializable"]
+        );
+        assert!(interface_names("java/lang/Object").is_empty());
+    }
+
+    #[test]
+    fn test_get_simple_binary_name() {
+        if !boot_vm() {
+            return;
+        }
+        let simple_binary_name = |class_name: &str| {
+            let args = vec![Reference(class_object(class_name))];
+            call_native(get_simple_binary_name0, args).get_ref()
+        };
+        assert_eq!(
+            simple_binary_name("java/util/Map$Entry"),
+            intern_string(&JavaStr::from_str("Entry").into())
+        );
+        // top-level and anonymous classes
+        assert_eq!(simple_binary_name("java/util/Map"), 0);
+        assert_eq!(simple_binary_name("java/util/Collections$1"), 0);
+    }
+}
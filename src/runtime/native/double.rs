@@ -32,4 +32,42 @@ fn long_bits_to_double(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
     let bits = env.args[0].get_long();
     let value = f64::from_bits(bits as u64);
     Ok(Some(NativeVariable::Double(value)))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::native::{NativeFunction, call_native};
+
+    fn call(function: NativeFunction, arg: NativeVariable) -> NativeVariable {
+        call_native(function, vec![arg])
+    }
+
+    #[test]
+    fn test_double_bits_round_trip() {
+        for value in [
+            0.0f64,
+            -0.0,
+            1.5,
+            f64::MIN_POSITIVE,
+            f64::INFINITY,
+            f64::NAN,
+        ] {
+            let bits = call(double_to_raw_long_bits, NativeVariable::Double(value)).get_long();
+            assert_eq!(bits, value.to_bits() as i64);
+            let value_back = call(long_bits_to_double, NativeVariable::Long(bits)).get_double();
+            assert_eq!(value_back.to_bits(), value.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_double_raw_bits_keep_nan_payload() {
+        // doubleToLongBits canonicalizes NaN on the java side, the raw version must not
+        let bits = 0xfff8_0000_0000_0001u64 as i64;
+        let value = call(long_bits_to_double, NativeVariable::Long(bits)).get_double();
+        assert!(value.is_nan());
+        assert_eq!(
+            call(double_to_raw_long_bits, NativeVariable::Double(value)).get_long(),
+            bits
+        );
+    }
+}
@@ -32,4 +32,42 @@ fn int_bits_to_float(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
     let bits = env.args[0].get_int();
     let value = f32::from_bits(bits as u32);
     Ok(Some(NativeVariable::Float(value)))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::native::{NativeFunction, call_native};
+
+    fn call(function: NativeFunction, arg: NativeVariable) -> NativeVariable {
+        call_native(function, vec![arg])
+    }
+
+    #[test]
+    fn test_float_bits_round_trip() {
+        for value in [
+            0.0f32,
+            -0.0,
+            1.5,
+            f32::MIN_POSITIVE,
+            f32::INFINITY,
+            f32::NAN,
+        ] {
+            let bits = call(float_to_raw_int_bits, NativeVariable::Float(value)).get_int();
+            assert_eq!(bits, value.to_bits() as i32);
+            let value_back = call(int_bits_to_float, NativeVariable::Int(bits)).get_float();
+            assert_eq!(value_back.to_bits(), value.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_float_raw_bits_keep_nan_payload() {
+        // floatToIntBits canonicalizes NaN on the java side, the raw version must not
+        let bits = 0x7fc0_0001u32 as i32;
+        let value = call(int_bits_to_float, NativeVariable::Int(bits)).get_float();
+        assert!(value.is_nan());
+        assert_eq!(
+            call(float_to_raw_int_bits, NativeVariable::Float(value)).get_int(),
+            bits
+        );
+    }
+}
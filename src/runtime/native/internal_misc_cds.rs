@@ -1,6 +1,9 @@
 use crate::descriptor::FieldType;
 use crate::runtime::{NativeEnv, NativeResult, NativeVariable, native::NATIVE_FUNCTIONS};
 
+// class data sharing is not supported, so these are stubs: nothing is dumped or shared, and no
+// archived objects are ever loaded
+
 // private static native boolean isDumpingClassList0();
 fn is_dumping_class_list0(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
     Ok(Some(NativeVariable::Boolean(false)))
@@ -22,6 +25,7 @@ fn get_random_seed_for_dumping(_env: NativeEnv) -> NativeResult<Option<NativeVar
 
 // public static native void initializeFromArchive(Class<?> c);
 fn initialize_from_archive(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    // the archived fields stay null, and the callers compute them instead
     Ok(None)
 }
 
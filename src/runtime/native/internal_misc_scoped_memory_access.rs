@@ -0,0 +1,13 @@
+use crate::runtime::native::{NATIVE_FUNCTIONS, native_nop};
+
+pub(super) fn register_natives() {
+    // hotspot registers closeScope0 here, memory segments are not supported yet
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/ScopedMemoryAccess".to_string(),
+            "registerNatives".to_string(),
+            vec![],
+        ),
+        native_nop,
+    );
+}
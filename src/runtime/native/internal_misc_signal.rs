@@ -0,0 +1,22 @@
+use crate::{
+    descriptor::FieldType,
+    runtime::{NativeEnv, NativeResult, NativeVariable, native::NATIVE_FUNCTIONS},
+};
+
+// private static native int findSignal0(String sigName);
+fn find_signal0(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    // signals are not handled by this vm, so every signal is unknown, and Signal.handle is never
+    // reached. Terminator.setup ignores unknown signals, shutdown hooks only run on exit
+    Ok(Some(NativeVariable::Int(-1)))
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Signal".to_string(),
+            "findSignal0".to_string(),
+            vec![FieldType::Object("java/lang/String".to_string())],
+        ),
+        find_signal0,
+    );
+}
@@ -0,0 +1,332 @@
+use crate::{
+    descriptor::FieldType,
+    runtime::{
+        Exception, NativeEnv, NativeResult, NativeVariable, Object, Variable,
+        famous_classes::{ILLEGAL_ARGUMENT_EXCEPTION_CLASS, INTERNAL_ERROR_CLASS},
+        inheritance::get_array_type,
+        native::{NATIVE_FUNCTIONS, class::get_class, string::get_string},
+        structs::{get_array_index, put_array_index},
+    },
+};
+use std::sync::atomic::{Ordering, fence};
+
+/// Offset of the first element that `arrayBaseOffset` reports, the same as hotspot with
+/// compressed class pointers. Elements are not addressed by offsets in this vm, an offset is
+/// only meaningful relative to this base.
+const ARRAY_BASE_OFFSET: i32 = 16;
+
+// the element type of the array class in args[1]
+fn array_element_type(env: &NativeEnv) -> NativeResult<FieldType> {
+    let class = get_class(env, env.args[1].get_ref());
+    get_array_type(&class).ok_or_else(|| {
+        Exception::new_vm_msg(
+            ILLEGAL_ARGUMENT_EXCEPTION_CLASS
+                .get()
+                .expect("must have init"),
+            &format!(
+                "{} is not an array class",
+                class.class_name().replace('/', ".")
+            ),
+        )
+    })
+}
+
+// the index of the element at `offset` when `obj` is an array, whose elements are `element_size`
+// bytes, otherwise `offset` is a field slot from objectFieldOffset
+fn element_index(obj: &dyn Object, offset: i64, element_size: usize) -> Option<usize> {
+    obj.get_class()
+        .is_array()
+        .then(|| (offset - ARRAY_BASE_OFFSET as i64) as usize / element_size)
+}
+
+/// # Safety
+///
+/// `offset` must be of a reference field or element of `obj`
+unsafe fn get_reference(obj: &dyn Object, offset: i64) -> u32 {
+    unsafe {
+        match element_index(obj, offset, size_of::<u32>()) {
+            Some(index) => get_array_index(obj, index),
+            None => obj.get_field(offset as _).reference,
+        }
+    }
+}
+
+/// # Safety
+///
+/// `offset` must be of a reference field or element of `obj`
+unsafe fn put_reference(obj: &dyn Object, offset: i64, x: u32) {
+    unsafe {
+        match element_index(obj, offset, size_of::<u32>()) {
+            Some(index) => put_array_index(obj, index, x),
+            None => obj.put_field(offset as _, Variable { reference: x }),
+        }
+    }
+}
+
+// private native int arrayBaseOffset0(Class<?> arrayClass);
+fn array_base_offset0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    array_element_type(&env)?;
+    Ok(Some(NativeVariable::Int(ARRAY_BASE_OFFSET)))
+}
+
+// private native int arrayIndexScale0(Class<?> arrayClass);
+fn array_index_scale0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let element_type = array_element_type(&env)?;
+    Ok(Some(NativeVariable::Int(
+        element_type.get_field_type_size() as _,
+    )))
+}
+
+// private native long objectFieldOffset1(Class<?> c, String name);
+fn object_field_offset1(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let class = get_class(&env, env.args[1].get_ref());
+    let name = get_string(&env, env.args[2].get_ref());
+    // fields are not addressed by bytes in this vm, the offset of a field is its slot
+    let Some(field) = class.find_field_info(&name, false) else {
+        return Err(Exception::new_vm_msg(
+            INTERNAL_ERROR_CLASS.get().expect("must have init"),
+            &name,
+        ));
+    };
+    Ok(Some(NativeVariable::Long(field.index as _)))
+}
+
+// public native int getIntVolatile(Object o, long offset);
+fn get_int_volatile(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let obj = env.heap.read().unwrap().get(env.args[1].get_ref());
+    let offset = env.args[2].get_long();
+    // SAFETY: offset is from objectFieldOffset, of an int field
+    let value = unsafe { obj.get_field(offset as _).get_int() };
+    Ok(Some(NativeVariable::Int(value)))
+}
+
+// public native Object getReferenceVolatile(Object o, long offset);
+fn get_reference_volatile(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let obj = env.heap.read().unwrap().get(env.args[1].get_ref());
+    // SAFETY: offset is of a reference field or element
+    let value = unsafe { get_reference(obj.as_ref(), env.args[2].get_long()) };
+    Ok(Some(NativeVariable::Reference(value)))
+}
+
+// public native void putReferenceVolatile(Object o, long offset, Object x);
+fn put_reference_volatile(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let obj = env.heap.read().unwrap().get(env.args[1].get_ref());
+    // SAFETY: offset is of a reference field or element
+    unsafe { put_reference(obj.as_ref(), env.args[2].get_long(), env.args[3].get_ref()) };
+    Ok(None)
+}
+
+// public final native boolean compareAndSetInt(Object o, long offset, int expected, int x);
+fn compare_and_set_int(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    // TODO: not atomic against other threads, fields are not guarded by the heap lock
+    let obj = env.heap.read().unwrap().get(env.args[1].get_ref());
+    let offset = env.args[2].get_long() as usize;
+    let expected = env.args[3].get_int();
+    let x = env.args[4].get_int();
+    // SAFETY: offset is from objectFieldOffset, of an int field
+    let swapped = unsafe {
+        let swapped = obj.get_field(offset).get_int() == expected;
+        if swapped {
+            obj.put_field(offset, Variable { int: x });
+        }
+        swapped
+    };
+    Ok(Some(NativeVariable::Boolean(swapped)))
+}
+
+// public final native boolean compareAndSetLong(Object o, long offset, long expected, long x);
+fn compare_and_set_long(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    // TODO: not atomic against other threads, like compareAndSetInt
+    let obj = env.heap.read().unwrap().get(env.args[1].get_ref());
+    let offset = env.args[2].get_long() as usize;
+    let expected = env.args[3].get_long();
+    let x = env.args[4].get_long();
+    // SAFETY: offset is from objectFieldOffset, of a long field, which takes two slots
+    let swapped = unsafe {
+        let swapped =
+            Variable::get_long(obj.get_field(offset), obj.get_field(offset + 1)) == expected;
+        if swapped {
+            let (upper, lower) = Variable::put_long(x);
+            obj.put_field(offset, upper);
+            obj.put_field(offset + 1, lower);
+        }
+        swapped
+    };
+    Ok(Some(NativeVariable::Boolean(swapped)))
+}
+
+// public final native boolean compareAndSetReference(Object o, long offset, Object expected, Object x);
+fn compare_and_set_reference(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    // TODO: not atomic against other threads, like compareAndSetInt
+    let obj = env.heap.read().unwrap().get(env.args[1].get_ref());
+    let offset = env.args[2].get_long();
+    let expected = env.args[3].get_ref();
+    let x = env.args[4].get_ref();
+    // SAFETY: offset is of a reference field or element
+    let swapped = unsafe {
+        let swapped = get_reference(obj.as_ref(), offset) == expected;
+        if swapped {
+            put_reference(obj.as_ref(), offset, x);
+        }
+        swapped
+    };
+    Ok(Some(NativeVariable::Boolean(swapped)))
+}
+
+// public native void loadFence();
+fn load_fence(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    fence(Ordering::Acquire);
+    Ok(None)
+}
+
+// public native void storeFence();
+fn store_fence(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    fence(Ordering::Release);
+    Ok(None)
+}
+
+// public native void fullFence();
+fn full_fence(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    fence(Ordering::SeqCst);
+    Ok(None)
+}
+
+fn native_unsafe_register_natives(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "arrayBaseOffset0".to_string(),
+            vec![FieldType::Object("java/lang/Class".to_string())],
+        ),
+        array_base_offset0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "arrayIndexScale0".to_string(),
+            vec![FieldType::Object("java/lang/Class".to_string())],
+        ),
+        array_index_scale0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "objectFieldOffset1".to_string(),
+            vec![
+                FieldType::Object("java/lang/Class".to_string()),
+                FieldType::Object("java/lang/String".to_string()),
+            ],
+        ),
+        object_field_offset1,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "getIntVolatile".to_string(),
+            vec![
+                FieldType::Object("java/lang/Object".to_string()),
+                FieldType::Long,
+            ],
+        ),
+        get_int_volatile,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "getReferenceVolatile".to_string(),
+            vec![
+                FieldType::Object("java/lang/Object".to_string()),
+                FieldType::Long,
+            ],
+        ),
+        get_reference_volatile,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "putReferenceVolatile".to_string(),
+            vec![
+                FieldType::Object("java/lang/Object".to_string()),
+                FieldType::Long,
+                FieldType::Object("java/lang/Object".to_string()),
+            ],
+        ),
+        put_reference_volatile,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "compareAndSetInt".to_string(),
+            vec![
+                FieldType::Object("java/lang/Object".to_string()),
+                FieldType::Long,
+                FieldType::Int,
+                FieldType::Int,
+            ],
+        ),
+        compare_and_set_int,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "compareAndSetLong".to_string(),
+            vec![
+                FieldType::Object("java/lang/Object".to_string()),
+                FieldType::Long,
+                FieldType::Long,
+                FieldType::Long,
+            ],
+        ),
+        compare_and_set_long,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "compareAndSetReference".to_string(),
+            vec![
+                FieldType::Object("java/lang/Object".to_string()),
+                FieldType::Long,
+                FieldType::Object("java/lang/Object".to_string()),
+                FieldType::Object("java/lang/Object".to_string()),
+            ],
+        ),
+        compare_and_set_reference,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "loadFence".to_string(),
+            vec![],
+        ),
+        load_fence,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "storeFence".to_string(),
+            vec![],
+        ),
+        store_fence,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "fullFence".to_string(),
+            vec![],
+        ),
+        full_fence,
+    );
+
+    Ok(None)
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/Unsafe".to_string(),
+            "registerNatives".to_string(),
+            vec![],
+        ),
+        native_unsafe_register_natives,
+    );
+}
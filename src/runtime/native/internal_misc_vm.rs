@@ -0,0 +1,18 @@
+use crate::runtime::{NativeEnv, NativeResult, NativeVariable, native::NATIVE_FUNCTIONS};
+
+// private static native void initialize();
+// hotspot registers the rest of the natives of VM here, they are in the native table already
+fn initialize(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    Ok(None)
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/misc/VM".to_string(),
+            "initialize".to_string(),
+            vec![],
+        ),
+        initialize,
+    );
+}
@@ -0,0 +1,776 @@
+use crate::{
+    class::JavaStr,
+    consts::{ClassAccessFlag, MethodAccessFlag},
+    descriptor::FieldType,
+    runtime::{
+        Class, Exception, MethodInfo, NativeEnv, NativeResult, NativeVariable, Thread, Variable,
+        VmEnv, VtableIndex,
+        class_loader::{
+            abstract_method_error, get_class_object, initialize_class, new_array, new_instance,
+            select_interface_method,
+        },
+        famous_classes::{
+            ILLEGAL_ARGUMENT_EXCEPTION_CLASS, INSTANTIATION_EXCEPTION_CLASS,
+            INVOCATION_TARGET_EXCEPTION_CLASS, NO_SUCH_METHOD_EXCEPTION_CLASS,
+            NULL_POINTER_EXCEPTION_CLASS,
+        },
+        global::{BOOTSTRAP_CLASS_LOADER, HEAP},
+        inheritance::is_assignable_to,
+        native::{
+            NATIVE_FUNCTIONS,
+            class::{field_type_class, get_class},
+            lang_reflect_array::put_element,
+        },
+        structs::get_array_index,
+    },
+};
+use std::sync::Arc;
+
+// private static native Object invoke0(Method m, Object obj, Object[] args);
+fn invoke0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let method = env.args[0].get_ref();
+    let obj = env.args[1].get_ref();
+    let args = env.args[2].get_ref();
+
+    // SAFETY: Method.clazz is a Class and Method.slot is an int
+    let declaring_class = get_class(&env, unsafe { get_field(&env, method, "clazz").reference });
+    let slot = unsafe { get_field(&env, method, "slot").int } as usize;
+    let method_info = &declaring_class.methods[slot];
+
+    let thread = Thread::new(0);
+    let is_static = method_info.access_flags.contains(MethodAccessFlag::STATIC);
+    let (class, index) = if is_static {
+        initialize_class(&VmEnv::new(&thread, env.heap), &declaring_class)?;
+        (Arc::clone(&declaring_class), slot)
+    } else {
+        if obj == 0 {
+            return Err(Exception::new_vm(
+                NULL_POINTER_EXCEPTION_CLASS.get().expect("must have init"),
+            ));
+        }
+        let obj_class = Arc::clone(env.heap.read().unwrap().get(obj).get_class());
+        if !is_assignable_to(&obj_class, &declaring_class) {
+            return Err(illegal_argument(
+                "object is not an instance of declaring class",
+            ));
+        }
+        if method_info.access_flags.contains(MethodAccessFlag::PRIVATE) {
+            (Arc::clone(&declaring_class), slot)
+        } else {
+            select_virtual_method(&obj_class, method_info)?
+        }
+    };
+
+    let mut call_args = Vec::with_capacity(method_info.descriptor.parameters.len() + 1);
+    if !is_static {
+        call_args.push(NativeVariable::Reference(obj));
+    }
+    let is_varargs = method_info.access_flags.contains(MethodAccessFlag::VARARGS);
+    call_args.extend(unbox_args(
+        &env,
+        args,
+        &method_info.descriptor.parameters,
+        is_varargs,
+    )?);
+
+    let ret = match thread.invoke_method(Arc::clone(&class), &class.methods[index], &call_args) {
+        Ok(ret) => ret,
+        Err(exception) => return Err(new_invocation_target_exception(&thread, exception)?),
+    };
+    let ret = match ret {
+        Some(ret) => box_value(&env, &thread, ret)?,
+        None => 0,
+    };
+    Ok(Some(NativeVariable::Reference(ret)))
+}
+
+// private static native Object newInstance0(Constructor<?> c, Object[] args);
+fn new_instance0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let constructor = env.args[0].get_ref();
+    let args = env.args[1].get_ref();
+
+    // SAFETY: Constructor.clazz is a Class and Constructor.slot is an int
+    let class = get_class(&env, unsafe {
+        get_field(&env, constructor, "clazz").reference
+    });
+    let slot = unsafe { get_field(&env, constructor, "slot").int } as usize;
+
+    let thread = Thread::new(0);
+    let parameters = &class.methods[slot].descriptor.parameters;
+    let obj = construct(&env, &thread, &class, parameters, args)?;
+    Ok(Some(NativeVariable::Reference(obj)))
+}
+
+/// Allocates an instance of `class` like `new` does, then runs its constructor taking
+/// `parameters` with the unboxed `args`
+fn construct(
+    env: &NativeEnv,
+    thread: &Thread,
+    class: &Arc<Class>,
+    parameters: &[FieldType],
+    args: u32,
+) -> NativeResult<u32> {
+    if class
+        .access_flags
+        .intersects(ClassAccessFlag::ABSTRACT | ClassAccessFlag::INTERFACE)
+    {
+        return Err(Exception::new_vm_msg(
+            INSTANTIATION_EXCEPTION_CLASS.get().expect("must have init"),
+            &class.class_name().replace('/', "."),
+        ));
+    }
+    let Some(constructor) = class.resolve_method(&JavaStr::from_str("<init>"), parameters) else {
+        let parameters: Vec<_> = parameters.iter().map(|p| p.to_string()).collect();
+        return Err(Exception::new_vm_msg(
+            NO_SUCH_METHOD_EXCEPTION_CLASS
+                .get()
+                .expect("must have init"),
+            &format!(
+                "{}.<init>({})",
+                class.class_name().replace('/', "."),
+                parameters.join(", ")
+            ),
+        ));
+    };
+
+    let is_varargs = constructor.access_flags.contains(MethodAccessFlag::VARARGS);
+    let mut call_args = unbox_args(env, args, parameters, is_varargs)?;
+    initialize_class(&VmEnv::new(thread, env.heap), class)?;
+    let obj = new_instance(class);
+    call_args.insert(0, NativeVariable::Reference(obj));
+
+    if let Err(exception) = thread.invoke_method(Arc::clone(class), constructor, &call_args) {
+        return Err(new_invocation_target_exception(thread, exception)?);
+    }
+    Ok(obj)
+}
+
+/// Selects the implementation of `method` for an object of `class`, like `invokevirtual` and
+/// `invokeinterface` do
+pub(super) fn select_virtual_method(
+    class: &Arc<Class>,
+    method: &MethodInfo,
+) -> NativeResult<(Arc<Class>, usize)> {
+    let vtable_entry = class
+        .vtable
+        .iter()
+        .find(|entry| entry.name == method.name && entry.descriptor == method.descriptor);
+    let selected = match vtable_entry.map(|entry| &entry.index) {
+        Some(VtableIndex::InThisClass(index)) => Some((Arc::clone(class), *index)),
+        Some(
+            VtableIndex::OtherClass { class, index } | VtableIndex::OtherInterface { class, index },
+        ) => Some((Arc::clone(class), *index)),
+        // throws IncompatibleClassChangeError for conflicting default methods
+        Some(VtableIndex::DefaultConflict { .. }) | None => select_interface_method(class, method)?,
+    };
+
+    match selected {
+        Some((class, index))
+            if !class.methods[index]
+                .access_flags
+                .contains(MethodAccessFlag::ABSTRACT) =>
+        {
+            Ok((class, index))
+        }
+        _ => Err(abstract_method_error(class, method)),
+    }
+}
+
+/// Unboxes the `Object[]` `args` to the types of `parameters`, widening primitives as needed.
+/// For a varargs method, the trailing arguments are packed into the array it takes, unless
+/// they already are one.
+fn unbox_args(
+    env: &NativeEnv,
+    args: u32,
+    parameters: &[FieldType],
+    is_varargs: bool,
+) -> NativeResult<Vec<NativeVariable>> {
+    let mut args = if args == 0 {
+        // null is accepted for methods without parameters
+        vec![]
+    } else {
+        let args = env.heap.read().unwrap().get(args);
+        (0..args.get_array_size(size_of::<u32>()))
+            // SAFETY: args is an Object[]
+            .map(|i| unsafe { get_array_index::<u32, _>(args.as_ref(), i) })
+            .collect()
+    };
+    // the flag is not checked when a class is loaded, so a malformed varargs method may not
+    // take an array
+    let packs_varargs = is_varargs
+        && matches!(parameters.last(), Some(FieldType::Array(_)))
+        // too few arguments are reported as the wrong number
+        && args.len() + 1 >= parameters.len()
+        && !ends_with_array(env, &args, parameters)?;
+    if packs_varargs {
+        let fixed = parameters.len() - 1;
+        let array = pack_varargs(env, &args[fixed..], &parameters[fixed])?;
+        args.truncate(fixed);
+        args.push(array);
+    }
+    if args.len() != parameters.len() {
+        return Err(illegal_argument("wrong number of arguments"));
+    }
+
+    let mut unboxed = Vec::with_capacity(args.len());
+    for (&arg, parameter) in args.iter().zip(parameters) {
+        unboxed.push(unbox_arg(env, arg, parameter)?);
+    }
+    Ok(unboxed)
+}
+
+/// Whether `args` for a varargs method taking `parameters` end with the array it takes, or
+/// null in its place
+fn ends_with_array(env: &NativeEnv, args: &[u32], parameters: &[FieldType]) -> NativeResult<bool> {
+    if args.len() != parameters.len() {
+        return Ok(false);
+    }
+    let last = args[args.len() - 1];
+    if last == 0 {
+        return Ok(true);
+    }
+    let last_class = Arc::clone(env.heap.read().unwrap().get(last).get_class());
+    Ok(is_assignable_to(
+        &last_class,
+        &field_type_class(&parameters[parameters.len() - 1])?,
+    ))
+}
+
+/// Allocates an array of `array_type` holding the unboxed `args`, as the compiler does for
+/// the trailing arguments of a call to a varargs method
+fn pack_varargs(env: &NativeEnv, args: &[u32], array_type: &FieldType) -> NativeResult<u32> {
+    let FieldType::Array(element_type) = array_type else {
+        panic!("varargs must be an array");
+    };
+    let mut elements = Vec::with_capacity(args.len());
+    for &arg in args {
+        elements.push(unbox_arg(env, arg, element_type)?);
+    }
+    let array_class = field_type_class(array_type)?;
+    let array = new_array(&mut env.heap.write().unwrap(), array_class, elements.len());
+    let array_obj = env.heap.read().unwrap().get(array);
+    for (i, element) in elements.into_iter().enumerate() {
+        put_element(array_obj.as_ref(), i as _, element)?;
+    }
+    Ok(array)
+}
+
+/// Unboxes `arg` to the type of `parameter`, widening primitives as needed
+fn unbox_arg(env: &NativeEnv, arg: u32, parameter: &FieldType) -> NativeResult<NativeVariable> {
+    let arg = match parameter {
+        FieldType::Object(_) | FieldType::Array(_) => {
+            if arg != 0 {
+                let arg_class = Arc::clone(env.heap.read().unwrap().get(arg).get_class());
+                if !is_assignable_to(&arg_class, &field_type_class(parameter)?) {
+                    return Err(illegal_argument("argument type mismatch"));
+                }
+            }
+            Some(NativeVariable::Reference(arg))
+        }
+        _ => unbox(env, arg).and_then(|value| widen(value, parameter)),
+    };
+    arg.ok_or_else(|| illegal_argument("argument type mismatch"))
+}
+
+/// The primitive value held by the box `obj`, or `None` if `obj` is null or not a box
+pub(super) fn unbox(env: &NativeEnv, obj: u32) -> Option<NativeVariable> {
+    if obj == 0 {
+        return None;
+    }
+    let obj = env.heap.read().unwrap().get(obj);
+    let class = obj.get_class();
+    let field = class.find_field_info("value", false)?;
+    // SAFETY: `value` of a box is of the boxed type, category 2 values take two slots
+    let value = unsafe {
+        let v1 = obj.get_field(field.index);
+        match class.class_name() {
+            "java/lang/Boolean" => NativeVariable::Boolean(v1.int != 0),
+            "java/lang/Byte" => NativeVariable::Byte(v1.int as _),
+            "java/lang/Character" => NativeVariable::Char(v1.int as _),
+            "java/lang/Short" => NativeVariable::Short(v1.int as _),
+            "java/lang/Integer" => NativeVariable::Int(v1.int),
+            "java/lang/Float" => NativeVariable::Float(v1.float),
+            "java/lang/Long" => {
+                NativeVariable::Long(Variable::get_long(v1, obj.get_field(field.index + 1)))
+            }
+            "java/lang/Double" => {
+                NativeVariable::Double(Variable::get_double(v1, obj.get_field(field.index + 1)))
+            }
+            _ => return None,
+        }
+    };
+    Some(value)
+}
+
+/// Applies identity or widening primitive conversion of `value` to `target`
+pub(super) fn widen(value: NativeVariable, target: &FieldType) -> Option<NativeVariable> {
+    use FieldType as T;
+    use NativeVariable as V;
+
+    let widened = match (value, target) {
+        (V::Boolean(b), T::Boolean) => V::Boolean(b),
+        (V::Byte(b), T::Byte) => V::Byte(b),
+        (V::Byte(b), T::Short) => V::Short(b as _),
+        (V::Short(s), T::Short) => V::Short(s),
+        (V::Char(c), T::Char) => V::Char(c),
+        (V::Byte(b), _) => return widen(V::Int(b as _), target),
+        (V::Short(s), _) => return widen(V::Int(s as _), target),
+        (V::Char(c), _) => return widen(V::Int(c as _), target),
+        (V::Int(i), T::Int) => V::Int(i),
+        (V::Int(i), _) => return widen(V::Long(i as _), target),
+        (V::Long(l), T::Long) => V::Long(l),
+        (V::Long(l), T::Float) => V::Float(l as _),
+        (V::Long(l), T::Double) => V::Double(l as _),
+        (V::Float(f), T::Float) => V::Float(f),
+        (V::Float(f), T::Double) => V::Double(f as _),
+        (V::Double(d), T::Double) => V::Double(d),
+        _ => return None,
+    };
+    Some(widened)
+}
+
+/// Boxes a primitive `value` into a new object of its wrapper class, references are returned as is
+pub(super) fn box_value(
+    env: &NativeEnv,
+    thread: &Thread,
+    value: NativeVariable,
+) -> NativeResult<u32> {
+    let (class_name, v1, v2) = match value {
+        NativeVariable::Reference(reference) => return Ok(reference),
+        NativeVariable::Boolean(b) => ("java/lang/Boolean", Variable { int: b as _ }, None),
+        NativeVariable::Byte(b) => ("java/lang/Byte", Variable { int: b as _ }, None),
+        NativeVariable::Char(c) => ("java/lang/Character", Variable { int: c as _ }, None),
+        NativeVariable::Short(s) => ("java/lang/Short", Variable { int: s as _ }, None),
+        NativeVariable::Int(int) => ("java/lang/Integer", Variable { int }, None),
+        NativeVariable::Float(float) => ("java/lang/Float", Variable { float }, None),
+        NativeVariable::Long(l) => {
+            let (upper, lower) = Variable::put_long(l);
+            ("java/lang/Long", upper, Some(lower))
+        }
+        NativeVariable::Double(d) => {
+            let (upper, lower) = Variable::put_double(d);
+            ("java/lang/Double", upper, Some(lower))
+        }
+    };
+
+    let class = BOOTSTRAP_CLASS_LOADER
+        .get()
+        .unwrap()
+        .resolve_class(class_name)?;
+    initialize_class(&VmEnv::new(thread, env.heap), &class)?;
+    let id = new_instance(&class);
+    let index = class
+        .find_field_info("value", false)
+        .expect("box must have value")
+        .index;
+    let obj = env.heap.read().unwrap().get(id);
+    // SAFETY: obj is newly allocated and not yet visible to other threads
+    unsafe {
+        obj.put_field(index, v1);
+        if let Some(v2) = v2 {
+            obj.put_field(index + 1, v2);
+        }
+    }
+    Ok(id)
+}
+
+/// Wraps an exception thrown by the invoked method into an `InvocationTargetException`
+fn new_invocation_target_exception(
+    thread: &Thread,
+    exception: Exception,
+) -> NativeResult<Exception> {
+    let target = match exception {
+        Exception::UserException(target) => target,
+        Exception::VmException {
+            exception_type,
+            message,
+        } => thread.new_exception_object(&exception_type, &message)?,
+        Exception::Exit(_) => return Err(exception),
+    };
+
+    let class = INVOCATION_TARGET_EXCEPTION_CLASS
+        .get()
+        .expect("must have init");
+    initialize_class(&VmEnv::new(thread, &HEAP), class)?;
+    let obj = new_instance(class);
+    let constructor = class
+        .resolve_method(
+            &JavaStr::from_str("<init>"),
+            &[FieldType::Object("java/lang/Throwable".to_string())],
+        )
+        .expect("InvocationTargetException must have constructor with target");
+    thread.invoke_method(
+        Arc::clone(class),
+        constructor,
+        &[
+            NativeVariable::Reference(obj),
+            NativeVariable::Reference(target),
+        ],
+    )?;
+    Ok(Exception::new(obj))
+}
+
+// public static native Class<?> getCallerClass();
+fn get_caller_class(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let caller = match env.caller {
+        Some(caller) => get_class_object(caller)?,
+        None => 0,
+    };
+    Ok(Some(NativeVariable::Reference(caller)))
+}
+
+fn illegal_argument(message: &str) -> Exception {
+    Exception::new_vm_msg(
+        ILLEGAL_ARGUMENT_EXCEPTION_CLASS
+            .get()
+            .expect("must have init"),
+        message,
+    )
+}
+
+/// # Safety
+///
+/// `obj` must be a non-array object which has an instance field `name`
+unsafe fn get_field(env: &NativeEnv, obj: u32, name: &str) -> Variable {
+    let obj = env.heap.read().unwrap().get(obj);
+    let field = obj
+        .get_class()
+        .find_field_info(name, false)
+        .expect("must have field");
+    unsafe { obj.get_field(field.index) }
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/reflect/NativeMethodAccessorImpl".to_string(),
+            "invoke0".to_string(),
+            vec![
+                FieldType::Object("java/lang/reflect/Method".to_string()),
+                FieldType::Object("java/lang/Object".to_string()),
+                FieldType::Array(Box::new(FieldType::Object("java/lang/Object".to_string()))),
+            ],
+        ),
+        invoke0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/reflect/NativeConstructorAccessorImpl".to_string(),
+            "newInstance0".to_string(),
+            vec![
+                FieldType::Object("java/lang/reflect/Constructor".to_string()),
+                FieldType::Array(Box::new(FieldType::Object("java/lang/Object".to_string()))),
+            ],
+        ),
+        new_instance0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/reflect/Reflection".to_string(),
+            "getCallerClass".to_string(),
+            vec![],
+        ),
+        get_caller_class,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{
+        class_loader::intern_string,
+        famous_classes::OBJECT_CLASS,
+        global::HEAP,
+        native::{
+            boot_vm, call_native, class::new_reference_array, string::get_string, try_call_native,
+        },
+    };
+    use std::sync::LazyLock;
+
+    fn env() -> NativeEnv<'static> {
+        static THREAD: LazyLock<Thread> = LazyLock::new(|| Thread::new(0));
+        NativeEnv {
+            args: vec![],
+            heap: &HEAP,
+            class: Arc::clone(OBJECT_CLASS.get().unwrap()),
+            caller: None,
+            thread_id: THREAD.thread_id(),
+            thread: &THREAD,
+        }
+    }
+
+    fn array_elements(array: u32) -> Vec<u32> {
+        let array = HEAP.read().unwrap().get(array);
+        (0..array.get_array_size(size_of::<u32>()))
+            .map(|i| unsafe { get_array_index::<u32, _>(array.as_ref(), i) })
+            .collect()
+    }
+
+    /// The method objects of the methods declared by `class_name` named `name`
+    fn method_objects(class_name: &str, name: &str) -> Vec<u32> {
+        let class = BOOTSTRAP_CLASS_LOADER
+            .get()
+            .unwrap()
+            .resolve_class(class_name)
+            .unwrap();
+        let get_declared_methods0 = *NATIVE_FUNCTIONS
+            .get(&(
+                "java/lang/Class".to_string(),
+                "getDeclaredMethods0".to_string(),
+                vec![FieldType::Boolean],
+            ))
+            .unwrap();
+        let args = vec![
+            NativeVariable::Reference(get_class_object(class).unwrap()),
+            NativeVariable::Boolean(false),
+        ];
+        let name = intern_string(&JavaStr::from_str(name).into());
+        array_elements(call_native(get_declared_methods0, args).get_ref())
+            .into_iter()
+            .filter(|&method| unsafe { get_field(&env(), method, "name").reference == name })
+            .collect()
+    }
+
+    /// The declared method of `class_name` named `name` with `parameter_count` parameters
+    fn method_object(class_name: &str, name: &str, parameter_count: usize) -> u32 {
+        method_objects(class_name, name)
+            .into_iter()
+            .find(|&method| unsafe {
+                array_elements(get_field(&env(), method, "parameterTypes").reference).len()
+                    == parameter_count
+            })
+            .unwrap()
+    }
+
+    fn invoke(method: u32, obj: u32, args: Vec<NativeVariable>) -> NativeResult<u32> {
+        let thread = Thread::new(0);
+        let mut boxed = vec![];
+        for arg in args {
+            boxed.push(box_value(&env(), &thread, arg)?);
+        }
+        let args = new_reference_array(&env(), OBJECT_CLASS.get().unwrap(), &boxed)?;
+        let args = vec![
+            NativeVariable::Reference(method),
+            NativeVariable::Reference(obj),
+            NativeVariable::Reference(args),
+        ];
+        Ok(try_call_native(invoke0, args)?.unwrap().get_ref())
+    }
+
+    fn exception_class_name(exception: Exception) -> String {
+        match exception {
+            Exception::VmException { exception_type, .. } => exception_type.class_name().into(),
+            Exception::UserException(obj) => HEAP
+                .read()
+                .unwrap()
+                .get(obj)
+                .get_class()
+                .class_name()
+                .into(),
+            Exception::Exit(status) => panic!("exited with {status}"),
+        }
+    }
+
+    #[test]
+    fn test_invoke_static() {
+        if !boot_vm() {
+            return;
+        }
+        let sum = method_object("java/lang/Integer", "sum", 2);
+        let ret = invoke(
+            sum,
+            0,
+            vec![NativeVariable::Int(40), NativeVariable::Int(2)],
+        )
+        .unwrap();
+        assert!(matches!(unbox(&env(), ret), Some(NativeVariable::Int(42))));
+
+        // int arguments are widened to long
+        let sum = method_object("java/lang/Long", "sum", 2);
+        let ret = invoke(
+            sum,
+            0,
+            vec![NativeVariable::Int(40), NativeVariable::Long(2)],
+        )
+        .unwrap();
+        assert!(matches!(unbox(&env(), ret), Some(NativeVariable::Long(42))));
+    }
+
+    #[test]
+    fn test_invoke_virtual() {
+        if !boot_vm() {
+            return;
+        }
+        // the abstract Number.intValue dispatches to Integer.intValue
+        let int_value = method_object("java/lang/Number", "intValue", 0);
+        let thread = Thread::new(0);
+        let seven = box_value(&env(), &thread, NativeVariable::Int(7)).unwrap();
+        let ret = invoke(int_value, seven, vec![]).unwrap();
+        assert!(matches!(unbox(&env(), ret), Some(NativeVariable::Int(7))));
+
+        let err = invoke(int_value, 0, vec![]).unwrap_err();
+        assert_eq!(exception_class_name(err), "java/lang/NullPointerException");
+    }
+
+    #[test]
+    fn test_invoke_illegal_arguments() {
+        if !boot_vm() {
+            return;
+        }
+        let sum = method_object("java/lang/Integer", "sum", 2);
+        let err = invoke(sum, 0, vec![NativeVariable::Int(1)]).unwrap_err();
+        assert_eq!(
+            exception_class_name(err),
+            "java/lang/IllegalArgumentException"
+        );
+        // long is not narrowed to int
+        let err = invoke(
+            sum,
+            0,
+            vec![NativeVariable::Int(1), NativeVariable::Long(2)],
+        )
+        .unwrap_err();
+        assert_eq!(
+            exception_class_name(err),
+            "java/lang/IllegalArgumentException"
+        );
+    }
+
+    #[test]
+    fn test_invoke_wraps_target_exception() {
+        if !boot_vm() {
+            return;
+        }
+        let parse_int = method_object("java/lang/Integer", "parseInt", 1);
+        let err = invoke(parse_int, 0, vec![NativeVariable::Reference(0)]).unwrap_err();
+        let Exception::UserException(obj) = err else {
+            panic!("must be user exception");
+        };
+        assert_eq!(
+            HEAP.read().unwrap().get(obj).get_class().class_name(),
+            "java/lang/reflect/InvocationTargetException"
+        );
+        let target = unsafe { get_field(&env(), obj, "target").reference };
+        assert_eq!(
+            HEAP.read().unwrap().get(target).get_class().class_name(),
+            "java/lang/NumberFormatException"
+        );
+    }
+
+    #[test]
+    fn test_invoke_varargs() {
+        if !boot_vm() {
+            return;
+        }
+        // String.join(CharSequence, CharSequence...), not join(CharSequence, Iterable)
+        let join = method_objects("java/lang/String", "join")
+            .into_iter()
+            .find(|&method| unsafe {
+                get_field(&env(), method, "modifiers").int & MethodAccessFlag::VARARGS.bits() as i32
+                    != 0
+            })
+            .unwrap();
+        let string = |s: &str| intern_string(&JavaStr::from_str(s).into());
+        let joined = |args: Vec<u32>| {
+            let args = args.into_iter().map(NativeVariable::Reference).collect();
+            invoke(join, 0, args).map(|ret| get_string(&env(), ret))
+        };
+        let char_sequence = resolve_class("java/lang/CharSequence");
+
+        // an explicit array is passed as is
+        let elements = new_reference_array(&env(), &char_sequence, &[string("a"), string("b")]);
+        let ret = joined(vec![string(","), elements.unwrap()]);
+        assert_eq!(ret.unwrap(), "a,b");
+        // the trailing arguments are packed into an array
+        let ret = joined(vec![string(","), string("a"), string("b"), string("c")]);
+        assert_eq!(ret.unwrap(), "a,b,c");
+        let ret = joined(vec![string(","), string("a")]);
+        assert_eq!(ret.unwrap(), "a");
+        let ret = joined(vec![string(",")]);
+        assert_eq!(ret.unwrap(), "");
+
+        // Integer is not a CharSequence
+        let thread = Thread::new(0);
+        let one = box_value(&env(), &thread, NativeVariable::Int(1)).unwrap();
+        let err = joined(vec![string(","), string("a"), one]).unwrap_err();
+        assert_eq!(
+            exception_class_name(err),
+            "java/lang/IllegalArgumentException"
+        );
+        let err = joined(vec![]).unwrap_err();
+        assert_eq!(
+            exception_class_name(err),
+            "java/lang/IllegalArgumentException"
+        );
+    }
+
+    fn resolve_class(class_name: &str) -> Arc<Class> {
+        BOOTSTRAP_CLASS_LOADER
+            .get()
+            .unwrap()
+            .resolve_class(class_name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_new_instance() {
+        if !boot_vm() {
+            return;
+        }
+        let get_declared_constructors0 = *NATIVE_FUNCTIONS
+            .get(&(
+                "java/lang/Class".to_string(),
+                "getDeclaredConstructors0".to_string(),
+                vec![FieldType::Boolean],
+            ))
+            .unwrap();
+        let integer_class = resolve_class("java/lang/Integer");
+        let args = vec![
+            NativeVariable::Reference(get_class_object(Arc::clone(&integer_class)).unwrap()),
+            NativeVariable::Boolean(false),
+        ];
+        let constructors = array_elements(call_native(get_declared_constructors0, args).get_ref());
+        // Integer(int) and Integer(String)
+        assert_eq!(constructors.len(), 2);
+        let int_class = get_class_object(field_type_class(&FieldType::Int).unwrap()).unwrap();
+        let from_int = constructors
+            .into_iter()
+            .find(|&constructor| unsafe {
+                array_elements(get_field(&env(), constructor, "parameterTypes").reference)
+                    == [int_class]
+            })
+            .unwrap();
+
+        let thread = Thread::new(0);
+        let five = box_value(&env(), &thread, NativeVariable::Int(5)).unwrap();
+        let args = new_reference_array(&env(), OBJECT_CLASS.get().unwrap(), &[five]).unwrap();
+        let args = vec![
+            NativeVariable::Reference(from_int),
+            NativeVariable::Reference(args),
+        ];
+        let obj = call_native(new_instance0, args).get_ref();
+        assert!(Arc::ptr_eq(
+            HEAP.read().unwrap().get(obj).get_class(),
+            &integer_class
+        ));
+        assert_eq!(unsafe { get_field(&env(), obj, "value").int }, 5);
+    }
+
+    #[test]
+    fn test_construct_errors() {
+        if !boot_vm() {
+            return;
+        }
+        let thread = Thread::new(0);
+        let err = construct(&env(), &thread, &resolve_class("java/lang/Number"), &[], 0);
+        assert_eq!(
+            exception_class_name(err.unwrap_err()),
+            "java/lang/InstantiationException"
+        );
+        let err = construct(&env(), &thread, &resolve_class("java/lang/Integer"), &[], 0);
+        assert_eq!(
+            exception_class_name(err.unwrap_err()),
+            "java/lang/NoSuchMethodException"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{
+    class::JavaStr,
+    runtime::{
+        NativeEnv, NativeResult, NativeVariable,
+        class_loader::intern_string,
+        famous_classes::STRING_CLASS,
+        global::SYSTEM_PROPERTIES,
+        native::{NATIVE_FUNCTIONS, class::new_reference_array},
+    },
+};
+use dashmap::DashMap;
+use std::{env, path::MAIN_SEPARATOR_STR};
+
+/// Properties the vm starts with, `java.home` is added when the vm is built
+pub(in crate::runtime) fn initial_system_properties() -> DashMap<String, String> {
+    let os_name = match env::consts::OS {
+        "linux" => "Linux",
+        "macos" => "Mac OS X",
+        "windows" => "Windows",
+        "freebsd" => "FreeBSD",
+        os => os,
+    };
+    let os_arch = match env::consts::ARCH {
+        "x86_64" => "amd64",
+        "x86" => "i386",
+        arch => arch,
+    };
+    let (line_separator, path_separator) = if cfg!(windows) {
+        ("\r\n", ";")
+    } else {
+        ("\n", ":")
+    };
+
+    let properties = DashMap::new();
+    for (key, value) in [
+        ("java.version", "17"),
+        ("java.specification.version", "17"),
+        ("java.vm.specification.version", "17"),
+        ("java.class.version", "61.0"),
+        ("java.vm.name", env!("CARGO_PKG_NAME")),
+        ("java.vm.version", env!("CARGO_PKG_VERSION")),
+        ("os.name", os_name),
+        ("os.arch", os_arch),
+        ("file.separator", MAIN_SEPARATOR_STR),
+        ("path.separator", path_separator),
+        ("line.separator", line_separator),
+        ("file.encoding", "UTF-8"),
+        ("native.encoding", "UTF-8"),
+        ("sun.jnu.encoding", "UTF-8"),
+    ] {
+        properties.insert(key.to_string(), value.to_string());
+    }
+    if let Ok(user_dir) = env::current_dir() {
+        properties.insert("user.dir".to_string(), user_dir.display().to_string());
+    }
+    if let Some(user_home) = env::home_dir() {
+        properties.insert("user.home".to_string(), user_home.display().to_string());
+    }
+    // hotspot reads the password database instead, and also falls back to ?
+    let user_name = env::var(if cfg!(windows) { "USERNAME" } else { "USER" });
+    properties.insert(
+        "user.name".to_string(),
+        user_name.unwrap_or_else(|_| "?".to_string()),
+    );
+    properties.insert(
+        "java.io.tmpdir".to_string(),
+        env::temp_dir().display().to_string(),
+    );
+    properties
+}
+
+// private static native String[] vmProperties();
+fn vm_properties(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    // key and value pairs, flattened
+    let strings: Vec<_> = SYSTEM_PROPERTIES
+        .iter()
+        .flat_map(|entry| [entry.key().clone(), entry.value().clone()])
+        .map(|string| intern_string(&JavaStr::from_str(&string).into()))
+        .collect();
+    Ok(Some(NativeVariable::Reference(new_reference_array(
+        &env,
+        STRING_CLASS.get().expect("must have init"),
+        &strings,
+    )?)))
+}
+
+// the indexes into the platform properties, as constants of SystemProps$Raw, and the properties
+// they are the defaults of
+const PLATFORM_PROPERTIES: [(&str, &str); 11] = [
+    ("_file_encoding_NDX", "file.encoding"),
+    ("_file_separator_NDX", "file.separator"),
+    ("_java_io_tmpdir_NDX", "java.io.tmpdir"),
+    ("_line_separator_NDX", "line.separator"),
+    ("_os_arch_NDX", "os.arch"),
+    ("_os_name_NDX", "os.name"),
+    ("_path_separator_NDX", "path.separator"),
+    ("_sun_jnu_encoding_NDX", "sun.jnu.encoding"),
+    ("_user_dir_NDX", "user.dir"),
+    ("_user_home_NDX", "user.home"),
+    ("_user_name_NDX", "user.name"),
+];
+
+// private static native String[] platformProperties();
+fn platform_properties(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let constant = |name: &str| {
+        let field = env
+            .class
+            .find_field_info(name, true)
+            .unwrap_or_else(|| panic!("SystemProps$Raw must have {name}"));
+        // SAFETY: the indexes are static final ints
+        unsafe { env.class.get_static_field(field.index).get_int() as usize }
+    };
+    // null where the platform defines nothing, the vm properties have the same values and take
+    // precedence anyway
+    let mut strings = vec![0; constant("FIXED_LENGTH")];
+    for (name, key) in PLATFORM_PROPERTIES {
+        if let Some(value) = SYSTEM_PROPERTIES.get(key) {
+            strings[constant(name)] = intern_string(&JavaStr::from_str(&value).into());
+        }
+    }
+    Ok(Some(NativeVariable::Reference(new_reference_array(
+        &env,
+        STRING_CLASS.get().expect("must have init"),
+        &strings,
+    )?)))
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/util/SystemProps$Raw".to_string(),
+            "platformProperties".to_string(),
+            vec![],
+        ),
+        platform_properties,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "jdk/internal/util/SystemProps$Raw".to_string(),
+            "vmProperties".to_string(),
+            vec![],
+        ),
+        vm_properties,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{
+        Thread,
+        global::HEAP,
+        native::{boot_vm, call_native, string::get_string},
+        structs::get_array_index,
+    };
+    use std::{collections::HashMap, sync::Arc};
+
+    #[test]
+    fn test_vm_properties() {
+        if !boot_vm() {
+            return;
+        }
+        let array = call_native(vm_properties, vec![]).get_ref();
+        let array = HEAP.read().unwrap().get(array);
+        let thread = Thread::new(0);
+        let env = NativeEnv {
+            args: vec![],
+            heap: &HEAP,
+            class: Arc::clone(STRING_CLASS.get().unwrap()),
+            caller: None,
+            thread_id: thread.thread_id(),
+            thread: &thread,
+        };
+        let strings: Vec<_> = (0..array.get_array_size(size_of::<u32>()))
+            .map(|i| get_string(&env, unsafe { get_array_index(array.as_ref(), i) }))
+            .collect();
+        let properties: HashMap<_, _> = strings
+            .chunks(2)
+            .map(|pair| (pair[0].as_str(), pair[1].as_str()))
+            .collect();
+
+        let line_separator = if cfg!(windows) { "\r\n" } else { "\n" };
+        assert_eq!(properties["line.separator"], line_separator);
+        if cfg!(target_os = "linux") {
+            assert_eq!(properties["os.name"], "Linux");
+        }
+        assert!(!properties["os.name"].is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    descriptor::FieldType,
+    runtime::{
+        NativeEnv, NativeResult, NativeVariable,
+        native::{NATIVE_FUNCTIONS, native_nop},
+    },
+};
+
+// private static native long getHandle(int d);
+fn get_handle(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    // handles are only used on windows
+    Ok(Some(NativeVariable::Long(-1)))
+}
+
+// private static native boolean getAppend(int fd);
+fn get_append(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    Ok(Some(NativeVariable::Boolean(false)))
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/io/FileDescriptor".to_string(),
+            "initIDs".to_string(),
+            vec![],
+        ),
+        native_nop,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/io/FileDescriptor".to_string(),
+            "getHandle".to_string(),
+            vec![FieldType::Int],
+        ),
+        get_handle,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/io/FileDescriptor".to_string(),
+            "getAppend".to_string(),
+            vec![FieldType::Int],
+        ),
+        get_append,
+    );
+}
@@ -0,0 +1,12 @@
+use crate::runtime::native::{NATIVE_FUNCTIONS, native_nop};
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/io/FileInputStream".to_string(),
+            "initIDs".to_string(),
+            vec![],
+        ),
+        native_nop,
+    );
+}
@@ -0,0 +1,129 @@
+use crate::{
+    descriptor::FieldType,
+    runtime::{
+        Exception, NativeEnv, NativeResult, NativeVariable,
+        famous_classes::{
+            INDEX_OUT_OF_BOUND_EXCEPTION_CLASS, IO_EXCEPTION_CLASS, NULL_POINTER_EXCEPTION_CLASS,
+        },
+        native::{NATIVE_FUNCTIONS, native_nop},
+    },
+};
+use std::{io::Write, slice};
+
+// reads `this.fd.fd`
+fn get_fd(env: &NativeEnv, this: u32) -> NativeResult<i32> {
+    let heap = env.heap.read().unwrap();
+    let this_obj = heap.get(this);
+    let fd_field = env
+        .class
+        .find_field_info("fd", false)
+        .expect("FileOutputStream must have fd");
+    // SAFETY: fd is a FileDescriptor
+    let fd_ref = unsafe { this_obj.get_field(fd_field.index).reference };
+    if fd_ref == 0 {
+        return Err(Exception::new_vm(
+            NULL_POINTER_EXCEPTION_CLASS.get().expect("must have init"),
+        ));
+    }
+    let fd_obj = heap.get(fd_ref);
+    let fd_field = fd_obj
+        .get_class()
+        .find_field_info("fd", false)
+        .expect("FileDescriptor must have fd");
+    // SAFETY: fd is an int
+    Ok(unsafe { fd_obj.get_field(fd_field.index).int })
+}
+
+fn write_fd(fd: i32, bytes: &[u8]) -> NativeResult<()> {
+    let result = match fd {
+        1 => std::io::stdout().write_all(bytes),
+        2 => std::io::stderr().write_all(bytes),
+        // TODO: support opened files
+        _ => {
+            return Err(Exception::new_vm_msg(
+                IO_EXCEPTION_CLASS.get().expect("must have init"),
+                "Stream Closed",
+            ));
+        }
+    };
+    result.map_err(|e| {
+        Exception::new_vm_msg(
+            IO_EXCEPTION_CLASS.get().expect("must have init"),
+            &e.to_string(),
+        )
+    })
+}
+
+// private native void write(int b, boolean append) throws IOException;
+fn write(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let this = env.args[0].get_ref();
+    let b = env.args[1].get_int();
+
+    let fd = get_fd(&env, this)?;
+    write_fd(fd, &[b as u8])?;
+    Ok(None)
+}
+
+// private native void writeBytes(byte b[], int off, int len, boolean append)
+//     throws IOException;
+fn write_bytes(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let this = env.args[0].get_ref();
+    let bytes_ref = env.args[1].get_ref();
+    let off = env.args[2].get_int();
+    let len = env.args[3].get_int();
+
+    if bytes_ref == 0 {
+        return Err(Exception::new_vm(
+            NULL_POINTER_EXCEPTION_CLASS.get().expect("must have init"),
+        ));
+    }
+    let bytes = env.heap.read().unwrap().get(bytes_ref);
+    let size = bytes.get_array_size(1);
+    if off < 0 || len < 0 || off as usize + len as usize > size {
+        return Err(Exception::new_vm(
+            INDEX_OUT_OF_BOUND_EXCEPTION_CLASS
+                .get()
+                .expect("must have init"),
+        ));
+    }
+
+    let fd = get_fd(&env, this)?;
+    // SAFETY: bytes is a byte array and the range is checked above
+    let bytes = unsafe {
+        slice::from_raw_parts(bytes.get_u8_array_const().add(off as usize), len as usize)
+    };
+    write_fd(fd, bytes)?;
+    Ok(None)
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/io/FileOutputStream".to_string(),
+            "initIDs".to_string(),
+            vec![],
+        ),
+        native_nop,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/io/FileOutputStream".to_string(),
+            "write".to_string(),
+            vec![FieldType::Int, FieldType::Boolean],
+        ),
+        write,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/io/FileOutputStream".to_string(),
+            "writeBytes".to_string(),
+            vec![
+                FieldType::Array(Box::new(FieldType::Byte)),
+                FieldType::Int,
+                FieldType::Int,
+                FieldType::Boolean,
+            ],
+        ),
+        write_bytes,
+    );
+}
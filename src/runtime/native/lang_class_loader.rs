@@ -0,0 +1,66 @@
+use crate::{
+    descriptor::FieldType,
+    runtime::{
+        NativeEnv, NativeResult, NativeVariable,
+        class_loader::get_class_object,
+        global::BOOTSTRAP_CLASS_LOADER,
+        native::{NATIVE_FUNCTIONS, string::get_string},
+        structs::get_array_index,
+    },
+};
+
+// static native Class<?> defineClass1(ClassLoader loader, String name, byte[] b, int off, int len,
+//                                     ProtectionDomain pd, String source);
+// there is only the bootstrap class loader, which defines the class whatever `loader` is
+fn define_class1(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let name = env.args[1].get_ref();
+    let bytes = env.args[2].get_ref();
+    let offset = env.args[3].get_int() as usize;
+    let length = env.args[4].get_int() as usize;
+
+    // ClassLoader.defineClass checked the name and the bounds
+    let class_name = get_string(&env, name).replace('.', "/");
+    let bytes = {
+        let bytes = env.heap.read().unwrap().get(bytes);
+        (offset..offset + length)
+            // SAFETY: b is a byte[]
+            .map(|i| unsafe { get_array_index::<i8, _>(bytes.as_ref(), i) } as u8)
+            .collect::<Vec<_>>()
+    };
+    let class = BOOTSTRAP_CLASS_LOADER
+        .get()
+        .unwrap()
+        .define_class_from_bytes(&class_name, &bytes)?;
+    Ok(Some(NativeVariable::Reference(get_class_object(class)?)))
+}
+
+fn native_class_loader_register_natives(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/ClassLoader".to_string(),
+            "defineClass1".to_string(),
+            vec![
+                FieldType::Object("java/lang/ClassLoader".to_string()),
+                FieldType::Object("java/lang/String".to_string()),
+                FieldType::Array(Box::new(FieldType::Byte)),
+                FieldType::Int,
+                FieldType::Int,
+                FieldType::Object("java/security/ProtectionDomain".to_string()),
+                FieldType::Object("java/lang/String".to_string()),
+            ],
+        ),
+        define_class1,
+    );
+    Ok(None)
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/ClassLoader".to_string(),
+            "registerNatives".to_string(),
+            vec![],
+        ),
+        native_class_loader_register_natives,
+    );
+}
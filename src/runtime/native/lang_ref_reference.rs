@@ -0,0 +1,99 @@
+use crate::{
+    descriptor::FieldType,
+    runtime::{
+        NativeEnv, NativeResult, NativeVariable, Variable, famous_classes::REFERENCE_CLASS,
+        native::NATIVE_FUNCTIONS,
+    },
+};
+use std::thread;
+
+// private native boolean refersTo0(Object o);
+fn refers_to0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let this = env.heap.read().unwrap().get(env.args[0].get_ref());
+    // SAFETY: Reference.referent is a reference
+    let referent = unsafe { this.get_field(referent_field_index()).reference };
+    Ok(Some(NativeVariable::Boolean(
+        referent == env.args[1].get_ref(),
+    )))
+}
+
+// private native void clear0();
+fn clear0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let this = env.heap.read().unwrap().get(env.args[0].get_ref());
+    // SAFETY: Reference.referent is a reference
+    unsafe {
+        this.put_field(referent_field_index(), Variable { reference: 0 });
+    }
+    Ok(None)
+}
+
+// private static native void waitForReferencePendingList();
+// references are only made pending by a garbage collector, which there is none of, so the
+// reference handler thread blocks here for good
+fn wait_for_reference_pending_list(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    loop {
+        thread::park();
+    }
+}
+
+// private static native Reference<?> getAndClearReferencePendingList();
+fn get_and_clear_reference_pending_list(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    Ok(Some(NativeVariable::Reference(0)))
+}
+
+// private static native boolean hasReferencePendingList();
+fn has_reference_pending_list(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    Ok(Some(NativeVariable::Boolean(false)))
+}
+
+fn referent_field_index() -> usize {
+    REFERENCE_CLASS
+        .get()
+        .expect("must have init")
+        .find_field_info("referent", false)
+        .expect("reference must have referent")
+        .index
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/ref/Reference".to_string(),
+            "refersTo0".to_string(),
+            vec![FieldType::Object("java/lang/Object".to_string())],
+        ),
+        refers_to0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/ref/Reference".to_string(),
+            "clear0".to_string(),
+            vec![],
+        ),
+        clear0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/ref/Reference".to_string(),
+            "waitForReferencePendingList".to_string(),
+            vec![],
+        ),
+        wait_for_reference_pending_list,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/ref/Reference".to_string(),
+            "getAndClearReferencePendingList".to_string(),
+            vec![],
+        ),
+        get_and_clear_reference_pending_list,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/ref/Reference".to_string(),
+            "hasReferencePendingList".to_string(),
+            vec![],
+        ),
+        has_reference_pending_list,
+    );
+}
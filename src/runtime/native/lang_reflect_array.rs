@@ -0,0 +1,349 @@
+use crate::{
+    descriptor::FieldType,
+    runtime::{
+        Class, Exception, NativeEnv, NativeResult, NativeVariable, Object, Thread,
+        class_loader::new_multi_array,
+        famous_classes::{ILLEGAL_ARGUMENT_EXCEPTION_CLASS, NULL_POINTER_EXCEPTION_CLASS},
+        global::BOOTSTRAP_CLASS_LOADER,
+        inheritance::{get_array_len, get_array_type, is_assignable_to},
+        native::{
+            NATIVE_FUNCTIONS, NativeFunction,
+            class::{field_type_class, get_class, is_primitive},
+            internal_reflect::{box_value, unbox, widen},
+        },
+        structs::{get_array_index, get_array_index_checked, put_array_index_checked},
+    },
+};
+use std::sync::Arc;
+
+// public static native int getLength(Object array) throws IllegalArgumentException;
+fn get_length(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let (array, _) = array_object(&env, env.args[0].get_ref())?;
+    let length = get_array_len(array.as_ref());
+    Ok(Some(NativeVariable::Int(length as _)))
+}
+
+// public static native Object get(Object array, int index)
+//     throws IllegalArgumentException, ArrayIndexOutOfBoundsException;
+fn get(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let element = get_element(&env)?;
+    let thread = Thread::new(0);
+    let boxed = box_value(&env, &thread, element)?;
+    Ok(Some(NativeVariable::Reference(boxed)))
+}
+
+/// `Array.getInt` and the like, reads the element widened to `target`
+fn get_primitive(env: NativeEnv, target: FieldType) -> NativeResult<Option<NativeVariable>> {
+    let element = get_element(&env)?;
+    let widened =
+        widen(element, &target).ok_or_else(|| illegal_argument("argument type mismatch"))?;
+    Ok(Some(widened))
+}
+
+// public static native boolean getBoolean(Object array, int index)
+fn get_boolean(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    get_primitive(env, FieldType::Boolean)
+}
+
+// public static native byte getByte(Object array, int index)
+fn get_byte(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    get_primitive(env, FieldType::Byte)
+}
+
+// public static native char getChar(Object array, int index)
+fn get_char(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    get_primitive(env, FieldType::Char)
+}
+
+// public static native short getShort(Object array, int index)
+fn get_short(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    get_primitive(env, FieldType::Short)
+}
+
+// public static native int getInt(Object array, int index)
+fn get_int(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    get_primitive(env, FieldType::Int)
+}
+
+// public static native long getLong(Object array, int index)
+fn get_long(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    get_primitive(env, FieldType::Long)
+}
+
+// public static native float getFloat(Object array, int index)
+fn get_float(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    get_primitive(env, FieldType::Float)
+}
+
+// public static native double getDouble(Object array, int index)
+fn get_double(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    get_primitive(env, FieldType::Double)
+}
+
+// public static native void set(Object array, int index, Object value)
+//     throws IllegalArgumentException, ArrayIndexOutOfBoundsException;
+fn set(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let value = env.args[2].get_ref();
+    let (array, element_type) = array_object(&env, env.args[0].get_ref())?;
+    let value = match &element_type {
+        FieldType::Object(_) | FieldType::Array(_) => {
+            if value != 0 {
+                let value_class = Arc::clone(env.heap.read().unwrap().get(value).get_class());
+                if !is_assignable_to(&value_class, &field_type_class(&element_type)?) {
+                    return Err(illegal_argument("array element type mismatch"));
+                }
+            }
+            NativeVariable::Reference(value)
+        }
+        _ => unbox(&env, value)
+            .and_then(|value| widen(value, &element_type))
+            .ok_or_else(|| illegal_argument("argument type mismatch"))?,
+    };
+    put_element(array.as_ref(), env.args[1].get_int(), value)?;
+    Ok(None)
+}
+
+// public static native void setInt(Object array, int index, int i) and the like, the value
+// is widened to the element type
+fn set_primitive(mut env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let value = env.args.pop().expect("must have value");
+    let (array, element_type) = array_object(&env, env.args[0].get_ref())?;
+    let value = match element_type {
+        FieldType::Object(_) | FieldType::Array(_) => None,
+        _ => widen(value, &element_type),
+    }
+    .ok_or_else(|| illegal_argument("argument type mismatch"))?;
+    put_element(array.as_ref(), env.args[1].get_int(), value)?;
+    Ok(None)
+}
+
+/// The object of the array `array` and its element type
+fn array_object(env: &NativeEnv, array: u32) -> NativeResult<(Arc<dyn Object>, FieldType)> {
+    if array == 0 {
+        return Err(Exception::new_vm(
+            NULL_POINTER_EXCEPTION_CLASS.get().expect("must have init"),
+        ));
+    }
+    let array = env.heap.read().unwrap().get(array);
+    let element_type = get_array_type(array.get_class())
+        .ok_or_else(|| illegal_argument("Argument is not an array"))?;
+    Ok((array, element_type))
+}
+
+/// Reads the element `args[1]` of the array `args[0]`
+fn get_element(env: &NativeEnv) -> NativeResult<NativeVariable> {
+    let (array, element_type) = array_object(env, env.args[0].get_ref())?;
+    let array = array.as_ref();
+    let index = env.args[1].get_int();
+    // SAFETY: the array is read as its element type
+    let element = unsafe {
+        match element_type {
+            FieldType::Boolean => {
+                NativeVariable::Boolean(get_array_index_checked::<i8, _>(array, index)? != 0)
+            }
+            FieldType::Byte => NativeVariable::Byte(get_array_index_checked(array, index)?),
+            FieldType::Char => NativeVariable::Char(get_array_index_checked(array, index)?),
+            FieldType::Short => NativeVariable::Short(get_array_index_checked(array, index)?),
+            FieldType::Int => NativeVariable::Int(get_array_index_checked(array, index)?),
+            FieldType::Long => NativeVariable::Long(get_array_index_checked(array, index)?),
+            FieldType::Float => NativeVariable::Float(get_array_index_checked(array, index)?),
+            FieldType::Double => NativeVariable::Double(get_array_index_checked(array, index)?),
+            FieldType::Object(_) | FieldType::Array(_) => {
+                NativeVariable::Reference(get_array_index_checked(array, index)?)
+            }
+        }
+    };
+    Ok(element)
+}
+
+/// Writes `value`, already converted to the element type of `array`, to the element `index`
+pub(super) fn put_element(array: &dyn Object, index: i32, value: NativeVariable) -> NativeResult<()> {
+    // SAFETY: value is of the element type of the array
+    unsafe {
+        match value {
+            NativeVariable::Boolean(b) => put_array_index_checked(array, index, b as i8),
+            NativeVariable::Byte(b) => put_array_index_checked(array, index, b),
+            NativeVariable::Char(c) => put_array_index_checked(array, index, c),
+            NativeVariable::Short(s) => put_array_index_checked(array, index, s),
+            NativeVariable::Int(i) => put_array_index_checked(array, index, i),
+            NativeVariable::Long(l) => put_array_index_checked(array, index, l),
+            NativeVariable::Float(f) => put_array_index_checked(array, index, f),
+            NativeVariable::Double(d) => put_array_index_checked(array, index, d),
+            NativeVariable::Reference(r) => put_array_index_checked(array, index, r),
+        }
+    }
+}
+
+// private static native Object newArray(Class<?> componentType, int length)
+//     throws NegativeArraySizeException;
+fn new_array(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let length = env.args[1].get_int();
+    let array_class = array_class_of(&env, env.args[0].get_ref(), 1)?;
+    let array = new_multi_array(env.heap, &array_class.class_name, &[length])?;
+    Ok(Some(NativeVariable::Reference(array)))
+}
+
+// private static native Object multiNewArray(Class<?> componentType, int[] dimensions)
+//     throws IllegalArgumentException, NegativeArraySizeException;
+fn multi_new_array(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let dimensions = env.args[1].get_ref();
+    if dimensions == 0 {
+        return Err(Exception::new_vm(
+            NULL_POINTER_EXCEPTION_CLASS.get().expect("must have init"),
+        ));
+    }
+    let dims: Vec<i32> = {
+        let dimensions = env.heap.read().unwrap().get(dimensions);
+        (0..get_array_len(dimensions.as_ref()))
+            // SAFETY: dimensions is an int[]
+            .map(|i| unsafe { get_array_index(dimensions.as_ref(), i) })
+            .collect()
+    };
+    if dims.is_empty() {
+        return Err(illegal_argument("Empty dimensions array"));
+    }
+    let array_class = array_class_of(&env, env.args[0].get_ref(), dims.len())?;
+    let array = new_multi_array(env.heap, &array_class.class_name, &dims)?;
+    Ok(Some(NativeVariable::Reference(array)))
+}
+
+/// The array class of `dimensions` more dimensions than the class object `component_type`
+fn array_class_of(
+    env: &NativeEnv,
+    component_type: u32,
+    dimensions: usize,
+) -> NativeResult<Arc<Class>> {
+    if component_type == 0 {
+        return Err(Exception::new_vm(
+            NULL_POINTER_EXCEPTION_CLASS.get().expect("must have init"),
+        ));
+    }
+    let component = get_class(env, component_type);
+    // as `multianewarray`, array classes have at most 255 dimensions
+    let component_dimensions = component
+        .class_name
+        .bytes()
+        .take_while(|&c| c == b'[')
+        .count();
+    if component_dimensions + dimensions > 255 {
+        return Err(illegal_argument("Dimension limit exceeded"));
+    }
+
+    let loader = BOOTSTRAP_CLASS_LOADER.get().unwrap();
+    let mut array_class = if is_primitive(&component) {
+        let element_type = match component.class_name() {
+            "boolean" => FieldType::Boolean,
+            "byte" => FieldType::Byte,
+            "char" => FieldType::Char,
+            "short" => FieldType::Short,
+            "int" => FieldType::Int,
+            "long" => FieldType::Long,
+            "float" => FieldType::Float,
+            "double" => FieldType::Double,
+            // void.class
+            _ => {
+                return Err(Exception::new_vm(
+                    ILLEGAL_ARGUMENT_EXCEPTION_CLASS
+                        .get()
+                        .expect("must have init"),
+                ));
+            }
+        };
+        loader.resolve_primitive_array_class(&element_type)?
+    } else {
+        loader.resolve_object_array_class(&component)?
+    };
+    for _ in 1..dimensions {
+        array_class = loader.resolve_object_array_class(&array_class)?;
+    }
+    Ok(array_class)
+}
+
+fn illegal_argument(message: &str) -> Exception {
+    Exception::new_vm_msg(
+        ILLEGAL_ARGUMENT_EXCEPTION_CLASS
+            .get()
+            .expect("must have init"),
+        message,
+    )
+}
+
+pub(super) fn register_natives() {
+    let getters: [(&str, NativeFunction); 10] = [
+        ("getLength", get_length),
+        ("get", get),
+        ("getBoolean", get_boolean),
+        ("getByte", get_byte),
+        ("getChar", get_char),
+        ("getShort", get_short),
+        ("getInt", get_int),
+        ("getLong", get_long),
+        ("getFloat", get_float),
+        ("getDouble", get_double),
+    ];
+    for (name, function) in getters {
+        let mut params = vec![FieldType::Object("java/lang/Object".to_string())];
+        if name != "getLength" {
+            params.push(FieldType::Int);
+        }
+        NATIVE_FUNCTIONS.insert(
+            (
+                "java/lang/reflect/Array".to_string(),
+                name.to_string(),
+                params,
+            ),
+            function,
+        );
+    }
+    let setters: [(&str, FieldType, NativeFunction); 9] = [
+        (
+            "set",
+            FieldType::Object("java/lang/Object".to_string()),
+            set,
+        ),
+        ("setBoolean", FieldType::Boolean, set_primitive),
+        ("setByte", FieldType::Byte, set_primitive),
+        ("setChar", FieldType::Char, set_primitive),
+        ("setShort", FieldType::Short, set_primitive),
+        ("setInt", FieldType::Int, set_primitive),
+        ("setLong", FieldType::Long, set_primitive),
+        ("setFloat", FieldType::Float, set_primitive),
+        ("setDouble", FieldType::Double, set_primitive),
+    ];
+    for (name, value_type, function) in setters {
+        NATIVE_FUNCTIONS.insert(
+            (
+                "java/lang/reflect/Array".to_string(),
+                name.to_string(),
+                vec![
+                    FieldType::Object("java/lang/Object".to_string()),
+                    FieldType::Int,
+                    value_type,
+                ],
+            ),
+            function,
+        );
+    }
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/reflect/Array".to_string(),
+            "newArray".to_string(),
+            vec![
+                FieldType::Object("java/lang/Class".to_string()),
+                FieldType::Int,
+            ],
+        ),
+        new_array,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/reflect/Array".to_string(),
+            "multiNewArray".to_string(),
+            vec![
+                FieldType::Object("java/lang/Class".to_string()),
+                FieldType::Array(Box::new(FieldType::Int)),
+            ],
+        ),
+        multi_new_array,
+    );
+}
@@ -0,0 +1,48 @@
+use crate::runtime::{NativeEnv, NativeResult, NativeVariable, native::NATIVE_FUNCTIONS};
+use std::{num::NonZero, thread};
+
+// public native int availableProcessors();
+fn available_processors(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let processors = thread::available_parallelism().map_or(1, NonZero::get);
+    Ok(Some(NativeVariable::Int(processors as i32)))
+}
+
+// public native long maxMemory();
+fn max_memory(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    // the heap grows without a limit
+    Ok(Some(NativeVariable::Long(i64::MAX)))
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Runtime".to_string(),
+            "availableProcessors".to_string(),
+            vec![],
+        ),
+        available_processors,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Runtime".to_string(),
+            "maxMemory".to_string(),
+            vec![],
+        ),
+        max_memory,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::native::{boot_vm, call_native};
+
+    #[test]
+    fn test_available_processors() {
+        if !boot_vm() {
+            return;
+        }
+        let processors = call_native(available_processors, vec![NativeVariable::Reference(0)]);
+        assert!(processors.get_int() >= 1);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{
+    descriptor::FieldType,
+    runtime::{Exception, NativeEnv, NativeResult, NativeVariable, native::NATIVE_FUNCTIONS},
+};
+
+// static native void beforeHalt();
+fn before_halt(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    Ok(None)
+}
+
+// static native void halt0(int status);
+// the shutdown hooks have already run in Shutdown.exit, hooks added with Runtime.addShutdownHook
+// are started as threads, which are not supported yet
+fn halt0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    Err(Exception::Exit(env.args[0].get_int()))
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Shutdown".to_string(),
+            "beforeHalt".to_string(),
+            vec![],
+        ),
+        before_halt,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Shutdown".to_string(),
+            "halt0".to_string(),
+            vec![FieldType::Int],
+        ),
+        halt0,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::native::{boot_vm, try_call_native};
+
+    #[test]
+    fn test_halt0() {
+        if !boot_vm() {
+            return;
+        }
+        let result = try_call_native(halt0, vec![NativeVariable::Int(3)]);
+        assert!(matches!(result, Err(Exception::Exit(3))));
+    }
+}
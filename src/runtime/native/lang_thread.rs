@@ -0,0 +1,361 @@
+use crate::{
+    class::JavaStr,
+    descriptor::FieldType,
+    runtime::{
+        DEFAULT_MAX_FRAME_SIZE, Exception, NativeEnv, NativeResult, NativeVariable, Thread,
+        Variable, VmEnv,
+        class_loader::{initialize_class, new_instance},
+        famous_classes::THREAD_CLASS,
+        global::{
+            BOOTSTRAP_CLASS_LOADER, MAIN_THREAD_OBJECT, NON_DAEMON_THREADS, THREAD_OBJECTS, VERBOSE,
+        },
+        heap::Heap,
+        native::{
+            NATIVE_FUNCTIONS, get_string_chars, internal_reflect::select_virtual_method,
+            native_nop, new_string,
+        },
+        structs::Object,
+    },
+};
+use std::{
+    process,
+    sync::{Arc, RwLock, atomic::Ordering},
+    thread,
+};
+
+// Thread.NORM_PRIORITY
+const NORM_PRIORITY: i32 = 5;
+// JVMTI_THREAD_STATE_ALIVE
+const THREAD_STATUS_ALIVE: i32 = 0x0001;
+// JVMTI_THREAD_STATE_ALIVE | JVMTI_THREAD_STATE_RUNNABLE, `Thread.getState` maps it to RUNNABLE
+const THREAD_STATUS_RUNNABLE: i32 = 0x0005;
+// JVMTI_THREAD_STATE_TERMINATED
+const THREAD_STATUS_TERMINATED: i32 = 0x0002;
+
+// public static native Thread currentThread();
+fn current_thread(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    if let Some(thread) = THREAD_OBJECTS.get(&env.thread_id) {
+        return Ok(Some(NativeVariable::Reference(*thread)));
+    }
+    // threads other than main are attached to the main thread group, like hotspot does
+    let main_thread = *MAIN_THREAD_OBJECT.get().expect("must have init");
+    let main_thread = env.heap.read().unwrap().get(main_thread);
+    // SAFETY: Thread.group is a reference
+    let group = unsafe { main_thread.get_field(thread_field_index("group")).reference };
+    let thread = new_thread_object(env.heap, None, group)?;
+    let thread = *THREAD_OBJECTS.entry(env.thread_id).or_insert(thread);
+    Ok(Some(NativeVariable::Reference(thread)))
+}
+
+// public final native boolean isAlive();
+fn is_alive(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let this = env.heap.read().unwrap().get(env.args[0].get_ref());
+    // SAFETY: Thread.threadStatus is an int
+    let status = unsafe { this.get_field(thread_field_index("threadStatus")).get_int() };
+    Ok(Some(NativeVariable::Boolean(
+        status & THREAD_STATUS_ALIVE != 0,
+    )))
+}
+
+// private native void start0();
+fn start0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let this = env.args[0].get_ref();
+    let obj = env.heap.read().unwrap().get(this);
+    let stack_size_index = thread_field_index("stackSize");
+    // SAFETY: Thread.stackSize is a long, Thread.name a string and Thread.daemon a boolean
+    let (stack_size, name, daemon) = unsafe {
+        (
+            Variable::get_long(
+                obj.get_field(stack_size_index),
+                obj.get_field(stack_size_index + 1),
+            ),
+            obj.get_field(thread_field_index("name")).reference,
+            obj.get_field(thread_field_index("daemon")).int != 0,
+        )
+    };
+    let name = String::from_utf16_lossy(&get_string_chars(env.heap, name));
+
+    let thread = Thread::new(max_frame_size(stack_size));
+    // alive before start returns, however late the new thread runs
+    THREAD_OBJECTS.insert(thread.thread_id(), this);
+    set_thread_status(&*obj, THREAD_STATUS_RUNNABLE, thread.thread_id() as i64);
+    if VERBOSE.load(Ordering::Relaxed) {
+        println!("start thread {name} as thread {}", thread.thread_id());
+    }
+
+    if !daemon {
+        *NON_DAEMON_THREADS.0.lock().unwrap() += 1;
+    }
+    let heap = env.heap;
+    let spawned = thread::Builder::new().name(name).spawn(move || {
+        run_thread(heap, thread, this);
+        if !daemon {
+            *NON_DAEMON_THREADS.0.lock().unwrap() -= 1;
+            NON_DAEMON_THREADS.1.notify_all();
+        }
+    });
+    if let Err(error) = spawned {
+        if !daemon {
+            *NON_DAEMON_THREADS.0.lock().unwrap() -= 1;
+        }
+        set_thread_status(&*obj, 0, 0);
+        let out_of_memory_error = BOOTSTRAP_CLASS_LOADER
+            .get()
+            .expect("must have init")
+            .resolve_class("java/lang/OutOfMemoryError")?;
+        return Err(Exception::new_vm_msg(
+            &out_of_memory_error,
+            &format!("unable to create native thread: {error}"),
+        ));
+    }
+    Ok(None)
+}
+
+/// Runs `Thread.run` of the started thread object `this`, then lets the threads joining it go,
+/// like hotspot does.
+fn run_thread(heap: &'static RwLock<Heap>, thread: Thread<'static>, this: u32) {
+    let thread_class = THREAD_CLASS.get().expect("must have init");
+    let run = thread_class
+        .resolve_method(&JavaStr::from_str("run"), &[])
+        .expect("thread must have run");
+    let class = Arc::clone(heap.read().unwrap().get(this).get_class());
+    let result = select_virtual_method(&class, run).and_then(|(class, index)| {
+        thread.invoke_method(
+            Arc::clone(&class),
+            &class.methods[index],
+            &[NativeVariable::Reference(this)],
+        )
+    });
+    let result = match result {
+        Err(Exception::Exit(status)) => process::exit(status),
+        Err(exception) => dispatch_uncaught_exception(&thread, this, exception),
+        Ok(_) => Ok(()),
+    };
+    // Thread.exit removes it from its group
+    let result = result.and_then(|()| {
+        let exit = thread_class
+            .resolve_method(&JavaStr::from_str("exit"), &[])
+            .expect("thread must have exit");
+        thread.invoke_method(
+            Arc::clone(thread_class),
+            exit,
+            &[NativeVariable::Reference(this)],
+        )
+    });
+    if let Err(Exception::Exit(status)) = result {
+        process::exit(status);
+    }
+
+    // `join` waits on the thread object until it is not alive
+    let obj = heap.read().unwrap().get(this);
+    let monitor = obj.get_monitor();
+    monitor.enter();
+    set_thread_status(&*obj, THREAD_STATUS_TERMINATED, 0);
+    monitor.notify(true);
+    // SAFETY: entered above
+    unsafe { monitor.exit() };
+    THREAD_OBJECTS.remove(&thread.thread_id());
+}
+
+// like hotspot, exceptions thrown by the handler are ignored
+fn dispatch_uncaught_exception(
+    thread: &Thread,
+    this: u32,
+    exception: Exception,
+) -> NativeResult<()> {
+    let throwable = match exception {
+        Exception::VmException {
+            exception_type,
+            message,
+        } => thread.new_exception_object(&exception_type, &message)?,
+        Exception::UserException(throwable) => throwable,
+        Exception::Exit(_) => unreachable!("exit is not caught"),
+    };
+    let thread_class = THREAD_CLASS.get().expect("must have init");
+    let dispatch = thread_class
+        .resolve_method(
+            &JavaStr::from_str("dispatchUncaughtException"),
+            &[FieldType::Object("java/lang/Throwable".to_string())],
+        )
+        .expect("thread must have dispatchUncaughtException");
+    match thread.invoke_method(
+        Arc::clone(thread_class),
+        dispatch,
+        &[
+            NativeVariable::Reference(this),
+            NativeVariable::Reference(throwable),
+        ],
+    ) {
+        Err(exit @ Exception::Exit(_)) => Err(exit),
+        _ => Ok(()),
+    }
+}
+
+// `eetop` is the id of the runtime thread rather than hotspot's `JavaThread`, 0 when not running
+fn set_thread_status(obj: &dyn Object, status: i32, eetop: i64) {
+    let (eetop_upper, eetop_lower) = Variable::put_long(eetop);
+    // SAFETY: Thread.threadStatus is an int, Thread.eetop a long
+    unsafe {
+        obj.put_field(thread_field_index("threadStatus"), Variable { int: status });
+        obj.put_field(thread_field_index("eetop"), eetop_upper);
+        obj.put_field(thread_field_index("eetop") + 1, eetop_lower);
+    }
+}
+
+/// The `max_frame_size` of `Thread::new` for a thread created with a `stackSize` of so many bytes,
+/// which is ignored when not positive, like hotspot does.
+pub(in crate::runtime) fn max_frame_size(stack_size: i64) -> usize {
+    match usize::try_from(stack_size) {
+        Ok(stack_size) if stack_size > 0 => stack_size.div_ceil(size_of::<Variable>()),
+        _ => DEFAULT_MAX_FRAME_SIZE,
+    }
+}
+
+fn thread_field_index(name: &str) -> usize {
+    THREAD_CLASS
+        .get()
+        .expect("must have init")
+        .find_field_info(name, false)
+        .unwrap_or_else(|| panic!("thread must have field {name}"))
+        .index
+}
+
+/// Creates the `system` thread group, the `main` group in it, and the running `main` thread in
+/// the group, as hotspot does before running any java code.
+pub(in crate::runtime) fn new_main_thread_object(heap: &'static RwLock<Heap>) -> NativeResult<u32> {
+    let thread = Thread::new(0);
+    let thread_group_class = BOOTSTRAP_CLASS_LOADER
+        .get()
+        .expect("must have init")
+        .resolve_class("java/lang/ThreadGroup")?;
+    initialize_class(&VmEnv::new(&thread, heap), &thread_group_class)?;
+    let new_thread_group = |parameters: &[FieldType], args: &[NativeVariable]| {
+        let constructor = thread_group_class
+            .resolve_method(&JavaStr::from_str("<init>"), parameters)
+            .expect("thread group must have constructor");
+        let group = new_instance(&thread_group_class);
+        let args = [&[NativeVariable::Reference(group)], args].concat();
+        thread
+            .invoke_method(Arc::clone(&thread_group_class), constructor, &args)
+            .map(|_| group)
+    };
+
+    let system_group = new_thread_group(&[], &[])?;
+    let name = new_string(heap, &"main".encode_utf16().collect::<Vec<_>>());
+    let main_group = new_thread_group(
+        &[
+            FieldType::Object("java/lang/ThreadGroup".to_string()),
+            FieldType::Object("java/lang/String".to_string()),
+        ],
+        &[
+            NativeVariable::Reference(system_group),
+            NativeVariable::Reference(name),
+        ],
+    )?;
+    new_thread_object(heap, Some("main"), main_group)
+}
+
+/// Creates a `java.lang.Thread` in `group` that is already running, named `name` or `Thread-n`
+/// like the threads created without a name.
+///
+/// The constructor is not run, as it asks for the thread creating it.
+fn new_thread_object(
+    heap: &'static RwLock<Heap>,
+    name: Option<&str>,
+    group: u32,
+) -> NativeResult<u32> {
+    let thread_class = THREAD_CLASS.get().expect("must have init");
+    let thread = Thread::new(0);
+    initialize_class(&VmEnv::new(&thread, heap), thread_class)?;
+    // numbers are taken from the same sequences as the threads created in java
+    let next_number = |method_name: &str| {
+        let method = thread_class
+            .resolve_method(&JavaStr::from_str(method_name), &[])
+            .unwrap_or_else(|| panic!("thread must have {method_name}"));
+        thread
+            .invoke_method(Arc::clone(thread_class), method, &[])
+            .map(|number| number.expect("must return number"))
+    };
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => format!("Thread-{}", next_number("nextThreadNum")?.get_int()),
+    };
+    let tid = next_number("nextThreadID")?.get_long();
+
+    let name = new_string(heap, &name.encode_utf16().collect::<Vec<_>>());
+    let thread_object = new_instance(thread_class);
+    let obj = heap.read().unwrap().get(thread_object);
+    let (tid_upper, tid_lower) = Variable::put_long(tid);
+    // SAFETY: obj is newly allocated and not yet visible to other threads, the fields are of the
+    // types put
+    unsafe {
+        obj.put_field(thread_field_index("name"), Variable { reference: name });
+        obj.put_field(thread_field_index("group"), Variable { reference: group });
+        obj.put_field(thread_field_index("tid"), tid_upper);
+        obj.put_field(thread_field_index("tid") + 1, tid_lower);
+        obj.put_field(
+            thread_field_index("priority"),
+            Variable { int: NORM_PRIORITY },
+        );
+        obj.put_field(
+            thread_field_index("threadStatus"),
+            Variable {
+                int: THREAD_STATUS_RUNNABLE,
+            },
+        );
+    }
+    Ok(thread_object)
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Thread".to_string(),
+            "registerNatives".to_string(),
+            vec![],
+        ),
+        native_nop,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Thread".to_string(),
+            "currentThread".to_string(),
+            vec![],
+        ),
+        current_thread,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Thread".to_string(),
+            "isAlive".to_string(),
+            vec![],
+        ),
+        is_alive,
+    );
+    NATIVE_FUNCTIONS.insert(
+        ("java/lang/Thread".to_string(), "start0".to_string(), vec![]),
+        start0,
+    );
+    // priorities are left to the os
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Thread".to_string(),
+            "setPriority0".to_string(),
+            vec![FieldType::Int],
+        ),
+        native_nop,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_frame_size() {
+        assert_eq!(max_frame_size(0), DEFAULT_MAX_FRAME_SIZE);
+        assert_eq!(max_frame_size(-1), DEFAULT_MAX_FRAME_SIZE);
+        assert_eq!(max_frame_size(4096), 4096 / size_of::<Variable>());
+        assert_eq!(max_frame_size(4097), 4096 / size_of::<Variable>() + 1);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    descriptor::FieldType,
+    runtime::{
+        NativeEnv, NativeResult, NativeVariable,
+        native::{NATIVE_FUNCTIONS, NativeFunction},
+    },
+};
+
+// results come from the platform libm, which is not guaranteed to be bit-for-bit fdlibm
+fn unary(env: NativeEnv, f: fn(f64) -> f64) -> NativeResult<Option<NativeVariable>> {
+    let a = env.args[0].get_double();
+    Ok(Some(NativeVariable::Double(f(a))))
+}
+
+fn binary(env: NativeEnv, f: fn(f64, f64) -> f64) -> NativeResult<Option<NativeVariable>> {
+    let a = env.args[0].get_double();
+    let b = env.args[1].get_double();
+    Ok(Some(NativeVariable::Double(f(a, b))))
+}
+
+// public static native double sin(double a);
+fn sin(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::sin)
+}
+
+// public static native double cos(double a);
+fn cos(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::cos)
+}
+
+// public static native double tan(double a);
+fn tan(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::tan)
+}
+
+// public static native double asin(double a);
+fn asin(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::asin)
+}
+
+// public static native double acos(double a);
+fn acos(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::acos)
+}
+
+// public static native double atan(double a);
+fn atan(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::atan)
+}
+
+// public static native double log(double a);
+fn log(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::ln)
+}
+
+// public static native double log10(double a);
+fn log10(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::log10)
+}
+
+// public static native double sqrt(double a);
+fn sqrt(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::sqrt)
+}
+
+// public static native double IEEEremainder(double f1, double f2);
+fn ieee_remainder(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    binary(env, remainder)
+}
+
+// public static native double atan2(double y, double x);
+fn atan2(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    binary(env, f64::atan2)
+}
+
+// public static native double sinh(double x);
+fn sinh(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::sinh)
+}
+
+// public static native double cosh(double x);
+fn cosh(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::cosh)
+}
+
+// public static native double tanh(double x);
+fn tanh(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::tanh)
+}
+
+// public static native double expm1(double x);
+fn expm1(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::exp_m1)
+}
+
+// public static native double log1p(double x);
+fn log1p(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    unary(env, f64::ln_1p)
+}
+
+// IEEE 754 remainder: x - n * y where n is x / y rounded to the nearest integer, ties to even
+fn remainder(x: f64, y: f64) -> f64 {
+    if x.is_nan() || y.is_nan() || x.is_infinite() || y == 0.0 {
+        return f64::NAN;
+    }
+    if y.is_infinite() {
+        return x;
+    }
+    let y = y.abs();
+    // `%` is exact, so the parity of the quotient can be read from the remainder of 2y
+    let mut r = (x % (2.0 * y)).abs();
+    let odd = r >= y;
+    if odd {
+        r -= y;
+    }
+    if 2.0 * r > y || (2.0 * r == y && odd) {
+        r -= y;
+    }
+    if x.is_sign_negative() { -r } else { r }
+}
+
+pub(super) fn register_natives() {
+    let natives: [(&str, usize, NativeFunction); 16] = [
+        ("sin", 1, sin),
+        ("cos", 1, cos),
+        ("tan", 1, tan),
+        ("asin", 1, asin),
+        ("acos", 1, acos),
+        ("atan", 1, atan),
+        ("log", 1, log),
+        ("log10", 1, log10),
+        ("sqrt", 1, sqrt),
+        ("IEEEremainder", 2, ieee_remainder),
+        ("atan2", 2, atan2),
+        ("sinh", 1, sinh),
+        ("cosh", 1, cosh),
+        ("tanh", 1, tanh),
+        ("expm1", 1, expm1),
+        ("log1p", 1, log1p),
+    ];
+    for (name, param_count, function) in natives {
+        NATIVE_FUNCTIONS.insert(
+            (
+                "java/lang/StrictMath".to_string(),
+                name.to_string(),
+                vec![FieldType::Double; param_count],
+            ),
+            function,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::native::call_native;
+    use std::f64::consts::{E, FRAC_PI_2, FRAC_PI_4, PI};
+
+    fn call(function: NativeFunction, args: &[f64]) -> f64 {
+        let args = args.iter().map(|&a| NativeVariable::Double(a)).collect();
+        call_native(function, args).get_double()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-12,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_known_values() {
+        assert_close(call(sin, &[FRAC_PI_2]), 1.0);
+        assert_close(call(cos, &[PI]), -1.0);
+        assert_close(call(tan, &[FRAC_PI_4]), 1.0);
+        assert_close(call(asin, &[1.0]), FRAC_PI_2);
+        assert_close(call(acos, &[-1.0]), PI);
+        assert_close(call(atan, &[1.0]), FRAC_PI_4);
+        assert_close(call(log, &[E]), 1.0);
+        assert_close(call(log10, &[1000.0]), 3.0);
+        assert_close(call(sqrt, &[2.0]), std::f64::consts::SQRT_2);
+        assert_close(call(atan2, &[1.0, -1.0]), 3.0 * FRAC_PI_4);
+        assert_close(call(sinh, &[1.0]), (E - 1.0 / E) / 2.0);
+        assert_close(call(cosh, &[1.0]), (E + 1.0 / E) / 2.0);
+        assert_close(call(tanh, &[0.5]), 0.46211715726000974);
+        assert_close(call(expm1, &[1e-10]), 1.00000000005e-10);
+        assert_close(call(log1p, &[E - 1.0]), 1.0);
+        assert!(call(sqrt, &[-1.0]).is_nan());
+    }
+
+    #[test]
+    fn test_ieee_remainder() {
+        assert_eq!(call(ieee_remainder, &[5.0, 3.0]), -1.0);
+        assert_eq!(call(ieee_remainder, &[-5.0, 3.0]), 1.0);
+        // ties round the quotient to even
+        assert_eq!(call(ieee_remainder, &[4.5, 3.0]), -1.5);
+        assert_eq!(call(ieee_remainder, &[7.5, 3.0]), 1.5);
+        assert_eq!(call(ieee_remainder, &[3.0, f64::INFINITY]), 3.0);
+        assert!(call(ieee_remainder, &[-6.0, 3.0]).is_sign_negative());
+        assert!(call(ieee_remainder, &[1.0, 0.0]).is_nan());
+        assert!(call(ieee_remainder, &[f64::INFINITY, 1.0]).is_nan());
+    }
+}
@@ -1,9 +1,16 @@
-use crate::runtime::{
-    Exception, NativeEnv, NativeResult, NativeVariable,
-    famous_classes::{CLONE_NOT_SUPPORTED_EXCEPTION_CLASS, CLONEABLE_CLASS},
-    inheritance::is_class_implements,
-    native::NATIVE_FUNCTIONS,
+use crate::{
+    descriptor::FieldType,
+    runtime::{
+        Exception, NativeEnv, NativeResult, NativeVariable,
+        class_loader::get_class_object,
+        famous_classes::{
+            CLONE_NOT_SUPPORTED_EXCEPTION_CLASS, CLONEABLE_CLASS, ILLEGAL_ARGUMENT_EXCEPTION_CLASS,
+        },
+        inheritance::is_class_implements,
+        native::NATIVE_FUNCTIONS,
+    },
 };
+use std::{sync::Arc, time::Duration};
 
 // public native int hashCode();
 pub(super) fn native_object_hash_code(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
@@ -38,6 +45,49 @@ fn native_object_clone(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
     Ok(Some(NativeVariable::Reference(cloned)))
 }
 
+// public final native Class<?> getClass();
+fn native_object_get_class(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let this = env.args[0].get_ref();
+    let class = Arc::clone(env.heap.read().unwrap().get(this).get_class());
+    Ok(Some(NativeVariable::Reference(get_class_object(class)?)))
+}
+
+// public final native void notify();
+// public final native void notifyAll();
+// the owner is not checked yet, no IllegalMonitorStateException is thrown
+fn native_object_notify(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let object = env.heap.read().unwrap().get(env.args[0].get_ref());
+    object.get_monitor().notify(false);
+    Ok(None)
+}
+
+fn native_object_notify_all(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let object = env.heap.read().unwrap().get(env.args[0].get_ref());
+    object.get_monitor().notify(true);
+    Ok(None)
+}
+
+// public final native void wait(long timeoutMillis) throws InterruptedException;
+// `wait()` and `wait(long, int)` call this one. threads cannot be interrupted yet, so it never
+// throws InterruptedException
+fn native_object_wait(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let timeout = env.args[1].get_long();
+    if timeout < 0 {
+        return Err(Exception::new_vm_msg(
+            ILLEGAL_ARGUMENT_EXCEPTION_CLASS
+                .get()
+                .expect("must have init"),
+            "timeout value is negative",
+        ));
+    }
+    let object = env.heap.read().unwrap().get(env.args[0].get_ref());
+    // 0 waits until notified
+    object
+        .get_monitor()
+        .wait((timeout > 0).then(|| Duration::from_millis(timeout as u64)));
+    Ok(None)
+}
+
 pub(super) fn register_natives() {
     NATIVE_FUNCTIONS.insert(
         (
@@ -51,4 +101,32 @@ pub(super) fn register_natives() {
         ("java/lang/Object".to_string(), "clone".to_string(), vec![]),
         native_object_clone,
     );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Object".to_string(),
+            "getClass".to_string(),
+            vec![],
+        ),
+        native_object_get_class,
+    );
+    NATIVE_FUNCTIONS.insert(
+        ("java/lang/Object".to_string(), "notify".to_string(), vec![]),
+        native_object_notify,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Object".to_string(),
+            "notifyAll".to_string(),
+            vec![],
+        ),
+        native_object_notify_all,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Object".to_string(),
+            "wait".to_string(),
+            vec![FieldType::Long],
+        ),
+        native_object_wait,
+    );
 }
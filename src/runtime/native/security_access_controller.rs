@@ -0,0 +1,19 @@
+use crate::runtime::{NativeEnv, NativeResult, NativeVariable, native::NATIVE_FUNCTIONS};
+
+// private static native AccessControlContext getStackAccessControlContext();
+fn get_stack_access_control_context(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    // every class is loaded by the bootstrap class loader, whose protection domain is null, and
+    // hotspot returns null when the stack holds only such classes
+    Ok(Some(NativeVariable::Reference(0)))
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/security/AccessController".to_string(),
+            "getStackAccessControlContext".to_string(),
+            vec![],
+        ),
+        get_stack_access_control_context,
+    );
+}
@@ -1,4 +1,108 @@
-use crate::runtime::{NativeEnv, NativeResult, NativeVariable, native::NATIVE_FUNCTIONS};
+use crate::runtime::{
+    NativeEnv, NativeResult, NativeVariable, Variable,
+    class_loader::{new_array, new_instance},
+    famous_classes::{BYTE_ARRAY_CLASS, STRING_CLASS},
+    heap::Heap,
+    interpreter::global::STRING_TABLE,
+    native::NATIVE_FUNCTIONS,
+    structs::{get_array_index, put_array_index},
+};
+use std::sync::{Arc, RwLock};
+
+/// Reads the `value` array of the `java.lang.String` `string`, its bytes, and whether they are
+/// UTF16
+fn get_string_value(heap: &RwLock<Heap>, string: u32) -> (u32, Vec<u8>, bool) {
+    let heap = heap.read().unwrap();
+    let string = heap.get(string);
+    let get = |name: &str| {
+        let field = string
+            .get_class()
+            .find_field_info(name, false)
+            .expect("string must have field");
+        // SAFETY: string is a String
+        unsafe { string.get_field(field.index) }
+    };
+    // SAFETY: String.value is a byte[] and String.coder is a byte
+    let (value, coder) = unsafe { (get("value").reference, get("coder").int) };
+
+    let value_obj = heap.get(value);
+    let bytes = (0..value_obj.get_array_size(size_of::<u8>()))
+        // SAFETY: value is a byte[]
+        .map(|i| unsafe { get_array_index::<i8, _>(value_obj.as_ref(), i) } as u8)
+        .collect();
+    (value, bytes, coder != 0)
+}
+
+/// Reads the contents of the `java.lang.String` `string`
+pub(super) fn get_string(env: &NativeEnv, string: u32) -> String {
+    String::from_utf16_lossy(&get_string_chars(env.heap, string))
+}
+
+/// Reads the UTF16 chars of the `java.lang.String` `string`
+pub(in crate::runtime) fn get_string_chars(heap: &RwLock<Heap>, string: u32) -> Vec<u16> {
+    let (_, bytes, utf16) = get_string_value(heap, string);
+    if !utf16 {
+        // LATIN1
+        bytes.iter().map(|&b| b as u16).collect()
+    } else {
+        // UTF16 in native byte order, see isBigEndian
+        bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+            .collect()
+    }
+}
+
+/// Allocates a `java.lang.String` of the UTF16 `chars`, which unlike `intern_string` is a new
+/// instance every time
+pub(in crate::runtime) fn new_string(heap: &RwLock<Heap>, chars: &[u16]) -> u32 {
+    let string_class = STRING_CLASS.get().expect("string class should be defined");
+    // compact strings are always enabled, see `intern_string`
+    let latin1 = chars.iter().all(|&c| c <= 0xFF);
+    let bytes: Vec<u8> = if latin1 {
+        chars.iter().map(|&c| c as u8).collect()
+    } else {
+        chars.iter().flat_map(|c| c.to_ne_bytes()).collect()
+    };
+
+    let string = new_instance(string_class);
+    let mut heap = heap.write().unwrap();
+    let byte_array_class = BYTE_ARRAY_CLASS.get().expect("must have init");
+    let value = new_array(&mut heap, Arc::clone(byte_array_class), bytes.len());
+    let value_obj = heap.get(value);
+    for (i, &b) in bytes.iter().enumerate() {
+        // SAFETY: value is a byte[] of bytes.len()
+        unsafe { put_array_index(value_obj.as_ref(), i, b as i8) };
+    }
+
+    let string_obj = heap.get(string);
+    let field_index = |name: &str| {
+        string_class
+            .find_field_info(name, false)
+            .expect("string must have field")
+            .index
+    };
+    // SAFETY: String.value is a byte[] and String.coder is a byte
+    unsafe {
+        string_obj.put_field(field_index("value"), Variable { reference: value });
+        string_obj.put_field(field_index("coder"), Variable { int: !latin1 as _ });
+    }
+    string
+}
+
+// public native String intern();
+fn native_string_intern(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let this = env.args[0].get_ref();
+    // strings from ldc are already in the table under their own bytes, other strings become the
+    // canonical instance themselves if they come first
+    let (value, bytes, utf16) = get_string_value(env.heap, this);
+    let interned =
+        STRING_TABLE
+            .write()
+            .unwrap()
+            .intern_heap_string(bytes.into(), utf16, this, value);
+    Ok(Some(NativeVariable::Reference(interned)))
+}
 
 // private static native boolean isBigEndian();
 fn native_stringutf16_isbegendian(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
@@ -18,4 +122,8 @@ pub(super) fn register_natives() {
         ),
         native_stringutf16_isbegendian,
     );
+    NATIVE_FUNCTIONS.insert(
+        ("java/lang/String".to_string(), "intern".to_string(), vec![]),
+        native_string_intern,
+    );
 }
@@ -1,18 +1,27 @@
 use crate::{
-    descriptor::FieldType,
+    descriptor::{FieldType, parse_method_descriptor},
     runtime::{
-        Exception, NativeEnv, NativeResult, NativeVariable,
+        Exception, NativeEnv, NativeResult, NativeVariable, Thread, Variable,
         famous_classes::{
-            ARRAY_STORE_EXCEPTION_CLASS, INDEX_OUT_OF_BOUND_EXCEPTION_CLASS,
+            ARRAY_INDEX_OUT_OF_BOUND_EXCEPTION_CLASS, ARRAY_STORE_EXCEPTION_CLASS,
             NULL_POINTER_EXCEPTION_CLASS,
         },
         inheritance::{get_array_type, is_assignable_to},
         native::NATIVE_FUNCTIONS,
-        structs::get_array_index,
+        structs::get_array_index_checked,
     },
 };
 use std::sync::LazyLock;
 
+/// Runs `System.initPhase1` as hotspot does before the main class, which sets up the system
+/// properties and the standard streams. The module system of `initPhase2` and the system class
+/// loader of `initPhase3` are not set up yet.
+pub(in crate::runtime) fn initialize_system() -> NativeResult<()> {
+    let (_, descriptor) = parse_method_descriptor("()V").expect("valid descriptor");
+    Thread::new(0).invoke_static("java/lang/System", "initPhase1", &descriptor, &[])?;
+    Ok(())
+}
+
 //     public static native void arraycopy(Object src,  int  srcPos,
 //                                         Object dest, int destPos,
 //                                         int length);
@@ -21,9 +30,8 @@ fn native_system_arraycopy(env: NativeEnv) -> NativeResult<Option<NativeVariable
     let src_pos = env.args[1].get_int();
     let dest_ref = env.args[2].get_ref();
     let dest_pos = env.args[3].get_int();
-    let mut length = env.args[4].get_int();
+    let length = env.args[4].get_int();
 
-    // TODO: check bound and type
     if dest_ref == 0 || src_ref == 0 {
         return Err(Exception::new_vm(
             NULL_POINTER_EXCEPTION_CLASS.get().expect("must have init"),
@@ -32,19 +40,23 @@ fn native_system_arraycopy(env: NativeEnv) -> NativeResult<Option<NativeVariable
     let src = env.heap.read().unwrap().get(src_ref);
     let dest = env.heap.read().unwrap().get(dest_ref);
     let Some(src_type) = get_array_type(src.get_class()) else {
-        return Err(Exception::new_vm(
-            ARRAY_STORE_EXCEPTION_CLASS.get().expect("must have init"),
-        ));
+        return Err(array_store_exception(&format!(
+            "arraycopy: source type {} is not an array",
+            src.get_class().class_name().replace('/', ".")
+        )));
     };
     let Some(dest_type) = get_array_type(dest.get_class()) else {
-        return Err(Exception::new_vm(
-            ARRAY_STORE_EXCEPTION_CLASS.get().expect("must have init"),
-        ));
+        return Err(array_store_exception(&format!(
+            "arraycopy: destination type {} is not an array",
+            dest.get_class().class_name().replace('/', ".")
+        )));
     };
     if (src_type.is_primitive() || dest_type.is_primitive()) && src_type != dest_type {
-        return Err(Exception::new_vm(
-            ARRAY_STORE_EXCEPTION_CLASS.get().expect("must have init"),
-        ));
+        return Err(array_store_exception(&format!(
+            "arraycopy: type mismatch: can not copy {}[] into {}[]",
+            array_kind(&src_type),
+            array_kind(&dest_type)
+        )));
     }
 
     let src_ele_size = src_type.get_field_type_size();
@@ -54,41 +66,69 @@ fn native_system_arraycopy(env: NativeEnv) -> NativeResult<Option<NativeVariable
 
     debug_assert_eq!(dest_ele_size, src_ele_size, "dest_ele_size != src_ele_size");
 
-    if src_pos < 0
-        || dest_pos < 0
-        || length < 0
-        || src_pos + length > src_len as i32
-        || dest_pos + length > dest_len as i32
-    {
-        return Err(Exception::new_vm(
-            INDEX_OUT_OF_BOUND_EXCEPTION_CLASS
+    // in i64, the last index can overflow i32
+    let out_of_bounds = if src_pos < 0 {
+        Some(format!(
+            "arraycopy: source index {src_pos} out of bounds for {}[{src_len}]",
+            array_kind(&src_type)
+        ))
+    } else if dest_pos < 0 {
+        Some(format!(
+            "arraycopy: destination index {dest_pos} out of bounds for {}[{dest_len}]",
+            array_kind(&dest_type)
+        ))
+    } else if length < 0 {
+        Some(format!("arraycopy: length {length} is negative"))
+    } else if src_pos as i64 + length as i64 > src_len as i64 {
+        Some(format!(
+            "arraycopy: last source index {} out of bounds for {}[{src_len}]",
+            src_pos as i64 + length as i64,
+            array_kind(&src_type)
+        ))
+    } else if dest_pos as i64 + length as i64 > dest_len as i64 {
+        Some(format!(
+            "arraycopy: last destination index {} out of bounds for {}[{dest_len}]",
+            dest_pos as i64 + length as i64,
+            array_kind(&dest_type)
+        ))
+    } else {
+        None
+    };
+    if let Some(message) = out_of_bounds {
+        return Err(Exception::new_vm_msg(
+            ARRAY_INDEX_OUT_OF_BOUND_EXCEPTION_CLASS
                 .get()
                 .expect("must have init"),
+            &message,
         ));
     }
 
+    // elements before the first one not assignable to the destination are still copied, and
+    // the exception is thrown after them
+    let mut copy_length = length;
     let mut arr_store_exception = None;
 
-    if !src_type.is_primitive() {
+    // every element of an array assignable to the destination is assignable to its elements
+    if !src_type.is_primitive() && !is_assignable_to(src.get_class(), dest.get_class()) {
+        let dest_ele_class = dest
+            .get_class()
+            .array_element_type
+            .as_ref()
+            .expect("must be array");
         for i in src_pos..src_pos + length {
-            let ele_ref: u32 = unsafe { get_array_index(src.as_ref(), i as usize) };
+            let ele_ref: u32 = unsafe { get_array_index_checked(src.as_ref(), i) }?;
             if ele_ref == 0 {
                 continue;
             }
             let src_ele = env.heap.read().unwrap().get(ele_ref);
-            if !is_assignable_to(
-                src_ele.get_class(),
-                dest.get_class()
-                    .array_element_type
-                    .as_ref()
-                    .expect("must be array"),
-            ) {
-                length = i - src_pos;
-                arr_store_exception = Some(Exception::new_vm(
-                    INDEX_OUT_OF_BOUND_EXCEPTION_CLASS
-                        .get()
-                        .expect("must have init"),
-                ));
+            if !is_assignable_to(src_ele.get_class(), dest_ele_class) {
+                copy_length = i - src_pos;
+                arr_store_exception = Some(array_store_exception(&format!(
+                    "arraycopy: element type mismatch: can not cast one of the elements of \
+                     {}[] to the type of the destination array, {}",
+                    src_type.to_string().replace('/', "."),
+                    dest_ele_class.class_name().replace('/', ".")
+                )));
                 break;
             }
         }
@@ -104,7 +144,7 @@ fn native_system_arraycopy(env: NativeEnv) -> NativeResult<Option<NativeVariable
             .get_u8_array()
             .add(dest_pos as usize * dest_ele_size)
     };
-    let real_length = length as usize * src_ele_size;
+    let real_length = copy_length as usize * src_ele_size;
     if src_ref == dest_ref {
         unsafe {
             real_dest.copy_from(real_src, real_length);
@@ -121,6 +161,22 @@ fn native_system_arraycopy(env: NativeEnv) -> NativeResult<Option<NativeVariable
     Ok(None)
 }
 
+fn array_store_exception(message: &str) -> Exception {
+    Exception::new_vm_msg(
+        ARRAY_STORE_EXCEPTION_CLASS.get().expect("must have init"),
+        message,
+    )
+}
+
+/// The array type as hotspot names it in arraycopy messages, e.g. `int` or `object array`
+fn array_kind(element_type: &FieldType) -> String {
+    if element_type.is_primitive() {
+        element_type.to_string()
+    } else {
+        "object array".to_string()
+    }
+}
+
 // public static native long currentTimeMillis();
 fn current_time_millis(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
     let millis = std::time::SystemTime::now()
@@ -137,6 +193,33 @@ fn nano_time(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
     Ok(Some(NativeVariable::Long(nanos)))
 }
 
+// the streams are static final fields, which only natives can set after System is initialized
+fn set_stream(env: &NativeEnv, name: &str) -> NativeResult<Option<NativeVariable>> {
+    let field = env
+        .class
+        .find_field_info(name, true)
+        .unwrap_or_else(|| panic!("System must have {name}"));
+    let stream = env.args[0].get_ref();
+    env.class
+        .set_static_field(field.index, Variable { reference: stream });
+    Ok(None)
+}
+
+// private static native void setIn0(InputStream in);
+fn set_in0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    set_stream(&env, "in")
+}
+
+// private static native void setOut0(PrintStream out);
+fn set_out0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    set_stream(&env, "out")
+}
+
+// private static native void setErr0(PrintStream err);
+fn set_err0(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    set_stream(&env, "err")
+}
+
 fn native_system_register_natives(_env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
     NATIVE_FUNCTIONS.insert(
         (
@@ -177,6 +260,30 @@ fn native_system_register_natives(_env: NativeEnv) -> NativeResult<Option<Native
         ),
         super::object::native_object_hash_code,
     );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/System".to_string(),
+            "setIn0".to_string(),
+            vec![FieldType::Object("java/io/InputStream".to_string())],
+        ),
+        set_in0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/System".to_string(),
+            "setOut0".to_string(),
+            vec![FieldType::Object("java/io/PrintStream".to_string())],
+        ),
+        set_out0,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/System".to_string(),
+            "setErr0".to_string(),
+            vec![FieldType::Object("java/io/PrintStream".to_string())],
+        ),
+        set_err0,
+    );
 
     Ok(None)
 }
@@ -0,0 +1,212 @@
+use crate::{
+    consts::MethodAccessFlag,
+    descriptor::FieldType,
+    runtime::{
+        AttributeInfo, Class, NativeEnv, NativeResult, NativeVariable, Variable,
+        class_loader::{get_class_object, new_array},
+        famous_classes::THROWABLE_CLASS,
+        global::BOOTSTRAP_CLASS_LOADER,
+        inheritance::is_same_or_sub_class_of,
+        native::{NATIVE_FUNCTIONS, class::get_class, new_string},
+        structs::{get_array_index, put_array_index},
+    },
+};
+use std::sync::Arc;
+
+// The backtrace of a throwable is an int[] with the class object, the index in its methods and the
+// offset in the code of every frame, from the top. Objects are never moved or freed, so the class
+// objects stay where the references point.
+const BACKTRACE_FRAME_INTS: usize = 3;
+
+// line number of the elements of native methods, see StackTraceElement.isNativeMethod
+const NATIVE_METHOD_LINE_NUMBER: i32 = -2;
+
+// private native Throwable fillInStackTrace(int dummy);
+fn fill_in_stack_trace(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let this = env.args[0].get_ref();
+    let this_class = Arc::clone(env.heap.read().unwrap().get(this).get_class());
+    let is_of_this = |class: &Arc<Class>| is_same_or_sub_class_of(&this_class, class);
+    // like hotspot, the frames filling in the stack trace and constructing the throwable are left
+    // out
+    let backtrace: Vec<_> = env
+        .thread
+        .backtrace()
+        .into_iter()
+        .skip_while(|(class, index, _)| {
+            &*class.methods[*index].name.to_str() == "fillInStackTrace" && is_of_this(class)
+        })
+        .skip_while(|(class, index, _)| {
+            &*class.methods[*index].name.to_str() == "<init>" && is_of_this(class)
+        })
+        .collect();
+
+    let mut frames = Vec::with_capacity(backtrace.len() * BACKTRACE_FRAME_INTS);
+    for (class, index, offset) in backtrace {
+        frames.extend([get_class_object(class)? as i32, index as i32, offset as i32]);
+    }
+    let int_array_class = BOOTSTRAP_CLASS_LOADER
+        .get()
+        .expect("must have init")
+        .resolve_class("[I")?;
+    let array = new_array(
+        &mut env.heap.write().unwrap(),
+        int_array_class,
+        frames.len(),
+    );
+    let array_obj = env.heap.read().unwrap().get(array);
+    for (i, int) in frames.iter().enumerate() {
+        // SAFETY: the array is an int[] of this length
+        unsafe { put_array_index(array_obj.as_ref(), i, *int) }
+    }
+
+    let this_obj = env.heap.read().unwrap().get(this);
+    // SAFETY: Throwable.backtrace is an Object, Throwable.depth an int
+    unsafe {
+        this_obj.put_field(
+            throwable_field_index("backtrace"),
+            Variable { reference: array },
+        );
+        this_obj.put_field(
+            throwable_field_index("depth"),
+            Variable {
+                int: (frames.len() / BACKTRACE_FRAME_INTS) as i32,
+            },
+        );
+    }
+    Ok(Some(NativeVariable::Reference(this)))
+}
+
+// private static native void initStackTraceElements(StackTraceElement[] elements, Throwable x);
+fn init_stack_trace_elements(env: NativeEnv) -> NativeResult<Option<NativeVariable>> {
+    let elements = env.heap.read().unwrap().get(env.args[0].get_ref());
+    let throwable = env.heap.read().unwrap().get(env.args[1].get_ref());
+    // SAFETY: Throwable.backtrace is an Object, Throwable.depth an int
+    let (backtrace, depth) = unsafe {
+        (
+            throwable
+                .get_field(throwable_field_index("backtrace"))
+                .reference,
+            throwable.get_field(throwable_field_index("depth")).int as usize,
+        )
+    };
+    let backtrace = env.heap.read().unwrap().get(backtrace);
+    for i in 0..depth {
+        // SAFETY: the backtrace is an int[] of the frames, the elements a StackTraceElement[]
+        let (class_object, index, offset, element) = unsafe {
+            let frame = i * BACKTRACE_FRAME_INTS;
+            (
+                get_array_index::<i32, _>(backtrace.as_ref(), frame) as u32,
+                get_array_index::<i32, _>(backtrace.as_ref(), frame + 1) as usize,
+                get_array_index::<i32, _>(backtrace.as_ref(), frame + 2) as u32,
+                get_array_index::<u32, _>(elements.as_ref(), i),
+            )
+        };
+        let class = get_class(&env, class_object);
+        let method = &class.methods[index];
+
+        let file_name = class.attributes.iter().find_map(|attr| match attr {
+            AttributeInfo::SourceFile(file_name) => Some(file_name),
+            _ => None,
+        });
+        let line_number_table = method
+            .attributes
+            .iter()
+            .find_map(|attr| match attr {
+                AttributeInfo::Code(code) => Some(&code.attributes),
+                _ => None,
+            })
+            .into_iter()
+            .flatten()
+            .filter_map(|attr| match attr {
+                AttributeInfo::LineNumberTable(table) => Some(table),
+                _ => None,
+            })
+            .flatten();
+        // the line of the closest start at or before the offset
+        let line_number = if method.access_flags.contains(MethodAccessFlag::NATIVE) {
+            NATIVE_METHOD_LINE_NUMBER
+        } else {
+            line_number_table
+                .filter(|item| item.start_pc as u32 <= offset)
+                .max_by_key(|item| item.start_pc)
+                .map_or(-1, |item| item.line_number as i32)
+        };
+
+        let string =
+            |string: &str| new_string(env.heap, &string.encode_utf16().collect::<Vec<_>>());
+        let declaring_class = string(&class.class_name.replace('/', "."));
+        let method_name = string(&method.name.to_str());
+        let file_name = file_name.map_or(0, |file_name| string(&file_name.to_str()));
+
+        let element = env.heap.read().unwrap().get(element);
+        let field_index = |name| {
+            element
+                .get_class()
+                .find_field_info(name, false)
+                .unwrap_or_else(|| panic!("stack trace element must have field {name}"))
+                .index
+        };
+        // SAFETY: the fields are of the types put
+        unsafe {
+            element.put_field(
+                field_index("declaringClassObject"),
+                Variable {
+                    reference: class_object,
+                },
+            );
+            element.put_field(
+                field_index("declaringClass"),
+                Variable {
+                    reference: declaring_class,
+                },
+            );
+            element.put_field(
+                field_index("methodName"),
+                Variable {
+                    reference: method_name,
+                },
+            );
+            element.put_field(
+                field_index("fileName"),
+                Variable {
+                    reference: file_name,
+                },
+            );
+            element.put_field(field_index("lineNumber"), Variable { int: line_number });
+        }
+    }
+    Ok(None)
+}
+
+fn throwable_field_index(name: &str) -> usize {
+    THROWABLE_CLASS
+        .get()
+        .expect("must have init")
+        .find_field_info(name, false)
+        .unwrap_or_else(|| panic!("throwable must have field {name}"))
+        .index
+}
+
+pub(super) fn register_natives() {
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/Throwable".to_string(),
+            "fillInStackTrace".to_string(),
+            vec![FieldType::Int],
+        ),
+        fill_in_stack_trace,
+    );
+    NATIVE_FUNCTIONS.insert(
+        (
+            "java/lang/StackTraceElement".to_string(),
+            "initStackTraceElements".to_string(),
+            vec![
+                FieldType::Array(Box::new(FieldType::Object(
+                    "java/lang/StackTraceElement".to_string(),
+                ))),
+                FieldType::Object("java/lang/Throwable".to_string()),
+            ],
+        ),
+        init_stack_trace_elements,
+    );
+}
@@ -1,6 +1,10 @@
 use std::{
     cell::Cell,
-    sync::{Arc, RwLock},
+    fmt::Write,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
 };
 
 pub use crate::runtime::heap::string_table::*;
@@ -11,8 +15,13 @@ pub use object::*;
 use crate::{
     class::JavaStr,
     consts::{ClassAccessFlag, FieldAccessFlag, MethodAccessFlag},
-    descriptor::{FieldDescriptor, FieldType, MethodDescriptor},
-    runtime::{Variable, famous_classes::CLASS_FORMAT_ERROR_CLASS},
+    descriptor::{
+        FieldDescriptor, FieldType, JavaTypeSignature, MethodDescriptor, parse_field_signature,
+    },
+    runtime::{
+        Variable,
+        famous_classes::{CLASS_FORMAT_ERROR_CLASS, REFERENCE_CLASS},
+    },
 };
 
 mod attributes;
@@ -30,12 +39,18 @@ pub struct Class {
     pub(crate) instance_fields_info: Vec<FieldInfo>,
     pub(crate) methods: Vec<MethodInfo>,
     pub(crate) attributes: Vec<AttributeInfo>,
-    pub(crate) static_fields: Vec<RwLock<Variable>>,
+    // a slot for each int, float or reference, and two for each long or double. JVM allows data
+    // races on fields, so the slots are atomics rather than locks, and writes of a long or double
+    // are not atomic as a whole, which JLS 17.7 allows
+    pub(crate) static_fields: Vec<AtomicU32>,
     // only for arrays of reference type
     pub(crate) array_element_type: Option<Arc<Class>>,
     pub(in crate::runtime) clinit_call: parking_lot::ReentrantMutex<Cell<ClinitStatus>>,
     // contains all methods inherited from super classes, and default methods from super interfaces
     pub(crate) vtable: Vec<VtableEntry>,
+    // whether instances need finalize() to run before they are reclaimed, see
+    // has_nontrivial_finalizer
+    pub(crate) has_finalizer: bool,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -57,9 +72,106 @@ pub enum VtableIndex {
     InThisClass(usize),
     OtherClass { class: Arc<Class>, index: usize },
     OtherInterface { class: Arc<Class>, index: usize },
+    // one of the maximally-specific default methods of unrelated interfaces, none of which is
+    // selected, so invoking it throws IncompatibleClassChangeError
+    DefaultConflict { class: Arc<Class>, index: usize },
 }
 
 impl Class {
+    /// Binary name in internal form, e.g. `java/lang/Object`
+    pub fn class_name(&self) -> &str {
+        &self.class_name
+    }
+
+    /// The generic type of the field `name` declared by this class, see
+    /// [`FieldInfo::generic_type`]. `None` if there is no such field, or its signature is
+    /// malformed.
+    pub fn declared_field_generic_type(&self, name: &str) -> Option<JavaTypeSignature> {
+        // instance_fields_info starts with the fields inherited from super classes
+        let inherited = self
+            .super_class
+            .as_ref()
+            .map_or(0, |s| s.instance_fields_info.len());
+        let name = JavaStr::from_str(name);
+        self.instance_fields_info[inherited..]
+            .iter()
+            .chain(&self.static_fields_info)
+            .find(|f| f.name.as_ref() == name.as_ref())?
+            .generic_type()
+    }
+
+    /// Whether this class is deprecated, by `@Deprecated` or the `Deprecated` attribute.
+    pub fn is_deprecated(&self) -> bool {
+        is_deprecated(&self.attributes)
+    }
+
+    /// Whether the method `name` with `descriptor` like `(I)V` declared by this class is
+    /// deprecated, see [`Class::is_deprecated`]. `None` if there is no such method.
+    pub fn is_declared_method_deprecated(&self, name: &str, descriptor: &str) -> Option<bool> {
+        let name = JavaStr::from_str(name);
+        let method = self.methods.iter().find(|m| {
+            m.name.as_ref() == name.as_ref() && m.descriptor.to_descriptor() == descriptor
+        })?;
+        Some(is_deprecated(&method.attributes))
+    }
+
+    /// The instance field layout, for debugging field resolution. A line `index: Class.name type`
+    /// for each field in slot order, the inherited fields first with the class that declares
+    /// them.
+    pub fn dump_field_layout(&self) -> String {
+        // from java.lang.Object down to this class
+        let mut classes = vec![];
+        let mut class = Some(self);
+        while let Some(c) = class {
+            classes.push(c);
+            class = c.super_class.as_deref();
+        }
+        classes.reverse();
+
+        let mut layout = String::new();
+        for (i, field) in self.instance_fields_info.iter().enumerate() {
+            // each class declares the fields after the ones of its super class
+            let declaring = classes
+                .iter()
+                .find(|c| i < c.instance_fields_info.len())
+                .expect("field is declared by a class in the hierarchy");
+            writeln!(
+                layout,
+                "{}: {}.{} {}",
+                field.index,
+                declaring.class_name,
+                field.name.to_str(),
+                field.descriptor.0
+            )
+            .unwrap();
+        }
+        layout
+    }
+
+    /// Whether this class declares or inherits a `finalize()` that does more than return, unlike
+    /// `Object.finalize`.
+    ///
+    /// There is no collector yet and objects are never reclaimed, so finalizers never run.
+    pub fn has_nontrivial_finalizer(&self) -> bool {
+        self.has_finalizer
+    }
+
+    /// Whether this is `java.lang.ref.Reference` or a subclass like `WeakReference`, whose
+    /// `referent` field is not meant to keep the referent alive.
+    ///
+    /// There is no collector yet, so referents are held strongly and never cleared.
+    pub fn is_reference(&self) -> bool {
+        let reference = REFERENCE_CLASS.get().expect("must have init");
+        let mut class = Some(self);
+        while let Some(c) = class {
+            if std::ptr::eq(c, reference.as_ref()) {
+                return true;
+            }
+            class = c.super_class.as_deref();
+        }
+        false
+    }
+
     pub(super) fn resolve_method(
         &self,
         name: &JavaStr,
@@ -76,22 +188,89 @@ impl Class {
         }
         None
     }
+    pub(super) fn find_field_info(&self, name: &str, is_static: bool) -> Option<&FieldInfo> {
+        let fields = if is_static {
+            &self.static_fields_info
+        } else {
+            &self.instance_fields_info
+        };
+        let name = JavaStr::from_str(name);
+        fields.iter().find(|f| f.name.as_ref() == name.as_ref())
+    }
+
+    /// The constant at `index`, which must be in range, as the operands of the instructions are
+    /// checked when the class is linked
     pub(super) fn get_constant(&self, index: u16) -> &ConstantPoolInfo {
+        debug_assert!(
+            (1..=self.constant_pool.len()).contains(&(index as usize)),
+            "constant pool index {index} out of range of {}",
+            self.class_name
+        );
         &self.constant_pool[index as usize - 1]
     }
 
+    // sequentially consistent, as volatile fields need
     pub(super) fn get_static_field(&self, index: usize) -> Variable {
-        *self.static_fields[index].read().unwrap()
+        Variable {
+            reference: self.static_fields[index].load(Ordering::SeqCst),
+        }
     }
 
     pub(super) fn set_static_field(&self, index: usize, value: Variable) {
-        *self.static_fields[index].write().unwrap() = value;
+        // SAFETY: static fields hold ints, floats or references, all of them 4 bytes
+        let bits = unsafe { value.reference };
+        self.static_fields[index].store(bits, Ordering::SeqCst);
     }
 
     pub(super) fn is_array(&self) -> bool {
         self.class_name.starts_with("[")
     }
 
+    /// The `InnerClasses` entry describing this class itself, `None` for top-level classes
+    pub(super) fn inner_class_info(&self) -> Option<&InnerClassInfo> {
+        self.attributes.iter().find_map(|attr| {
+            let AttributeInfo::InnerClasses(classes) = attr else {
+                return None;
+            };
+            classes
+                .iter()
+                .find(|info| info.inner_class.name == self.class_name)
+        })
+    }
+
+    /// The classes permitted to extend or implement this class, `None` if it is not sealed
+    pub(super) fn permitted_subclasses(&self) -> Option<&[CpClassInfo]> {
+        self.attributes.iter().find_map(|attr| {
+            let AttributeInfo::PermittedSubclasses(classes) = attr else {
+                return None;
+            };
+            Some(classes.as_slice())
+        })
+    }
+
+    /// The components of a record class, `None` if the class is not a record
+    pub(super) fn record_components(&self) -> Option<&[RecordComponentInfo]> {
+        self.attributes.iter().find_map(|attr| {
+            let AttributeInfo::Record(components) = attr else {
+                return None;
+            };
+            Some(components.as_slice())
+        })
+    }
+
+    /// The `BootstrapMethods` table indexed by `invokedynamic` and dynamically-computed constants
+    pub(super) fn bootstrap_methods(&self) -> &[BootstrapMethod] {
+        self.attributes
+            .iter()
+            .find_map(|attr| {
+                let AttributeInfo::BootstrapMethods(methods) = attr else {
+                    return None;
+                };
+                Some(methods.as_slice())
+            })
+            .unwrap_or_default()
+    }
+
     pub(super) fn package_name(&self) -> &str {
         let Some((package, _)) = self.class_name.rsplit_once('/') else {
             return "";
@@ -117,6 +296,58 @@ pub struct MethodInfo {
     pub(crate) attributes: Vec<AttributeInfo>,
 }
 
+impl FieldInfo {
+    /// The type from the `Signature` attribute, or the erased type if there is none, as
+    /// `Field.getGenericType` returns. `None` if the signature is malformed, for which java
+    /// throws `GenericSignatureFormatError`.
+    pub(crate) fn generic_type(&self) -> Option<JavaTypeSignature> {
+        let Some(signature) = signature_of(&self.attributes) else {
+            return Some((&self.descriptor.0).into());
+        };
+        let (_, signature) = parse_field_signature(&signature.to_str()).ok()?;
+        Some(JavaTypeSignature::Reference(signature))
+    }
+}
+
+impl MethodInfo {
+    /// The checked exceptions from the `Exceptions` attribute, as `Method.getExceptionTypes`
+    /// returns.
+    pub(crate) fn exception_types(&self) -> Vec<FieldType> {
+        self.attributes
+            .iter()
+            .find_map(|attr| match attr {
+                AttributeInfo::Exceptions(exceptions) => Some(exceptions),
+                _ => None,
+            })
+            .map_or_else(Vec::new, |exceptions| {
+                exceptions
+                    .iter()
+                    .map(|exception| FieldType::Object(exception.name.to_string()))
+                    .collect()
+            })
+    }
+}
+
+fn signature_of(attributes: &[AttributeInfo]) -> Option<&JavaStr> {
+    attributes.iter().find_map(|attr| match attr {
+        AttributeInfo::Signature(signature) => Some(signature.as_ref()),
+        _ => None,
+    })
+}
+
+// javac emits the Deprecated attribute along with the annotation, but other compilers may emit
+// either of them
+fn is_deprecated(attributes: &[AttributeInfo]) -> bool {
+    let deprecated = FieldType::Object("java/lang/Deprecated".to_string());
+    attributes.iter().any(|attr| match attr {
+        AttributeInfo::Deprecated => true,
+        AttributeInfo::RuntimeVisibleAnnotations(annotations) => annotations
+            .iter()
+            .any(|annotation| annotation.type_descriptor.0 == deprecated),
+        _ => false,
+    })
+}
+
 #[derive(Debug)]
 pub enum Exception {
     VmException {
@@ -124,6 +355,9 @@ pub enum Exception {
         message: String,
     },
     UserException(u32),
+    /// `Runtime.exit` or `Runtime.halt` with the exit status, unwinds every frame without running
+    /// exception handlers.
+    Exit(i32),
 }
 
 impl Exception {
@@ -141,6 +375,21 @@ impl Exception {
         }
     }
 
+    /// Creates a vm exception whose message points at the instruction `pc` of `class.method_name`,
+    /// e.g. `Foo.bar @12: unknown instruction 203`
+    pub(crate) fn new_vm_at(
+        exception_type: &Arc<Class>,
+        class: &Class,
+        method_name: &str,
+        pc: usize,
+        message: &str,
+    ) -> Self {
+        Self::new_vm_msg(
+            exception_type,
+            &format!("{}.{method_name} @{pc}: {message}", class.class_name),
+        )
+    }
+
     pub(crate) fn new(exception: u32) -> Self {
         Exception::UserException(exception)
     }
@@ -156,3 +405,85 @@ impl From<nom::Err<nom::error::Error<&[u8]>>> for Exception {
 }
 
 pub type NativeResult<T> = ::std::result::Result<T, Exception>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::gen_primitive_class;
+    use std::thread;
+
+    #[test]
+    fn test_field_generic_type() {
+        let field_info = |attributes| FieldInfo {
+            access_flags: FieldAccessFlag::PRIVATE,
+            name: JavaStr::from_str("names").into(),
+            descriptor: FieldDescriptor(FieldType::Object("java/util/List".to_string())),
+            attributes,
+            index: 0,
+        };
+        let signature = |signature: &str| {
+            vec![AttributeInfo::Signature(
+                JavaStr::from_str(signature).into(),
+            )]
+        };
+
+        // List<String> names
+        let generic = field_info(signature("Ljava/util/List<Ljava/lang/String;>;"));
+        assert_eq!(
+            generic.generic_type().unwrap().to_string(),
+            "java/util/List<java/lang/String>"
+        );
+        let raw = field_info(vec![]);
+        assert_eq!(raw.generic_type().unwrap().to_string(), "java/util/List");
+        let malformed = field_info(signature("Ljava/util/List<Ljava/lang/String;>"));
+        assert_eq!(malformed.generic_type(), None);
+    }
+
+    fn class_with_static_fields(slots: usize) -> Class {
+        Class {
+            static_fields: (0..slots).map(|_| AtomicU32::new(0)).collect(),
+            ..gen_primitive_class(Arc::from("Foo"))
+        }
+    }
+
+    #[test]
+    fn test_static_field_long_slots() {
+        let class = class_with_static_fields(3);
+        let (upper, lower) = Variable::put_long(-(1 << 40) - 7);
+        class.set_static_field(0, upper);
+        class.set_static_field(1, lower);
+        class.set_static_field(2, Variable { float: 1.5 });
+
+        let long =
+            unsafe { Variable::get_long(class.get_static_field(0), class.get_static_field(1)) };
+        assert_eq!(long, -(1 << 40) - 7);
+        assert_eq!(unsafe { class.get_static_field(2).float }, 1.5);
+    }
+
+    #[test]
+    fn test_concurrent_static_fields() {
+        const THREADS: usize = 4;
+        const ROUNDS: i32 = 10_000;
+        let class = class_with_static_fields(THREADS + 1);
+
+        thread::scope(|scope| {
+            // every thread counts in its own slot, and overwrites the shared last slot
+            for slot in 0..THREADS {
+                let class = &class;
+                scope.spawn(move || {
+                    for _ in 0..ROUNDS {
+                        let count = unsafe { class.get_static_field(slot).int };
+                        class.set_static_field(slot, Variable { int: count + 1 });
+                        class.set_static_field(THREADS, Variable { int: slot as i32 });
+                    }
+                });
+            }
+        });
+
+        for slot in 0..THREADS {
+            assert_eq!(unsafe { class.get_static_field(slot).int }, ROUNDS);
+        }
+        let last = unsafe { class.get_static_field(THREADS).int };
+        assert!((0..THREADS as i32).contains(&last), "{last}");
+    }
+}
@@ -1,8 +1,13 @@
 use crate::{
     class::JavaStr,
+    consts::{InnerClassAccessFlag, RequiresFlag},
     descriptor::{FieldDescriptor, ReturnType},
-    runtime::{CpClassInfo, MethodHandle},
+    runtime::{
+        CpClassInfo, CpNameAndTypeInfo, MethodHandle,
+        interpreter::decoder::{DecodedInstruction, decode},
+    },
 };
+use once_cell::sync::OnceCell;
 use std::sync::Arc;
 
 #[derive(Debug, Clone)]
@@ -16,13 +21,25 @@ pub enum AttributeInfo {
     StackMapTable(Vec<StackMapFrame>),
     Deprecated,
     Signature(Arc<JavaStr>),
-    Exceptions,
+    /// Checked exceptions a method is declared to throw.
+    Exceptions(Vec<CpClassInfo>),
     Module(Module),
     ModulePackages(Vec<Arc<JavaStr>>),
     ModuleHashes,
     ModuleTarget(Arc<JavaStr>),
-    InnerClasses,
+    InnerClasses(Vec<InnerClassInfo>),
+    NestHost(CpClassInfo),
+    NestMembers(Vec<CpClassInfo>),
+    /// The classes allowed to directly extend or implement a sealed class.
+    PermittedSubclasses(Vec<CpClassInfo>),
+    /// The class of a local or anonymous class, and the method it is declared in, if any.
+    EnclosingMethod {
+        class: CpClassInfo,
+        method: Option<CpNameAndTypeInfo<Arc<JavaStr>>>,
+    },
     BootstrapMethods(Vec<BootstrapMethod>),
+    /// The components of a record class, in declaration order.
+    Record(Vec<RecordComponentInfo>),
     Unknown(Arc<JavaStr>),
 }
 
@@ -33,6 +50,18 @@ pub struct CodeAttribute {
     pub(crate) code: Arc<[u8]>,
     pub(crate) exception_table: Vec<ExceptionTableItem>,
     pub(crate) attributes: Vec<AttributeInfo>,
+    /// `code` decoded on the first invocation, the raw bytes are kept for the offsets of the
+    /// exception table and attributes.
+    pub(crate) instructions: OnceCell<Arc<[DecodedInstruction]>>,
+}
+
+impl CodeAttribute {
+    /// The decoded `code`, or the offset of an instruction cut short by the end of the code, which
+    /// linking rejects.
+    pub(crate) fn instructions(&self) -> Result<&Arc<[DecodedInstruction]>, usize> {
+        self.instructions
+            .get_or_try_init(|| decode(&self.code).map(Into::into))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -123,13 +152,84 @@ pub struct LocalVariable {
 }
 
 #[derive(Debug, Clone)]
-pub struct StackMapFrame {
-    // TODO:
+pub enum StackMapFrame {
+    // also same_frame_extended
+    Same {
+        offset_delta: u16,
+    },
+    // also same_locals_1_stack_item_frame_extended
+    SameLocals1StackItem {
+        offset_delta: u16,
+        stack: VerificationTypeInfo,
+    },
+    Chop {
+        offset_delta: u16,
+        k: u8,
+    },
+    Append {
+        offset_delta: u16,
+        locals: Vec<VerificationTypeInfo>,
+    },
+    Full {
+        offset_delta: u16,
+        locals: Vec<VerificationTypeInfo>,
+        stack: Vec<VerificationTypeInfo>,
+    },
+}
+
+impl StackMapFrame {
+    pub(crate) fn offset_delta(&self) -> u16 {
+        match self {
+            StackMapFrame::Same { offset_delta }
+            | StackMapFrame::SameLocals1StackItem { offset_delta, .. }
+            | StackMapFrame::Chop { offset_delta, .. }
+            | StackMapFrame::Append { offset_delta, .. }
+            | StackMapFrame::Full { offset_delta, .. } => *offset_delta,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationTypeInfo {
+    Top,
+    Integer,
+    Float,
+    Double,
+    Long,
+    Null,
+    UninitializedThis,
+    Object(Arc<str>),
+    // offset of the `new` instruction that created the object
+    Uninitialized(u16),
+}
+
+impl VerificationTypeInfo {
+    pub(crate) fn slot_count(&self) -> usize {
+        match self {
+            VerificationTypeInfo::Long | VerificationTypeInfo::Double => 2,
+            _ => 1,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Module {
+    pub(crate) name: Arc<JavaStr>,
+    pub(crate) flags: u16,
+    pub(crate) version: Option<Arc<JavaStr>>,
+    pub(crate) requires: Vec<ModuleRequire>,
     pub(crate) exports: Vec<ModuleExport>,
+    pub(crate) opens: Vec<ModuleOpen>,
+    // class names of the services used
+    pub(crate) uses: Vec<Arc<str>>,
+    pub(crate) provides: Vec<ModuleProvide>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModuleRequire {
+    pub(crate) requires: Arc<JavaStr>,
+    pub(crate) requires_flags: RequiresFlag,
+    pub(crate) requires_version: Option<Arc<JavaStr>>,
 }
 
 #[derive(Debug, Clone)]
@@ -139,8 +239,40 @@ pub struct ModuleExport {
     pub(crate) exports_to: Vec<Arc<JavaStr>>,
 }
 
+#[derive(Debug, Clone)]
+pub struct ModuleOpen {
+    pub(crate) opens: Arc<JavaStr>,
+    pub(crate) opens_flags: u16,
+    pub(crate) opens_to: Vec<Arc<JavaStr>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModuleProvide {
+    // class names of the service and its implementations
+    pub(crate) provides: Arc<str>,
+    pub(crate) provides_with: Vec<Arc<str>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InnerClassInfo {
+    pub(crate) inner_class: CpClassInfo,
+    // None if the inner class is not a member, i.e. top-level, local or anonymous
+    pub(crate) outer_class: Option<CpClassInfo>,
+    // None if the inner class is anonymous
+    pub(crate) inner_name: Option<Arc<JavaStr>>,
+    pub(crate) inner_class_access_flags: InnerClassAccessFlag,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordComponentInfo {
+    pub(crate) name: Arc<JavaStr>,
+    pub(crate) descriptor: FieldDescriptor,
+    pub(crate) attributes: Vec<AttributeInfo>,
+}
+
 #[derive(Debug, Clone)]
 pub struct BootstrapMethod {
     pub(crate) bootstrap_method: MethodHandle,
+    // constant pool indexes of the static arguments, resolved when the call site is linked
     pub(crate) bootstrap_arguments: Vec<u16>,
 }
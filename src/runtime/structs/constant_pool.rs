@@ -20,7 +20,7 @@ pub enum ConstantPoolInfo {
     InterfaceMethodref(Methodref),
     NameAndType(CpNameAndTypeInfo<Arc<JavaStr>>),
     MethodHandle(MethodHandle),
-    MethodType,
+    MethodType(MethodDescriptor),
     Dynamic {
         bootstrap_method_attr_index: u16,
         name_and_type: CpNameAndTypeInfo<FieldDescriptor>,
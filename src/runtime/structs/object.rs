@@ -1,9 +1,14 @@
-use crate::runtime::{Class, Variable, heap::HeapObject};
-use parking_lot::{RawMutex, RawThreadId, lock_api::RawReentrantMutex};
+use crate::runtime::{
+    Class, Exception, NativeResult, Variable,
+    famous_classes::ARRAY_INDEX_OUT_OF_BOUND_EXCEPTION_CLASS,
+    heap::{HeapObject, reflection::SpecialClassObject},
+};
+use parking_lot::{Condvar, Mutex, RawMutex, RawThreadId, lock_api::RawReentrantMutex};
 use std::{
     fmt::{Debug, Formatter},
     mem, slice,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 pub(in crate::runtime) trait Object {
@@ -25,6 +30,10 @@ pub(in crate::runtime) trait Object {
         None
     }
 
+    fn as_class_object(&self) -> Option<&SpecialClassObject> {
+        None
+    }
+
     /// # Safety
     ///
     /// Must ensure that this object is array with element of size element_size
@@ -86,14 +95,84 @@ where
     }
 }
 
+/// Like [`put_array_index`], but throws `ArrayIndexOutOfBoundsException` if `index` is out of
+/// bounds
+///
+/// # Safety
+///
+/// Must ensure that this object is array of type T
+/// Must ensure there is no concurrent read/write
+#[allow(private_bounds)]
+pub(in crate::runtime) unsafe fn put_array_index_checked<T, O>(
+    obj: &O,
+    index: i32,
+    v: T,
+) -> NativeResult<()>
+where
+    O: Object + ?Sized,
+    T: ArrayType,
+{
+    let index = check_array_index::<T, _>(obj, index)?;
+    unsafe { put_array_index(obj, index, v) };
+    Ok(())
+}
+
+/// Like [`get_array_index`], but throws `ArrayIndexOutOfBoundsException` if `index` is out of
+/// bounds
+///
+/// # Safety
+///
+/// Must ensure that this object is array of type T
+/// Must ensure there is no concurrent write
+pub(in crate::runtime) unsafe fn get_array_index_checked<T, O>(
+    obj: &O,
+    index: i32,
+) -> NativeResult<T>
+where
+    O: Object + ?Sized,
+    T: ArrayType,
+{
+    let index = check_array_index::<T, _>(obj, index)?;
+    Ok(unsafe { get_array_index(obj, index) })
+}
+
+fn check_array_index<T, O>(obj: &O, index: i32) -> NativeResult<usize>
+where
+    O: Object + ?Sized,
+{
+    let length = obj.get_array_size(size_of::<T>());
+    match usize::try_from(index) {
+        Ok(index) if index < length => Ok(index),
+        _ => Err(Exception::new_vm_msg(
+            ARRAY_INDEX_OUT_OF_BOUND_EXCEPTION_CLASS
+                .get()
+                .expect("must have init"),
+            &format!("Index {index} out of bounds for length {length}"),
+        )),
+    }
+}
+
 pub(in crate::runtime) struct ObjectMonitor {
     lock: RawReentrantMutex<RawMutex, RawThreadId>,
+    wait_set: Mutex<WaitSet>,
+    notified: Condvar,
+}
+
+// threads in `ObjectMonitor::wait`, and the notifications not taken by them yet
+struct WaitSet {
+    waiting: usize,
+    notifications: usize,
 }
 
 impl ObjectMonitor {
     pub const fn new() -> Self {
         Self {
             lock: RawReentrantMutex::INIT,
+            wait_set: Mutex::new(WaitSet {
+                waiting: 0,
+                notifications: 0,
+            }),
+            notified: Condvar::new(),
         }
     }
 
@@ -101,11 +180,76 @@ impl ObjectMonitor {
         self.lock.lock();
     }
 
+    /// Enters the monitor if it is free or already owned by current thread, without blocking.
+    // for natives taking locks without blocking, none of which is there yet
+    #[allow(dead_code)]
+    pub fn try_enter(&self) -> bool {
+        self.lock.try_lock()
+    }
+
+    /// Enters the monitor, giving up once `timeout` has passed while another thread owns it.
+    #[allow(dead_code)]
+    pub fn try_enter_timeout(&self, timeout: Duration) -> bool {
+        self.lock.try_lock_for(timeout)
+    }
+
     /// SAFETY: the lock must be held by current thread
     pub unsafe fn exit(&self) {
         debug_assert!(self.lock.is_locked() && self.lock.is_owned_by_current_thread());
         unsafe { self.lock.unlock() }
     }
+
+    /// Waits until notified or, with a `timeout`, until it has passed, and returns whether it was
+    /// notified. The monitor is released meanwhile however many times current thread entered it,
+    /// and entered as many times again before returning.
+    pub fn wait(&self, timeout: Option<Duration>) -> bool {
+        // a timeout too far to tell apart from forever
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut wait_set = self.wait_set.lock();
+        wait_set.waiting += 1;
+        // not entered when called outside of synchronized code, which is not rejected yet
+        let mut entered = 0;
+        while self.lock.is_owned_by_current_thread() {
+            // SAFETY: owned by current thread
+            unsafe { self.lock.unlock() };
+            entered += 1;
+        }
+
+        // the condvar may wake up spuriously, so only a notification or the deadline end the wait
+        let notified = loop {
+            if wait_set.notifications > 0 {
+                wait_set.notifications -= 1;
+                break true;
+            }
+            match deadline {
+                Some(deadline) if Instant::now() >= deadline => break false,
+                Some(deadline) => {
+                    self.notified.wait_until(&mut wait_set, deadline);
+                }
+                None => self.notified.wait(&mut wait_set),
+            }
+        };
+        wait_set.waiting -= 1;
+        drop(wait_set);
+
+        for _ in 0..entered {
+            self.lock.lock();
+        }
+        notified
+    }
+
+    /// Wakes up one of the threads waiting in [`Self::wait`], or all of them.
+    pub fn notify(&self, all: bool) {
+        let mut wait_set = self.wait_set.lock();
+        let unnotified = wait_set.waiting - wait_set.notifications;
+        if all {
+            wait_set.notifications += unnotified;
+            self.notified.notify_all();
+        } else if unnotified > 0 {
+            wait_set.notifications += 1;
+            self.notified.notify_one();
+        }
+    }
 }
 
 impl Default for ObjectMonitor {
@@ -148,3 +292,98 @@ pub(in crate::runtime) mod private {
     impl Sealed for i64 {}
     impl Sealed for u32 {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{sync::mpsc, thread};
+
+    #[test]
+    fn test_try_enter() {
+        let monitor = Arc::new(ObjectMonitor::new());
+        assert!(monitor.try_enter());
+        // reentrant
+        assert!(monitor.try_enter());
+        assert!(monitor.try_enter_timeout(Duration::ZERO));
+
+        let other = Arc::clone(&monitor);
+        let held = thread::spawn(move || {
+            (
+                other.try_enter(),
+                other.try_enter_timeout(Duration::from_millis(10)),
+            )
+        })
+        .join()
+        .unwrap();
+        assert_eq!(held, (false, false));
+
+        unsafe {
+            monitor.exit();
+            monitor.exit();
+            monitor.exit();
+        }
+        let other = Arc::clone(&monitor);
+        let released = thread::spawn(move || {
+            let entered = other.try_enter();
+            unsafe { other.exit() };
+            entered
+        })
+        .join()
+        .unwrap();
+        assert!(released);
+    }
+
+    #[test]
+    fn test_try_enter_timeout_waits_for_exit() {
+        let monitor = Arc::new(ObjectMonitor::new());
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (exit_tx, exit_rx) = mpsc::channel::<()>();
+
+        let owner = Arc::clone(&monitor);
+        let owner = thread::spawn(move || {
+            owner.enter();
+            entered_tx.send(()).unwrap();
+            exit_rx.recv().unwrap();
+            unsafe { owner.exit() };
+        });
+        entered_rx.recv().unwrap();
+        assert!(!monitor.try_enter());
+
+        exit_tx.send(()).unwrap();
+        assert!(monitor.try_enter_timeout(Duration::from_secs(10)));
+        unsafe { monitor.exit() };
+        owner.join().unwrap();
+    }
+
+    #[test]
+    fn test_wait_notify() {
+        let monitor = Arc::new(ObjectMonitor::new());
+        monitor.enter();
+        monitor.enter();
+        let start = Instant::now();
+        assert!(!monitor.wait(Some(Duration::from_millis(20))));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        // the waiter releases the monitor, and enters it as many times again once notified
+        let (waiting_tx, waiting_rx) = mpsc::channel();
+        let waiter = Arc::clone(&monitor);
+        let waiter = thread::spawn(move || {
+            waiter.enter();
+            waiting_tx.send(()).unwrap();
+            let notified = waiter.wait(None);
+            let entered = waiter.lock.is_owned_by_current_thread();
+            unsafe { waiter.exit() };
+            (notified, entered)
+        });
+        unsafe {
+            monitor.exit();
+            monitor.exit();
+        }
+        waiting_rx.recv().unwrap();
+        // once the waiter has released the monitor in wait
+        monitor.enter();
+        monitor.notify(false);
+        unsafe { monitor.exit() };
+        assert_eq!(waiter.join().unwrap(), (true, true));
+    }
+}
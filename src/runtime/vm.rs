@@ -0,0 +1,180 @@
+use crate::{
+    descriptor::MethodDescriptor,
+    runtime::{
+        Class, ClassPathModule, JModModule, ModuleLoader, NativeResult, NativeVariable, Thread,
+        VmEnv,
+        class_loader::{BootstrapClassLoader, initialize_class},
+        famous_classes::init_famous_classes,
+        global::{
+            ASSERTION_STATUS, BOOTSTRAP_CLASS_LOADER, HEAP, MAIN_THREAD_OBJECT, SYSTEM_PROPERTIES,
+            VERBOSE, VERIFY_CATEGORIES,
+        },
+        native::{initialize_system, new_main_thread_object},
+    },
+};
+use std::{
+    path::PathBuf,
+    sync::{Arc, atomic::Ordering},
+};
+
+/// Configures and boots the vm.
+///
+/// The vm still lives in globals, so only one vm can be built per process.
+pub struct VmBuilder {
+    java_home: Option<PathBuf>,
+    modules: Vec<Box<dyn ModuleLoader + Send + Sync + 'static>>,
+    heap_size_hint: usize,
+    assertion_status: bool,
+    verbose: bool,
+    verify_categories: bool,
+}
+
+impl VmBuilder {
+    pub fn new() -> Self {
+        Self {
+            java_home: None,
+            modules: vec![],
+            heap_size_hint: 0,
+            assertion_status: false,
+            verbose: false,
+            verify_categories: false,
+        }
+    }
+
+    /// Loads `java.base` from the jmods of the JDK at `java_home`.
+    pub fn java_home(mut self, java_home: impl Into<PathBuf>) -> Self {
+        self.java_home = Some(java_home.into());
+        self
+    }
+
+    /// Adds a module to the bootstrap class loader, after `java.base`.
+    pub fn module(mut self, module: impl ModuleLoader + Send + Sync + 'static) -> Self {
+        self.modules.push(Box::new(module));
+        self
+    }
+
+    /// Adds a directory to the class path, searched after the ones added before.
+    pub fn class_path(self, class_path: impl Into<PathBuf>) -> Self {
+        self.module(ClassPathModule::new("main", class_path))
+    }
+
+    /// Number of objects to reserve room for in the heap.
+    pub fn heap_size_hint(mut self, heap_size_hint: usize) -> Self {
+        self.heap_size_hint = heap_size_hint;
+        self
+    }
+
+    /// Whether `assert` statements are enabled, like `-ea`.
+    pub fn assertion_status(mut self, assertion_status: bool) -> Self {
+        self.assertion_status = assertion_status;
+        self
+    }
+
+    /// Whether to trace class loading and method calls to stdout, off by default.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Whether linking rejects class files whose instructions take a value of the wrong category
+    /// off the operand stack, e.g. `pop` applied to a long, for class files of every version.
+    /// Off by default.
+    pub fn verify_categories(mut self, verify_categories: bool) -> Self {
+        self.verify_categories = verify_categories;
+        self
+    }
+
+    /// # Panics
+    ///
+    /// If a vm has already been built in this process.
+    pub fn build(self) -> Vm {
+        let mut bootstrap_class_loader = BootstrapClassLoader::new();
+        if let Some(java_home) = self.java_home {
+            SYSTEM_PROPERTIES.insert("java.home".to_string(), java_home.display().to_string());
+            bootstrap_class_loader.add_module(Box::new(JModModule::new(java_home, "java.base")));
+        }
+        for module in self.modules {
+            bootstrap_class_loader.add_module(module);
+        }
+        if BOOTSTRAP_CLASS_LOADER.set(bootstrap_class_loader).is_err() {
+            panic!("vm is already built");
+        }
+
+        ASSERTION_STATUS.store(self.assertion_status, Ordering::Relaxed);
+        VERBOSE.store(self.verbose, Ordering::Relaxed);
+        VERIFY_CATEGORIES.store(self.verify_categories, Ordering::Relaxed);
+        HEAP.write().unwrap().reserve(self.heap_size_hint);
+
+        init_famous_classes();
+        let main_thread = new_main_thread_object(&HEAP).expect("must create main thread");
+        MAIN_THREAD_OBJECT
+            .set(main_thread)
+            .expect("vm is only built once");
+        // as hotspot does, so that AccessibleObject hands JavaLangReflectAccess to SharedSecrets
+        // before ReflectionFactory reads it
+        let method_class = BOOTSTRAP_CLASS_LOADER
+            .get()
+            .expect("must have init")
+            .resolve_class("java/lang/reflect/Method")
+            .expect("must load method");
+        let thread = Thread::new(0);
+        initialize_class(&VmEnv::new(&thread, &HEAP), &method_class)
+            .expect("must initialize method");
+        initialize_system().expect("must initialize system");
+
+        Vm { _private: () }
+    }
+}
+
+impl Default for VmBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Vm {
+    _private: (),
+}
+
+impl Vm {
+    pub fn resolve_class(&self, class_name: &str) -> NativeResult<Arc<Class>> {
+        BOOTSTRAP_CLASS_LOADER
+            .get()
+            .expect("vm must be built")
+            .resolve_class(class_name)
+    }
+
+    /// Defines the class `class_name` in internal form from the class file `bytes`, a
+    /// `LinkageError` if it is already defined.
+    pub fn define_class(&self, class_name: &str, bytes: &[u8]) -> NativeResult<Arc<Class>> {
+        BOOTSTRAP_CLASS_LOADER
+            .get()
+            .expect("vm must be built")
+            .define_class_from_bytes(class_name, bytes)
+    }
+
+    /// Names of the classes loaded so far in internal form, sorted. Classes are loaded lazily,
+    /// when first resolved.
+    pub fn loaded_class_names(&self) -> Vec<Arc<str>> {
+        BOOTSTRAP_CLASS_LOADER
+            .get()
+            .expect("vm must be built")
+            .loaded_class_names()
+    }
+
+    /// The instance field layout of the class `class_name`, see [`Class::dump_field_layout`].
+    pub fn dump_field_layout(&self, class_name: &str) -> NativeResult<String> {
+        Ok(self.resolve_class(class_name)?.dump_field_layout())
+    }
+
+    /// Runs the static method on a new thread, see [`Thread::invoke_static`].
+    pub fn invoke_static(
+        &self,
+        class_name: &str,
+        method_name: &str,
+        descriptor: &MethodDescriptor,
+        args: &[NativeVariable],
+    ) -> NativeResult<Option<NativeVariable>> {
+        Thread::new(0).invoke_static(class_name, method_name, descriptor, args)
+    }
+}
@@ -0,0 +1,78 @@
+use jvm::{
+    descriptor::parse_method_descriptor,
+    runtime::{Exception, VmBuilder},
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Compiles `source` of `class_name` into `dir`, with `class_path` to resolve other classes.
+fn compile(java_home: &Path, dir: &Path, class_path: &Path, class_name: &str, source: &str) {
+    fs::create_dir_all(dir).unwrap();
+    let source_file = dir.join(format!("{class_name}.java"));
+    fs::write(&source_file, source).unwrap();
+    let status = Command::new(java_home.join("bin/javac"))
+        .arg("-cp")
+        .arg(class_path)
+        .arg("-d")
+        .arg(dir)
+        .arg(&source_file)
+        .status()
+        .expect("cannot run javac");
+    assert!(status.success(), "javac failed");
+    fs::remove_file(source_file).unwrap();
+}
+
+#[test]
+fn test_multiple_class_path_entries() {
+    let Some(java_home) = env::var_os("JAVA_HOME").map(PathBuf::from) else {
+        eprintln!("JAVA_HOME is not set, skipping");
+        return;
+    };
+    let base = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("class_path");
+    let first = base.join("first");
+    let second = base.join("second");
+
+    // both entries contribute to the unnamed package
+    compile(
+        &java_home,
+        &second,
+        &second,
+        "Second",
+        "public class Second { static int value() { return 41; } }",
+    );
+    compile(
+        &java_home,
+        &first,
+        &second,
+        "First",
+        "public class First { static int run() { return Second.value() + 1; } }",
+    );
+
+    let vm = VmBuilder::new()
+        .java_home(java_home)
+        .class_path(first)
+        .class_path(second)
+        .build();
+
+    assert_eq!(vm.resolve_class("Second").unwrap().class_name(), "Second");
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let ret = vm.invoke_static("First", "run", &descriptor, &[]);
+    assert_eq!(ret.unwrap().unwrap().get_int(), 42);
+
+    let Err(Exception::VmException {
+        exception_type,
+        message,
+    }) = vm.resolve_class("Third")
+    else {
+        panic!("must throw vm exception");
+    };
+    assert_eq!(
+        exception_type.class_name(),
+        "java/lang/NoClassDefFoundError"
+    );
+    assert_eq!(message, "Third");
+}
@@ -0,0 +1,212 @@
+use jvm::{
+    descriptor::FieldType,
+    runtime::{self, Vm, VmBuilder},
+};
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::Command,
+    sync::{Mutex, MutexGuard, OnceLock, PoisonError},
+};
+
+/// Compiles the java sources under `tests/java` and boots the vm with them on the class path.
+///
+/// The vm lives in globals, so the returned guard serializes tests within a test binary.
+/// Returns `None` when `JAVA_HOME` is not set, so tests can be skipped on machines without a JDK.
+pub fn vm() -> Option<(&'static Vm, MutexGuard<'static, ()>)> {
+    static VM: OnceLock<Option<(Vm, Mutex<()>)>> = OnceLock::new();
+
+    let vm = VM.get_or_init(|| {
+        let Some(java_home) = env::var_os("JAVA_HOME").map(PathBuf::from) else {
+            eprintln!("JAVA_HOME is not set, skipping");
+            return None;
+        };
+        let sources = fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/java"))
+            .expect("cannot read test sources")
+            .map(|entry| entry.expect("cannot read test sources").path());
+        let classes = classes_dir();
+        let status = Command::new(java_home.join("bin/javac"))
+            .arg("-d")
+            .arg(&classes)
+            .args(sources)
+            .status()
+            .expect("cannot run javac");
+        assert!(status.success(), "javac failed");
+
+        let vm = VmBuilder::new()
+            .java_home(java_home)
+            .class_path(classes)
+            .assertion_status(true)
+            .verify_categories(true)
+            .build();
+        Some((vm, Mutex::new(())))
+    });
+    vm.as_ref()
+        .map(|(vm, lock)| (vm, lock.lock().unwrap_or_else(PoisonError::into_inner)))
+}
+
+/// Directory on the class path of the vm, where the compiled test classes are written.
+pub fn classes_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("classes")
+}
+
+/// Runs `public static void main(String[])` of `class`, returns whether it completed normally.
+// not every test binary runs a main class
+#[allow(dead_code)]
+pub fn run_main(class: &str) -> bool {
+    let mut thread = runtime::Thread::new(0);
+    thread.new_main_frame(
+        class,
+        "main",
+        &[FieldType::Array(Box::new(FieldType::Object(
+            "java/lang/String".to_string(),
+        )))],
+    );
+    thread.execute().is_ok()
+}
+
+/// Writes a class `class_name` with a single method `public static int run()`, for bytecode
+/// javac never emits.
+///
+/// `stack_map_table` holds the entries of the StackMapTable, which is left out if empty.
+/// Constant #9 is the int 40 and #10 the long `(1 << 40) + 2`, for the ldc instructions.
+#[allow(dead_code)]
+pub fn write_class_file(
+    class_name: &str,
+    max_stack: u16,
+    max_locals: u16,
+    code: &[u8],
+    stack_map_table: &[&[u8]],
+) {
+    fn utf8(constant: &str) -> Vec<u8> {
+        let mut bytes = vec![1];
+        bytes.extend((constant.len() as u16).to_be_bytes());
+        bytes.extend(constant.as_bytes());
+        bytes
+    }
+
+    let mut class = vec![];
+    class.extend(0xCAFEBABE_u32.to_be_bytes());
+    // java 8
+    class.extend([0, 0, 0, 52]);
+    let constants = [
+        utf8(class_name),
+        vec![7, 0, 1],
+        utf8("java/lang/Object"),
+        vec![7, 0, 3],
+        utf8("run"),
+        utf8("()I"),
+        utf8("Code"),
+        utf8("StackMapTable"),
+        [&[3][..], &40_i32.to_be_bytes()].concat(),
+        [&[5][..], &((1_i64 << 40) + 2).to_be_bytes()].concat(),
+    ];
+    // the long takes two entries
+    class.extend((constants.len() as u16 + 2).to_be_bytes());
+    constants.iter().for_each(|c| class.extend(c));
+    // public, this class #2, super class #4, no interfaces or fields, 1 method
+    class.extend([0x00, 0x21, 0x00, 0x02, 0x00, 0x04, 0, 0, 0, 0, 0, 1]);
+    // public static int run(), 1 attribute
+    class.extend([0x00, 0x09, 0x00, 0x05, 0x00, 0x06, 0x00, 0x01]);
+
+    let mut attributes = vec![];
+    if !stack_map_table.is_empty() {
+        let entries = stack_map_table.concat();
+        attributes.extend([0x00, 0x08]);
+        attributes.extend((2 + entries.len() as u32).to_be_bytes());
+        attributes.extend((stack_map_table.len() as u16).to_be_bytes());
+        attributes.extend(entries);
+    }
+    class.extend([0x00, 0x07]);
+    class.extend((12 + code.len() as u32 + attributes.len() as u32).to_be_bytes());
+    class.extend(max_stack.to_be_bytes());
+    class.extend(max_locals.to_be_bytes());
+    class.extend((code.len() as u32).to_be_bytes());
+    class.extend(code);
+    // no exception table
+    class.extend([0, 0]);
+    class.extend((!stack_map_table.is_empty() as u16).to_be_bytes());
+    class.extend(attributes);
+    // no class attributes
+    class.extend([0, 0]);
+
+    fs::write(classes_dir().join(format!("{class_name}.class")), class).unwrap();
+}
+
+/// Rewrites the class file written by [`write_class_file`] as java 5, which loads without the
+/// type checking verifier, so the checks of the interpreter are reached.
+#[allow(dead_code)]
+pub fn downgrade_class_file(class_name: &str) {
+    let path = classes_dir().join(format!("{class_name}.class"));
+    let mut class = fs::read(&path).unwrap();
+    class[6..8].copy_from_slice(&49_u16.to_be_bytes());
+    fs::write(path, class).unwrap();
+}
+
+/// Rewrites the class file of `class_name` to extend `super_class`, which its constant pool must
+/// already name as a class, and sets or clears `ACC_SUPER`, for class hierarchies javac never
+/// emits.
+#[allow(dead_code)]
+pub fn set_super_class(class_name: &str, super_class: &str, acc_super: bool) {
+    let path = classes_dir().join(format!("{class_name}.class"));
+    let mut class = fs::read(&path).unwrap();
+    let u16_at =
+        |class: &[u8], offset: usize| u16::from_be_bytes([class[offset], class[offset + 1]]);
+
+    let mut utf8s = vec![];
+    let mut classes = vec![];
+    let mut offset = 10;
+    let mut index = 1;
+    while index < u16_at(&class, 8) {
+        let tag = class[offset];
+        offset += match tag {
+            1 => {
+                let len = u16_at(&class, offset + 1) as usize;
+                utf8s.push((index, class[offset + 3..offset + 3 + len].to_vec()));
+                3 + len
+            }
+            7 => {
+                classes.push((index, u16_at(&class, offset + 1)));
+                3
+            }
+            8 | 16 | 19 | 20 => 3,
+            15 => 4,
+            3 | 4 | 9..=12 | 17 | 18 => 5,
+            5 | 6 => 9,
+            _ => panic!("unknown constant tag {tag}"),
+        };
+        // longs and doubles take two entries
+        index += if matches!(tag, 5 | 6) { 2 } else { 1 };
+    }
+    let (super_index, _) = classes
+        .iter()
+        .find(|(_, name_index)| utf8s.contains(&(*name_index, super_class.as_bytes().to_vec())))
+        .expect("super class must be in the constant pool");
+
+    // access flags, this class, super class
+    let access_flags = (u16_at(&class, offset) & !0x0020) | if acc_super { 0x0020 } else { 0 };
+    class[offset..offset + 2].copy_from_slice(&access_flags.to_be_bytes());
+    class[offset + 4..offset + 6].copy_from_slice(&super_index.to_be_bytes());
+    fs::write(path, class).unwrap();
+}
+
+/// Compiles `source` of `class_name` onto the class path of the vm, against the classes already
+/// there, e.g. to build a class against an older version of another one.
+#[allow(dead_code)]
+pub fn compile(class_name: &str, source: &str) {
+    let sources = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("sources");
+    let source_file = sources.join(format!("{class_name}.java"));
+    fs::create_dir_all(source_file.parent().unwrap()).unwrap();
+    fs::write(&source_file, source).unwrap();
+
+    let java_home = PathBuf::from(env::var_os("JAVA_HOME").expect("JAVA_HOME must be set"));
+    let status = Command::new(java_home.join("bin/javac"))
+        .arg("-cp")
+        .arg(classes_dir())
+        .arg("-d")
+        .arg(classes_dir())
+        .arg(&source_file)
+        .status()
+        .expect("cannot run javac");
+    assert!(status.success(), "javac failed");
+}
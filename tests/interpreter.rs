@@ -0,0 +1,874 @@
+mod common;
+
+use jvm::{
+    descriptor::parse_method_descriptor,
+    runtime::{self, Exception, NativeVariable},
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn test_invoke_static_long_args() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("InvokeStaticLong"));
+}
+
+#[test]
+fn test_invoke_virtual_long_args() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("InvokeVirtualLong"));
+}
+
+#[test]
+fn test_invoke_interface_long_args() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("InvokeInterfaceLong"));
+}
+
+#[test]
+fn test_iinc() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("Increments"));
+}
+
+#[test]
+fn test_shifts() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // only the low 5 bits of the distance count for ints, and the low 6 bits for longs
+    let distances = [0, 1, 31, 32, 33, 40, 63, 64, 65, -1];
+
+    let (_, descriptor) = parse_method_descriptor("(II)I").unwrap();
+    for method in ["ishl", "ishr", "iushr"] {
+        for v in [1, -8, i32::MIN] {
+            for s in distances {
+                let expected = match method {
+                    "ishl" => v.wrapping_shl(s as u32),
+                    "ishr" => v.wrapping_shr(s as u32),
+                    _ => (v as u32).wrapping_shr(s as u32) as i32,
+                };
+                let args = [NativeVariable::Int(v), NativeVariable::Int(s)];
+                let ret = vm.invoke_static("Shifts", method, &descriptor, &args);
+                let ret = ret.unwrap().unwrap().get_int();
+                assert_eq!(ret, expected, "{method}({v}, {s})");
+            }
+        }
+    }
+
+    let (_, descriptor) = parse_method_descriptor("(JI)J").unwrap();
+    for method in ["lshl", "lshr", "lushr"] {
+        for v in [1, -8, i64::MIN] {
+            for s in distances {
+                let expected = match method {
+                    "lshl" => v.wrapping_shl(s as u32),
+                    "lshr" => v.wrapping_shr(s as u32),
+                    _ => (v as u64).wrapping_shr(s as u32) as i64,
+                };
+                let args = [NativeVariable::Long(v), NativeVariable::Int(s)];
+                let ret = vm.invoke_static("Shifts", method, &descriptor, &args);
+                let ret = ret.unwrap().unwrap().get_long();
+                assert_eq!(ret, expected, "{method}({v}, {s})");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_object_get_class() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("ObjectGetClass"));
+}
+
+#[test]
+fn test_nan_compare() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    let (_, descriptor) = parse_method_descriptor("()Z").unwrap();
+    for (method, expected) in [
+        ("floatEq", false),
+        ("floatNe", true),
+        ("floatLt", false),
+        ("floatGt", false),
+        ("doubleEq", false),
+        ("doubleNe", true),
+    ] {
+        let ret = vm.invoke_static("NanCompare", method, &descriptor, &[]);
+        assert_eq!(ret.unwrap().unwrap().get_boolean(), expected, "{method}");
+    }
+}
+
+#[test]
+fn test_integer_strings() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let ret = vm.invoke_static("IntegerStrings", "parse", &descriptor, &[]);
+    assert_eq!(ret.unwrap().unwrap().get_int(), 123);
+
+    let (_, descriptor) = parse_method_descriptor("()Z").unwrap();
+    for method in ["valueOf", "minValue"] {
+        let ret = vm.invoke_static("IntegerStrings", method, &descriptor, &[]);
+        assert!(ret.unwrap().unwrap().get_boolean(), "{method}");
+    }
+
+    let (_, descriptor) = parse_method_descriptor("(I)I").unwrap();
+    for i in [0, 123, -45, i32::MAX, i32::MIN] {
+        let ret = vm.invoke_static(
+            "IntegerStrings",
+            "roundTrip",
+            &descriptor,
+            &[NativeVariable::Int(i)],
+        );
+        assert_eq!(ret.unwrap().unwrap().get_int(), i);
+    }
+
+    let (_, descriptor) = parse_method_descriptor("(II)I").unwrap();
+    for (a, b) in [(0, i32::MIN), (i32::MIN, 1), (i32::MAX, -1)] {
+        let args = [NativeVariable::Int(a), NativeVariable::Int(b)];
+        let ret = vm.invoke_static("IntegerStrings", "subtract", &descriptor, &args);
+        let ret = ret.unwrap().unwrap().get_int();
+        assert_eq!(ret, a.wrapping_sub(b), "{a} - {b}");
+    }
+    let (_, descriptor) = parse_method_descriptor("(JJ)J").unwrap();
+    for (a, b) in [(0, i64::MIN), (i64::MIN, 1), (i64::MAX, -1)] {
+        let args = [NativeVariable::Long(a), NativeVariable::Long(b)];
+        let ret = vm.invoke_static("IntegerStrings", "subtractLong", &descriptor, &args);
+        let ret = ret.unwrap().unwrap().get_long();
+        assert_eq!(ret, a.wrapping_sub(b), "{a} - {b}");
+    }
+}
+
+#[test]
+fn test_float_remainder() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // the result of a % b in JLS 15.17.3, the sign of a finite result is the sign of a
+    let cases = [
+        (5.0, 0.0, f64::NAN),
+        (f64::INFINITY, 2.0, f64::NAN),
+        (f64::NEG_INFINITY, f64::INFINITY, f64::NAN),
+        (f64::NAN, 1.0, f64::NAN),
+        (1.0, f64::NAN, f64::NAN),
+        (5.0, f64::INFINITY, 5.0),
+        (-0.0, 2.0, -0.0),
+        (0.0, f64::NEG_INFINITY, 0.0),
+        (5.5, -2.0, 1.5),
+        (-5.0, 3.0, -2.0),
+        (-5.0, -3.0, -2.0),
+    ];
+
+    let (_, descriptor) = parse_method_descriptor("(FF)F").unwrap();
+    for (a, b, expected) in cases {
+        let args = [
+            NativeVariable::Float(a as f32),
+            NativeVariable::Float(b as f32),
+        ];
+        let ret = vm.invoke_static("Remainder", "frem", &descriptor, &args);
+        let ret = ret.unwrap().unwrap().get_float();
+        if expected.is_nan() {
+            assert!(ret.is_nan(), "{a} % {b}");
+        } else {
+            assert_eq!(ret.to_bits(), (expected as f32).to_bits(), "{a} % {b}");
+        }
+    }
+
+    let (_, descriptor) = parse_method_descriptor("(DD)D").unwrap();
+    for (a, b, expected) in cases {
+        let args = [NativeVariable::Double(a), NativeVariable::Double(b)];
+        let ret = vm.invoke_static("Remainder", "drem", &descriptor, &args);
+        let ret = ret.unwrap().unwrap().get_double();
+        if expected.is_nan() {
+            assert!(ret.is_nan(), "{a} % {b}");
+        } else {
+            assert_eq!(ret.to_bits(), expected.to_bits(), "{a} % {b}");
+        }
+    }
+}
+
+#[test]
+fn test_narrowing_conversions() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    let invoke = |method: &str, descriptor: &str, arg: NativeVariable| {
+        let (_, descriptor) = parse_method_descriptor(descriptor).unwrap();
+        let ret = vm.invoke_static("Conversions", method, &descriptor, &[arg]);
+        ret.unwrap().unwrap()
+    };
+
+    // (value, to int, to long), rounded toward zero and saturated, NaN is 0
+    let cases = [
+        (f64::NAN, 0, 0),
+        (f64::INFINITY, i32::MAX, i64::MAX),
+        (f64::NEG_INFINITY, i32::MIN, i64::MIN),
+        (1e20, i32::MAX, i64::MAX),
+        (-1e20, i32::MIN, i64::MIN),
+        (1e30, i32::MAX, i64::MAX),
+        (-1e30, i32::MIN, i64::MIN),
+        (3e9, i32::MAX, 3_000_000_000),
+        (-3e9, i32::MIN, -3_000_000_000),
+        (2.75, 2, 2),
+        (-2.75, -2, -2),
+        (-0.0, 0, 0),
+    ];
+    for (value, int, long) in cases {
+        let float = || NativeVariable::Float(value as f32);
+        let double = || NativeVariable::Double(value);
+        assert_eq!(invoke("f2i", "(F)I", float()).get_int(), int, "f2i {value}");
+        assert_eq!(
+            invoke("f2l", "(F)J", float()).get_long(),
+            long,
+            "f2l {value}"
+        );
+        assert_eq!(
+            invoke("d2i", "(D)I", double()).get_int(),
+            int,
+            "d2i {value}"
+        );
+        assert_eq!(
+            invoke("d2l", "(D)J", double()).get_long(),
+            long,
+            "d2l {value}"
+        );
+    }
+
+    // the low 32 bits
+    for (value, int) in [
+        (1_i64 << 32, 0),
+        ((1 << 32) + 5, 5),
+        (i64::MAX, -1),
+        (i64::MIN, 0),
+        (-1, -1),
+        (0x8000_0000, i32::MIN),
+    ] {
+        let long = NativeVariable::Long(value);
+        assert_eq!(invoke("l2i", "(J)I", long).get_int(), int, "l2i {value}");
+    }
+}
+
+#[test]
+fn test_thread_invoke_static() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    let (_, descriptor) = parse_method_descriptor("(II)I").unwrap();
+    let mut thread = runtime::Thread::new(1024);
+    let args = [NativeVariable::Int(40), NativeVariable::Int(2)];
+    let ret = thread.invoke_static("VmExample", "add", &descriptor, &args);
+    assert_eq!(ret.unwrap().unwrap().get_int(), 42);
+}
+
+#[test]
+fn test_stack_size() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let depth = |max_frame_size| {
+        let mut thread = runtime::Thread::new(max_frame_size);
+        let ret = thread.invoke_static("StackDepth", "run", &descriptor, &[]);
+        ret.unwrap().unwrap().get_int()
+    };
+
+    let small = depth(4096);
+    assert!(small > 1000, "{small}");
+    // each frame of recurse takes 2 slots of operand stack, and the frames below are the same
+    assert_eq!(depth(8192), small + 2048);
+}
+
+#[test]
+fn test_thread_locals() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    let (_, get) = parse_method_descriptor("()I").unwrap();
+    let (_, set) = parse_method_descriptor("(I)V").unwrap();
+    let (_, remove) = parse_method_descriptor("()V").unwrap();
+    // each runtime thread has its own thread object, so its own values
+    let mut first = runtime::Thread::new(1024);
+    let mut second = runtime::Thread::new(1024);
+    let call = |thread: &mut runtime::Thread, method, descriptor, args: &[NativeVariable]| {
+        thread
+            .invoke_static("ThreadLocals", method, descriptor, args)
+            .unwrap()
+            .map(|ret| ret.get_int())
+    };
+
+    assert_eq!(call(&mut first, "get", &get, &[]), Some(-1));
+    call(&mut first, "set", &set, &[NativeVariable::Int(1)]);
+    assert_eq!(call(&mut second, "get", &get, &[]), Some(-1));
+    call(&mut second, "set", &set, &[NativeVariable::Int(2)]);
+    assert_eq!(call(&mut first, "get", &get, &[]), Some(1));
+    assert_eq!(call(&mut second, "get", &get, &[]), Some(2));
+
+    call(&mut first, "remove", &remove, &[]);
+    assert_eq!(call(&mut first, "get", &get, &[]), Some(-1));
+    assert_eq!(call(&mut second, "get", &get, &[]), Some(2));
+
+    // initial values are created per thread too
+    for expected in 1..=3 {
+        assert_eq!(call(&mut first, "count", &get, &[]), Some(expected));
+    }
+    assert_eq!(call(&mut second, "count", &get, &[]), Some(1));
+}
+
+#[test]
+fn test_vm_exception_message() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("ExceptionMessage"));
+}
+
+#[test]
+fn test_dup_and_pop_forms() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("DupForms"));
+}
+
+#[test]
+fn test_local_index_out_of_max_locals() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // iload 3; ireturn, which javac never emits with max_locals 1
+    common::write_class_file("BadLocals", 1, 1, &[0x15, 0x03, 0xAC], &[]);
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let Err(Exception::VmException {
+        exception_type,
+        message,
+    }) = vm.invoke_static("BadLocals", "run", &descriptor, &[])
+    else {
+        panic!("must throw vm exception");
+    };
+    assert_eq!(exception_type.class_name(), "java/lang/VerifyError");
+    assert!(message.starts_with("BadLocals.run @"), "{message}");
+    assert!(
+        message.ends_with("local variable index 3 out of max_locals 1"),
+        "{message}"
+    );
+}
+
+#[test]
+fn test_return_after_handler() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // the handler discards the operand stack but the return address under it
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let ret = vm.invoke_static("HandlerStack", "run", &descriptor, &[]);
+    assert_eq!(ret.unwrap().unwrap().get_int(), 42);
+}
+
+#[test]
+fn test_vm_exception_location() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // iconst_1; istore_0; iload_0; iload 3; iadd; ireturn, the error is at the second load
+    let code = [0x04, 0x3B, 0x1A, 0x15, 0x03, 0x60, 0xAC];
+    common::write_class_file("BadLocalsAt", 2, 1, &code, &[]);
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let Err(Exception::VmException {
+        exception_type,
+        message,
+    }) = vm.invoke_static("BadLocalsAt", "run", &descriptor, &[])
+    else {
+        panic!("must throw vm exception");
+    };
+    assert_eq!(exception_type.class_name(), "java/lang/VerifyError");
+    assert_eq!(
+        message,
+        "BadLocalsAt.run @3: local variable index 3 out of max_locals 1"
+    );
+}
+
+#[test]
+fn test_branch_out_of_code() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // iconst_0; ifeq 101; iconst_1; ireturn
+    common::write_class_file(
+        "BadBranch",
+        1,
+        0,
+        &[0x03, 0x99, 0x00, 0x64, 0x04, 0xAC],
+        &[],
+    );
+    common::downgrade_class_file("BadBranch");
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let Err(Exception::VmException {
+        exception_type,
+        message,
+    }) = vm.invoke_static("BadBranch", "run", &descriptor, &[])
+    else {
+        panic!("must throw vm exception");
+    };
+    assert_eq!(exception_type.class_name(), "java/lang/VerifyError");
+    assert_eq!(
+        message,
+        "BadBranch.run @1: illegal target of jump or branch"
+    );
+}
+
+#[test]
+fn test_wide_without_wide_form() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // wide nop; iconst_1; ireturn
+    let code = [0xC4, 0x00, 0x04, 0xAC];
+    common::write_class_file("WideNop", 1, 0, &code, &[]);
+    common::write_class_file("OldWideNop", 1, 0, &code, &[]);
+    common::downgrade_class_file("OldWideNop");
+
+    // rejected by the verifier when defined, or by the interpreter for old class files
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    for class_name in ["WideNop", "OldWideNop"] {
+        let Err(Exception::VmException {
+            exception_type,
+            message,
+        }) = vm.invoke_static(class_name, "run", &descriptor, &[])
+        else {
+            panic!("{class_name} must throw vm exception");
+        };
+        assert_eq!(exception_type.class_name(), "java/lang/VerifyError");
+        assert_eq!(
+            message,
+            format!("{class_name}.run @0: illegal instruction after wide")
+        );
+    }
+}
+
+#[test]
+fn test_jsr_ret() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // a finally block as javac before java 6 compiled it, into a subroutine:
+    // bipush 40; istore_0; jsr 8; iload_0; ireturn; 8: astore_1; iinc 0 2; ret 1
+    let code = [
+        0x10, 0x28, 0x3B, 0xA8, 0x00, 0x05, 0x1A, 0xAC, 0x4C, 0x84, 0x00, 0x02, 0xA9, 0x01,
+    ];
+    common::write_class_file("JsrFinally", 1, 2, &code, &[]);
+    // iconst_1; istore_0; ret 0
+    common::write_class_file("RetInt", 1, 1, &[0x04, 0x3B, 0xA9, 0x00], &[]);
+    // ret 1, before anything is stored
+    common::write_class_file("RetUnset", 1, 2, &[0xA9, 0x01], &[]);
+    // jsr and ret are only allowed without the type checking verifier
+    for class_name in ["JsrFinally", "RetInt", "RetUnset"] {
+        common::downgrade_class_file(class_name);
+    }
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let ret = vm.invoke_static("JsrFinally", "run", &descriptor, &[]);
+    assert_eq!(ret.unwrap().unwrap().get_int(), 42);
+
+    for (class_name, expected) in [
+        (
+            "RetInt",
+            "RetInt.run @2: local variable 0 does not hold a return address",
+        ),
+        (
+            "RetUnset",
+            "RetUnset.run @0: local variable 1 does not hold a return address",
+        ),
+    ] {
+        let Err(Exception::VmException {
+            exception_type,
+            message,
+        }) = vm.invoke_static(class_name, "run", &descriptor, &[])
+        else {
+            panic!("{class_name} must throw vm exception");
+        };
+        assert_eq!(exception_type.class_name(), "java/lang/VerifyError");
+        assert_eq!(message, expected);
+    }
+}
+
+#[test]
+fn test_ldc_constant_category() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // ldc2_w #10; ldc #9; i2l; ladd; l2i; ireturn
+    let code = [0x14, 0x00, 0x0A, 0x12, 0x09, 0x85, 0x61, 0x88, 0xAC];
+    common::write_class_file("LdcCategory", 4, 0, &code, &[]);
+    // ldc #10; ireturn
+    common::write_class_file("LdcLong", 1, 0, &[0x12, 0x0A, 0xAC], &[]);
+    common::downgrade_class_file("LdcLong");
+    // ldc2_w #9; l2i; ireturn
+    common::write_class_file("Ldc2Int", 2, 0, &[0x14, 0x00, 0x09, 0x88, 0xAC], &[]);
+    common::downgrade_class_file("Ldc2Int");
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let ret = vm.invoke_static("LdcCategory", "run", &descriptor, &[]);
+    assert_eq!(ret.unwrap().unwrap().get_int(), 42);
+
+    for (class_name, expected) in [
+        ("LdcLong", "LdcLong.run @0: illegal constant type for ldc"),
+        (
+            "Ldc2Int",
+            "Ldc2Int.run @0: illegal constant type for ldc2_w",
+        ),
+    ] {
+        let Err(Exception::VmException {
+            exception_type,
+            message,
+        }) = vm.invoke_static(class_name, "run", &descriptor, &[])
+        else {
+            panic!("{class_name} must throw vm exception");
+        };
+        assert_eq!(exception_type.class_name(), "java/lang/VerifyError");
+        assert_eq!(message, expected);
+    }
+}
+
+#[test]
+fn test_dup_category_forms() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // the long constant #10 is (1 << 40) + 2, its l2i is 2
+    let forms: [(&str, &[u8], i32); 4] = [
+        // dup_x2 form 2, an int above a long: ldc2_w #10; bipush 40; dup_x2; pop; l2i; iadd;
+        // ireturn
+        (
+            "DupX2Long",
+            &[0x14, 0x00, 0x0A, 0x10, 0x28, 0x5B, 0x57, 0x88, 0x60, 0xAC],
+            42,
+        ),
+        // dup2_x2 form 2, a long above two ints: bipush 40; iconst_0; ldc2_w #10; dup2_x2;
+        // pop2; pop; istore_0; l2i; iload_0; iadd; ireturn
+        (
+            "Dup2X2LongInts",
+            &[
+                0x10, 0x28, 0x03, 0x14, 0x00, 0x0A, 0x5E, 0x58, 0x57, 0x3B, 0x88, 0x1A, 0x60, 0xAC,
+            ],
+            42,
+        ),
+        // dup2_x2 form 3, two ints above a long: ldc2_w #10; bipush 40; iconst_2; dup2_x2;
+        // isub; istore_0; l2i; iadd; isub; iload_0; iadd; ireturn
+        (
+            "Dup2X2IntsLong",
+            &[
+                0x14, 0x00, 0x0A, 0x10, 0x28, 0x05, 0x5E, 0x64, 0x3B, 0x88, 0x60, 0x64, 0x1A, 0x60,
+                0xAC,
+            ],
+            (40 - (2 + 2)) + (40 - 2),
+        ),
+        // dup2_x2 form 4, a long above a long: ldc2_w #10; lconst_1; dup2_x2; ladd; lsub; l2i;
+        // ireturn
+        (
+            "Dup2X2Longs",
+            &[0x14, 0x00, 0x0A, 0x0A, 0x5E, 0x61, 0x65, 0x88, 0xAC],
+            -2,
+        ),
+    ];
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    for (class_name, code, expected) in forms {
+        common::write_class_file(class_name, 6, 1, code, &[]);
+        let ret = vm.invoke_static(class_name, "run", &descriptor, &[]);
+        assert_eq!(ret.unwrap().unwrap().get_int(), expected, "{class_name}");
+    }
+}
+
+#[test]
+fn test_boolean_array_store() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // a = new T[3]; a[0] = 6; a[1] = 7; a[2] = -1; return a[0] + 2 * a[1] + 4 * a[2], which javac
+    // never emits for boolean[]:
+    // iconst_3; newarray atype; astore_0; aload_0; iconst_0; bipush 6; bastore; aload_0; iconst_1;
+    // bipush 7; bastore; aload_0; iconst_2; iconst_m1; bastore; aload_0; iconst_0; baload;
+    // aload_0; iconst_1; baload; iconst_2; imul; iadd; aload_0; iconst_2; baload; iconst_4; imul;
+    // iadd; ireturn
+    let code = |atype| {
+        [
+            0x06, 0xBC, atype, 0x4B, 0x2A, 0x03, 0x10, 0x06, 0x54, 0x2A, 0x04, 0x10, 0x07, 0x54,
+            0x2A, 0x05, 0x02, 0x54, 0x2A, 0x03, 0x33, 0x2A, 0x04, 0x33, 0x05, 0x68, 0x60, 0x2A,
+            0x05, 0x33, 0x07, 0x68, 0x60, 0xAC,
+        ]
+    };
+    // only the lowest bit is stored into a boolean[], a byte[] keeps the low 8 bits
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    for (class_name, atype, expected) in [("BooleanStore", 4, 6), ("ByteStore", 8, 16)] {
+        common::write_class_file(class_name, 3, 1, &code(atype), &[]);
+        let ret = vm.invoke_static(class_name, "run", &descriptor, &[]);
+        assert_eq!(ret.unwrap().unwrap().get_int(), expected, "{class_name}");
+    }
+}
+
+#[test]
+fn test_array_copy() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("ArrayCopy"));
+}
+
+#[test]
+fn test_array_bounds() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("ArrayBounds"));
+}
+
+#[test]
+fn test_field_layout() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("FieldLayout"));
+}
+
+#[test]
+fn test_object_to_string() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("ObjectToString"));
+}
+
+#[test]
+fn test_string_intern() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("StringIntern"));
+}
+
+#[test]
+fn test_string_concat() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("StringConcat"));
+}
+
+#[test]
+fn test_string_hash_code() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("StringHashCode"));
+}
+
+#[test]
+fn test_string_bytes() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("StringBytes"));
+}
+
+#[test]
+fn test_system_properties() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("SystemProperties"));
+}
+
+#[test]
+fn test_enclosing_class() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("EnclosingClasses"));
+}
+
+#[test]
+fn test_enums() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    // TODO: Enum.valueOf, it reads the enum constants through Class.reflectionData, which is held
+    // by a SoftReference that cannot be initialized without Thread.currentThread
+    assert!(common::run_main("Enums"));
+}
+
+#[test]
+fn test_nestmate_private_access() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("Nestmates"));
+}
+
+#[test]
+fn test_default_methods() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("DefaultMethods"));
+}
+
+#[test]
+fn test_current_thread() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("CurrentThread"));
+}
+
+#[test]
+fn test_switch_padding() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("Switches"));
+}
+
+#[test]
+fn test_timed_wait() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("TimedWait"));
+
+    let (_, descriptor) = parse_method_descriptor("(J)V").unwrap();
+    let start = Instant::now();
+    let ret = vm.invoke_static(
+        "TimedWait",
+        "waitMillis",
+        &descriptor,
+        &[NativeVariable::Long(50)],
+    );
+    assert!(ret.unwrap().is_none());
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn test_wait_until_notified() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    let (_, descriptor) = parse_method_descriptor("()V").unwrap();
+    let waiter = {
+        let descriptor = descriptor.clone();
+        thread::spawn(move || {
+            vm.invoke_static("TimedWait", "waitUntilNotified", &descriptor, &[])
+                .is_ok()
+        })
+    };
+    // a timeout of 0 waits until notified, however long it takes
+    thread::sleep(Duration::from_millis(100));
+    assert!(!waiter.is_finished());
+
+    let ret = vm.invoke_static("TimedWait", "notifyWaiter", &descriptor, &[]);
+    assert!(ret.unwrap().is_none());
+    assert!(waiter.join().unwrap());
+}
+
+#[test]
+fn test_thread_join() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("ThreadJoin"));
+}
+
+#[test]
+fn test_synchronized_methods() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    let (_, fail) = parse_method_descriptor("()V").unwrap();
+    // from another thread, which would keep owning the monitor if it was not exited
+    let failed = thread::spawn(move || {
+        vm.invoke_static("SynchronizedMethods", "fail", &fail, &[])
+            .is_err()
+    });
+    assert!(failed.join().unwrap());
+
+    let (_, add) = parse_method_descriptor("(I)V").unwrap();
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let add = add.clone();
+            thread::spawn(move || {
+                for method in ["addStatic", "addShared"] {
+                    let ret = vm.invoke_static(
+                        "SynchronizedMethods",
+                        method,
+                        &add,
+                        &[NativeVariable::Int(10_000)],
+                    );
+                    assert!(ret.unwrap().is_none());
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let (_, counts) = parse_method_descriptor("()I").unwrap();
+    let ret = vm.invoke_static("SynchronizedMethods", "counts", &counts, &[]);
+    assert_eq!(ret.unwrap().unwrap().get_int(), 80_000);
+}
+
+// cargo test --release --test interpreter -- --ignored --nocapture bench_tight_loop
+#[test]
+#[ignore]
+fn bench_tight_loop() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    let n = 10_000_000;
+    let expected = (0..n).fold(0_i32, |sum, i: i32| {
+        let sum = sum.wrapping_add(i.wrapping_mul(31) ^ (sum as u32 >> 3) as i32);
+        if i & 7 == 0 { sum.wrapping_sub(i) } else { sum }
+    });
+
+    let (_, descriptor) = parse_method_descriptor("(I)I").unwrap();
+    let start = Instant::now();
+    let ret = vm.invoke_static("TightLoop", "sum", &descriptor, &[NativeVariable::Int(n)]);
+    let elapsed = start.elapsed();
+    assert_eq!(ret.unwrap().unwrap().get_int(), expected);
+    eprintln!("TightLoop.sum({n}) took {elapsed:?}");
+}
+
+// cargo test --release --test interpreter -- --ignored --nocapture bench_new_arrays
+#[test]
+#[ignore]
+fn bench_new_arrays() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    let n = 1_000_000;
+    let (_, descriptor) = parse_method_descriptor("(I)I").unwrap();
+    let start = Instant::now();
+    let ret = vm.invoke_static(
+        "TightLoop",
+        "newArrays",
+        &descriptor,
+        &[NativeVariable::Int(n)],
+    );
+    let elapsed = start.elapsed();
+    assert_eq!(ret.unwrap().unwrap().get_int(), 4 * n);
+    eprintln!("TightLoop.newArrays({n}) took {elapsed:?}");
+}
@@ -0,0 +1,81 @@
+import java.lang.reflect.Array;
+
+public class ArrayAccess {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    static String outOfBounds(Object array, int index) {
+        try {
+            Array.get(array, index);
+        } catch (ArrayIndexOutOfBoundsException e) {
+            return e.getMessage();
+        }
+        return null;
+    }
+
+    static boolean setMismatch(Object array, Object value) {
+        try {
+            Array.set(array, 0, value);
+        } catch (IllegalArgumentException e) {
+            return true;
+        }
+        return false;
+    }
+
+    static boolean getIntMismatch(Object array) {
+        try {
+            Array.getInt(array, 0);
+        } catch (IllegalArgumentException e) {
+            return true;
+        }
+        return false;
+    }
+
+    public static void main(String[] args) {
+        int[] ints = {1, 2, 3};
+        check(Array.getLength(ints) == 3, "int[] length");
+        check(Array.getInt(ints, 1) == 2, "getInt");
+        check(Array.getLong(ints, 2) == 3L, "getLong widens");
+        check(Array.getDouble(ints, 0) == 1.0, "getDouble widens");
+        Object boxed = Array.get(ints, 2);
+        check(boxed instanceof Integer && ((Integer) boxed).intValue() == 3, "get boxes");
+
+        Array.setInt(ints, 0, 10);
+        check(ints[0] == 10, "setInt");
+        // boxes are created directly, the Integer cache needs the saved properties of a booted VM
+        Array.set(ints, 1, new Integer(20));
+        check(ints[1] == 20, "set unboxes");
+        Array.set(ints, 2, new Short((short) 30));
+        check(ints[2] == 30, "set widens");
+        Array.setByte(ints, 2, (byte) 7);
+        check(ints[2] == 7, "setByte widens");
+
+        long[] longs = new long[2];
+        Array.setLong(longs, 1, 1L << 40);
+        check(longs[1] == 1L << 40, "setLong");
+        check(((Long) Array.get(longs, 1)).longValue() == 1L << 40, "get long");
+
+        boolean[] booleans = new boolean[1];
+        Array.setBoolean(booleans, 0, true);
+        check(booleans[0] && Array.getBoolean(booleans, 0), "boolean");
+
+        Object[] objects = new String[2];
+        Array.set(objects, 0, "a");
+        check("a".equals(objects[0]) && Array.get(objects, 0) == objects[0], "Object[]");
+        Array.set(objects, 1, null);
+        check(Array.get(objects, 1) == null, "null element");
+
+        check("Index 3 out of bounds for length 3".equals(outOfBounds(ints, 3)), "index 3");
+        check("Index -1 out of bounds for length 2".equals(outOfBounds(objects, -1)), "index -1");
+        check(setMismatch(objects, new Integer(1)), "Integer into String[]");
+        check(setMismatch(ints, "a"), "String into int[]");
+        check(setMismatch(ints, new Long(1)), "Long into int[]");
+        check(setMismatch(ints, null), "null into int[]");
+        check(getIntMismatch(objects), "getInt of String[]");
+        check(getIntMismatch(new long[1]), "getInt of long[]");
+        check(getIntMismatch("not an array"), "not an array");
+    }
+}
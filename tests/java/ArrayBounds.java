@@ -0,0 +1,70 @@
+public class ArrayBounds {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    static String loadInt(int[] array, int index) {
+        try {
+            int ignored = array[index];
+        } catch (ArrayIndexOutOfBoundsException e) {
+            return e.getMessage();
+        }
+        return null;
+    }
+
+    static String storeInt(int[] array, int index) {
+        try {
+            array[index] = 1;
+        } catch (ArrayIndexOutOfBoundsException e) {
+            return e.getMessage();
+        }
+        return null;
+    }
+
+    static String loadLong(long[] array, int index) {
+        try {
+            long ignored = array[index];
+        } catch (ArrayIndexOutOfBoundsException e) {
+            return e.getMessage();
+        }
+        return null;
+    }
+
+    static String storeByte(byte[] array, int index) {
+        try {
+            array[index] = 1;
+        } catch (ArrayIndexOutOfBoundsException e) {
+            return e.getMessage();
+        }
+        return null;
+    }
+
+    static String storeObject(Object[] array, int index) {
+        try {
+            array[index] = "x";
+        } catch (ArrayIndexOutOfBoundsException e) {
+            return e.getMessage();
+        }
+        return null;
+    }
+
+    public static void main(String[] args) {
+        int[] ints = {7, 8, 9};
+        check("Index -1 out of bounds for length 3".equals(loadInt(ints, -1)), "load -1");
+        check("Index 3 out of bounds for length 3".equals(loadInt(ints, 3)), "load length");
+        check("Index -2147483648 out of bounds for length 3".equals(loadInt(ints, Integer.MIN_VALUE)),
+                "load min");
+        check(loadInt(ints, 2) == null, "load in range");
+        check("Index -1 out of bounds for length 3".equals(storeInt(ints, -1)), "store -1");
+        check("Index 3 out of bounds for length 3".equals(storeInt(ints, 3)), "store length");
+        check(ints[0] == 7 && ints[1] == 8 && ints[2] == 9, "failed stores do not write");
+
+        check("Index 0 out of bounds for length 0".equals(loadLong(new long[0], 0)), "empty long");
+        check("Index -1 out of bounds for length 2".equals(loadLong(new long[2], -1)), "long -1");
+        check("Index 5 out of bounds for length 5".equals(storeByte(new byte[5], 5)), "byte length");
+        check("Index -1 out of bounds for length 1".equals(storeObject(new String[1], -1)),
+                "object -1");
+    }
+}
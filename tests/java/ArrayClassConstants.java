@@ -0,0 +1,33 @@
+public class ArrayClassConstants {
+    static boolean elementInitialized;
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        // each class literal is an ldc of a class constant naming the array type
+        check(int[].class.isArray(), "int[] is array");
+        check(int[].class == new int[0].getClass(), "int[] is the class of int arrays");
+        check(int[].class.getName().equals("[I"), "int[] name");
+        check(long[][].class.getComponentType() == long[].class, "long[][]");
+
+        check(ArrayClassElement[].class.isArray(), "ArrayClassElement[] is array");
+        check(ArrayClassElement[][].class.getComponentType() == ArrayClassElement[].class,
+                "ArrayClassElement[][]");
+        check(ArrayClassElement[].class.getComponentType() == ArrayClassElement.class,
+                "ArrayClassElement[]");
+        check(new ArrayClassElement[1][1].getClass() == ArrayClassElement[][].class,
+                "ArrayClassElement[][] is the class of its arrays");
+        // loading an array class does not initialize its element class
+        check(!elementInitialized, "ArrayClassElement is not initialized");
+    }
+}
+
+class ArrayClassElement {
+    static {
+        ArrayClassConstants.elementInitialized = true;
+    }
+}
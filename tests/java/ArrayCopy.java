@@ -0,0 +1,103 @@
+public class ArrayCopy {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    // Arrays.equals needs Unsafe
+    static boolean equals(int[] a, int... b) {
+        if (a.length != b.length) {
+            return false;
+        }
+        for (int i = 0; i < a.length; i++) {
+            if (a[i] != b[i]) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    static void expectStoreException(Object src, Object dest, String message) {
+        try {
+            System.arraycopy(src, 0, dest, 0, 1);
+        } catch (ArrayStoreException e) {
+            check(message.equals(e.getMessage()), e.getMessage());
+            return;
+        }
+        throw new AssertionError(message);
+    }
+
+    static void expectIndexException(Object src, int srcPos, Object dest, int destPos, int length,
+            String message) {
+        try {
+            System.arraycopy(src, srcPos, dest, destPos, length);
+        } catch (ArrayIndexOutOfBoundsException e) {
+            check(message.equals(e.getMessage()), e.getMessage());
+            return;
+        }
+        throw new AssertionError(message);
+    }
+
+    public static void main(String[] args) {
+        expectStoreException(new int[1], new long[1],
+                "arraycopy: type mismatch: can not copy int[] into long[]");
+        expectStoreException(new Object[1], new int[1],
+                "arraycopy: type mismatch: can not copy object array[] into int[]");
+        expectStoreException(new int[1], new Object[1],
+                "arraycopy: type mismatch: can not copy int[] into object array[]");
+        expectStoreException(new Object[] {new Object()}, new String[1],
+                "arraycopy: element type mismatch: can not cast one of the elements of "
+                        + "java.lang.Object[] to the type of the destination array, java.lang.String");
+        expectStoreException("abc", new char[3],
+                "arraycopy: source type java.lang.String is not an array");
+        expectStoreException(new char[3], "abc",
+                "arraycopy: destination type java.lang.String is not an array");
+
+        int[] ten = new int[10];
+        expectIndexException(ten, -1, ten, 0, 1,
+                "arraycopy: source index -1 out of bounds for int[10]");
+        expectIndexException(new String[2], 0, new Object[10], -2, 1,
+                "arraycopy: destination index -2 out of bounds for object array[10]");
+        expectIndexException(ten, 0, ten, 0, -1, "arraycopy: length -1 is negative");
+        expectIndexException(ten, 5, ten, 0, 6,
+                "arraycopy: last source index 11 out of bounds for int[10]");
+        expectIndexException(ten, 0, new int[3], 1, 3,
+                "arraycopy: last destination index 4 out of bounds for int[3]");
+        expectIndexException(ten, Integer.MAX_VALUE, ten, 0, 1,
+                "arraycopy: last source index 2147483648 out of bounds for int[10]");
+        // nothing to copy, even at the end
+        System.arraycopy(ten, 10, ten, 0, 0);
+
+        // the elements before the incompatible one are copied before the exception
+        Object[] mixed = {"a", "b", new Object(), "d"};
+        String[] partial = new String[4];
+        try {
+            System.arraycopy(mixed, 0, partial, 0, 4);
+            throw new AssertionError("partial copy");
+        } catch (ArrayStoreException expected) {
+        }
+        check(partial[0] == "a" && partial[1] == "b", "copied before the exception");
+        check(partial[2] == null && partial[3] == null, "not copied after the exception");
+
+        // overlapping copies within the same array, in both directions
+        int[] ints = {1, 2, 3, 4, 5};
+        System.arraycopy(ints, 0, ints, 1, 4);
+        check(equals(ints, 1, 1, 2, 3, 4), "copy forward");
+        System.arraycopy(ints, 2, ints, 0, 3);
+        check(equals(ints, 2, 3, 4, 3, 4), "copy backward");
+        String[] strings = {"a", "b", "c"};
+        System.arraycopy(strings, 0, strings, 1, 2);
+        check(strings[0] == "a" && strings[1] == "a" && strings[2] == "b", "copy references");
+
+        // into an array of a supertype
+        String[] source = {"x", null, "z"};
+        Object[] objects = new Object[4];
+        System.arraycopy(source, 0, objects, 1, 3);
+        check(objects[0] == null && objects[1] == "x" && objects[2] == null && objects[3] == "z",
+                "copy into a supertype array");
+        CharSequence[] sequences = new CharSequence[2];
+        System.arraycopy(source, 1, sequences, 0, 2);
+        check(sequences[0] == null && sequences[1] == "z", "copy into an interface array");
+    }
+}
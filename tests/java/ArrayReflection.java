@@ -0,0 +1,58 @@
+import java.lang.reflect.Array;
+
+public class ArrayReflection {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    static boolean negativeSize(Class<?> componentType, int... dimensions) {
+        try {
+            Array.newInstance(componentType, dimensions);
+        } catch (NegativeArraySizeException e) {
+            return true;
+        }
+        return false;
+    }
+
+    static boolean illegalArgument(Class<?> componentType, int... dimensions) {
+        try {
+            Array.newInstance(componentType, dimensions);
+        } catch (IllegalArgumentException e) {
+            return true;
+        }
+        return false;
+    }
+
+    public static void main(String[] args) {
+        Object ints = Array.newInstance(int.class, 3);
+        check(ints.getClass() == int[].class, "int[] class");
+        check(((int[]) ints).length == 3, "int[] length");
+
+        Object longs = Array.newInstance(long.class, 4);
+        check(((long[]) longs).length == 4 && ((long[]) longs)[3] == 0, "long[]");
+
+        Object strings = Array.newInstance(String.class, 2);
+        check(strings.getClass() == String[].class, "String[] class");
+        check(((String[]) strings)[1] == null, "String[] element");
+
+        int[][] matrix = (int[][]) Array.newInstance(int.class, 2, 3);
+        check(matrix.getClass() == int[][].class, "int[][] class");
+        check(matrix.length == 2, "outer length");
+        check(matrix[0].length == 3 && matrix[1].length == 3, "inner length");
+        matrix[1][2] = 5;
+        check(matrix[0][2] == 0, "rows are distinct");
+
+        byte[][][] cube = (byte[][][]) Array.newInstance(byte.class, 2, 1, 4);
+        check(cube[1][0].length == 4, "byte[][][]");
+
+        Object[] rows = (Object[]) Array.newInstance(int[].class, 2);
+        check(rows.getClass() == int[][].class && rows[0] == null, "int[] component");
+
+        check(negativeSize(int.class, -1), "negative length");
+        check(negativeSize(String.class, 2, -1), "negative inner length");
+        check(illegalArgument(void.class, 1), "void component");
+        check(illegalArgument(int.class), "empty dimensions");
+    }
+}
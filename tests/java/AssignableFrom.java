@@ -0,0 +1,65 @@
+import java.io.Serializable;
+import java.util.AbstractList;
+import java.util.ArrayList;
+import java.util.Collection;
+import java.util.List;
+
+public class AssignableFrom {
+    interface Shape {}
+
+    interface Polygon extends Shape {}
+
+    static class Square implements Polygon {}
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        // classes
+        check(Object.class.isAssignableFrom(String.class), "Object <- String");
+        check(!String.class.isAssignableFrom(Object.class), "String <- Object");
+        check(AbstractList.class.isAssignableFrom(ArrayList.class), "AbstractList <- ArrayList");
+
+        // interfaces, including super interfaces of implemented interfaces
+        check(Shape.class.isAssignableFrom(Square.class), "Shape <- Square");
+        check(Shape.class.isAssignableFrom(Polygon.class), "Shape <- Polygon");
+        check(!Polygon.class.isAssignableFrom(Shape.class), "Polygon <- Shape");
+        check(Collection.class.isAssignableFrom(List.class), "Collection <- List");
+        check(Object.class.isAssignableFrom(Shape.class), "Object <- Shape");
+
+        // arrays
+        check(Object[].class.isAssignableFrom(String[].class), "Object[] <- String[]");
+        check(!String[].class.isAssignableFrom(Object[].class), "String[] <- Object[]");
+        check(Shape[].class.isAssignableFrom(Square[].class), "Shape[] <- Square[]");
+        check(Object.class.isAssignableFrom(int[].class), "Object <- int[]");
+        check(Cloneable.class.isAssignableFrom(int[].class), "Cloneable <- int[]");
+        check(Serializable.class.isAssignableFrom(String[][].class), "Serializable <- String[][]");
+        check(!Object[].class.isAssignableFrom(int[].class), "Object[] <- int[]");
+        check(!long[].class.isAssignableFrom(int[].class), "long[] <- int[]");
+
+        // primitives are only assignable from themselves
+        check(int.class.isAssignableFrom(int.class), "int <- int");
+        check(!long.class.isAssignableFrom(int.class), "long <- int");
+        check(!Integer.class.isAssignableFrom(int.class), "Integer <- int");
+        check(!int.class.isAssignableFrom(Integer.class), "int <- Integer");
+        check(!Object.class.isAssignableFrom(int.class), "Object <- int");
+
+        // isInstance
+        Object square = new Square();
+        check(Shape.class.isInstance(square), "square instanceof Shape");
+        check(!String.class.isInstance(square), "square instanceof String");
+        check(!Shape.class.isInstance(null), "null instanceof Shape");
+        check(Object[].class.isInstance(new String[0]), "String[] instanceof Object[]");
+        check(!int.class.isInstance("1"), "\"1\" instanceof int");
+
+        try {
+            Object.class.isAssignableFrom(null);
+            throw new AssertionError("not thrown");
+        } catch (NullPointerException e) {
+            // expected
+        }
+    }
+}
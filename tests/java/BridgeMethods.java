@@ -0,0 +1,50 @@
+import java.lang.reflect.Method;
+import java.util.Comparator;
+
+public class BridgeMethods {
+    static class ByLength implements Comparator<String> {
+        @Override
+        public int compare(String a, String b) {
+            return a.length() - b.length();
+        }
+    }
+
+    static class Varargs {
+        static int count(String... values) {
+            return values.length;
+        }
+
+        static int fixed(String[] values) {
+            return values.length;
+        }
+    }
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) throws Exception {
+        Method typed = ByLength.class.getDeclaredMethod("compare", String.class, String.class);
+        check(!typed.isBridge(), "typed compare is not a bridge");
+        check(!typed.isSynthetic(), "typed compare is not synthetic");
+        check(!typed.isVarArgs(), "typed compare is not varargs");
+
+        Method bridge = ByLength.class.getDeclaredMethod("compare", Object.class, Object.class);
+        check(bridge.isBridge(), "erased compare is a bridge");
+        check(bridge.isSynthetic(), "erased compare is synthetic");
+        check(bridge.getReturnType() == int.class, "bridge return type");
+
+        int bridges = 0;
+        for (Method method : ByLength.class.getDeclaredMethods()) {
+            if (method.isBridge()) {
+                bridges++;
+            }
+        }
+        check(bridges == 1, "one bridge method");
+
+        check(Varargs.class.getDeclaredMethod("count", String[].class).isVarArgs(), "count is varargs");
+        check(!Varargs.class.getDeclaredMethod("fixed", String[].class).isVarArgs(), "fixed is not varargs");
+    }
+}
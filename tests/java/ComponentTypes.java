@@ -0,0 +1,23 @@
+public class ComponentTypes {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        check(int[].class.isArray(), "int[] is array");
+        check(String[].class.isArray(), "String[] is array");
+        check(!String.class.isArray(), "String is not array");
+        check(!int.class.isArray(), "int is not array");
+
+        check(int[].class.getComponentType() == int.class, "int[]");
+        check(boolean[].class.getComponentType() == boolean.class, "boolean[]");
+        check(double[].class.getComponentType() == double.class, "double[]");
+        check(String[].class.getComponentType() == String.class, "String[]");
+        check(new Object[0].getClass().getComponentType() == Object.class, "Object[]");
+        check(int[][].class.getComponentType() == int[].class, "int[][]");
+        check(String.class.getComponentType() == null, "String");
+        check(int.class.getComponentType() == null, "int");
+    }
+}
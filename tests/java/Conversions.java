@@ -0,0 +1,21 @@
+public class Conversions {
+    static int f2i(float v) {
+        return (int) v;
+    }
+
+    static long f2l(float v) {
+        return (long) v;
+    }
+
+    static int d2i(double v) {
+        return (int) v;
+    }
+
+    static long d2l(double v) {
+        return (long) v;
+    }
+
+    static int l2i(long v) {
+        return (int) v;
+    }
+}
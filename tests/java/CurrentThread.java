@@ -0,0 +1,17 @@
+public class CurrentThread {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        Thread thread = Thread.currentThread();
+        check(thread == Thread.currentThread(), "same thread object");
+        check(thread.getId() == 1, "main thread is the first thread");
+        check(thread.getName().equals("main"), "main thread name");
+        check(thread.getPriority() == Thread.NORM_PRIORITY, "main thread priority");
+        check(!thread.isDaemon(), "main thread is not daemon");
+        check(thread.getState() == Thread.State.RUNNABLE, "main thread is running");
+    }
+}
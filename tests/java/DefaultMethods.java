@@ -0,0 +1,68 @@
+public class DefaultMethods {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    interface Top {
+        default int m() {
+            return 1;
+        }
+    }
+
+    interface Left extends Top {
+        default int m() {
+            return 2;
+        }
+    }
+
+    interface Right extends Top {
+    }
+
+    // Left.m is more specific than Top.m, which Right inherits
+    static class Diamond implements Left, Right {
+    }
+
+    static class Base implements Top {
+    }
+
+    // the default method of Base is replaced by a more specific one
+    static class Sub extends Base implements Left {
+    }
+
+    static class Override extends Sub {
+        public int m() {
+            return 3;
+        }
+    }
+
+    interface Deep extends Right {
+    }
+
+    // only inherited through super interfaces of super interfaces
+    static class DeepImpl implements Deep {
+    }
+
+    public static void main(String[] args) {
+        Diamond diamond = new Diamond();
+        check(diamond.m() == 2, "invokevirtual on diamond");
+        check(((Top) diamond).m() == 2, "invokeinterface Top on diamond");
+        check(((Right) diamond).m() == 2, "invokeinterface Right on diamond");
+
+        Sub sub = new Sub();
+        check(sub.m() == 2, "invokevirtual on sub");
+        check(((Base) sub).m() == 2, "invokevirtual Base on sub");
+        check(((Top) sub).m() == 2, "invokeinterface Top on sub");
+        check(((Top) new Base()).m() == 1, "invokeinterface Top on base");
+
+        Sub override = new Override();
+        check(override.m() == 3, "invokevirtual on override");
+        check(((Base) override).m() == 3, "invokevirtual Base on override");
+        check(((Left) override).m() == 3, "invokeinterface Left on override");
+
+        DeepImpl deep = new DeepImpl();
+        check(deep.m() == 1, "invokevirtual on deep");
+        check(((Deep) deep).m() == 1, "invokeinterface Deep on deep");
+    }
+}
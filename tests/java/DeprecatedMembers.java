@@ -0,0 +1,17 @@
+@Deprecated
+public class DeprecatedMembers {
+    @Deprecated(since = "1.1", forRemoval = true)
+    public static int old(int value) {
+        return value;
+    }
+
+    /**
+     * @deprecated use {@link #current()} instead
+     */
+    @Deprecated
+    public static void older() {}
+
+    public static void current() {}
+
+    static class Kept {}
+}
@@ -0,0 +1,46 @@
+public class DupForms {
+    static long counter = 1L << 40;
+    long field = -1L;
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        // getstatic; dup2; lconst_1; ladd; putstatic: dup2 on a long
+        long old = counter++;
+        check(old == 1L << 40, "dup2 long old");
+        check(counter == (1L << 40) + 1, "dup2 long new");
+
+        // aload; iload; dup2; iaload: dup2 on two ints
+        int[] ints = {40, 2};
+        int i = 1;
+        ints[i] += ints[0];
+        check(ints[1] == 42 && ints[0] == 40, "dup2 two ints");
+
+        // aload; iload; dup2; laload: dup2 on reference and int below a long
+        long[] longs = {Long.MIN_VALUE, 7L};
+        longs[i] <<= 33;
+        check(longs[1] == 7L << 33 && longs[0] == Long.MIN_VALUE, "dup2 array long");
+
+        // dup2_x1 on a long
+        DupForms forms = new DupForms();
+        long before = forms.field--;
+        check(before == -1L && forms.field == -2L, "dup2_x1 long");
+
+        // dup_x2 with two category 1 values below
+        int assigned = ints[0] = 3;
+        check(assigned == 3 && ints[0] == 3, "dup_x2");
+
+        // dup2_x2 with a long and two category 1 values below
+        long assignedLong = longs[0] = 1L << 50;
+        check(assignedLong == 1L << 50 && longs[0] == 1L << 50, "dup2_x2");
+
+        // pop2 on a long
+        Long.valueOf(counter);
+        counter++;
+        check(counter == (1L << 40) + 2, "pop2");
+    }
+}
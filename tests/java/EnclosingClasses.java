@@ -0,0 +1,43 @@
+public class EnclosingClasses {
+    static class Member {
+    }
+
+    static Object inField = new Object() {
+    };
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    static Object anonymous() {
+        return new Object() {
+        };
+    }
+
+    static Object local() {
+        class Local {
+        }
+        return new Local();
+    }
+
+    public static void main(String[] args) {
+        check(anonymous().getClass().getEnclosingClass() == EnclosingClasses.class, "anonymous");
+        check(local().getClass().getEnclosingClass() == EnclosingClasses.class, "local");
+        // declared in <clinit>, the enclosing method is not recorded
+        check(inField.getClass().getEnclosingClass() == EnclosingClasses.class, "in field");
+        check(Member.class.getEnclosingClass() == EnclosingClasses.class, "member");
+        check(EnclosingClasses.class.getEnclosingClass() == null, "top-level");
+
+        Runnable nested = new Runnable() {
+            public void run() {
+                Object inner = new Object() {
+                };
+                check(inner.getClass().getEnclosingClass() == getClass(), "nested anonymous");
+            }
+        };
+        nested.run();
+        check(nested.getClass().getEnclosingClass() == EnclosingClasses.class, "outer anonymous");
+    }
+}
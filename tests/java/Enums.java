@@ -0,0 +1,64 @@
+public class Enums {
+    enum Color {
+        RED, GREEN, BLUE;
+
+        Color next() {
+            return values()[(ordinal() + 1) % values().length];
+        }
+    }
+
+    enum Planet {
+        MERCURY(3.303e+23) {
+            @Override
+            boolean isInner() {
+                return true;
+            }
+        },
+        JUPITER(1.9e+27);
+
+        final double mass;
+
+        Planet(double mass) {
+            this.mass = mass;
+        }
+
+        boolean isInner() {
+            return false;
+        }
+    }
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        // values() clones $VALUES filled by <clinit>
+        Color[] colors = Color.values();
+        check(colors.length == 3, "values length");
+        check(colors != Color.values(), "values copy");
+        String[] names = {"RED", "GREEN", "BLUE"};
+        for (int i = 0; i < colors.length; i++) {
+            check(colors[i].ordinal() == i, "ordinal");
+            check(colors[i].name().equals(names[i]), "name");
+        }
+        check(Color.BLUE.next() == Color.RED, "next");
+        check(Color.RED.compareTo(Color.BLUE) < 0, "compareTo");
+
+        switch (Color.GREEN) {
+            case GREEN:
+                break;
+            default:
+                throw new AssertionError("switch");
+        }
+
+        // constants with a body are anonymous subclasses
+        check(Planet.MERCURY.isInner() && !Planet.JUPITER.isInner(), "constant body");
+        check(Planet.MERCURY.getDeclaringClass() == Planet.class, "getDeclaringClass");
+        check(Planet.JUPITER.mass == 1.9e+27, "constructor argument");
+
+        check(Color.class.isEnum(), "isEnum");
+        check(!Planet.MERCURY.getClass().isEnum(), "constant body isEnum");
+    }
+}
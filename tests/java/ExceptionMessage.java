@@ -0,0 +1,16 @@
+public class ExceptionMessage {
+    static int divide(int a, int b) {
+        return a / b;
+    }
+
+    public static void main(String[] args) {
+        try {
+            divide(1, 0);
+            throw new AssertionError("not thrown");
+        } catch (ArithmeticException e) {
+            if (!"/ by zero".equals(e.getMessage())) {
+                throw new AssertionError(e.getMessage());
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+public class FieldLayout {
+    static class Mixed {
+        int first;
+        long wide;
+        int last;
+    }
+
+    static class MixedChild extends Mixed {
+        double extra;
+        int tail;
+    }
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        Mixed mixed = new Mixed();
+        check(mixed.first == 0 && mixed.wide == 0 && mixed.last == 0, "defaults");
+        mixed.first = -1;
+        mixed.wide = 0x1234567890abcdefL;
+        mixed.last = 42;
+        check(mixed.first == -1, "first");
+        check(mixed.wide == 0x1234567890abcdefL, "wide");
+        check(mixed.last == 42, "last");
+
+        MixedChild child = new MixedChild();
+        child.first = 1;
+        child.wide = -2L;
+        child.last = 3;
+        child.extra = 4.5;
+        child.tail = 5;
+        check(child.first == 1 && child.wide == -2L && child.last == 3, "inherited");
+        check(child.extra == 4.5 && child.tail == 5, "declared");
+        child.wide = Long.MIN_VALUE;
+        check(child.last == 3 && child.extra == 4.5, "neighbors of wide");
+    }
+}
@@ -0,0 +1,22 @@
+public class Finalizers {
+    static int finalized;
+
+    static class Counted {
+        @Override
+        protected void finalize() {
+            finalized++;
+        }
+    }
+
+    static class Inherited extends Counted {
+    }
+
+    static class Empty {
+        @Override
+        protected void finalize() {
+        }
+    }
+
+    static class Plain {
+    }
+}
@@ -0,0 +1,46 @@
+public class ForName {
+    static int initialized;
+
+    static class Lazy {
+        static {
+            initialized++;
+        }
+
+        static void touch() {}
+    }
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    static String notFound(String name) {
+        try {
+            Class.forName(name);
+        } catch (ClassNotFoundException e) {
+            return e.getMessage();
+        }
+        return null;
+    }
+
+    public static void main(String[] args) throws ClassNotFoundException {
+        Class<?> string = Class.forName("java.lang.String");
+        check(string == String.class, "String");
+        check("java.lang.String".equals(string.getName()), "String name");
+        check(Class.forName("[Ljava.lang.String;") == String[].class, "String[]");
+        check(Class.forName("[I") == int[].class, "int[]");
+
+        Class<?> lazy = Class.forName("ForName$Lazy", false, null);
+        check(lazy == Lazy.class, "Lazy");
+        check(initialized == 0, "not initialized");
+        check(Class.forName("ForName$Lazy", true, null) == lazy, "same class");
+        check(initialized == 1, "initialized");
+        Lazy.touch();
+        check(initialized == 1, "initialized once");
+
+        check("NoSuchClass".equals(notFound("NoSuchClass")), "missing class");
+        check("java/lang/String".equals(notFound("java/lang/String")), "slashes");
+        check("int".equals(notFound("int")), "primitive");
+    }
+}
@@ -0,0 +1,10 @@
+import java.util.List;
+import java.util.Map;
+
+public class GenericFields<T> {
+    List<String> names;
+    static Map<String, List<? extends Number>>[] cache;
+    List raw;
+    T value;
+    int count;
+}
@@ -0,0 +1,19 @@
+public class HandlerStack {
+    static int thrower() {
+        throw new IllegalStateException();
+    }
+
+    // 1 is on the operand stack when the exception is thrown
+    static int caught() {
+        try {
+            return 1 + thrower();
+        } catch (IllegalStateException e) {
+            return 2;
+        }
+    }
+
+    // returned into once caught() has handled the exception
+    static int run() {
+        return 40 + caught();
+    }
+}
@@ -0,0 +1,31 @@
+public class Increments {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        // javac compiles these into iinc, with a signed byte constant
+        int i = 10;
+        i -= 3;
+        check(i == 7, "iinc -3");
+        i += -128;
+        check(i == -121, "iinc -128");
+        // and into wide iinc, with a signed short constant
+        i -= 200;
+        check(i == -321, "wide iinc -200");
+        i += 1000;
+        check(i == 679, "wide iinc 1000");
+
+        int max = Integer.MAX_VALUE;
+        max++;
+        check(max == Integer.MIN_VALUE, "iinc overflow");
+
+        int counted = 0;
+        for (int j = 100; j > 0; j -= 7) {
+            counted++;
+        }
+        check(counted == 15, "count down");
+    }
+}
@@ -0,0 +1,26 @@
+public class IntegerStrings {
+    static int parse() {
+        return Integer.parseInt("123");
+    }
+
+    static boolean valueOf() {
+        return String.valueOf(123).equals("123");
+    }
+
+    static int roundTrip(int i) {
+        return Integer.parseInt(String.valueOf(i));
+    }
+
+    static boolean minValue() {
+        return Integer.toString(Integer.MIN_VALUE).equals("-2147483648");
+    }
+
+    // formatting MIN_VALUE works on negative values so it never overflows, these do
+    static int subtract(int a, int b) {
+        return a - b;
+    }
+
+    static long subtractLong(long a, long b) {
+        return a - b;
+    }
+}
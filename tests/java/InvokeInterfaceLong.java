@@ -0,0 +1,55 @@
+public class InvokeInterfaceLong {
+    interface Adder {
+        long add(long a, double b);
+
+        default long addTwice(long a, double b) {
+            return 2 * add(a, b);
+        }
+    }
+
+    static class Plain implements Adder {
+        final int base;
+
+        Plain(int base) {
+            this.base = base;
+        }
+
+        @Override
+        public long add(long a, double b) {
+            return base + a + (long) b;
+        }
+    }
+
+    static class Halved extends Plain {
+        Halved(int base) {
+            super(base);
+        }
+
+        @Override
+        public long addTwice(long a, double b) {
+            Adder adder = this;
+            return adder.add(a, b);
+        }
+    }
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        // the receiver is below four slots of arguments, not two
+        Adder[] adders = {new Plain(1), new Halved(1)};
+        check(adders[0].add(1L << 40, 2.0) == (1L << 40) + 3, "add");
+        check(adders[0].addTwice(1L << 40, 2.0) == 2 * ((1L << 40) + 3), "default addTwice");
+        check(adders[1].addTwice(1L << 40, 2.0) == (1L << 40) + 3, "overridden addTwice");
+
+        Adder nothing = null;
+        try {
+            nothing.add(0, 0);
+            throw new AssertionError("null receiver");
+        } catch (NullPointerException expected) {
+        }
+    }
+}
@@ -0,0 +1,28 @@
+public class InvokeStaticLong {
+    static long sum(long a, long b) {
+        return a + b;
+    }
+
+    static long scaled(int factor, long a, long b) {
+        // the factor stays on the caller's stack below the arguments of sum
+        return factor * sum(a, b);
+    }
+
+    public static void main(String[] args) {
+        long acc = 0;
+        for (int i = 0; i < 1000; i++) {
+            acc = sum(acc, i);
+        }
+        if (acc != 499500L) {
+            throw new AssertionError(acc);
+        }
+
+        long scaledAcc = 0;
+        for (int i = 0; i < 1000; i++) {
+            scaledAcc = sum(scaledAcc, scaled(3, 1L << 40, -i));
+        }
+        if (scaledAcc != 3 * (1000 * (1L << 40) - 499500L)) {
+            throw new AssertionError(scaledAcc);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+public class InvokeVirtualLong {
+    final long base;
+
+    InvokeVirtualLong(long base) {
+        this.base = base;
+    }
+
+    long add(long a, double b) {
+        return base + a + (long) b;
+    }
+
+    static class Doubled extends InvokeVirtualLong {
+        Doubled(long base) {
+            super(base);
+        }
+
+        @Override
+        long add(long a, double b) {
+            return 2 * super.add(a, b);
+        }
+    }
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        // the receiver is below four slots of arguments, not two
+        InvokeVirtualLong[] receivers = {new InvokeVirtualLong(1), new Doubled(1)};
+        check(receivers[0].add(1L << 40, 2.0) == (1L << 40) + 3, "add");
+        check(receivers[1].add(1L << 40, 2.0) == 2 * ((1L << 40) + 3), "overridden add");
+
+        InvokeVirtualLong nothing = null;
+        try {
+            nothing.add(0, 0);
+            throw new AssertionError("null receiver");
+        } catch (NullPointerException expected) {
+        }
+    }
+}
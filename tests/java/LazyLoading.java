@@ -0,0 +1,12 @@
+public class LazyLoading extends LazyLoadingBase {
+    // never run, so LazyLoadingUnused is never resolved
+    static Object unused() {
+        return new LazyLoadingUnused();
+    }
+}
+
+class LazyLoadingBase {
+}
+
+class LazyLoadingUnused {
+}
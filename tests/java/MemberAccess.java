@@ -0,0 +1,29 @@
+package access;
+
+// callers outside of the package are compiled against a version with every member public, see
+// test_member_access
+public class MemberAccess {
+    private static int secret = 4;
+
+    public static int open() {
+        return 1;
+    }
+
+    protected static int guarded() {
+        return 2;
+    }
+
+    static int local() {
+        return 3;
+    }
+
+    public static int fromPackage() {
+        return MemberAccessNeighbor.read();
+    }
+}
+
+class MemberAccessNeighbor {
+    static int read() {
+        return MemberAccess.guarded() + MemberAccess.local();
+    }
+}
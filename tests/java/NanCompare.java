@@ -0,0 +1,31 @@
+public class NanCompare {
+    static boolean floatEq() {
+        float a = 0.0f / 0.0f;
+        return a == a;
+    }
+
+    static boolean floatNe() {
+        float a = 0.0f / 0.0f;
+        return a != a;
+    }
+
+    static boolean floatLt() {
+        float a = 0.0f / 0.0f;
+        return a < 1.0f;
+    }
+
+    static boolean floatGt() {
+        float a = 0.0f / 0.0f;
+        return a > 1.0f;
+    }
+
+    static boolean doubleEq() {
+        double a = 0.0 / 0.0;
+        return a == a;
+    }
+
+    static boolean doubleNe() {
+        double a = 0.0 / 0.0;
+        return a != a;
+    }
+}
@@ -0,0 +1,32 @@
+import java.util.Map;
+
+public class NestedClasses {
+    static class Member {
+        class Inner {}
+    }
+
+    interface Callback {}
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        check(Member.class.getDeclaringClass() == NestedClasses.class, "Member");
+        check(Member.Inner.class.getDeclaringClass() == Member.class, "Member.Inner");
+        check(Callback.class.getDeclaringClass() == NestedClasses.class, "Callback");
+        check(Map.Entry.class.getDeclaringClass() == Map.class, "Map.Entry");
+
+        // top-level classes are not declared anywhere
+        check(NestedClasses.class.getDeclaringClass() == null, "NestedClasses");
+        check(String.class.getDeclaringClass() == null, "String");
+
+        // neither are local and anonymous classes
+        class Local {}
+        check(Local.class.getDeclaringClass() == null, "Local");
+        Callback anonymous = new Callback() {};
+        check(anonymous.getClass().getDeclaringClass() == null, "anonymous");
+    }
+}
@@ -0,0 +1,61 @@
+public class Nestmates {
+    private int secret = 42;
+    private static long counter;
+
+    private static void bump() {
+        counter++;
+    }
+
+    private int peek(Inner inner) {
+        // the host reads private members of its nest member
+        return inner.hidden + inner.twice();
+    }
+
+    private static class Inner {
+        private int hidden;
+
+        private Inner(int hidden) {
+            this.hidden = hidden;
+        }
+
+        private int twice() {
+            return hidden * 2;
+        }
+
+        int read(Nestmates outer) {
+            // a nest member reads private members of its host
+            bump();
+            return outer.secret;
+        }
+
+        int readSibling(Sibling sibling) {
+            // and of other members in the same nest
+            return sibling.value;
+        }
+    }
+
+    private static class Sibling {
+        private int value = 7;
+    }
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        Nestmates outer = new Nestmates();
+        Inner inner = new Inner(5);
+
+        check(inner.read(outer) == 42, "Inner reads Nestmates.secret");
+        check(counter == 1, "Inner calls Nestmates.bump");
+        check(outer.peek(inner) == 15, "Nestmates reads Inner.hidden");
+        check(inner.readSibling(new Sibling()) == 7, "Inner reads Sibling.value");
+
+        outer.secret = 1;
+        inner.hidden = 2;
+        check(inner.read(outer) == 1, "Nestmates.secret written");
+        check(inner.twice() == 4, "Inner.hidden written");
+    }
+}
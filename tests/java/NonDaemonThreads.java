@@ -0,0 +1,37 @@
+public class NonDaemonThreads {
+    static final Object LOCK = new Object();
+    static volatile boolean mainPrinted;
+
+    static class Worker extends Thread {
+        @Override
+        public void run() {
+            while (!mainPrinted) {
+            }
+            System.out.println("worker");
+        }
+    }
+
+    // never notified, the vm exits without it
+    static class Daemon extends Thread {
+        Daemon() {
+            setDaemon(true);
+        }
+
+        @Override
+        public void run() {
+            synchronized (LOCK) {
+                try {
+                    LOCK.wait();
+                } catch (InterruptedException e) {
+                }
+            }
+        }
+    }
+
+    public static void main(String[] args) {
+        new Daemon().start();
+        new Worker().start();
+        System.out.println("main");
+        mainPrinted = true;
+    }
+}
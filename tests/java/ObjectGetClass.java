@@ -0,0 +1,15 @@
+public class ObjectGetClass {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        Object object = new ObjectGetClass();
+        check(object.getClass() == ObjectGetClass.class, "instance");
+        check(object.getClass() == new ObjectGetClass().getClass(), "same class object");
+        check("".getClass() == String.class, "string");
+        check(new int[0].getClass() == int[].class, "array");
+    }
+}
@@ -0,0 +1,30 @@
+public class ObjectToString {
+    static class Plain {
+    }
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        Object object = new Object();
+        String string = object.toString();
+        String expected = new StringBuilder("java.lang.Object@")
+                .append(Integer.toHexString(object.hashCode()))
+                .toString();
+        check(string.equals(expected), string);
+        check(string.equals(object.toString()), "stable hash code");
+
+        Plain plain = new Plain();
+        String plainString = plain.toString();
+        check(plainString.startsWith("ObjectToString$Plain@"), plainString);
+
+        // what javac emits for "x" + plain without invokedynamic
+        String concat = new StringBuilder().append("x").append(plain).toString();
+        check(concat.equals(new StringBuilder("x").append(plainString).toString()), concat);
+        check(String.valueOf(plain).equals(plainString), "String.valueOf");
+        check(String.valueOf((Object) null).equals("null"), "String.valueOf(null)");
+    }
+}
@@ -0,0 +1,16 @@
+package p;
+
+// t is made public after the other classes are compiled, see test_package_private_override
+public class OverrideA {
+    int m() {
+        return 1;
+    }
+
+    int t() {
+        return 1;
+    }
+
+    public int n() {
+        return 1;
+    }
+}
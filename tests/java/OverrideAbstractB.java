@@ -0,0 +1,5 @@
+package q;
+
+public abstract class OverrideAbstractB extends p.OverrideA {
+    public abstract int n();
+}
@@ -0,0 +1,7 @@
+package p;
+
+public class OverrideAbstractC extends q.OverrideAbstractB {
+    public int n() {
+        return 3;
+    }
+}
@@ -0,0 +1,12 @@
+package q;
+
+// neither method overrides the package private one of OverrideA in another package
+public class OverrideB extends p.OverrideA {
+    int m() {
+        return 2;
+    }
+
+    int t() {
+        return 2;
+    }
+}
@@ -0,0 +1,13 @@
+package p;
+
+// overrides the methods of OverrideA in the same package, but not the package private ones of
+// OverrideB
+public class OverrideC extends q.OverrideB {
+    public int m() {
+        return 3;
+    }
+
+    public int t() {
+        return 3;
+    }
+}
@@ -0,0 +1,44 @@
+import java.lang.reflect.RecordComponent;
+import java.util.List;
+
+public class RecordComponents {
+    record Point(int x, long y, String label) {}
+
+    record Tagged<T>(List<T> values) {}
+
+    record Empty() {}
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) throws Exception {
+        check(Point.class.isRecord(), "Point is a record");
+        check(!RecordComponents.class.isRecord(), "RecordComponents is not a record");
+        check(!Record.class.isRecord(), "Record itself is not a record");
+        check(RecordComponents.class.getRecordComponents() == null, "not a record has no components");
+
+        RecordComponent[] components = Point.class.getRecordComponents();
+        check(components.length == 3, "Point has 3 components");
+        check(components[0].getName().equals("x"), "x name");
+        check(components[0].getType() == int.class, "x type");
+        check(components[1].getName().equals("y"), "y name");
+        check(components[1].getType() == long.class, "y type");
+        check(components[2].getName().equals("label"), "label name");
+        check(components[2].getType() == String.class, "label type");
+        check(components[2].getDeclaringRecord() == Point.class, "declaring record");
+        check(components[2].getGenericSignature() == null, "label is not generic");
+
+        check(components[0].getAccessor().getName().equals("x"), "x accessor name");
+        check(components[1].getAccessor().getReturnType() == long.class, "y accessor type");
+
+        RecordComponent values = Tagged.class.getRecordComponents()[0];
+        check(values.getType() == List.class, "values type");
+        check(values.getGenericSignature().equals("Ljava/util/List<TT;>;"), "values signature");
+
+        check(Empty.class.isRecord(), "Empty is a record");
+        check(Empty.class.getRecordComponents().length == 0, "Empty has no components");
+    }
+}
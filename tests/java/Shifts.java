@@ -0,0 +1,25 @@
+public class Shifts {
+    static int ishl(int v, int s) {
+        return v << s;
+    }
+
+    static int ishr(int v, int s) {
+        return v >> s;
+    }
+
+    static int iushr(int v, int s) {
+        return v >>> s;
+    }
+
+    static long lshl(long v, int s) {
+        return v << s;
+    }
+
+    static long lshr(long v, int s) {
+        return v >> s;
+    }
+
+    static long lushr(long v, int s) {
+        return v >>> s;
+    }
+}
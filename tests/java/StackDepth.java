@@ -0,0 +1,18 @@
+public class StackDepth {
+    static int depth;
+
+    static void recurse() {
+        depth++;
+        recurse();
+    }
+
+    static int run() {
+        depth = 0;
+        try {
+            recurse();
+        } catch (StackOverflowError e) {
+            return depth;
+        }
+        return -1;
+    }
+}
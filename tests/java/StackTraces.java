@@ -0,0 +1,24 @@
+public class StackTraces {
+    static void fail(int depth) {
+        if (depth == 0) {
+            throw new IllegalStateException("boom");
+        }
+        fail(depth - 1);
+    }
+
+    static void wrap() {
+        try {
+            fail(1);
+        } catch (IllegalStateException e) {
+            throw new RuntimeException("wrapped", e);
+        }
+    }
+
+    public static void main(String[] args) {
+        try {
+            wrap();
+        } catch (RuntimeException e) {
+            e.printStackTrace();
+        }
+    }
+}
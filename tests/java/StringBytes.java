@@ -0,0 +1,52 @@
+import java.nio.charset.StandardCharsets;
+
+public class StringBytes {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    static boolean equal(byte[] a, byte[] b) {
+        if (a.length != b.length) {
+            return false;
+        }
+        for (int i = 0; i < a.length; i++) {
+            if (a[i] != b[i]) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    public static void main(String[] args) throws Exception {
+        String literal = "h\u00e9llo \u4e2d\u6587 \ud83d\ude00";
+        byte[] utf8 = {
+            'h', (byte) 0xc3, (byte) 0xa9, 'l', 'l', 'o', ' ',
+            (byte) 0xe4, (byte) 0xb8, (byte) 0xad, (byte) 0xe6, (byte) 0x96, (byte) 0x87, ' ',
+            (byte) 0xf0, (byte) 0x9f, (byte) 0x98, (byte) 0x80,
+        };
+        check(equal(literal.getBytes("UTF-8"), utf8), "literal to bytes by name");
+        check(equal(literal.getBytes(StandardCharsets.UTF_8), utf8), "literal to bytes");
+
+        String heap = new StringBuilder("h\u00e9llo ").append("\u4e2d\u6587 \ud83d\ude00").toString();
+        check(equal(heap.getBytes("UTF-8"), utf8), "heap string to bytes by name");
+        check(equal(heap.getBytes(StandardCharsets.UTF_8), utf8), "heap string to bytes");
+
+        check(new String(utf8, "UTF-8").equals(literal), "bytes to string by name");
+        check(new String(utf8, StandardCharsets.UTF_8).equals(heap), "bytes to string");
+        check(new String(heap.getBytes("UTF-8"), "UTF-8").equals(heap), "round trip");
+        // file.encoding is UTF-8
+        check(equal(literal.getBytes(), utf8), "literal to bytes by default");
+        check(equal(heap.getBytes(), utf8), "heap string to bytes by default");
+        check(new String(utf8).equals(literal), "bytes to string by default");
+
+        String ascii = "plain ascii";
+        check(new String(ascii.getBytes("UTF-8"), "UTF-8").equals(ascii), "ascii round trip");
+        // non-ascii but still latin1, compacted to a byte per char
+        String latin1 = "caf\u00e9";
+        check(latin1.getBytes("UTF-8").length == 5, "latin1 to utf-8 bytes");
+        check(latin1.getBytes(StandardCharsets.ISO_8859_1).length == 4, "latin1 bytes");
+        check(new String(latin1.getBytes("UTF-8"), "UTF-8").equals(latin1), "latin1 round trip");
+    }
+}
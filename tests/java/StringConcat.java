@@ -0,0 +1,61 @@
+public class StringConcat {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    static class Named {
+        public String toString() {
+            return "named";
+        }
+    }
+
+    public static void main(String[] args) {
+        int n = 42;
+        String s = "x";
+        String concat = n + s;
+        check(concat.equals("42x"), "int and string");
+        check(concat != "42x", "concatenation is a new string");
+        check(concat.length() == 3, "length of the concatenation");
+
+        long l = -7L;
+        check(("a" + n + s + l).equals("a42x-7"), "constant segments between arguments");
+        check(("" + Integer.MIN_VALUE + Long.MAX_VALUE).equals("-21474836489223372036854775807"),
+                "min and max");
+
+        Object object = new Named();
+        String nothing = null;
+        check(("[" + object + "]").equals("[named]"), "object uses toString");
+        check(("[" + nothing + "]").equals("[null]"), "null string");
+        check(("" + object + (Object) null).equals("namednull"), "null object");
+
+        char c = 'c';
+        boolean b = true;
+        byte small = -1;
+        check((c + "" + b + small).equals("ctrue-1"), "char, boolean and byte");
+
+        double d = 1.5;
+        float f = 100.0f;
+        check((d + "," + f + "," + 1e20 + "," + 0.0001).equals("1.5,100.0,1.0E20,1.0E-4"),
+                "floating point");
+
+        // variables rather than constants, javac folds constants into the recipe
+        double large = 1e20, tiny = 1e-5, lowest = 0.001, highest = 1e7, below = 9999999.0;
+        check((large + "," + tiny + "," + lowest + "," + highest + "," + below)
+                .equals("1.0E20,1.0E-5,0.001,1.0E7,9999999.0"), "scientific notation bounds");
+        double nan = Double.NaN, infinity = Double.POSITIVE_INFINITY, zero = -0.0;
+        check((nan + "," + infinity + "," + -infinity + "," + zero).equals("NaN,Infinity,-Infinity,-0.0"),
+                "special values");
+        double third = 1.0 / 3, precise = 123456789.0, minimum = Double.MIN_VALUE;
+        check((third + "," + precise + "," + minimum).equals("0.3333333333333333,1.23456789E8,4.9E-324"),
+                "shortest digits");
+        float tenth = 0.1f, floatLarge = 1e10f, floatMax = Float.MAX_VALUE, floatNan = Float.NaN;
+        check((tenth + "," + floatLarge + "," + floatMax + "," + floatNan).equals("0.1,1.0E10,3.4028235E38,NaN"),
+                "float digits");
+
+        String wide = "\u4e2d" + n;
+        check(wide.equals("\u4e2d42"), "utf16 concatenation");
+        check(("\u0001" + n + "\u0002").equals("\u000142\u0002"), "tags in constants");
+    }
+}
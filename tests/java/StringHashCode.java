@@ -0,0 +1,22 @@
+public class StringHashCode {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        // documented values of s[0]*31^(n-1) + s[1]*31^(n-2) + ... + s[n-1]
+        check("".hashCode() == 0, "empty");
+        check("a".hashCode() == 97, "a");
+        check("hello".hashCode() == 99162322, "hello");
+        check("hello".hashCode() == "hello".hashCode(), "cached");
+        check("polygenelubricants".hashCode() == Integer.MIN_VALUE, "overflow");
+        check("\u4e2d\u6587".hashCode() == 646394, "utf16");
+
+        String built = new StringBuilder("hel").append("lo").toString();
+        check(built.hashCode() == "hello".hashCode(), "heap string");
+        String wide = new StringBuilder("\u4e2d").append("\u6587").toString();
+        check(wide.hashCode() == "\u4e2d\u6587".hashCode(), "utf16 heap string");
+    }
+}
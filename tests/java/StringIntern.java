@@ -0,0 +1,23 @@
+public class StringIntern {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        String literal = "ab";
+        String built = new StringBuilder().append("a").append("b").toString();
+        check(built != literal, "built string is a new object");
+        check(built.intern() == literal, "built string interns to the literal");
+        check(literal.intern() == literal, "literal interns to itself");
+
+        String fresh = new StringBuilder("not a ").append("literal").toString();
+        String interned = fresh.intern();
+        check(interned == fresh, "first intern keeps the receiver");
+        check(new String(fresh).intern() == interned, "copy interns to the first");
+
+        String wide = new StringBuilder().append("\u4e2d").append("\u6587").toString();
+        check(wide.intern() == "\u4e2d\u6587", "utf16 string interns to the literal");
+    }
+}
@@ -0,0 +1,48 @@
+import java.util.AbstractCollection;
+import java.util.AbstractList;
+import java.util.ArrayList;
+import java.util.List;
+
+public class SuperclassWalk {
+    static class Base {}
+
+    static class Middle extends Base {}
+
+    static class Leaf extends Middle {}
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    static int depth(Class<?> c) {
+        int depth = 0;
+        for (Class<?> s = c.getSuperclass(); s != null; s = s.getSuperclass()) {
+            depth++;
+        }
+        return depth;
+    }
+
+    public static void main(String[] args) {
+        check(Leaf.class.getSuperclass() == Middle.class, "Leaf -> Middle");
+        check(Middle.class.getSuperclass() == Base.class, "Middle -> Base");
+        check(Base.class.getSuperclass() == Object.class, "Base -> Object");
+        check(depth(Leaf.class) == 3, "depth of Leaf");
+
+        check(ArrayList.class.getSuperclass() == AbstractList.class, "ArrayList -> AbstractList");
+        check(AbstractList.class.getSuperclass() == AbstractCollection.class,
+                "AbstractList -> AbstractCollection");
+        check(depth(ArrayList.class) == 3, "depth of ArrayList");
+
+        // Object, interfaces and primitives have no superclass
+        check(Object.class.getSuperclass() == null, "Object");
+        check(List.class.getSuperclass() == null, "List");
+        check(int.class.getSuperclass() == null, "int");
+        check(void.class.getSuperclass() == null, "void");
+
+        // arrays extend Object directly
+        check(int[].class.getSuperclass() == Object.class, "int[]");
+        check(String[][].class.getSuperclass() == Object.class, "String[][]");
+    }
+}
@@ -0,0 +1,77 @@
+public class Switches {
+    static int calls;
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    // the switches start at offsets 9 to 12, so their operands have different padding
+    static int table(int i) {
+        calls++;
+        switch (i) {
+            case 1:
+                return 10;
+            case 2:
+                return 20;
+            case 3:
+                return 30;
+            default:
+                return -1;
+        }
+    }
+
+    static int tableShifted(int i) {
+        calls++;
+        switch (-i) {
+            case -1:
+                return 1;
+            case 0:
+                return 2;
+            case 1:
+                return 3;
+            default:
+                return 4;
+        }
+    }
+
+    static int lookup(int i) {
+        calls++;
+        switch (i + 1) {
+            case -100:
+                return 1;
+            case 7:
+                return 2;
+            case 100000:
+                return 3;
+            default:
+                return 0;
+        }
+    }
+
+    static int lookupShifted(int i) {
+        calls++;
+        switch (i - 10) {
+            case 5:
+                return 1;
+            case 1000:
+                return 2;
+            default:
+                return 0;
+        }
+    }
+
+    public static void main(String[] args) {
+        check(table(1) == 10 && table(2) == 20 && table(3) == 30, "tableswitch cases");
+        check(table(0) == -1 && table(4) == -1, "tableswitch default");
+        check(tableShifted(1) == 1 && tableShifted(0) == 2 && tableShifted(-1) == 3,
+                "shifted tableswitch cases");
+        check(tableShifted(Integer.MIN_VALUE) == 4, "shifted tableswitch default");
+        check(lookup(-101) == 1 && lookup(6) == 2 && lookup(99999) == 3, "lookupswitch cases");
+        check(lookup(7) == 0, "lookupswitch default");
+        check(lookupShifted(15) == 1 && lookupShifted(1010) == 2, "shifted lookupswitch cases");
+        check(lookupShifted(5) == 0, "shifted lookupswitch default");
+        check(calls == 16, "calls");
+    }
+}
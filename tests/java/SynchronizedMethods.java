@@ -0,0 +1,33 @@
+public class SynchronizedMethods {
+    static final SynchronizedMethods SHARED = new SynchronizedMethods();
+    static int staticCount;
+    int count;
+
+    // the increments are not atomic, threads running them at once would lose some
+    static synchronized void addStatic(int times) {
+        for (int i = 0; i < times; i++) {
+            int count = staticCount;
+            staticCount = count + 1;
+        }
+    }
+
+    synchronized void add(int times) {
+        for (int i = 0; i < times; i++) {
+            int count = this.count;
+            this.count = count + 1;
+        }
+    }
+
+    static void addShared(int times) {
+        SHARED.add(times);
+    }
+
+    static int counts() {
+        return staticCount + SHARED.count;
+    }
+
+    // the monitor is exited when the exception leaves the method
+    static synchronized void fail() {
+        throw new IllegalStateException();
+    }
+}
@@ -0,0 +1,10 @@
+public class SystemExit {
+    public static void main(String[] args) {
+        try {
+            System.exit(3);
+        } finally {
+            // exit unwinds without running handlers
+            System.exit(4);
+        }
+    }
+}
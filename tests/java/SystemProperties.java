@@ -0,0 +1,23 @@
+public class SystemProperties {
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) {
+        check(System.getProperty("java.version").startsWith("17"), "java.version");
+        check(System.getProperty("java.home") != null, "java.home");
+        check(System.getProperty("user.name") != null, "user.name");
+        check(System.getProperty("file.encoding").equals("UTF-8"), "file.encoding");
+        check(System.getProperty("line.separator").equals(System.lineSeparator()), "line.separator");
+        check(System.getProperty("no.such.property") == null, "missing");
+        check(System.getProperty("no.such.property", "default").equals("default"), "default");
+
+        check(System.setProperty("rust.jvm.test", "first") == null, "set new");
+        check(System.getProperty("rust.jvm.test").equals("first"), "get set");
+        check(System.setProperty("rust.jvm.test", "second").equals("first"), "set again");
+        check(System.clearProperty("rust.jvm.test").equals("second"), "clear");
+        check(System.getProperty("rust.jvm.test") == null, "get cleared");
+    }
+}
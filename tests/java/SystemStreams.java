@@ -0,0 +1,11 @@
+public class SystemStreams {
+    public static void main(String[] args) {
+        if (System.in == null || System.out == null || System.err == null) {
+            throw new AssertionError("streams are set up with System");
+        }
+        System.out.println("h\u00e9llo " + "\u4e2d\u6587");
+        System.out.print(42);
+        System.out.flush();
+        System.err.println("to stderr");
+    }
+}
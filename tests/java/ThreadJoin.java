@@ -0,0 +1,84 @@
+public class ThreadJoin {
+    static final Object LOCK = new Object();
+    static volatile boolean waiting;
+    static boolean released;
+
+    static class Counter extends Thread {
+        int count;
+
+        @Override
+        public void run() {
+            for (int i = 0; i < 1000; i++) {
+                count++;
+            }
+        }
+    }
+
+    static class Named implements Runnable {
+        String name;
+
+        @Override
+        public void run() {
+            name = Thread.currentThread().getName();
+        }
+    }
+
+    // waits until main releases it
+    static class Waiter extends Thread {
+        @Override
+        public void run() {
+            synchronized (LOCK) {
+                waiting = true;
+                while (!released) {
+                    try {
+                        LOCK.wait();
+                    } catch (InterruptedException e) {
+                        throw new AssertionError(e);
+                    }
+                }
+            }
+        }
+    }
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) throws InterruptedException {
+        Counter counter = new Counter();
+        check(!counter.isAlive(), "not alive before start");
+        check(counter.getState() == Thread.State.NEW, "new before start");
+        counter.start();
+        counter.join();
+        check(!counter.isAlive(), "not alive after join");
+        check(counter.getState() == Thread.State.TERMINATED, "terminated after join");
+        check(counter.count == 1000, "counted before join returned");
+        try {
+            counter.start();
+            check(false, "started twice");
+        } catch (IllegalThreadStateException e) {
+        }
+
+        Named named = new Named();
+        Thread thread = new Thread(named, "named");
+        thread.start();
+        thread.join();
+        check("named".equals(named.name), "runs as the thread started");
+        check(Thread.currentThread().getName().equals("main"), "main is still main");
+
+        Waiter waiter = new Waiter();
+        waiter.start();
+        while (!waiting) {
+        }
+        waiter.join(10);
+        check(waiter.isAlive(), "join(10) returns while the thread runs");
+        synchronized (LOCK) {
+            released = true;
+            LOCK.notifyAll();
+        }
+        waiter.join();
+        check(!waiter.isAlive(), "not alive after join");
+    }
+}
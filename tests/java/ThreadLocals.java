@@ -0,0 +1,28 @@
+public class ThreadLocals {
+    static final ThreadLocal<String> NAME = new ThreadLocal<>();
+    static final ThreadLocal<int[]> COUNTER = new ThreadLocal<>() {
+        @Override
+        protected int[] initialValue() {
+            return new int[1];
+        }
+    };
+
+    // the value of the calling thread, or -1 if it has not set one
+    static int get() {
+        String name = NAME.get();
+        return name == null ? -1 : Integer.parseInt(name);
+    }
+
+    static void set(int value) {
+        NAME.set(Integer.toString(value));
+    }
+
+    static void remove() {
+        NAME.remove();
+    }
+
+    // counts the calls made from the calling thread
+    static int count() {
+        return ++COUNTER.get()[0];
+    }
+}
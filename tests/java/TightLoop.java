@@ -0,0 +1,24 @@
+public class TightLoop {
+    // no calls in the loop, so the time is spent dispatching instructions
+    public static int sum(int n) {
+        int sum = 0;
+        for (int i = 0; i < n; i++) {
+            sum += i * 31 ^ (sum >>> 3);
+            if ((i & 7) == 0) {
+                sum -= i;
+            }
+        }
+        return sum;
+    }
+
+    // resolves the same array classes on every iteration
+    public static int newArrays(int n) {
+        int length = 0;
+        for (int i = 0; i < n; i++) {
+            int[] ints = new int[2];
+            String[] strings = new String[2];
+            length += ints.length + strings.length;
+        }
+        return length;
+    }
+}
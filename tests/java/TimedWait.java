@@ -0,0 +1,66 @@
+public class TimedWait {
+    static final Object LOCK = new Object();
+    static volatile boolean waiting;
+
+    static void waitMillis(long millis) throws InterruptedException {
+        Object lock = new Object();
+        synchronized (lock) {
+            lock.wait(millis);
+        }
+    }
+
+    // waits with no timeout, until `notifyWaiter` is called from another thread
+    static void waitUntilNotified() throws InterruptedException {
+        synchronized (LOCK) {
+            waiting = true;
+            LOCK.wait(0);
+            waiting = false;
+        }
+    }
+
+    static void notifyWaiter() {
+        while (!waiting) {
+        }
+        // entered only once the waiter has released it in wait
+        synchronized (LOCK) {
+            LOCK.notify();
+        }
+    }
+
+    static void check(boolean condition, String message) {
+        if (!condition) {
+            throw new AssertionError(message);
+        }
+    }
+
+    public static void main(String[] args) throws InterruptedException {
+        Object lock = new Object();
+        synchronized (lock) {
+            // nobody notifies, so both waits last until their timeout
+            long start = System.nanoTime();
+            lock.wait(50);
+            check(System.nanoTime() - start >= 50_000_000L, "wait(50) returned early");
+
+            // rounded up to 21ms
+            start = System.nanoTime();
+            lock.wait(20, 500_000);
+            check(System.nanoTime() - start >= 21_000_000L, "wait(20, 500000) returned early");
+
+            // a notification with no thread waiting is not kept for the next wait
+            lock.notify();
+            lock.notifyAll();
+            start = System.nanoTime();
+            lock.wait(10);
+            check(System.nanoTime() - start >= 10_000_000L, "wait(10) took an old notification");
+        }
+
+        try {
+            synchronized (lock) {
+                lock.wait(-1);
+            }
+            check(false, "negative timeout must throw");
+        } catch (IllegalArgumentException e) {
+            check(e.getMessage().equals("timeout value is negative"), "negative timeout message");
+        }
+    }
+}
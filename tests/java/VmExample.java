@@ -0,0 +1,19 @@
+public class VmExample {
+    static int add(int a, int b) {
+        return a + b;
+    }
+
+    static long mulAdd(long a, int b, long c) {
+        return a * b + c;
+    }
+
+    static double half(double d) {
+        return d / 2;
+    }
+
+    static boolean assertionsEnabled() {
+        boolean enabled = false;
+        assert enabled = true;
+        return enabled;
+    }
+}
@@ -0,0 +1,121 @@
+mod common;
+
+#[test]
+fn test_is_assignable_from() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("AssignableFrom"));
+}
+
+#[test]
+fn test_superclass_walk() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("SuperclassWalk"));
+}
+
+#[test]
+fn test_nested_classes() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("NestedClasses"));
+}
+
+#[test]
+fn test_field_generic_type() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    let class = vm.resolve_class("GenericFields").unwrap();
+    for (field, expected) in [
+        ("names", "java/util/List<java/lang/String>"),
+        (
+            "cache",
+            "java/util/Map<java/lang/String, java/util/List<? extends java/lang/Number>>[]",
+        ),
+        ("raw", "java/util/List"),
+        ("value", "T"),
+        ("count", "int"),
+    ] {
+        let generic_type = class.declared_field_generic_type(field);
+        assert_eq!(generic_type.unwrap().to_string(), expected, "{field}");
+    }
+    assert!(class.declared_field_generic_type("missing").is_none());
+}
+
+#[test]
+fn test_for_name() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("ForName"));
+}
+
+#[test]
+fn test_component_type() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("ComponentTypes"));
+}
+
+#[test]
+fn test_array_class_constants() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("ArrayClassConstants"));
+}
+
+#[test]
+fn test_array_new_instance() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("ArrayReflection"));
+}
+
+#[test]
+fn test_array_get_set() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("ArrayAccess"));
+}
+
+#[test]
+fn test_record_components() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("RecordComponents"));
+}
+
+#[test]
+fn test_bridge_methods() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    assert!(common::run_main("BridgeMethods"));
+}
+
+#[test]
+fn test_deprecated() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    let class = vm.resolve_class("DeprecatedMembers").unwrap();
+    assert!(class.is_deprecated());
+    assert_eq!(class.is_declared_method_deprecated("old", "(I)I"), Some(true));
+    assert_eq!(class.is_declared_method_deprecated("older", "()V"), Some(true));
+    assert_eq!(
+        class.is_declared_method_deprecated("current", "()V"),
+        Some(false)
+    );
+    assert_eq!(class.is_declared_method_deprecated("old", "()V"), None);
+    let kept = vm.resolve_class("DeprecatedMembers$Kept").unwrap();
+    assert!(!kept.is_deprecated());
+}
@@ -0,0 +1,211 @@
+mod common;
+
+use jvm::{
+    descriptor::parse_method_descriptor,
+    runtime::{Exception, NativeVariable},
+};
+use std::fs;
+
+// iconst_0; ifeq 6; iconst_1; ireturn; 6: iconst_2; ireturn
+const BRANCH_CODE: [u8; 8] = [0x03, 0x99, 0x00, 0x05, 0x04, 0xAC, 0x05, 0xAC];
+
+#[test]
+fn test_verify_valid_stack_map() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // same_frame at 6
+    common::write_class_file("ValidStackMap", 1, 0, &BRANCH_CODE, &[&[6]]);
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let ret = vm.invoke_static("ValidStackMap", "run", &descriptor, &[]);
+    assert!(matches!(ret, Ok(Some(NativeVariable::Int(2)))));
+}
+
+#[test]
+fn test_verify_broken_stack_map() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // same_locals_1_stack_item_frame at 6 with an int, but the stack is empty when branching
+    common::write_class_file("BrokenStackMap", 1, 0, &BRANCH_CODE, &[&[64 + 6, 1]]);
+    // the branch target has no frame
+    common::write_class_file("MissingStackMap", 1, 0, &BRANCH_CODE, &[]);
+
+    for (class_name, message) in [
+        (
+            "BrokenStackMap",
+            "BrokenStackMap.run @1: inconsistent stack depth 0 at branch target 6, \
+             stack map frame has 1",
+        ),
+        (
+            "MissingStackMap",
+            "MissingStackMap.run @1: expecting a stack map frame at branch target 6",
+        ),
+    ] {
+        let Err(Exception::VmException {
+            exception_type,
+            message: actual,
+        }) = vm.resolve_class(class_name)
+        else {
+            panic!("{class_name} must fail verification");
+        };
+        assert_eq!(exception_type.class_name(), "java/lang/VerifyError");
+        assert_eq!(actual, message);
+    }
+}
+
+#[test]
+fn test_verify_constant_references() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // ldc_w #99; ireturn, the constant pool has 11 entries
+    let bad_index = [0x13, 0x00, 0x63, 0xAC];
+    // ldc_w with only one byte of its index
+    let truncated = [0x13, 0x00];
+    // aconst_null; checkcast #5; pop; iconst_0; ireturn, #5 is the utf8 "run"
+    let wrong_kind = [0x01, 0xC0, 0x00, 0x05, 0x57, 0x03, 0xAC];
+
+    // the operands of old class files are checked as well, which are not verified otherwise
+    for (class_name, code, message) in [
+        (
+            "BadConstantIndex",
+            &bad_index[..],
+            "@0: illegal constant pool index 99",
+        ),
+        (
+            "TruncatedConstantIndex",
+            &truncated,
+            "@0: truncated instruction",
+        ),
+        ("WrongConstantKind", &wrong_kind, "@1: expecting a class"),
+    ] {
+        let old_class_name = format!("Old{class_name}");
+        common::write_class_file(class_name, 1, 0, code, &[]);
+        common::write_class_file(&old_class_name, 1, 0, code, &[]);
+        common::downgrade_class_file(&old_class_name);
+
+        for class_name in [class_name, &old_class_name] {
+            let Err(Exception::VmException {
+                exception_type,
+                message: actual,
+            }) = vm.resolve_class(class_name)
+            else {
+                panic!("{class_name} must fail verification");
+            };
+            assert_eq!(exception_type.class_name(), "java/lang/VerifyError");
+            assert_eq!(actual, format!("{class_name}.run {message}"));
+        }
+    }
+}
+
+#[test]
+fn test_verify_categories() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // lconst_1; pop2; iconst_2; ireturn
+    let pop2_long = [0x0A, 0x58, 0x05, 0xAC];
+    // lconst_1; pop; pop; iconst_2; ireturn, the stack depths agree with pop2
+    let pop_long = [0x0A, 0x57, 0x57, 0x05, 0xAC];
+    // iconst_1; lconst_1; swap; pop2; ireturn
+    let swap_long = [0x04, 0x0A, 0x5F, 0x58, 0xAC];
+
+    // class files without a StackMapTable are checked as well
+    for class_name in ["Pop2Long", "OldPop2Long"] {
+        common::write_class_file(class_name, 2, 0, &pop2_long, &[]);
+    }
+    common::downgrade_class_file("OldPop2Long");
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    for class_name in ["Pop2Long", "OldPop2Long"] {
+        let ret = vm.invoke_static(class_name, "run", &descriptor, &[]);
+        assert!(matches!(ret, Ok(Some(NativeVariable::Int(2)))));
+    }
+
+    for (class_name, code, message) in [
+        (
+            "PopLong",
+            &pop_long[..],
+            "@1: splitting a category 2 value on the operand stack",
+        ),
+        (
+            "SwapLong",
+            &swap_long,
+            "@2: splitting a category 2 value on the operand stack",
+        ),
+    ] {
+        let old_class_name = format!("Old{class_name}");
+        common::write_class_file(class_name, 3, 0, code, &[]);
+        common::write_class_file(&old_class_name, 3, 0, code, &[]);
+        common::downgrade_class_file(&old_class_name);
+
+        for class_name in [class_name, &old_class_name] {
+            let Err(Exception::VmException {
+                exception_type,
+                message: actual,
+            }) = vm.resolve_class(class_name)
+            else {
+                panic!("{class_name} must fail verification");
+            };
+            assert_eq!(exception_type.class_name(), "java/lang/VerifyError");
+            assert_eq!(actual, format!("{class_name}.run {message}"));
+        }
+    }
+}
+
+#[test]
+fn test_verify_uninitialized_this() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // aload_0; invokespecial Object.<init>; aload_0; invokevirtual hashCode; pop; return
+    let source = |class_name: &str| {
+        format!("public class {class_name} {{ public {class_name}() {{ super(); hashCode(); }} }}")
+    };
+    // aload_0; invokevirtual hashCode; pop; aload_0; invokespecial Object.<init>; return
+    let hash_code_first: fn(&mut [u8]) = |code| {
+        let patched = [&[0x2A], &code[5..8], &[0x57, 0x2A], &code[1..4]].concat();
+        code[..9].copy_from_slice(&patched);
+    };
+    // nop; nop; nop; nop; aload_0; nop; nop; nop; pop; return
+    let no_init: fn(&mut [u8]) = |code| {
+        code[..4].fill(0);
+        code[5..8].fill(0);
+    };
+
+    for (class_name, patch, message) in [
+        (
+            "ThisBeforeSuper",
+            hash_code_first,
+            "@1: uninitialized this used before calling <init>",
+        ),
+        (
+            "ReturnBeforeSuper",
+            no_init,
+            "@9: constructor must call super() or this() before return",
+        ),
+    ] {
+        common::compile(class_name, &source(class_name));
+        let path = common::classes_dir().join(format!("{class_name}.class"));
+        let mut class = fs::read(&path).unwrap();
+        let code = class
+            .windows(10)
+            .position(|code| {
+                code[..2] == [0x2A, 0xB7] && code[4..6] == [0x2A, 0xB6] && code[8..] == [0x57, 0xB1]
+            })
+            .expect("must have the code of the constructor");
+        patch(&mut class[code..code + 10]);
+        fs::write(path, class).unwrap();
+
+        let Err(Exception::VmException {
+            exception_type,
+            message: actual,
+        }) = vm.resolve_class(class_name)
+        else {
+            panic!("{class_name} must fail verification");
+        };
+        assert_eq!(exception_type.class_name(), "java/lang/VerifyError");
+        assert_eq!(actual, format!("{class_name}.<init> {message}"));
+    }
+}
@@ -0,0 +1,917 @@
+mod common;
+
+use jvm::{
+    descriptor::parse_method_descriptor,
+    runtime::{Exception, NativeVariable},
+};
+use std::{env, fs, process::Command};
+
+#[test]
+fn test_vm_invoke_static() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    let class = vm.resolve_class("VmExample").unwrap();
+    assert_eq!(class.class_name(), "VmExample");
+
+    let (_, descriptor) = parse_method_descriptor("(JIJ)J").unwrap();
+    let args = [
+        NativeVariable::Long(1 << 40),
+        NativeVariable::Int(-3),
+        NativeVariable::Long(7),
+    ];
+    let ret = vm.invoke_static("VmExample", "mulAdd", &descriptor, &args);
+    assert_eq!(ret.unwrap().unwrap().get_long(), -3 * (1 << 40) + 7);
+
+    let (_, descriptor) = parse_method_descriptor("(D)D").unwrap();
+    let ret = vm.invoke_static(
+        "VmExample",
+        "half",
+        &descriptor,
+        &[NativeVariable::Double(5.0)],
+    );
+    assert_eq!(ret.unwrap().unwrap().get_double(), 2.5);
+
+    let (_, descriptor) = parse_method_descriptor("()Z").unwrap();
+    let ret = vm.invoke_static("VmExample", "assertionsEnabled", &descriptor, &[]);
+    assert!(ret.unwrap().unwrap().get_boolean());
+
+    let (_, descriptor) = parse_method_descriptor("()V").unwrap();
+    let ret = vm.invoke_static("VmExample", "missing", &descriptor, &[]);
+    assert!(ret.is_err());
+}
+
+#[test]
+fn test_nontrivial_finalizer() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    for (class_name, expected) in [
+        ("Finalizers$Counted", true),
+        ("Finalizers$Inherited", true),
+        ("Finalizers$Empty", false),
+        ("Finalizers$Plain", false),
+        ("java/lang/Object", false),
+    ] {
+        let class = vm.resolve_class(class_name).unwrap();
+        assert_eq!(class.has_nontrivial_finalizer(), expected, "{class_name}");
+    }
+}
+
+#[test]
+fn test_reference_classes() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    for (class_name, expected) in [
+        ("java/lang/ref/Reference", true),
+        ("java/lang/ref/WeakReference", true),
+        ("java/lang/ref/SoftReference", true),
+        ("java/lang/ref/PhantomReference", true),
+        ("java/lang/ref/ReferenceQueue", false),
+        ("java/lang/Object", false),
+    ] {
+        let class = vm.resolve_class(class_name).unwrap();
+        assert_eq!(class.is_reference(), expected, "{class_name}");
+    }
+}
+
+#[test]
+fn test_string_constant_value() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // ConstantReader is compiled against a ConstantHolder whose NAME is not a constant, so it
+    // reads NAME with getstatic instead of inlining it, in the <clinit> of the ConstantHolder
+    // that has the constant
+    common::compile(
+        "ConstantHolder",
+        "public class ConstantHolder { static String NAME; }",
+    );
+    common::compile(
+        "ConstantReader",
+        "public class ConstantReader { static String read() { return ConstantHolder.NAME; } }",
+    );
+    common::compile(
+        "ConstantHolder",
+        "public class ConstantHolder {
+             static final String NAME = \"constant\";
+             static String early = ConstantReader.read();
+             static boolean early() { return early == NAME; }
+         }",
+    );
+    let (_, descriptor) = parse_method_descriptor("()Z").unwrap();
+    let ret = vm.invoke_static("ConstantHolder", "early", &descriptor, &[]);
+    assert!(ret.unwrap().unwrap().get_boolean());
+
+    // a String constant on an Object field
+    common::compile(
+        "BadConstant",
+        "public class BadConstant { static final String VALUE = \"bad\"; }",
+    );
+    let path = common::classes_dir().join("BadConstant.class");
+    let class = fs::read(&path).unwrap();
+    let descriptor = b"Ljava/lang/String;";
+    let at = class
+        .windows(descriptor.len())
+        .position(|w| w == descriptor)
+        .unwrap();
+    let mut patched = class.clone();
+    patched[at..at + descriptor.len()].copy_from_slice(b"Ljava/lang/Object;");
+    fs::write(&path, patched).unwrap();
+
+    let Err(Exception::VmException {
+        exception_type,
+        message,
+    }) = vm.resolve_class("BadConstant")
+    else {
+        panic!("must throw vm exception");
+    };
+    assert_eq!(exception_type.class_name(), "java/lang/ClassFormatError");
+    assert_eq!(
+        message,
+        "Bad string initial value in class file BadConstant"
+    );
+}
+
+#[test]
+fn test_loaded_class_names() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    let loaded = |class_name: &str| {
+        vm.loaded_class_names()
+            .iter()
+            .any(|name| name.as_ref() == class_name)
+    };
+    assert!(!loaded("LazyLoading"));
+
+    vm.resolve_class("LazyLoading").unwrap();
+    for class_name in ["LazyLoading", "LazyLoadingBase", "java/lang/Object"] {
+        assert!(loaded(class_name), "{class_name}");
+    }
+    assert!(!loaded("LazyLoadingUnused"));
+}
+
+#[test]
+fn test_silent_by_default() {
+    // the test harness captures stdout in process, so run this test again in a child process
+    const CHILD: &str = "JVM_SILENT_CHILD";
+    if env::var_os(CHILD).is_some() {
+        let Some((_vm, _guard)) = common::vm() else {
+            return;
+        };
+        println!("<main>");
+        assert!(common::run_main("ObjectToString"));
+        println!("</main>");
+        return;
+    }
+    if env::var_os("JAVA_HOME").is_none() {
+        return;
+    }
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["test_silent_by_default", "--exact", "--nocapture"])
+        .env(CHILD, "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let start = stdout.find("<main>\n").expect("main must run") + "<main>\n".len();
+    let end = stdout.find("</main>").expect("main must return");
+    assert_eq!(&stdout[start..end], "");
+}
+
+#[test]
+fn test_system_exit() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    let status = Command::new(env!("CARGO_BIN_EXE_java"))
+        .arg(common::classes_dir())
+        .arg("SystemExit")
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(3));
+}
+
+#[test]
+fn test_hello_world() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    let output = Command::new(env!("CARGO_BIN_EXE_java"))
+        .arg(common::classes_dir())
+        .arg("HelloWorld")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "Hello, World!\n");
+}
+
+#[test]
+fn test_system_streams() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    // System is initialized when the vm is built, so the standard streams can be printed to
+    let output = Command::new(env!("CARGO_BIN_EXE_java"))
+        .arg(common::classes_dir())
+        .arg("SystemStreams")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "h\u{e9}llo \u{4e2d}\u{6587}\n42"
+    );
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "to stderr\n");
+}
+
+#[test]
+fn test_non_daemon_threads() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    // the worker prints once main has returned, the daemon never finishes
+    let output = Command::new(env!("CARGO_BIN_EXE_java"))
+        .arg(common::classes_dir())
+        .arg("NonDaemonThreads")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "main\nworker\n");
+}
+
+#[test]
+fn test_stack_traces() {
+    let Some((_vm, _guard)) = common::vm() else {
+        return;
+    };
+    let output = Command::new(env!("CARGO_BIN_EXE_java"))
+        .arg(common::classes_dir())
+        .arg("StackTraces")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    // as printed by hotspot, the constructors and fillInStackTrace are left out
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "java.lang.RuntimeException: wrapped\n\
+         \tat StackTraces.wrap(StackTraces.java:13)\n\
+         \tat StackTraces.main(StackTraces.java:19)\n\
+         Caused by: java.lang.IllegalStateException: boom\n\
+         \tat StackTraces.fail(StackTraces.java:4)\n\
+         \tat StackTraces.fail(StackTraces.java:6)\n\
+         \tat StackTraces.wrap(StackTraces.java:11)\n\
+         \t... 1 more\n"
+    );
+}
+
+#[test]
+fn test_define_class() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    common::compile(
+        "DefinedFromBytes",
+        "public class DefinedFromBytes { int value = 42; \
+         static int make() { return new DefinedFromBytes().value; } }",
+    );
+    // only the bytes can define it
+    let class_file = common::classes_dir().join("DefinedFromBytes.class");
+    let bytes = fs::read(&class_file).unwrap();
+    fs::remove_file(&class_file).unwrap();
+
+    let class = vm.define_class("DefinedFromBytes", &bytes).unwrap();
+    assert_eq!(class.class_name(), "DefinedFromBytes");
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let ret = vm.invoke_static("DefinedFromBytes", "make", &descriptor, &[]);
+    assert_eq!(ret.unwrap().unwrap().get_int(), 42);
+
+    let Err(Exception::VmException {
+        exception_type,
+        message,
+    }) = vm.define_class("DefinedFromBytes", &bytes)
+    else {
+        panic!("must not define a class twice");
+    };
+    assert_eq!(exception_type.class_name(), "java/lang/LinkageError");
+    assert_eq!(message, "duplicate class definition for DefinedFromBytes");
+
+    let Err(Exception::VmException {
+        exception_type,
+        message,
+    }) = vm.define_class("Renamed", &bytes)
+    else {
+        panic!("must check the name");
+    };
+    assert_eq!(
+        exception_type.class_name(),
+        "java/lang/NoClassDefFoundError"
+    );
+    assert_eq!(message, "Renamed (wrong name: DefinedFromBytes)");
+
+    let Err(Exception::VmException { exception_type, .. }) =
+        vm.define_class("Truncated", &bytes[..20])
+    else {
+        panic!("must parse the class file");
+    };
+    assert_eq!(exception_type.class_name(), "java/lang/ClassFormatError");
+}
+
+#[test]
+fn test_member_access() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    common::compile(
+        "access/MemberAccess",
+        "package access; public class MemberAccess { \
+         public static int secret = 4; public static int open() { return 1; } \
+         public static int guarded() { return 2; } public static int local() { return 3; } }",
+    );
+    common::compile(
+        "AccessCaller",
+        "public class AccessCaller { \
+         static int open() { return access.MemberAccess.open(); } \
+         static int guarded() { return access.MemberAccess.guarded(); } \
+         static int local() { return access.MemberAccess.local(); } \
+         static int secret() { return access.MemberAccess.secret; } }",
+    );
+    common::compile(
+        "AccessSubclass",
+        "public class AccessSubclass extends access.MemberAccess { \
+         static int callGuarded() { return access.MemberAccess.guarded(); } }",
+    );
+    common::compile(
+        "access/MemberAccess",
+        &fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/java/MemberAccess.java"
+        ))
+        .unwrap(),
+    );
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let call = |class: &str, method: &str| {
+        vm.invoke_static(class, method, &descriptor, &[])
+            .map(|ret| ret.unwrap().get_int())
+    };
+    assert_eq!(call("AccessCaller", "open").unwrap(), 1);
+    assert_eq!(call("AccessSubclass", "callGuarded").unwrap(), 2);
+    assert_eq!(call("access/MemberAccess", "fromPackage").unwrap(), 5);
+
+    for (method, message) in [
+        (
+            "guarded",
+            "class AccessCaller tried to access protected method access.MemberAccess.guarded",
+        ),
+        (
+            "local",
+            "class AccessCaller tried to access package-private method access.MemberAccess.local",
+        ),
+        (
+            "secret",
+            "class AccessCaller tried to access private field access.MemberAccess.secret",
+        ),
+    ] {
+        let Err(Exception::VmException {
+            exception_type,
+            message: actual,
+        }) = call("AccessCaller", method)
+        else {
+            panic!("{method} must not be accessible");
+        };
+        assert_eq!(exception_type.class_name(), "java/lang/IllegalAccessError");
+        assert_eq!(actual, message);
+    }
+}
+
+#[test]
+fn test_abstract_method_error() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // the implementations are compiled before the abstract methods are added
+    common::compile(
+        "AbstractBase",
+        "public abstract class AbstractBase { abstract int run(); }",
+    );
+    common::compile("AbstractInterface", "public interface AbstractInterface {}");
+    common::compile(
+        "AbstractImpl",
+        "public class AbstractImpl extends AbstractBase implements AbstractInterface { \
+         int run() { return 1; } }",
+    );
+    common::compile(
+        "AbstractBase",
+        "public abstract class AbstractBase { abstract int run(); abstract int added(); }",
+    );
+    common::compile(
+        "AbstractInterface",
+        "public interface AbstractInterface { int added(); }",
+    );
+    common::compile(
+        "AbstractCaller",
+        "public class AbstractCaller { \
+         static int run() { AbstractBase base = new AbstractImpl(); return base.run(); } \
+         static int virtual() { AbstractBase base = new AbstractImpl(); return base.added(); } \
+         static int iface() { AbstractInterface i = new AbstractImpl(); return i.added(); } }",
+    );
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let ret = vm.invoke_static("AbstractCaller", "run", &descriptor, &[]);
+    assert_eq!(ret.unwrap().unwrap().get_int(), 1);
+    for method in ["virtual", "iface"] {
+        let Err(Exception::VmException {
+            exception_type,
+            message,
+        }) = vm.invoke_static("AbstractCaller", method, &descriptor, &[])
+        else {
+            panic!("{method} must throw");
+        };
+        assert_eq!(exception_type.class_name(), "java/lang/AbstractMethodError");
+        assert_eq!(message, "AbstractImpl.added()I");
+    }
+}
+
+#[test]
+fn test_miranda_method() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // the abstract class inherits the method from the interface without implementing it, and the
+    // subclass is compiled before the method is added
+    common::compile("MirandaInterface", "public interface MirandaInterface {}");
+    common::compile(
+        "MirandaBase",
+        "public abstract class MirandaBase implements MirandaInterface {}",
+    );
+    common::compile(
+        "MirandaImpl",
+        "public class MirandaImpl extends MirandaBase {}",
+    );
+    common::compile(
+        "MirandaInterface",
+        "public interface MirandaInterface { int missing(); }",
+    );
+    common::compile(
+        "MirandaCaller",
+        "public class MirandaCaller { \
+         static int viaClass() { MirandaBase base = new MirandaImpl(); return base.missing(); } \
+         static int caught() { try { return viaClass(); } catch (AbstractMethodError e) { \
+         return 2; } } }",
+    );
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let Err(Exception::VmException {
+        exception_type,
+        message,
+    }) = vm.invoke_static("MirandaCaller", "viaClass", &descriptor, &[])
+    else {
+        panic!("viaClass must throw");
+    };
+    assert_eq!(exception_type.class_name(), "java/lang/AbstractMethodError");
+    assert_eq!(message, "MirandaImpl.missing()I");
+
+    let ret = vm.invoke_static("MirandaCaller", "caught", &descriptor, &[]);
+    assert_eq!(ret.unwrap().unwrap().get_int(), 2);
+}
+
+#[test]
+fn test_incompatible_class_change() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // the caller is compiled before every member of the target changes between static and not
+    common::compile(
+        "KindTarget",
+        "public class KindTarget { public static int counter = 1; public int value = 2; \
+         public static int make() { return 3; } public int get() { return 4; } }",
+    );
+    common::compile(
+        "KindCaller",
+        "public class KindCaller { \
+         static int getStatic() { return KindTarget.counter; } \
+         static int getField() { return new KindTarget().value; } \
+         static int invokeStatic() { return KindTarget.make(); } \
+         static int invokeVirtual() { return new KindTarget().get(); } }",
+    );
+    common::compile(
+        "KindTarget",
+        "public class KindTarget { public int counter = 1; public static int value = 2; \
+         public int make() { return 3; } public static int get() { return 4; } }",
+    );
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    for (method, expected) in [
+        ("getStatic", "Expected static field KindTarget.counter"),
+        ("getField", "Expected non-static field KindTarget.value"),
+        ("invokeStatic", "Expected static method KindTarget.make()I"),
+        (
+            "invokeVirtual",
+            "Expecting non-static method KindTarget.get()I",
+        ),
+    ] {
+        let Err(Exception::VmException {
+            exception_type,
+            message,
+        }) = vm.invoke_static("KindCaller", method, &descriptor, &[])
+        else {
+            panic!("{method} must throw");
+        };
+        assert_eq!(
+            exception_type.class_name(),
+            "java/lang/IncompatibleClassChangeError"
+        );
+        assert_eq!(message, expected);
+    }
+}
+
+#[test]
+fn test_null_receiver_field_access() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // the caller is compiled before `removed` is dropped from the target
+    common::compile(
+        "NullReceiverTarget",
+        "public class NullReceiverTarget { public int kept; public long removed; }",
+    );
+    common::compile(
+        "NullReceiverCaller",
+        "public class NullReceiverCaller { \
+         static NullReceiverTarget target() { return null; } \
+         static int getKept() { return target().kept; } \
+         static int putKept() { target().kept = 1; return 0; } \
+         static int getRemoved() { return (int) target().removed; } \
+         static int putRemoved() { target().removed = 1; return 0; } }",
+    );
+    common::compile(
+        "NullReceiverTarget",
+        "public class NullReceiverTarget { public int kept; }",
+    );
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    for (method, expected) in [
+        ("getKept", "java/lang/NullPointerException"),
+        ("putKept", "java/lang/NullPointerException"),
+        // resolution fails before the receiver is checked
+        ("getRemoved", "java/lang/NoSuchFieldError"),
+        ("putRemoved", "java/lang/NoSuchFieldError"),
+    ] {
+        let Err(Exception::VmException { exception_type, .. }) =
+            vm.invoke_static("NullReceiverCaller", method, &descriptor, &[])
+        else {
+            panic!("{method} must throw");
+        };
+        assert_eq!(exception_type.class_name(), expected, "{method}");
+    }
+}
+
+#[test]
+fn test_dump_field_layout() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    common::compile(
+        "LayoutBase",
+        "public class LayoutBase { int a; long b; static int ignored; }",
+    );
+    common::compile(
+        "LayoutMiddle",
+        "public class LayoutMiddle extends LayoutBase { Object c; int a; }",
+    );
+    common::compile(
+        "LayoutLeaf",
+        "public class LayoutLeaf extends LayoutMiddle { double d; boolean e; String[] f; }",
+    );
+
+    // a long or double takes two slots, and a hidden field keeps its own slot
+    assert_eq!(
+        vm.dump_field_layout("LayoutLeaf").unwrap(),
+        "0: LayoutBase.a int\n\
+         1: LayoutBase.b long\n\
+         3: LayoutMiddle.c java/lang/Object\n\
+         4: LayoutMiddle.a int\n\
+         5: LayoutLeaf.d double\n\
+         7: LayoutLeaf.e boolean\n\
+         8: LayoutLeaf.f java/lang/String[]\n"
+    );
+    assert_eq!(
+        vm.dump_field_layout("LayoutBase").unwrap(),
+        "0: LayoutBase.a int\n1: LayoutBase.b long\n"
+    );
+}
+
+#[test]
+fn test_package_private_override() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // calls through the static type of OverrideA in its package p, and of OverrideB in q
+    common::compile(
+        "p/OverrideCallerA",
+        "package p; public class OverrideCallerA { \
+         static int mOfB() { return ((OverrideA) new q.OverrideB()).m(); } \
+         static int mOfC() { return ((OverrideA) new OverrideC()).m(); } \
+         static int tOfB() { return ((OverrideA) new q.OverrideB()).t(); } \
+         static int tOfC() { return ((OverrideA) new OverrideC()).t(); } \
+         static int nOfAbstractC() { return ((OverrideA) new OverrideAbstractC()).n(); } }",
+    );
+    common::compile(
+        "q/OverrideCallerB",
+        "package q; public class OverrideCallerB { \
+         static int mOfC() { return ((OverrideB) new p.OverrideC()).m(); } \
+         static int tOfC() { return ((OverrideB) new p.OverrideC()).t(); } }",
+    );
+    // once OverrideA.t is public, OverrideB.t overrides it, and OverrideC.t overrides it without
+    // overriding OverrideB.t
+    common::compile(
+        "p/OverrideA",
+        &fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/java/OverrideA.java"
+        ))
+        .unwrap()
+        .replace("int t()", "public int t()"),
+    );
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    for (class_name, method, expected) in [
+        ("p/OverrideCallerA", "mOfB", 1),
+        ("p/OverrideCallerA", "mOfC", 3),
+        ("q/OverrideCallerB", "mOfC", 2),
+        ("p/OverrideCallerA", "tOfB", 2),
+        ("p/OverrideCallerA", "tOfC", 3),
+        ("q/OverrideCallerB", "tOfC", 2),
+        // re-abstracted in q, and implemented again in p
+        ("p/OverrideCallerA", "nOfAbstractC", 3),
+    ] {
+        let ret = vm.invoke_static(class_name, method, &descriptor, &[]);
+        assert_eq!(
+            ret.unwrap().unwrap().get_int(),
+            expected,
+            "{class_name}.{method}"
+        );
+    }
+}
+
+#[test]
+fn test_conflicting_default_methods() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // the callers are compiled before ConflictRight gets a default method of its own, then
+    // neither of the maximally-specific ones is selected
+    common::compile("ConflictTop", "public interface ConflictTop { int m(); }");
+    common::compile(
+        "ConflictLeft",
+        "public interface ConflictLeft extends ConflictTop { default int m() { return 1; } }",
+    );
+    common::compile(
+        "ConflictRight",
+        "public interface ConflictRight extends ConflictTop { }",
+    );
+    common::compile(
+        "ConflictCaller",
+        "public class ConflictCaller { \
+         static class Impl implements ConflictLeft, ConflictRight { } \
+         static int virtual() { return new Impl().m(); } \
+         static int top() { return ((ConflictTop) new Impl()).m(); } \
+         static int left() { return ((ConflictLeft) new Impl()).m(); } }",
+    );
+    common::compile(
+        "ConflictRight",
+        "public interface ConflictRight extends ConflictTop { default int m() { return 2; } }",
+    );
+
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    for method in ["virtual", "top", "left"] {
+        let Err(Exception::VmException {
+            exception_type,
+            message,
+        }) = vm.invoke_static("ConflictCaller", method, &descriptor, &[])
+        else {
+            panic!("{method} must throw vm exception");
+        };
+        assert_eq!(
+            exception_type.class_name(),
+            "java/lang/IncompatibleClassChangeError",
+            "{method}"
+        );
+        assert_eq!(
+            message, "Conflicting default methods: ConflictLeft.m ConflictRight.m",
+            "{method}"
+        );
+    }
+}
+
+#[test]
+fn test_current_thread_of_new_threads() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // every call from the vm runs on a new thread, whose thread object is created on first use
+    common::compile(
+        "ThreadIds",
+        "public class ThreadIds { \
+         static long id() { \
+             Thread thread = Thread.currentThread(); \
+             return thread == Thread.currentThread() ? thread.getId() : -1; \
+         } \
+         static boolean named() { \
+             String name = Thread.currentThread().getName(); \
+             return name.startsWith(\"Thread-\") && !name.equals(\"Thread-\"); \
+         } }",
+    );
+
+    let (_, descriptor) = parse_method_descriptor("()J").unwrap();
+    let id = || {
+        vm.invoke_static("ThreadIds", "id", &descriptor, &[])
+            .unwrap()
+            .unwrap()
+            .get_long()
+    };
+    let first = id();
+    let second = id();
+    // the main thread takes 1
+    assert!(first > 1, "{first}");
+    assert!(second > 1, "{second}");
+    assert_ne!(first, second);
+
+    let (_, descriptor) = parse_method_descriptor("()Z").unwrap();
+    let named = vm
+        .invoke_static("ThreadIds", "named", &descriptor, &[])
+        .unwrap()
+        .unwrap();
+    assert!(named.get_boolean());
+}
+
+#[test]
+fn test_sealed_classes() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    // the subclasses that are not permitted are compiled before their super types are sealed
+    common::compile("SealedShape", "public abstract class SealedShape { }");
+    common::compile("SealedSides", "public interface SealedSides { }");
+    common::compile(
+        "SealedSquare",
+        "public final class SealedSquare extends SealedShape { }",
+    );
+    common::compile(
+        "SealedPentagon",
+        "public final class SealedPentagon implements SealedSides { }",
+    );
+    common::compile(
+        "SealedShape",
+        "public abstract sealed class SealedShape { \
+         public static final class Circle extends SealedShape { } }",
+    );
+    common::compile(
+        "SealedSides",
+        "public sealed interface SealedSides { final class Triangle implements SealedSides { } }",
+    );
+
+    vm.resolve_class("SealedShape$Circle").unwrap();
+    vm.resolve_class("SealedSides$Triangle").unwrap();
+    for (class_name, message) in [
+        (
+            "SealedSquare",
+            "class SealedSquare cannot inherit from sealed class SealedShape",
+        ),
+        (
+            "SealedPentagon",
+            "class SealedPentagon cannot implement sealed interface SealedSides",
+        ),
+    ] {
+        let Err(Exception::VmException {
+            exception_type,
+            message: actual,
+        }) = vm.resolve_class(class_name)
+        else {
+            panic!("{class_name} must not load");
+        };
+        assert_eq!(
+            exception_type.class_name(),
+            "java/lang/IncompatibleClassChangeError",
+            "{class_name}"
+        );
+        assert_eq!(actual, message);
+    }
+}
+
+#[test]
+fn test_missing_handler_class() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    common::compile(
+        "MissingHandler",
+        "public class MissingHandler extends RuntimeException { }",
+    );
+    common::compile(
+        "CatchesMissing",
+        "public class CatchesMissing {
+             static int uncaught() {
+                 try {
+                     throw new IllegalStateException();
+                 } catch (MissingHandler e) {
+                     return 1;
+                 }
+             }
+             static int caught() {
+                 try {
+                     return uncaught();
+                 } catch (NoClassDefFoundError e) {
+                     return e.getMessage().equals(\"MissingHandler\") ? 2 : 3;
+                 }
+             }
+             static int nested() {
+                 try {
+                     try {
+                         throw new IllegalStateException();
+                     } catch (MissingHandler e) {
+                         return 1;
+                     }
+                 } catch (NoClassDefFoundError e) {
+                     return 4;
+                 }
+             }
+         }",
+    );
+    fs::remove_file(common::classes_dir().join("MissingHandler.class")).unwrap();
+    // the type checking verifier would load the catch type
+    common::downgrade_class_file("CatchesMissing");
+
+    // the error loading the handler class replaces the exception in flight
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    let Err(Exception::VmException {
+        exception_type,
+        message,
+    }) = vm.invoke_static("CatchesMissing", "uncaught", &descriptor, &[])
+    else {
+        panic!("must throw vm exception");
+    };
+    assert_eq!(
+        exception_type.class_name(),
+        "java/lang/NoClassDefFoundError"
+    );
+    assert_eq!(message, "MissingHandler");
+
+    for (method, expected) in [("caught", 2), ("nested", 4)] {
+        let ret = vm.invoke_static("CatchesMissing", method, &descriptor, &[]);
+        assert_eq!(ret.unwrap().unwrap().get_int(), expected, "{method}");
+    }
+}
+
+#[test]
+fn test_invokespecial_super() {
+    let Some((vm, _guard)) = common::vm() else {
+        return;
+    };
+    common::compile(
+        "SuperCallTop",
+        "public class SuperCallTop { public int foo() { return 1; } }",
+    );
+    common::compile(
+        "SuperCallMiddle",
+        "public class SuperCallMiddle extends SuperCallTop { public int foo() { return 2; } }",
+    );
+    common::compile(
+        "SuperCallPlain",
+        "public class SuperCallPlain extends SuperCallMiddle {
+             public int foo() { return super.foo() * 10; }
+             static int run() { return new SuperCallPlain().foo(); }
+         }",
+    );
+    // super.foo() names SuperCallTop, which SuperCallMiddle is moved under afterwards
+    for class_name in ["SuperCallBottom", "SuperCallLegacy"] {
+        common::compile(
+            class_name,
+            &format!(
+                "public class {class_name} extends SuperCallTop {{
+                     public int foo() {{ return super.foo() * 10; }}
+                     static int run() {{ return new {class_name}().foo(); }}
+                     static Class<?> middle() {{ return SuperCallMiddle.class; }}
+                 }}"
+            ),
+        );
+    }
+    common::set_super_class("SuperCallBottom", "SuperCallMiddle", true);
+    common::set_super_class("SuperCallLegacy", "SuperCallMiddle", false);
+
+    // with ACC_SUPER the method is looked up from the direct superclass, without it the
+    // resolved method is invoked
+    let (_, descriptor) = parse_method_descriptor("()I").unwrap();
+    for (class_name, expected) in [
+        ("SuperCallPlain", 20),
+        ("SuperCallBottom", 20),
+        ("SuperCallLegacy", 10),
+    ] {
+        let ret = vm.invoke_static(class_name, "run", &descriptor, &[]);
+        assert_eq!(ret.unwrap().unwrap().get_int(), expected, "{class_name}");
+    }
+}